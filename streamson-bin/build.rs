@@ -27,6 +27,31 @@ fn root_page() -> man::Manual {
                 .long("--buffer-size")
                 .help("Sets input buffer size [default: 1048576]"),
         )
+        .flag(
+            Flag::new()
+                .long("--strict")
+                .help("Rejects invalid JSON input (numbers, literals, trailing commas, escapes)"),
+        )
+        .flag(
+            Flag::new()
+                .long("--skip-invalid")
+                .help("Skips the lines with an invalid JSON (useful with ndjson input format)"),
+        )
+        .option(
+            Opt::new("INPUT_FORMAT")
+                .long("--input-format")
+                .help("Sets the format of the input - json or ndjson [default: json]"),
+        )
+        .option(
+            Opt::new("COMPRESSION")
+                .long("--input-compression")
+                .help("Decompresses the input - none, gzip, zstd or auto [default: none]"),
+        )
+        .option(
+            Opt::new("COMPRESSION")
+                .long("--output-compression")
+                .help("Compresses the output - none, gzip or zstd [default: none]"),
+        )
         .arg(Arg::new("<strategy>"))
         .arg(Arg::new("[<args>]"))
         .custom(
@@ -54,6 +79,9 @@ fn all_page() -> man::Manual {
     Manual::new("sson-all")
         .about(strategies::All.description())
         .flag(Flag::new().long("--help").help("Prints help information"))
+        .flag(Flag::new().long("--flush-incomplete").help(
+            "Writes the converted data even when the input terminates in the middle of a JSON",
+        ))
         .flag(
             Flag::new()
                 .short("-V")
@@ -85,6 +113,9 @@ fn convert_page() -> man::Manual {
     Manual::new("sson-convert")
         .about(strategies::Convert.description())
         .flag(Flag::new().long("--help").help("Prints help information"))
+        .flag(Flag::new().long("--flush-incomplete").help(
+            "Writes the converted data even when the input terminates in the middle of a JSON",
+        ))
         .flag(
             Flag::new()
                 .short("-V")
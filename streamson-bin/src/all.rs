@@ -1,16 +1,18 @@
 use std::{
     error::Error,
-    io::{stdin, stdout, Read, Write},
+    io::{Read, Write},
     sync::{Arc, Mutex},
 };
 
-use clap::{App, ArgMatches};
+use clap::{App, Arg, ArgMatches};
 use streamson_lib::{
     handler::{self, Handler},
-    strategy::{self, Output, Strategy},
+    strategy::{self, OutputConverter, Strategy},
+    streamer::InputFormat,
 };
 
 use crate::{
+    compression::Writer,
     docs::{strategies, Element},
     handlers,
 };
@@ -20,10 +22,27 @@ pub fn prepare_all_subcommand() -> App<'static> {
         .visible_aliases(&strategies::All.aliases())
         .about(strategies::All.description())
         .arg(handlers::handlers_arg("all"))
+        .arg(
+            Arg::new("flush_incomplete")
+                .about("Writes the converted data even when the input terminates in the middle of a JSON")
+                .long("flush-incomplete"),
+        )
 }
 
-pub fn process_all(matches: &ArgMatches, buffer_size: usize) -> Result<(), Box<dyn Error>> {
+pub fn process_all(
+    matches: &ArgMatches,
+    buffer_size: usize,
+    strict: bool,
+    skip_invalid: bool,
+    input_format: InputFormat,
+    input: &mut dyn Read,
+    out: &mut Writer,
+) -> Result<(), Box<dyn Error>> {
     let mut all = strategy::All::new();
+    all.set_strict(strict);
+    all.set_skip_invalid(skip_invalid);
+    all.set_input_format(input_format);
+    all.set_flush_incomplete(matches.is_present("flush_incomplete"));
 
     let hndlrs: Vec<Arc<Mutex<handler::Group>>> = handlers::parse_handlers(matches, "all")?
         .into_iter()
@@ -39,8 +58,12 @@ pub fn process_all(matches: &ArgMatches, buffer_size: usize) -> Result<(), Box<d
         all.add_handler(handler.clone());
     }
 
+    let mut output_converter = OutputConverter::new();
     let mut buffer = vec![];
-    while let Ok(size) = stdin().take(buffer_size as u64).read_to_end(&mut buffer) {
+    while let Ok(size) = (&mut *input)
+        .take(buffer_size as u64)
+        .read_to_end(&mut buffer)
+    {
         if size == 0 {
             break;
         }
@@ -48,13 +71,9 @@ pub fn process_all(matches: &ArgMatches, buffer_size: usize) -> Result<(), Box<d
         let output = all.process(&buffer[..size])?;
 
         if converter {
-            for out in output {
-                if let Output::Data(data) = out {
-                    stdout().write_all(&data)?;
-                }
-            }
+            output_converter.write_all(&output, out)?;
         } else {
-            stdout().write_all(&buffer[..size])?;
+            out.write_all(&buffer[..size])?;
         }
 
         buffer.clear();
@@ -62,11 +81,7 @@ pub fn process_all(matches: &ArgMatches, buffer_size: usize) -> Result<(), Box<d
 
     if converter {
         // Input terminated try to hit strategy termination
-        for out in all.terminate()? {
-            if let Output::Data(data) = out {
-                stdout().write_all(&data)?;
-            }
-        }
+        output_converter.write_all(&all.terminate()?, out)?;
     } else {
         all.terminate()?;
     }
@@ -1,6 +1,7 @@
 use assert_cmd::Command;
+use flate2::{write::GzEncoder, Compression};
 use predicates::prelude::*;
-use std::env;
+use std::{env, io::Write};
 
 const INPUT_DATA: &str = r#"{
     "users": [{"name": "carl", "id": 1}, {"name": "paul", "id": 2}],
@@ -299,6 +300,99 @@ fn all(cmd_str: &str) {
     println!("OK (failed)");
 }
 
+fn strict(cmd_str: &str) {
+    print!("STRICT ");
+    Command::new(cmd_str)
+        .arg("--strict")
+        .arg("extract")
+        .arg("-m")
+        .arg("depth:1")
+        .write_stdin("[01]")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("IncorrectInput"));
+    println!("OK (failed)");
+
+    print!("NOT STRICT ");
+    Command::new(cmd_str)
+        .arg("extract")
+        .arg("-m")
+        .arg("depth:1")
+        .write_stdin("[01]")
+        .assert()
+        .success()
+        .stdout("01");
+    println!("OK");
+}
+
+fn skip_invalid(cmd_str: &str) {
+    print!("SKIP INVALID ");
+    Command::new(cmd_str)
+        .arg("--input-format")
+        .arg("ndjson")
+        .arg("--skip-invalid")
+        .arg("extract")
+        .arg("-m")
+        .arg("depth:1")
+        .write_stdin("[1]\n[x]\n[2]\n")
+        .assert()
+        .success()
+        .stdout("12");
+    println!("OK");
+
+    print!("NOT SKIP INVALID ");
+    Command::new(cmd_str)
+        .arg("--input-format")
+        .arg("ndjson")
+        .arg("extract")
+        .arg("-m")
+        .arg("depth:1")
+        .write_stdin("[1]\n[x]\n[2]\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("IncorrectInput"));
+    println!("OK (failed)");
+}
+
+fn compression(cmd_str: &str) {
+    print!("GZIP INPUT ");
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(INPUT_DATA.as_bytes()).unwrap();
+    let compressed = encoder.finish().unwrap();
+    Command::new(cmd_str)
+        .arg("-b")
+        .arg("10")
+        .arg("--input-compression")
+        .arg("auto")
+        .arg("extract")
+        .arg("-m")
+        .arg(r#"simple:{"users"}[]{"name"}"#)
+        .write_stdin(compressed)
+        .assert()
+        .success()
+        .stdout(r#""carl""paul""#);
+    println!("OK");
+
+    print!("ZSTD OUTPUT ");
+    let output = Command::new(cmd_str)
+        .arg("--output-compression")
+        .arg("zstd")
+        .arg("extract")
+        .arg("-m")
+        .arg(r#"simple:{"groups"}[]{"name"}"#)
+        .write_stdin(INPUT_DATA)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    assert_eq!(
+        zstd::decode_all(&output[..]).unwrap(),
+        br#""admin""staff""#.to_vec()
+    );
+    println!("OK");
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     assert!(args.len() == 2);
@@ -307,4 +401,7 @@ fn main() {
     convert(&args[1]);
     trigger(&args[1]);
     all(&args[1]);
+    strict(&args[1]);
+    skip_invalid(&args[1]);
+    compression(&args[1]);
 }
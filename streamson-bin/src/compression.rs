@@ -0,0 +1,87 @@
+use std::{
+    error::Error,
+    io::{self, stdin, stdout, Read, Stdout, Write},
+    str::FromStr,
+};
+
+use flate2::write::GzEncoder;
+use streamson_lib::input::FramedInput;
+
+/// Compression of the input or the output
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    /// Detected from the input data (input only)
+    Auto,
+}
+
+impl FromStr for Compression {
+    type Err = String;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "none" => Ok(Self::None),
+            "gzip" => Ok(Self::Gzip),
+            "zstd" => Ok(Self::Zstd),
+            "auto" => Ok(Self::Auto),
+            _ => Err(format!("Unknown compression '{}'", input)),
+        }
+    }
+}
+
+/// Wraps stdin into a decompressing reader
+pub fn input(compression: Compression) -> Result<Box<dyn Read>, Box<dyn Error>> {
+    Ok(match compression {
+        Compression::None => Box::new(FramedInput::new(stdin())),
+        Compression::Gzip => Box::new(FramedInput::gzip(stdin())),
+        Compression::Zstd => Box::new(FramedInput::zstd(stdin())?),
+        Compression::Auto => Box::new(FramedInput::auto(stdin())?),
+    })
+}
+
+/// Stdout which compresses the written data
+pub enum Writer {
+    Plain(Stdout),
+    Gzip(GzEncoder<Stdout>),
+    Zstd(zstd::stream::write::Encoder<'static, Stdout>),
+}
+
+impl Writer {
+    pub fn new(compression: Compression) -> io::Result<Self> {
+        Ok(match compression {
+            Compression::None | Compression::Auto => Self::Plain(stdout()),
+            Compression::Gzip => {
+                Self::Gzip(GzEncoder::new(stdout(), flate2::Compression::default()))
+            }
+            Compression::Zstd => Self::Zstd(zstd::stream::write::Encoder::new(stdout(), 0)?),
+        })
+    }
+
+    /// Writes the remaining compressed data
+    pub fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Plain(mut out) => out.flush(),
+            Self::Gzip(encoder) => encoder.finish()?.flush(),
+            Self::Zstd(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for Writer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(out) => out.write(buf),
+            Self::Gzip(encoder) => encoder.write(buf),
+            Self::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(out) => out.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+            Self::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
@@ -1,13 +1,17 @@
 use std::{
     error::Error,
-    io::{stdin, stdout, Read, Write},
+    io::Read,
     sync::{Arc, Mutex},
 };
 
-use clap::{App, ArgMatches};
-use streamson_lib::strategy::{self, Output, Strategy};
+use clap::{App, Arg, ArgMatches};
+use streamson_lib::{
+    strategy::{self, OutputConverter, Strategy},
+    streamer::InputFormat,
+};
 
 use crate::{
+    compression::Writer,
     docs::{strategies, Element},
     handlers, matchers,
 };
@@ -18,10 +22,27 @@ pub fn prepare_convert_subcommand() -> App<'static> {
         .about(strategies::Convert.description())
         .arg(matchers::matchers_arg())
         .arg(handlers::handlers_arg("convert"))
+        .arg(
+            Arg::new("flush_incomplete")
+                .about("Writes the converted data even when the input terminates in the middle of a JSON")
+                .long("flush-incomplete"),
+        )
 }
 
-pub fn process_convert(matches: &ArgMatches, buffer_size: usize) -> Result<(), Box<dyn Error>> {
+pub fn process_convert(
+    matches: &ArgMatches,
+    buffer_size: usize,
+    strict: bool,
+    skip_invalid: bool,
+    input_format: InputFormat,
+    input: &mut dyn Read,
+    out: &mut Writer,
+) -> Result<(), Box<dyn Error>> {
     let mut convert = strategy::Convert::new();
+    convert.set_strict(strict);
+    convert.set_skip_invalid(skip_invalid);
+    convert.set_input_format(input_format);
+    convert.set_flush_incomplete(matches.is_present("flush_incomplete"));
 
     let hndlrs = handlers::parse_handlers(matches, "convert")?;
     for (group, matcher) in matchers::parse_matchers(matches)? {
@@ -30,25 +51,21 @@ pub fn process_convert(matches: &ArgMatches, buffer_size: usize) -> Result<(), B
         }
     }
 
+    let mut converter = OutputConverter::new();
     let mut buffer = vec![];
-    while let Ok(size) = stdin().take(buffer_size as u64).read_to_end(&mut buffer) {
+    while let Ok(size) = (&mut *input)
+        .take(buffer_size as u64)
+        .read_to_end(&mut buffer)
+    {
         if size == 0 {
             break;
         }
-        for output in convert.process(&buffer[..size])? {
-            if let Output::Data(data) = output {
-                stdout().write_all(&data)?;
-            }
-        }
+        converter.write_all(&convert.process(&buffer[..size])?, out)?;
         buffer.clear();
     }
 
     // Input terminated try to hit strategy termination
-    for output in convert.terminate()? {
-        if let Output::Data(data) = output {
-            stdout().write_all(&data)?;
-        }
-    }
+    converter.write_all(&convert.terminate()?, out)?;
 
     Ok(())
 }
@@ -129,6 +129,16 @@ pub mod handlers {
     use lazy_static::lazy_static;
     use std::collections::HashMap;
 
+    create_doc_element!(
+        Aggregate,
+        "aggregate",
+        &["aggregate", "g"],
+        Some("[.group][:function]"),
+        "Reads entire JSON and writes aggregates of numbers\n\
+    grouped by path as JSON object to the output\n\
+    `function` - count, sum, min, max or avg (all of them if not set)\n\
+     Example: 'aggregate:sum'"
+    );
     create_doc_element!(
         Analyser,
         "analyser",
@@ -137,6 +147,15 @@ pub mod handlers {
         "Reads entire JSON and prints structure analysis to stderr\n\
     `group_types` - should distinguish between types as well"
     );
+    create_doc_element!(
+        AnalyserJson,
+        "analyser_json",
+        &["analyser_json", "j"],
+        Some("[.group][:group_types]"),
+        "Reads entire JSON and writes structure analysis\n\
+    as JSON object to the output\n\
+    `group_types` - should distinguish between types as well"
+    );
     create_doc_element!(
         File,
         "file",
@@ -156,6 +175,24 @@ pub mod handlers {
     otherwise it represents number of spaces in the output.\n\
     Example: 'indenter:2'"
     );
+    create_doc_element!(
+        Mask,
+        "mask",
+        &["mask", "m"],
+        Some("[.group][:letters,digits]"),
+        "Masks letters and digits of matched strings.\n\
+    `letters` - character which replaces letters (default 'X')\n\
+    `digits` - character which replaces digits (default '#')\n\
+    Example: 'mask:X,#'"
+    );
+    #[cfg(feature = "msgpack")]
+    create_doc_element!(
+        MsgPack,
+        "msgpack",
+        &["msgpack", "p"],
+        Some("[.group]"),
+        "Converts matched data to MessagePack."
+    );
     create_doc_element!(
         Regex,
         "regex",
@@ -164,6 +201,15 @@ pub mod handlers {
         "Uses sed regex to convert matched output.\n\
     Example: 'regex:s/user/User'"
     );
+    create_doc_element!(
+        Reorder,
+        "reorder",
+        &["reorder", "o"],
+        Some("[.group][:key1,key2,...]"),
+        "Re-orders keys of matched objects.\n\
+    Keys are emitted in the given order, other keys are appended.\n\
+    Example: 'reorder:id,name'"
+    );
     create_doc_element!(
         Replace,
         "replace",
@@ -174,6 +220,15 @@ pub mod handlers {
      going to be replaced.\n\
      Example: 'replace:null'"
     );
+    create_doc_element!(
+        Schema,
+        "schema",
+        &["schema", "c"],
+        Some("[.group][:per_json]"),
+        "Reads entire JSON and writes inferred\n\
+     JSON Schema (draft-07) to the output\n\
+     `per_json` - should emit a schema for each JSON"
+    );
     create_doc_element!(
         Shorten,
         "shorten",
@@ -185,6 +240,17 @@ pub mod handlers {
      chars will be inserted\n\
      Example: 'shorten:3,..\"'"
     );
+    #[cfg(feature = "sqlite")]
+    create_doc_element!(
+        Sqlite,
+        "sqlite",
+        &["sqlite", "q"],
+        Some("[.group]:database,table,column1,column2,..."),
+        "Inserts matched data into an SQLite table.\n\
+     Each matcher represents a column (in the order of matchers)\n\
+     and one row is inserted per matched group within a JSON.\n\
+     Example: 'sqlite:/tmp/users.db,users,id,name'"
+    );
     create_doc_element!(
         Unstringify,
         "unstringify",
@@ -193,17 +259,36 @@ pub mod handlers {
         "Expects a string on the input and\n\
      converts it to data e.g. '\"null\"' -> null"
     );
+    create_doc_element!(
+        Xml,
+        "xml",
+        &["xml", "l"],
+        Some("[.group][:root[,attributes]]"),
+        "Converts matched data to XML elements.\n\
+    Scalar object members can be stored as attributes.\n\
+     Example: 'xml:user,true'"
+    );
 
     lazy_static! {
         pub static ref MAP: HashMap<&'static str, &'static dyn Element> = {
             let mut res: HashMap<&'static str, &'static dyn Element> = HashMap::new();
+            res.insert(Aggregate.as_ref(), &Aggregate as &dyn Element);
             res.insert(Analyser.as_ref(), &Analyser as &dyn Element);
+            res.insert(AnalyserJson.as_ref(), &AnalyserJson as &dyn Element);
             res.insert(File.as_ref(), &File as &dyn Element);
             res.insert(Indenter.as_ref(), &Indenter as &dyn Element);
+            res.insert(Mask.as_ref(), &Mask as &dyn Element);
+            #[cfg(feature = "msgpack")]
+            res.insert(MsgPack.as_ref(), &MsgPack as &dyn Element);
             res.insert(Regex.as_ref(), &Regex as &dyn Element);
+            res.insert(Reorder.as_ref(), &Reorder as &dyn Element);
             res.insert(Replace.as_ref(), &Replace as &dyn Element);
+            res.insert(Schema.as_ref(), &Schema as &dyn Element);
             res.insert(Shorten.as_ref(), &Shorten as &dyn Element);
+            #[cfg(feature = "sqlite")]
+            res.insert(Sqlite.as_ref(), &Sqlite as &dyn Element);
             res.insert(Unstringify.as_ref(), &Unstringify as &dyn Element);
+            res.insert(Xml.as_ref(), &Xml as &dyn Element);
             res
         };
     }
@@ -222,11 +307,23 @@ pub mod matchers {
         "Matches data based on `definition`.\n\
     `[]` will match all items in array\n\
     `[1,3-5]` will match second, fourth to sixth item in array\n\
+    `[1:10:2]` will match every other item from the second to the tenth in array\n\
     `{}` will match any key in object\n\
+    `{!\"a\",\"b\"}` will match any key in object except `a` and `b`\n\
     `?` will match all items in dict or array\n\
+    `~` will match all scalar items (not dict nor array) in dict or array\n\
     `*` will match all items in dict or array 0 and times\n\
      Example: 'simple:{\"users\"}[]{\"name\"}'"
     );
+    create_doc_element!(
+        DeepKey,
+        "deep_key",
+        &["deep_key", "k"],
+        Some("[.group]:min_depth,key"),
+        "Matches data stored under `key` which are nested\n\
+    at least `min_depth` levels deep\n\
+     Example: 'deep_key:2,id'"
+    );
     create_doc_element!(
         Depth,
         "depth",
@@ -237,6 +334,24 @@ pub mod matchers {
     `to` max level to match (inclusive)\n\
      Example: 'depth:2-3'"
     );
+    create_doc_element!(
+        JsonPath,
+        "json_path",
+        &["json_path", "j"],
+        Some("[.group]:expression"),
+        "Matches data based on JSONPath `expression`\n\
+    (`$`, `.key`, `['key']`, `[*]`, `[1:10:2]` and `..` are supported)\n\
+     Example: 'json_path:$.users[*].name'"
+    );
+    create_doc_element!(
+        Parent,
+        "parent",
+        &["parent", "e"],
+        Some("[.group]:key"),
+        "Matches data which parent is stored under `key`\n\
+    (regardless of the index or key of the data itself)\n\
+     Example: 'parent:errors'"
+    );
     create_doc_element!(
         Regex,
         "regex",
@@ -246,13 +361,37 @@ pub mod matchers {
     (similar to simple matcher but uses regexes)\n\
      Example: 'regex:^\\{\"[Uu][Ss][Ee][Rr][Ss]\"\\}$'"
     );
+    create_doc_element!(
+        Value,
+        "value",
+        &["value", "l"],
+        Some("[.group]:path<op>value"),
+        "Matches scalars based on simple `path` and their content\n\
+    `==` and `!=` compare with a string, number, `true`, `false` or `null`\n\
+    `<`, `<=`, `>` and `>=` compare numbers\n\
+    `=~` matches strings using a regular expression\n\
+     Example: 'value:[]{\"status\"}==\"deleted\"'"
+    );
+    create_doc_element!(
+        ValueRegex,
+        "value_regex",
+        &["value_regex", "v"],
+        Some("[.group]:regex"),
+        "Matches strings whose (unquoted) value matches the regular expression\n\
+     Example: 'value_regex:ERROR|FATAL'"
+    );
 
     lazy_static! {
         pub static ref MAP: HashMap<&'static str, &'static dyn Element> = {
             let mut res: HashMap<&'static str, &'static dyn Element> = HashMap::new();
             res.insert(Simple.as_ref(), &Simple as &dyn Element);
             res.insert(Depth.as_ref(), &Depth as &dyn Element);
+            res.insert(DeepKey.as_ref(), &DeepKey as &dyn Element);
+            res.insert(Parent.as_ref(), &Parent as &dyn Element);
+            res.insert(JsonPath.as_ref(), &JsonPath as &dyn Element);
             res.insert(Regex.as_ref(), &Regex as &dyn Element);
+            res.insert(Value.as_ref(), &Value as &dyn Element);
+            res.insert(ValueRegex.as_ref(), &ValueRegex as &dyn Element);
             res
         };
     }
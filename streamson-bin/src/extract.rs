@@ -1,13 +1,17 @@
 use std::{
     error::Error,
-    io::{stdin, stdout, Read, Write},
+    io::{Read, Write},
     sync::{Arc, Mutex},
 };
 
 use clap::{App, Arg, ArgMatches};
-use streamson_lib::strategy::{self, Output, Strategy};
+use streamson_lib::{
+    strategy::{self, Output, Strategy},
+    streamer::InputFormat,
+};
 
 use crate::{
+    compression::Writer,
     docs::{strategies, Element},
     handlers, matchers,
 };
@@ -48,8 +52,19 @@ fn str_to_vec(input: &str) -> Vec<u8> {
     input.as_bytes().iter().copied().collect()
 }
 
-pub fn process_extract(matches: &ArgMatches, buffer_size: usize) -> Result<(), Box<dyn Error>> {
+pub fn process_extract(
+    matches: &ArgMatches,
+    buffer_size: usize,
+    strict: bool,
+    skip_invalid: bool,
+    input_format: InputFormat,
+    input: &mut dyn Read,
+    out: &mut Writer,
+) -> Result<(), Box<dyn Error>> {
     let mut extract = strategy::Extract::new();
+    extract.set_strict(strict);
+    extract.set_skip_invalid(skip_invalid);
+    extract.set_input_format(input_format);
 
     let separator = str_to_vec(matches.value_of("separator").unwrap_or(""));
     let before = str_to_vec(matches.value_of("before").unwrap_or(""));
@@ -70,10 +85,12 @@ pub fn process_extract(matches: &ArgMatches, buffer_size: usize) -> Result<(), B
 
     let mut buffer = vec![];
     let mut first = true;
-    let mut out = stdout();
 
     out.write_all(&before)?;
-    while let Ok(size) = stdin().take(buffer_size as u64).read_to_end(&mut buffer) {
+    while let Ok(size) = (&mut *input)
+        .take(buffer_size as u64)
+        .read_to_end(&mut buffer)
+    {
         if size == 0 {
             break;
         }
@@ -91,7 +108,7 @@ pub fn process_extract(matches: &ArgMatches, buffer_size: usize) -> Result<(), B
                 strategy::Output::Data(data) => {
                     out.write_all(&data)?;
                 }
-                strategy::Output::End => {}
+                strategy::Output::End | strategy::Output::Incomplete => {}
             }
         }
     }
@@ -99,7 +116,7 @@ pub fn process_extract(matches: &ArgMatches, buffer_size: usize) -> Result<(), B
     // Input terminated try to hit strategy termination
     for output in extract.terminate()? {
         if let Output::Data(data) = output {
-            stdout().write_all(&data)?;
+            out.write_all(&data)?;
         }
     }
 
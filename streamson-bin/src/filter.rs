@@ -1,13 +1,17 @@
 use std::{
     error::Error,
-    io::{stdin, stdout, Read, Write},
+    io::Read,
     sync::{Arc, Mutex},
 };
 
 use clap::{App, ArgMatches};
-use streamson_lib::strategy::{self, Output, Strategy};
+use streamson_lib::{
+    strategy::{self, OutputConverter, Strategy},
+    streamer::InputFormat,
+};
 
 use crate::{
+    compression::Writer,
     docs::{strategies, Element},
     handlers, matchers,
 };
@@ -20,8 +24,19 @@ pub fn prepare_filter_subcommand() -> App<'static> {
         .arg(handlers::handlers_arg("filter"))
 }
 
-pub fn process_filter(matches: &ArgMatches, buffer_size: usize) -> Result<(), Box<dyn Error>> {
+pub fn process_filter(
+    matches: &ArgMatches,
+    buffer_size: usize,
+    strict: bool,
+    skip_invalid: bool,
+    input_format: InputFormat,
+    input: &mut dyn Read,
+    out: &mut Writer,
+) -> Result<(), Box<dyn Error>> {
     let mut filter = strategy::Filter::new();
+    filter.set_strict(strict);
+    filter.set_skip_invalid(skip_invalid);
+    filter.set_input_format(input_format);
 
     let hndlrs = handlers::parse_handlers(matches, "filter")?;
 
@@ -36,26 +51,22 @@ pub fn process_filter(matches: &ArgMatches, buffer_size: usize) -> Result<(), Bo
         }
     }
 
+    let mut converter = OutputConverter::new();
     let mut buffer = vec![];
-    while let Ok(size) = stdin().take(buffer_size as u64).read_to_end(&mut buffer) {
+    while let Ok(size) = (&mut *input)
+        .take(buffer_size as u64)
+        .read_to_end(&mut buffer)
+    {
         if size == 0 {
             break;
         }
 
-        for output in filter.process(&buffer[..size])? {
-            if let Output::Data(data) = output {
-                stdout().write_all(&data)?;
-            }
-        }
+        converter.write_all(&filter.process(&buffer[..size])?, out)?;
         buffer.clear();
     }
 
     // Input terminated try to hit strategy termination
-    for output in filter.terminate()? {
-        if let Output::Data(data) = output {
-            stdout().write_all(&data)?;
-        }
-    }
+    converter.write_all(&filter.terminate()?, out)?;
 
     Ok(())
 }
@@ -52,13 +52,21 @@ pub fn parse_handlers(
 
 fn alias_to_handler_name(name_or_alias: &str) -> &str {
     match name_or_alias {
+        "g" | "aggregate" => "aggregate",
         "a" | "analyser" => "analyser",
+        "j" | "analyser_json" => "analyser_json",
         "f" | "file" => "file",
         "d" | "indenter" => "indenter",
+        "m" | "mask" => "mask",
+        "p" | "msgpack" => "msgpack",
         "x" | "regex" => "regex",
+        "o" | "reorder" => "reorder",
         "r" | "replace" => "replace",
+        "c" | "schema" => "schema",
         "s" | "shorten" => "shorten",
+        "q" | "sqlite" => "sqlite",
         "u" | "unstringify" => "unstringify",
+        "l" | "xml" => "xml",
         e => e,
     }
 }
@@ -84,6 +92,12 @@ pub fn make_handler(
     ));
 
     let inner: Arc<Mutex<dyn handler::Handler>> = match real_name {
+        "aggregate" => {
+            if !options.is_empty() {
+                return Err(wrong_number_of_options_error);
+            }
+            Arc::new(Mutex::new(handler::Aggregate::from_str(handler_string)?))
+        }
         "analyser" => {
             if !options.is_empty() {
                 return Err(wrong_number_of_options_error);
@@ -101,6 +115,18 @@ pub fn make_handler(
             })));
             Arc::new(Mutex::new(analyser))
         }
+        "analyser_json" => {
+            if !options.is_empty() {
+                return Err(wrong_number_of_options_error);
+            }
+            Arc::new(Mutex::new(handler::AnalyserJson::from_str(handler_string)?))
+        }
+        "schema" => {
+            if !options.is_empty() {
+                return Err(wrong_number_of_options_error);
+            }
+            Arc::new(Mutex::new(handler::SchemaInfer::from_str(handler_string)?))
+        }
         "file" => {
             if options.len() > 1 {
                 return Err(wrong_number_of_options_error);
@@ -113,18 +139,44 @@ pub fn make_handler(
             // print path option
             Arc::new(Mutex::new(handler))
         }
+        #[cfg(feature = "sqlite")]
+        "sqlite" => {
+            if !options.is_empty() {
+                return Err(wrong_number_of_options_error);
+            }
+            Arc::new(Mutex::new(handler::Sqlite::from_str(handler_string)?))
+        }
         "indenter" => {
             if !options.is_empty() {
                 return Err(wrong_number_of_options_error);
             }
             Arc::new(Mutex::new(handler::Indenter::from_str(handler_string)?))
         }
+        "mask" => {
+            if !options.is_empty() {
+                return Err(wrong_number_of_options_error);
+            }
+            Arc::new(Mutex::new(handler::Mask::from_str(handler_string)?))
+        }
+        #[cfg(feature = "msgpack")]
+        "msgpack" => {
+            if !options.is_empty() {
+                return Err(wrong_number_of_options_error);
+            }
+            Arc::new(Mutex::new(handler::MsgPack::new()))
+        }
         "regex" => {
             if !options.is_empty() {
                 return Err(wrong_number_of_options_error);
             }
             Arc::new(Mutex::new(handler::Regex::from_str(handler_string)?))
         }
+        "reorder" => {
+            if !options.is_empty() {
+                return Err(wrong_number_of_options_error);
+            }
+            Arc::new(Mutex::new(handler::ReorderKeys::from_str(handler_string)?))
+        }
         "replace" => {
             if !options.is_empty() {
                 return Err(wrong_number_of_options_error);
@@ -143,6 +195,12 @@ pub fn make_handler(
             }
             Arc::new(Mutex::new(handler::Unstringify::from_str(handler_string)?))
         }
+        "xml" => {
+            if !options.is_empty() {
+                return Err(wrong_number_of_options_error);
+            }
+            Arc::new(Mutex::new(handler::Xml::from_str(handler_string)?))
+        }
         _ => {
             return Err(error::Handler::new(format!(
                 "Unknown handler type {}",
@@ -1,4 +1,5 @@
 mod all;
+mod compression;
 mod convert;
 mod docs;
 mod extract;
@@ -18,9 +19,11 @@ use clap_generate::{
     Generator,
 };
 use lazy_static::lazy_static;
+use streamson_lib::streamer::InputFormat;
 
 use crate::{
     all::{prepare_all_subcommand, process_all},
+    compression::{Compression, Writer},
     convert::{prepare_convert_subcommand, process_convert},
     extract::{prepare_extract_subcommand, process_extract},
     filter::{prepare_filter_subcommand, process_filter},
@@ -49,6 +52,50 @@ fn prepare_app() -> App<'static> {
                 .default_value(&DEFAULT_BUFFER_SIZE_STRING)
                 .required(false),
         )
+        .arg(
+            Arg::new("strict")
+                .about("Rejects invalid JSON input (numbers, literals, trailing commas, escapes)")
+                .long("strict")
+                .takes_value(false)
+                .required(false),
+        )
+        .arg(
+            Arg::new("skip_invalid")
+                .about("Skips the lines with an invalid JSON (useful with ndjson input format)")
+                .long("skip-invalid")
+                .takes_value(false)
+                .required(false),
+        )
+        .arg(
+            Arg::new("input_format")
+                .about("Sets the format of the input (ndjson - each line is a separate JSON)")
+                .long("input-format")
+                .takes_value(true)
+                .possible_values(&["json", "ndjson"])
+                .value_name("INPUT_FORMAT")
+                .default_value("json")
+                .required(false),
+        )
+        .arg(
+            Arg::new("input_compression")
+                .about("Decompresses the input (auto - detected from the input data)")
+                .long("input-compression")
+                .takes_value(true)
+                .possible_values(&["none", "gzip", "zstd", "auto"])
+                .value_name("COMPRESSION")
+                .default_value("none")
+                .required(false),
+        )
+        .arg(
+            Arg::new("output_compression")
+                .about("Compresses the output")
+                .long("output-compression")
+                .takes_value(true)
+                .possible_values(&["none", "gzip", "zstd"])
+                .value_name("COMPRESSION")
+                .default_value("none")
+                .required(false),
+        )
         .subcommand(prepare_all_subcommand())
         .subcommand(prepare_convert_subcommand())
         .subcommand(prepare_extract_subcommand())
@@ -75,12 +122,39 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let arg_matches = app.clone().get_matches();
     let buffer_size: usize = arg_matches.value_of("buffer_size").unwrap().parse()?;
+    let strict = arg_matches.is_present("strict");
+    let skip_invalid = arg_matches.is_present("skip_invalid");
+    let input_format: InputFormat = arg_matches.value_of("input_format").unwrap().parse()?;
+    let input_compression: Compression =
+        arg_matches.value_of("input_compression").unwrap().parse()?;
+    let output_compression: Compression = arg_matches
+        .value_of("output_compression")
+        .unwrap()
+        .parse()?;
     match arg_matches.subcommand() {
-        Some(("all", matches)) => process_all(matches, buffer_size),
-        Some(("convert", matches)) => process_convert(matches, buffer_size),
-        Some(("extract", matches)) => process_extract(matches, buffer_size),
-        Some(("filter", matches)) => process_filter(matches, buffer_size),
-        Some(("trigger", matches)) => process_trigger(matches, buffer_size),
+        Some((name @ ("all" | "convert" | "extract" | "filter" | "trigger"), matches)) => {
+            let process = match name {
+                "all" => process_all,
+                "convert" => process_convert,
+                "extract" => process_extract,
+                "filter" => process_filter,
+                "trigger" => process_trigger,
+                _ => unreachable!(),
+            };
+            let mut input = compression::input(input_compression)?;
+            let mut output = Writer::new(output_compression)?;
+            process(
+                matches,
+                buffer_size,
+                strict,
+                skip_invalid,
+                input_format,
+                &mut input,
+                &mut output,
+            )?;
+            output.finish()?;
+            Ok(())
+        }
         Some(("completion", matches)) => match matches.value_of("shell") {
             Some("bash") => {
                 print_completions::<Bash>(&mut app);
@@ -47,9 +47,24 @@ pub fn make_matcher(
         "d" | "depth" => Ok(matcher::Combinator::new(matcher::Depth::from_str(
             matcher_string,
         )?)),
+        "j" | "json_path" => Ok(matcher::Combinator::new(matcher::JsonPath::from_str(
+            matcher_string,
+        )?)),
+        "k" | "deep_key" => Ok(matcher::Combinator::new(matcher::DeepKey::from_str(
+            matcher_string,
+        )?)),
+        "e" | "parent" => Ok(matcher::Combinator::new(matcher::ParentKey::from_str(
+            matcher_string,
+        )?)),
         "s" | "simple" => Ok(matcher::Combinator::new(matcher::Simple::from_str(
             matcher_string,
         )?)),
+        "l" | "value" => Ok(matcher::Combinator::new(matcher::Value::from_str(
+            matcher_string,
+        )?)),
+        "v" | "value_regex" => Ok(matcher::Combinator::new(matcher::ValueRegex::from_str(
+            matcher_string,
+        )?)),
         "x" | "regex" => Ok(matcher::Combinator::new(matcher::Regex::from_str(
             matcher_string,
         )?)),
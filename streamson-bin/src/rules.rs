@@ -4,13 +4,18 @@ pub fn handlers_for_strategy(strategy_name: &str) -> HashSet<&str> {
     let mut res = HashSet::new();
     match strategy_name {
         "all" => {
+            res.insert("aggregate");
             res.insert("analyser");
+            res.insert("analyser_json");
             res.insert("indenter");
+            res.insert("schema");
         }
         "extract" => {
             res.insert("file");
             // The rests makes sense only if extracted data are strings
+            res.insert("mask");
             res.insert("regex");
+            res.insert("reorder");
             res.insert("shorten");
             res.insert("unstringify");
         }
@@ -19,25 +24,36 @@ pub fn handlers_for_strategy(strategy_name: &str) -> HashSet<&str> {
             // file handler to create a sink for other handlers
             res.insert("file");
             // The rests makes sense only if extracted data are strings
+            res.insert("mask");
             res.insert("regex");
+            res.insert("reorder");
             res.insert("shorten");
             res.insert("unstringify");
         }
         "convert" => {
             res.insert("file");
             // The rests makes sense only if extracted data are strings
+            res.insert("mask");
+            #[cfg(feature = "msgpack")]
+            res.insert("msgpack");
             res.insert("regex");
+            res.insert("reorder");
             res.insert("replace");
             res.insert("shorten");
             res.insert("unstringify");
+            res.insert("xml");
         }
         "trigger" => {
             // Note that filter strategy should contain at least one
             // file handler to create a sink for other handlers
             res.insert("file");
             // The rests makes sense only if extracted data are strings
+            res.insert("mask");
             res.insert("regex");
+            res.insert("reorder");
             res.insert("shorten");
+            #[cfg(feature = "sqlite")]
+            res.insert("sqlite");
             res.insert("unstringify");
         }
         _ => unreachable!(),
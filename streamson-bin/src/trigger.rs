@@ -1,13 +1,17 @@
 use std::{
     error::Error,
-    io::{stdin, stdout, Read, Write},
+    io::{Read, Write},
     sync::{Arc, Mutex},
 };
 
 use clap::{App, ArgMatches};
-use streamson_lib::strategy::{self, Strategy};
+use streamson_lib::{
+    strategy::{self, Strategy},
+    streamer::InputFormat,
+};
 
 use crate::{
+    compression::Writer,
     docs::{strategies, Element},
     handlers, matchers,
 };
@@ -20,8 +24,19 @@ pub fn prepare_trigger_subcommand() -> App<'static> {
         .arg(handlers::handlers_arg("trigger"))
 }
 
-pub fn process_trigger(matches: &ArgMatches, buffer_size: usize) -> Result<(), Box<dyn Error>> {
+pub fn process_trigger(
+    matches: &ArgMatches,
+    buffer_size: usize,
+    strict: bool,
+    skip_invalid: bool,
+    input_format: InputFormat,
+    input: &mut dyn Read,
+    out: &mut Writer,
+) -> Result<(), Box<dyn Error>> {
     let mut trigger = strategy::Trigger::new();
+    trigger.set_strict(strict);
+    trigger.set_skip_invalid(skip_invalid);
+    trigger.set_input_format(input_format);
 
     let hndlrs = handlers::parse_handlers(matches, "trigger")?;
 
@@ -32,13 +47,16 @@ pub fn process_trigger(matches: &ArgMatches, buffer_size: usize) -> Result<(), B
     }
 
     let mut buffer = vec![];
-    while let Ok(size) = stdin().take(buffer_size as u64).read_to_end(&mut buffer) {
+    while let Ok(size) = (&mut *input)
+        .take(buffer_size as u64)
+        .read_to_end(&mut buffer)
+    {
         if size == 0 {
             break;
         }
         trigger.process(&buffer[..size])?;
         // forward input from stdin to stdout
-        stdout().write_all(&buffer[..size])?;
+        out.write_all(&buffer[..size])?;
         buffer.clear();
     }
     trigger.terminate()?;
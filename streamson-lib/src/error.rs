@@ -54,6 +54,16 @@ impl IncorrectInput {
     pub fn new(byte: u8, idx: usize) -> Self {
         Self { byte, idx }
     }
+
+    /// Incorrect byte
+    pub fn byte(&self) -> u8 {
+        self.byte
+    }
+
+    /// Total index of the incorrect byte
+    pub fn idx(&self) -> usize {
+        self.idx
+    }
 }
 
 impl Error for IncorrectInput {}
@@ -88,6 +98,44 @@ impl fmt::Display for InputTerminated {
     }
 }
 
+/// Configured limit of the input was exceeded
+#[derive(Debug, PartialEq, Clone)]
+pub struct LimitExceeded {
+    limit: String,
+    max: usize,
+    idx: usize,
+}
+
+impl LimitExceeded {
+    pub fn new<T>(limit: T, max: usize, idx: usize) -> Self
+    where
+        T: ToString,
+    {
+        Self {
+            limit: limit.to_string(),
+            max,
+            idx,
+        }
+    }
+
+    /// Total index where the limit was exceeded
+    pub fn idx(&self) -> usize {
+        self.idx
+    }
+}
+
+impl Error for LimitExceeded {}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Limit exceeded ({} is greater than {} on idx {})",
+            self.limit, self.max, self.idx
+        )
+    }
+}
+
 /// Path related error
 #[derive(Debug, PartialEq, Clone)]
 pub struct Path {
@@ -122,6 +170,7 @@ pub enum General {
     Utf8Error(Utf8Error),
     IncorrectInput(IncorrectInput),
     InputTerminated(InputTerminated),
+    LimitExceeded(LimitExceeded),
     IoError(io::Error),
 }
 
@@ -135,6 +184,7 @@ impl fmt::Display for General {
             Self::Utf8Error(err) => err.fmt(f),
             Self::IncorrectInput(err) => err.fmt(f),
             Self::InputTerminated(err) => err.fmt(f),
+            Self::LimitExceeded(err) => err.fmt(f),
             Self::IoError(err) => err.fmt(f),
         }
     }
@@ -156,4 +206,5 @@ impl_into_general!(Matcher, Self::Matcher);
 impl_into_general!(Utf8Error, Self::Utf8Error);
 impl_into_general!(IncorrectInput, Self::IncorrectInput);
 impl_into_general!(InputTerminated, Self::InputTerminated);
+impl_into_general!(LimitExceeded, Self::LimitExceeded);
 impl_into_general!(io::Error, Self::IoError);
@@ -0,0 +1,115 @@
+//! Extraction of a single value addressed by a JSON Pointer
+//!
+//! # Example
+//! ```
+//! let input = br#"{"users": [{"name": "first"}, {"name": "second"}]}"#;
+//! let value = streamson_lib::get(&input[..], "/users/1/name").unwrap();
+//! assert_eq!(value, Some(br#""second""#.to_vec()));
+//! ```
+
+use std::io::Read;
+
+use crate::{
+    error,
+    input::FramedInput,
+    matcher::Pointer,
+    strategy::{Extract, Output, Strategy},
+};
+
+/// Size of the chunks which are read from the reader
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Extracts the value at the given JSON Pointer
+///
+/// The reader is processed in chunks and the reading stops
+/// right after the first matching value is read, so the whole
+/// document doesn't need to be loaded into memory.
+///
+/// # Arguments
+/// * `reader` - input data
+/// * `pointer` - JSON Pointer (e.g. `/users/0/name`)
+///
+/// # Returns
+/// * `Ok(Some(value))` - raw data of the first matched value
+/// * `Ok(None)` - no value was found
+///
+/// # Errors
+/// If the pointer is invalid, the input can't be read or it is not a valid JSON.
+pub fn get<R: Read>(reader: R, pointer: &str) -> Result<Option<Vec<u8>>, error::General> {
+    let mut extract = Extract::new();
+    extract.add_matcher(Box::new(Pointer::new(pointer)?), None);
+
+    let mut value: Option<Vec<u8>> = None;
+    for chunk in FramedInput::new(reader).set_chunk_size(CHUNK_SIZE) {
+        for output in extract.process(&chunk?)? {
+            match output {
+                Output::Start(_) => value = Some(vec![]),
+                Output::Data(data) => {
+                    if let Some(value) = value.as_mut() {
+                        value.extend(data);
+                    }
+                }
+                Output::End => return Ok(value),
+                Output::Incomplete => return Ok(None),
+            }
+        }
+    }
+    extract.terminate()?;
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::get;
+    use std::io::{self, Read};
+
+    /// Reader which fails when it is read after the data are exhausted
+    struct Limited<'a> {
+        data: &'a [u8],
+    }
+
+    impl<'a> Read for Limited<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.data.is_empty() {
+                return Err(io::Error::other("read too far"));
+            }
+            let size = buf.len().min(self.data.len()).min(10);
+            buf[..size].copy_from_slice(&self.data[..size]);
+            self.data = &self.data[size..];
+            Ok(size)
+        }
+    }
+
+    #[test]
+    fn users() {
+        let input = format!(
+            r#"{{"meta": {{"count": 2}}, "users": [{{"name": "first", "tags": [1, 2]}}, {{"name": "second"}}], "rest": [{}]}}"#,
+            vec!["0"; 1000].join(", ")
+        );
+        assert_eq!(
+            get(input.as_bytes(), "/users/0/name").unwrap(),
+            Some(br#""first""#.to_vec())
+        );
+        assert_eq!(
+            get(input.as_bytes(), "/users/0/tags").unwrap(),
+            Some(b"[1, 2]".to_vec())
+        );
+        assert_eq!(get(input.as_bytes(), "/users/2/name").unwrap(), None);
+        assert_eq!(
+            get(&br#"{"a": 1}"#[..], "").unwrap(),
+            Some(br#"{"a": 1}"#.to_vec())
+        );
+        assert!(get(input.as_bytes(), "users").is_err());
+        assert!(get(&br#"{"a": "#[..], "/b").is_err());
+    }
+
+    #[test]
+    fn stops_early() {
+        // the data after the matched value are never read
+        let input = br#"{"users": [{"name": "first"}], "rest": "#;
+        assert_eq!(
+            get(Limited { data: &input[..] }, "/users/0/name").unwrap(),
+            Some(br#""first""#.to_vec())
+        );
+    }
+}
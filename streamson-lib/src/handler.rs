@@ -1,33 +1,113 @@
 //! Collections of handler (what to do with matched paths and data).
 //!
 
+pub mod aggregate;
 pub mod analyser;
+pub mod analyser_json;
+pub mod base64_to_file;
 pub mod buffer;
+pub mod case_keys;
+pub mod channel_sink;
+pub mod csv;
+pub mod dedup_by_key;
+pub mod dedup_per_document;
+pub mod envelope;
+pub mod error_collector;
 pub mod group;
+pub mod hash_sample;
+pub mod hyperloglog;
 pub mod indenter;
 pub mod indexer;
+#[cfg(feature = "with_serde")]
+pub mod json_patch;
+#[cfg(feature = "with_regex")]
+pub mod kind_regex_filter;
+pub mod kind_stream;
+pub mod mask;
+#[cfg(feature = "with_msgpack")]
+pub mod msgpack;
+pub mod ndjson_sink;
+pub mod object_wrap;
 pub mod output;
+pub mod pad;
+pub mod radix;
 #[cfg(feature = "with_regex")]
 pub mod regex;
+#[cfg(feature = "with_regex")]
+pub mod regex_replace;
+pub mod reorder_keys;
 pub mod replace;
+pub mod replace_by_kind;
+pub mod schema_infer;
+pub mod shard_by_key;
 pub mod shorten;
+#[cfg(feature = "with_sqlite")]
+pub mod sqlite;
+#[cfg(feature = "with_serde")]
+pub mod stable_hash;
+pub mod trim;
+pub mod tsv;
 pub mod unstringify;
+pub mod url_decode;
+pub mod xml;
+#[cfg(feature = "with_zstd")]
+pub mod zstd;
 
 use std::any::Any;
 
 use crate::{error, path::Path, streamer::Token};
 
+pub use self::aggregate::Aggregate;
 pub use self::analyser::Analyser;
+pub use self::analyser_json::AnalyserJson;
+pub use self::base64_to_file::Base64ToFile;
 pub use self::buffer::Buffer;
+pub use self::case_keys::CaseKeys;
+pub use self::channel_sink::ChannelSink;
+pub use self::csv::Csv;
+pub use self::dedup_by_key::DedupByKey;
+pub use self::dedup_per_document::DedupPerDocument;
+pub use self::envelope::Envelope;
+pub use self::error_collector::ErrorCollector;
 pub use self::group::Group;
+pub use self::hash_sample::HashSample;
+pub use self::hyperloglog::HyperLogLog;
 pub use self::indenter::Indenter;
 pub use self::indexer::Indexer;
+#[cfg(feature = "with_serde")]
+pub use self::json_patch::JsonPatch;
+#[cfg(feature = "with_regex")]
+pub use self::kind_regex_filter::KindRegexFilter;
+pub use self::kind_stream::KindStream;
+pub use self::mask::Mask;
+#[cfg(feature = "with_msgpack")]
+pub use self::msgpack::MsgPack;
+pub use self::ndjson_sink::NdjsonSink;
+pub use self::object_wrap::ObjectWrap;
 pub use self::output::Output;
+pub use self::pad::Pad;
+pub use self::radix::Radix;
 #[cfg(feature = "with_regex")]
 pub use self::regex::Regex;
+#[cfg(feature = "with_regex")]
+pub use self::regex_replace::RegexReplace;
+pub use self::reorder_keys::ReorderKeys;
 pub use self::replace::Replace;
+pub use self::replace_by_kind::ReplaceByKind;
+pub use self::schema_infer::SchemaInfer;
+pub use self::shard_by_key::ShardByKey;
 pub use self::shorten::Shorten;
+#[cfg(feature = "with_sqlite")]
+pub use self::sqlite::Sqlite;
+#[cfg(feature = "with_serde")]
+pub use self::stable_hash::StableHash;
+pub use self::trim::Trim;
+pub use self::tsv::Tsv;
 pub use self::unstringify::Unstringify;
+pub use self::url_decode::UrlDecode;
+pub use self::xml::Xml;
+#[cfg(feature = "with_zstd")]
+pub use self::zstd::Zstd;
 
 /// Shortcut to handler's output
 type HandlerOutput = Result<Option<Vec<u8>>, error::Handler>;
@@ -97,4 +177,13 @@ pub trait Handler: Send {
     fn input_finished(&mut self) -> HandlerOutput {
         Ok(None)
     }
+
+    /// Function which is supposed to be called when input terminates in the middle of a JSON
+    ///
+    /// It should return the data which were buffered within the handler
+    /// (e.g. sorted object entries) and reset the state of the unfinished element,
+    /// so that the converted data can be written on the best effort basis.
+    fn flush(&mut self) -> HandlerOutput {
+        Ok(None)
+    }
 }
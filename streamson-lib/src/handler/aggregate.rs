@@ -0,0 +1,390 @@
+//! Handler which aggregates matched numbers
+//!
+//! Numbers are grouped by the reduced path (e.g. `{"users"}[]{"age"}`)
+//! or by the index of the matcher. Count, sum, min, max and average
+//! are maintained for each group, other values are ignored.
+//!
+//! The results are written into the output as an object `{"<group>": value}`
+//! once the input is finished (or after each JSON when `per_json` is set).
+//! When no function is selected, all the aggregates are written
+//! (e.g. `{"count":2,"sum":3,"min":1,"max":2,"avg":1.5}`).
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(
+//!     handler::Aggregate::new().set_function(Some(handler::aggregate::Function::Sum))
+//! ));
+//!
+//! let mut all = strategy::All::new();
+//! all.set_convert(true);
+//! all.add_handler(handler.clone());
+//!
+//! all.process(br#"{"users": [{"age": 30}, {"age": 12}]}"#).unwrap();
+//!
+//! let results = handler.lock().unwrap().results();
+//! assert_eq!(results[0].0, r#"{"users"}[]{"age"}"#);
+//! assert_eq!(results[0].1.sum, 42.0);
+//!
+//! // writes `{"{\"users\"}[]{\"age\"}":42}` and resets the aggregates
+//! for output in all.terminate().unwrap() {
+//!     println!("{:?}", output);
+//! }
+//! ```
+
+use std::{any::Any, collections::HashMap, str, str::FromStr};
+
+use super::{analyser::to_recuded_array_str, Handler, HandlerOutput};
+use crate::{
+    error,
+    path::Path,
+    streamer::{escape, ParsedKind, Token},
+};
+
+/// Aggregate function which is written to the output
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Function {
+    Count,
+    Sum,
+    Min,
+    Max,
+    Avg,
+}
+
+impl AsRef<str> for Function {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Count => "count",
+            Self::Sum => "sum",
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Avg => "avg",
+        }
+    }
+}
+
+impl FromStr for Function {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "count" => Ok(Self::Count),
+            "sum" => Ok(Self::Sum),
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            "avg" => Ok(Self::Avg),
+            _ => Err(error::Handler::new(format!(
+                "Unknown aggregate function '{}'",
+                input
+            ))),
+        }
+    }
+}
+
+/// Aggregates of a single group
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aggregated {
+    /// Number of values
+    pub count: usize,
+    /// Sum of the values
+    pub sum: f64,
+    /// The lowest value
+    pub min: f64,
+    /// The highest value
+    pub max: f64,
+}
+
+impl Default for Aggregated {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            sum: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl Aggregated {
+    /// Adds a value
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    /// Returns the average of the values
+    pub fn avg(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+
+    /// Returns the result of the function
+    ///
+    /// # Arguments
+    /// * `function` - aggregate function
+    pub fn get(&self, function: Function) -> f64 {
+        match function {
+            Function::Count => self.count as f64,
+            Function::Sum => self.sum,
+            Function::Min => self.min,
+            Function::Max => self.max,
+            Function::Avg => self.avg(),
+        }
+    }
+}
+
+/// Handler which aggregates matched numbers
+#[derive(Debug, Default)]
+pub struct Aggregate {
+    /// Aggregates of the groups
+    groups: HashMap<String, Aggregated>,
+    /// Function written to the output (all of them if not set)
+    function: Option<Function>,
+    /// Group by matcher index instead of the reduced path
+    by_matcher: bool,
+    /// Emit (and reset) the aggregates after each JSON
+    per_json: bool,
+    /// Data of currently matched numbers (by matcher index)
+    buffers: HashMap<usize, Vec<u8>>,
+}
+
+impl Aggregate {
+    /// Creates a new handler which aggregates numbers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the function which is written to the output
+    ///
+    /// # Arguments
+    /// * `function` - aggregate function (`None` means all of them)
+    pub fn set_function(mut self, function: Option<Function>) -> Self {
+        self.function = function;
+        self
+    }
+
+    /// Sets whether the values are grouped by matcher index
+    ///
+    /// # Arguments
+    /// * `by_matcher` - group by matcher index instead of the reduced path
+    pub fn set_by_matcher(mut self, by_matcher: bool) -> Self {
+        self.by_matcher = by_matcher;
+        self
+    }
+
+    /// Sets whether the aggregates should be emitted after each JSON
+    pub fn set_per_json(mut self, per_json: bool) -> Self {
+        self.per_json = per_json;
+        self
+    }
+
+    /// Results of the aggregation sorted by the group
+    pub fn results(&self) -> Vec<(String, Aggregated)> {
+        let mut res: Vec<(String, Aggregated)> = self
+            .groups
+            .iter()
+            .map(|(group, aggregated)| (group.clone(), *aggregated))
+            .collect();
+        res.sort_by(|(a_group, _), (b_group, _)| a_group.cmp(b_group));
+        res
+    }
+
+    /// Serializes current results and resets the aggregation
+    fn emit(&mut self) -> Vec<u8> {
+        let items: Vec<String> = self
+            .results()
+            .into_iter()
+            .map(|(group, aggregated)| {
+                let value = match self.function {
+                    Some(function) => aggregated.get(function).to_string(),
+                    None => format!(
+                        r#"{{"count":{},"sum":{},"min":{},"max":{},"avg":{}}}"#,
+                        aggregated.count,
+                        aggregated.sum,
+                        aggregated.min,
+                        aggregated.max,
+                        aggregated.avg()
+                    ),
+                };
+                format!(r#""{}":{}"#, escape(&group), value)
+            })
+            .collect();
+        self.groups.clear();
+        format!("{{{}}}", items.join(",")).into_bytes()
+    }
+}
+
+impl Handler for Aggregate {
+    fn start(&mut self, _path: &Path, matcher_idx: usize, token: Token) -> HandlerOutput {
+        if let Token::Start(_, ParsedKind::Num | ParsedKind::Int | ParsedKind::Float) = token {
+            self.buffers.insert(matcher_idx, vec![]);
+        }
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], matcher_idx: usize) -> HandlerOutput {
+        if let Some(buffer) = self.buffers.get_mut(&matcher_idx) {
+            buffer.extend(data);
+        }
+        Ok(None)
+    }
+
+    fn end(&mut self, path: &Path, matcher_idx: usize, _token: Token) -> HandlerOutput {
+        let data = match self.buffers.remove(&matcher_idx) {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+        let value: f64 = str::from_utf8(&data)
+            .ok()
+            .and_then(|e| e.trim().parse().ok())
+            .ok_or_else(|| error::Handler::new("Failed to parse matched number"))?;
+        let group = if self.by_matcher {
+            matcher_idx.to_string()
+        } else {
+            to_recuded_array_str(path, None)
+        };
+        self.groups.entry(group).or_default().add(value);
+        Ok(None)
+    }
+
+    fn input_finished(&mut self) -> HandlerOutput {
+        if self.per_json {
+            Ok(None)
+        } else {
+            Ok(Some(self.emit()))
+        }
+    }
+
+    fn json_finished(&mut self) -> HandlerOutput {
+        if self.per_json {
+            Ok(Some(self.emit()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl FromStr for Aggregate {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() {
+            Ok(Self::default())
+        } else {
+            Ok(Self::default().set_function(Some(input.parse()?)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Aggregate, Aggregated, Function};
+    use crate::{
+        matcher::Simple,
+        strategy::{All, Output, Strategy, Trigger},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    fn aggregate(handler: Aggregate, inputs: &[&[u8]]) -> String {
+        let mut all = All::new();
+        all.set_convert(true);
+        all.add_handler(Arc::new(Mutex::new(handler)));
+
+        let mut output = vec![];
+        for input in inputs {
+            output.extend(all.process(input).unwrap());
+        }
+        output.extend(all.terminate().unwrap());
+        let data: Vec<u8> = output
+            .into_iter()
+            .flat_map(|e| match e {
+                Output::Data(data) => data,
+                _ => vec![],
+            })
+            .collect();
+        String::from_utf8(data).unwrap()
+    }
+
+    #[test]
+    fn functions() {
+        let input: &[&[u8]] = &[br#"{"a": [1, 2.5], "b": {"c": -"#, br#"3e1}, "d": "4"}"#];
+        assert_eq!(
+            aggregate(Aggregate::from_str("sum").unwrap(), input),
+            r#"{"{\"a\"}[]":3.5,"{\"b\"}{\"c\"}":-30}"#
+        );
+        assert_eq!(
+            aggregate(Aggregate::from_str("count").unwrap(), input),
+            r#"{"{\"a\"}[]":2,"{\"b\"}{\"c\"}":1}"#
+        );
+        assert_eq!(
+            aggregate(Aggregate::from_str("min").unwrap(), input),
+            r#"{"{\"a\"}[]":1,"{\"b\"}{\"c\"}":-30}"#
+        );
+        assert_eq!(
+            aggregate(Aggregate::from_str("max").unwrap(), input),
+            r#"{"{\"a\"}[]":2.5,"{\"b\"}{\"c\"}":-30}"#
+        );
+        assert_eq!(
+            aggregate(Aggregate::from_str("avg").unwrap(), input),
+            r#"{"{\"a\"}[]":1.75,"{\"b\"}{\"c\"}":-30}"#
+        );
+        assert_eq!(
+            aggregate(Aggregate::new(), &[b"[1, 3]"]),
+            r#"{"[]":{"count":2,"sum":4,"min":1,"max":3,"avg":2}}"#
+        );
+        assert!(Aggregate::from_str("median").is_err());
+    }
+
+    #[test]
+    fn per_json() {
+        assert_eq!(
+            aggregate(
+                Aggregate::from_str("sum").unwrap().set_per_json(true),
+                &[b"[1, 2] [3] {}"]
+            ),
+            r#"{"[]":3}{"[]":3}{}"#
+        );
+    }
+
+    #[test]
+    fn by_matcher() {
+        let handler = Arc::new(Mutex::new(Aggregate::new().set_by_matcher(true)));
+        let mut trigger = Trigger::new();
+        trigger.add_matcher(
+            Box::new(Simple::new(r#"{"prices"}[]"#).unwrap()),
+            handler.clone(),
+        );
+        trigger.add_matcher(Box::new(Simple::new(r#"{}"#).unwrap()), handler.clone());
+        trigger
+            .process(br#"{"prices": [10, 20, "x"], "total": 30}"#)
+            .unwrap();
+
+        let guard = handler.lock().unwrap();
+        let results = guard.results();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, "0");
+        assert_eq!(
+            results[0].1,
+            Aggregated {
+                count: 2,
+                sum: 30.0,
+                min: 10.0,
+                max: 20.0
+            }
+        );
+        assert_eq!(results[1].0, "1");
+        assert_eq!(results[1].1.get(Function::Avg), 30.0);
+    }
+}
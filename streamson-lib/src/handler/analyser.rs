@@ -33,7 +33,7 @@ impl Default for Analyser {
 
 /// Converts Path to string reducing arrays to "[]"
 /// e.g. {"users"}[0]{"name"} => {"users"}[]{"name"}
-fn to_recuded_array_str(path: &Path, kind: Option<ParsedKind>) -> String {
+pub(super) fn to_recuded_array_str(path: &Path, kind: Option<ParsedKind>) -> String {
     let mut res: String = path
         .get_path()
         .iter()
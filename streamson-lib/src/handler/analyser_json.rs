@@ -0,0 +1,190 @@
+//! Handler which emits the analysis of matched paths as JSON
+//!
+//! It works the same way as `Analyser`, but the results are
+//! written into the output as an object `{"<reduced path>": count}`
+//! once the input is finished (or after each JSON when `per_json` is set).
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(handler::AnalyserJson::new()));
+//!
+//! let mut all = strategy::All::new();
+//! all.set_convert(true);
+//! all.add_handler(handler);
+//!
+//! all.process(br#"{"users": [{"name": "first"}, {"name": "second"}]}"#).unwrap();
+//! for output in all.terminate().unwrap() {
+//!     println!("{:?}", output);
+//! }
+//! ```
+
+use std::{any::Any, str::FromStr};
+
+use super::{Analyser, Handler, HandlerOutput};
+use crate::{
+    error,
+    path::Path,
+    streamer::{escape, Token},
+};
+
+/// Handler which serializes path analysis into JSON
+pub struct AnalyserJson {
+    /// Analyser which collects the paths
+    analyser: Analyser,
+    /// Group by types as well
+    group_types: bool,
+    /// Emit (and reset) the analysis after each JSON
+    per_json: bool,
+}
+
+impl Default for AnalyserJson {
+    fn default() -> Self {
+        Self {
+            analyser: Analyser::new(),
+            group_types: false,
+            per_json: false,
+        }
+    }
+}
+
+impl AnalyserJson {
+    /// Creates a new handler which emits analysis as JSON
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets group types option
+    pub fn set_group_types(mut self, group_types: bool) -> Self {
+        self.group_types = group_types;
+        self.analyser = Analyser::new().set_group_types(group_types);
+        self
+    }
+
+    /// Sets whether the analysis should be emitted after each JSON
+    pub fn set_per_json(mut self, per_json: bool) -> Self {
+        self.per_json = per_json;
+        self
+    }
+
+    /// Serializes current results and resets the analysis
+    fn emit(&mut self) -> Vec<u8> {
+        let items: Vec<String> = self
+            .analyser
+            .results()
+            .into_iter()
+            .map(|(path, count)| format!(r#""{}":{}"#, escape(&path), count))
+            .collect();
+        self.analyser = Analyser::new().set_group_types(self.group_types);
+        format!("{{{}}}", items.join(",")).into_bytes()
+    }
+}
+
+impl Handler for AnalyserJson {
+    fn start(&mut self, path: &Path, matcher_idx: usize, token: Token) -> HandlerOutput {
+        self.analyser.start(path, matcher_idx, token)
+    }
+
+    fn input_finished(&mut self) -> HandlerOutput {
+        if self.per_json {
+            Ok(None)
+        } else {
+            Ok(Some(self.emit()))
+        }
+    }
+
+    fn json_finished(&mut self) -> HandlerOutput {
+        if self.per_json {
+            Ok(Some(self.emit()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl FromStr for AnalyserJson {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() {
+            Ok(Self::default())
+        } else {
+            let group_types: bool =
+                bool::from_str(input).map_err(|e| Self::Err::new(e.to_string()))?;
+            Ok(Self::default().set_group_types(group_types))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AnalyserJson;
+    use crate::strategy::{All, Output, Strategy};
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    fn analyse(handler: AnalyserJson, inputs: &[&[u8]]) -> String {
+        let mut all = All::new();
+        all.set_convert(true);
+        all.add_handler(Arc::new(Mutex::new(handler)));
+
+        let mut output = vec![];
+        for input in inputs {
+            output.extend(all.process(input).unwrap());
+        }
+        // analysis is emitted outside of any JSON
+        output.extend(all.terminate().unwrap());
+        let data: Vec<u8> = output
+            .into_iter()
+            .flat_map(|e| match e {
+                Output::Data(data) => data,
+                _ => vec![],
+            })
+            .collect();
+        String::from_utf8(data).unwrap()
+    }
+
+    #[test]
+    fn input_finished() {
+        let output = analyse(
+            AnalyserJson::new(),
+            &[
+                br#"{"users": [{"name": "first"}, {"na"#,
+                br#"me": "second"}]}"#,
+            ],
+        );
+        assert_eq!(
+            output,
+            r#"{"":1,"{\"users\"}":1,"{\"users\"}[]":2,"{\"users\"}[]{\"name\"}":2}"#
+        );
+    }
+
+    #[test]
+    fn per_json_with_types() {
+        let output = analyse(
+            AnalyserJson::from_str("true").unwrap().set_per_json(true),
+            &[br#"[1, "2"] [null]"#],
+        );
+        assert_eq!(
+            output,
+            r#"{"<array>":1,"[]<number>":1,"[]<string>":1}{"<array>":1,"[]<null>":1}"#
+        );
+    }
+
+    #[test]
+    fn escaped() {
+        let output = analyse(AnalyserJson::new(), &[br#"{"a\"b\\": 1}"#]);
+        assert_eq!(output, r#"{"":1,"{\"a\\\"b\\\\\"}":1}"#);
+    }
+}
@@ -0,0 +1,266 @@
+//! Handler which decodes matched base64 strings into files
+//! `"aGVsbG8="` -> `"/tmp/blob-0.bin"` (file contains `hello`)
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::{env, sync::{Arc, Mutex}};
+//!
+//! let handler = Arc::new(Mutex::new(handler::Base64ToFile::new(env::temp_dir(), "image-{}.png")));
+//! let matcher = matcher::Simple::new(r#"{"images"}[]{"data"}"#).unwrap();
+//!
+//! let mut convert = strategy::Convert::new();
+//!
+//! // Set the matcher for convert strategy
+//! convert.add_matcher(Box::new(matcher), handler);
+//!
+//! for input in vec![
+//!     br#"{"images": [{"data": "iVBORw0KGgo="}, "#.to_vec(),
+//!     br#"{"data": "iVBORw0KGgo="}]}"#.to_vec(),
+//! ] {
+//!     for converted_data in convert.process(&input).unwrap() {
+//!         println!("{:?}", converted_data);
+//!     }
+//! }
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    path::Path,
+    streamer::{escape, unescape, ParsedKind, Token},
+};
+use std::{any::Any, fs, mem, path::PathBuf, str::FromStr};
+
+/// Placeholder in the name template which is replaced by the index of the file
+const PLACEHOLDER: &str = "{}";
+
+/// Handler which decodes matched base64 strings and writes them to files
+///
+/// Each matched string is stored to a new file within `dir` and
+/// it is replaced by the path of the file in the output.
+/// The file name is created from `name_template` where `{}` is replaced
+/// by the index of the match (starting from 0).
+///
+/// Both standard and url-safe alphabets are accepted, padding is optional
+/// and whitespace is ignored.
+#[derive(Debug)]
+pub struct Base64ToFile {
+    /// Directory where the files are stored
+    dir: PathBuf,
+    /// Template of the file name
+    name_template: String,
+    /// Index of the next file
+    idx: usize,
+    /// Kind of the currently matched data
+    kind: Option<ParsedKind>,
+    /// Matched data
+    buffer: Vec<u8>,
+}
+
+impl Base64ToFile {
+    /// Creates a new handler which decodes base64 strings into files
+    ///
+    /// # Arguments
+    /// * `dir` - directory where the files will be stored
+    /// * `name_template` - file name template (`{}` is replaced by the index)
+    pub fn new<D, T>(dir: D, name_template: T) -> Self
+    where
+        D: Into<PathBuf>,
+        T: ToString,
+    {
+        Self {
+            dir: dir.into(),
+            name_template: name_template.to_string(),
+            idx: 0,
+            kind: None,
+            buffer: vec![],
+        }
+    }
+
+    /// Returns the path of the next file
+    fn next_path(&mut self) -> PathBuf {
+        let name = self
+            .name_template
+            .replace(PLACEHOLDER, &self.idx.to_string());
+        self.idx += 1;
+        self.dir.join(name)
+    }
+}
+
+impl FromStr for Base64ToFile {
+    type Err = error::Handler;
+    /// Parses `dir,name_template` (e.g. `/tmp,blob-{}.bin`)
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let splitted: Vec<_> = input.splitn(2, ',').collect();
+        if splitted.len() == 2 && splitted[1].contains(PLACEHOLDER) {
+            Ok(Self::new(splitted[0], splitted[1]))
+        } else {
+            Err(error::Handler::new("Failed to parse"))
+        }
+    }
+}
+
+/// Returns the value of a base64 character
+fn base64_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'A'..=b'Z' => Some(byte - b'A'),
+        b'a'..=b'z' => Some(byte - b'a' + 26),
+        b'0'..=b'9' => Some(byte - b'0' + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    }
+}
+
+/// Decodes base64 encoded data
+///
+/// # Returns
+/// * `None` if the data are not valid base64
+fn base64_decode(input: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    let mut padding = 0;
+    for byte in input.bytes().filter(|byte| !byte.is_ascii_whitespace()) {
+        if byte == b'=' {
+            padding += 1;
+            continue;
+        }
+        if padding > 0 {
+            // data after padding
+            return None;
+        }
+        acc = (acc << 6) | base64_value(byte)? as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    // 6 remaining bits can't form a byte
+    if bits == 6 || padding > 2 {
+        None
+    } else {
+        Some(output)
+    }
+}
+
+impl Handler for Base64ToFile {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, token: Token) -> HandlerOutput {
+        if let Token::Start(_, kind) = token {
+            self.kind = Some(kind);
+        }
+        self.buffer.clear();
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        let data = mem::take(&mut self.buffer);
+        if self.kind.take() != Some(ParsedKind::Str) || data.len() < 2 {
+            return Err(error::Handler::new("Matched data is not a string"));
+        }
+        let decoded = unescape(&data[1..data.len() - 1])
+            .and_then(|string| base64_decode(&string))
+            .ok_or_else(|| error::Handler::new("Matched string is not valid base64"))?;
+
+        let path = self.next_path();
+        fs::write(&path, decoded).map_err(error::Handler::new)?;
+
+        let path = path.to_string_lossy();
+        Ok(Some(format!(r#""{}""#, escape(&path)).into_bytes()))
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{base64_decode, Base64ToFile};
+    use crate::{
+        matcher::Simple,
+        strategy::{Convert, OutputConverter, Strategy},
+    };
+    use std::{
+        fs,
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+    use tempfile::tempdir;
+
+    #[test]
+    fn decode() {
+        assert_eq!(base64_decode("").unwrap(), b"");
+        assert_eq!(base64_decode("aGk=").unwrap(), b"hi");
+        assert_eq!(base64_decode("aGk").unwrap(), b"hi");
+        assert_eq!(base64_decode("aGVs\nbG8=").unwrap(), b"hello");
+        assert_eq!(base64_decode("-_8=").unwrap(), vec![0xfb, 0xff]);
+        assert!(base64_decode("a").is_none());
+        assert!(base64_decode("aG!k").is_none());
+        assert!(base64_decode("aG=k").is_none());
+    }
+
+    #[test]
+    fn files() {
+        let dir = tempdir().unwrap();
+        let mut convert = Convert::new();
+        convert.add_matcher(
+            Box::new(Simple::new(r#"[]{"data"}"#).unwrap()),
+            Arc::new(Mutex::new(Base64ToFile::new(dir.path(), "blob-{}.bin"))),
+        );
+
+        let input = br#"[{"data": "aGVsbG8="}, {"data": "AAEC/w==", "id": 2}]"#;
+        let mut output = vec![];
+        let mut converter = OutputConverter::new();
+        for part in input.chunks(5) {
+            for (_, data) in converter.convert(&convert.process(part).unwrap()) {
+                output.extend(data);
+            }
+        }
+
+        let first = dir.path().join("blob-0.bin");
+        let second = dir.path().join("blob-1.bin");
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!(
+                r#"[{{"data": "{}"}}, {{"data": "{}", "id": 2}}]"#,
+                first.to_str().unwrap(),
+                second.to_str().unwrap()
+            )
+        );
+        assert_eq!(fs::read(first).unwrap(), b"hello");
+        assert_eq!(fs::read(second).unwrap(), vec![0, 1, 2, 255]);
+    }
+
+    #[test]
+    fn invalid() {
+        let dir = tempdir().unwrap();
+        for input in &[&br#"[1]"#[..], br#"["a"]"#] {
+            let mut convert = Convert::new();
+            convert.add_matcher(
+                Box::new(Simple::new(r#"[]"#).unwrap()),
+                Arc::new(Mutex::new(Base64ToFile::new(dir.path(), "{}"))),
+            );
+            assert!(convert.process(input).is_err());
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert!(Base64ToFile::from_str("/tmp,blob-{}.bin").is_ok());
+        assert!(Base64ToFile::from_str("/tmp,blob.bin").is_err());
+        assert!(Base64ToFile::from_str("/tmp").is_err());
+    }
+}
@@ -28,8 +28,13 @@
 //! ```
 
 use super::{Handler, HandlerOutput};
-use crate::{error, path::Path, streamer::Token};
-use std::{any::Any, collections::VecDeque, str::FromStr};
+use crate::{
+    error,
+    path::Path,
+    strategy::{Metrics, Stats},
+    streamer::Token,
+};
+use std::{any::Any, collections::VecDeque, iter, str::FromStr};
 
 /// Buffer handler responsible for storing slitted JSONs into memory
 pub struct Buffer {
@@ -51,6 +56,8 @@ pub struct Buffer {
     input_finished_callback: Option<Box<dyn FnMut(&mut Self) + Send>>,
     /// Callback which is triggered entire JSON is processed from input
     json_finished_callback: Option<Box<dyn FnMut(&mut Self) + Send>>,
+    /// Collected statistics (only the peak of buffered bytes is used)
+    stats: Stats,
 }
 
 impl Default for Buffer {
@@ -65,6 +72,7 @@ impl Default for Buffer {
             results: VecDeque::new(),
             input_finished_callback: None,
             json_finished_callback: None,
+            stats: Stats::default(),
         }
     }
 }
@@ -87,7 +95,7 @@ impl FromStr for Buffer {
     }
 }
 
-trait Buff: Handler {
+pub(crate) trait Buff: Handler {
     fn _start(
         &mut self,
         _path: &Path,
@@ -163,7 +171,9 @@ impl Handler for Buffer {
     }
 
     fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
-        self._feed(data, _matcher_idx)
+        let output = self._feed(data, _matcher_idx)?;
+        self.stats.update_peak(self.current_buffer_size);
+        Ok(output)
     }
 
     fn end(&mut self, _path: &Path, _matcher_idx: usize, token: Token) -> HandlerOutput {
@@ -279,6 +289,18 @@ impl Buffer {
         popped
     }
 
+    /// Returns an iterator which pops the stored data until the buffer is empty
+    ///
+    /// # Example
+    /// ```
+    /// use streamson_lib::handler;
+    /// let mut buffer = handler::buffer::Buffer::new().set_use_path(true);
+    /// let data: Vec<Vec<u8>> = buffer.drain_iter().map(|(_, data)| data).collect();
+    /// ```
+    pub fn drain_iter(&mut self) -> impl Iterator<Item = (Option<String>, Vec<u8>)> + '_ {
+        iter::from_fn(move || self.pop())
+    }
+
     /// Sets max buffer size
     ///
     /// # Arguments
@@ -304,6 +326,23 @@ impl Buffer {
         self.json_finished_callback = callback;
     }
 
+    /// Drops the data of the unfinished matches
+    ///
+    /// Stored results are kept.
+    /// It should be used when the strategy recovers from an error.
+    pub fn clear_unfinished(&mut self) {
+        self.buffer.clear();
+        self.buffer_parts.clear();
+        self.current_buffer_size = self.results.iter().fold(0, |e, y| e + y.1.len());
+    }
+
+    /// Returns metrics collected during the processing
+    ///
+    /// Buffered bytes include both stored and unfinished data.
+    pub fn metrics(&self) -> Metrics {
+        Metrics::from(&self.stats)
+    }
+
     /// returns how much items are currently present in the buffer
     pub fn len(&self) -> usize {
         self.results.len()
@@ -386,6 +425,30 @@ mod tests {
         assert_eq!(guard.pop(), None);
     }
 
+    #[test]
+    fn drain_iter() {
+        let mut trigger = Trigger::new();
+        let buffer_handler = Arc::new(Mutex::new(Buffer::new().set_use_path(true)));
+        let matcher = Simple::new(r#"{"users"}[]"#).unwrap();
+
+        trigger.add_matcher(Box::new(matcher), buffer_handler.clone());
+        assert!(trigger.process(br#"{"users": ["first", "sec"#).is_ok());
+        assert!(trigger.process(br#"ond", "third"]}"#).is_ok());
+
+        let mut guard = buffer_handler.lock().unwrap();
+        let data: Vec<(Option<String>, Vec<u8>)> = guard.drain_iter().collect();
+        assert_eq!(
+            data,
+            vec![
+                (Some(r#"{"users"}[0]"#.into()), br#""first""#.to_vec()),
+                (Some(r#"{"users"}[1]"#.into()), br#""second""#.to_vec()),
+                (Some(r#"{"users"}[2]"#.into()), br#""third""#.to_vec()),
+            ]
+        );
+        assert!(guard.is_empty());
+        assert_eq!(guard.drain_iter().count(), 0);
+    }
+
     #[test]
     fn callbacks_convert() {
         let mut convert = Convert::new();
@@ -515,6 +578,7 @@ mod tests {
         assert!(filter.process(br#"{"nested": ["1"]}"#).is_ok());
         assert!(filter.terminate().is_ok());
 
+        // nested matches are buffered as well
         let json_data: Vec<usize> = json_callbacks_data
             .lock()
             .unwrap()
@@ -522,9 +586,9 @@ mod tests {
             .copied()
             .collect();
         assert_eq!(json_data.len(), 3);
-        assert_eq!(json_data[0], 1);
-        assert_eq!(json_data[1], 2);
-        assert_eq!(json_data[2], 3);
+        assert_eq!(json_data[0], 2);
+        assert_eq!(json_data[1], 4);
+        assert_eq!(json_data[2], 5);
 
         let terminate_data: Vec<usize> = terminate_callbacks_data
             .lock()
@@ -533,7 +597,7 @@ mod tests {
             .copied()
             .collect();
         assert_eq!(terminate_data.len(), 1);
-        assert_eq!(terminate_data[0], 3);
+        assert_eq!(terminate_data[0], 5);
     }
 
     #[test]
@@ -583,4 +647,28 @@ mod tests {
         assert_eq!(terminate_data.len(), 1);
         assert_eq!(terminate_data[0], 5);
     }
+
+    #[test]
+    fn metrics() {
+        let mut trigger = Trigger::new();
+        let buffer_handler = Arc::new(Mutex::new(Buffer::new()));
+        trigger.add_matcher(
+            Box::new(Simple::new(r#"{}"#).unwrap()),
+            buffer_handler.clone(),
+        );
+
+        let input = format!(
+            r#"{{"small": 1, "large": "{}", "last": 2}}"#,
+            "x".repeat(10000)
+        );
+        for part in input.as_bytes().chunks(100) {
+            trigger.process(part).unwrap();
+        }
+
+        let mut guard = buffer_handler.lock().unwrap();
+        assert_eq!(guard.metrics().peak_buffer_bytes, 10004);
+        assert_eq!(guard.drain_iter().count(), 3);
+        // peak remains
+        assert_eq!(guard.metrics().peak_buffer_bytes, 10004);
+    }
 }
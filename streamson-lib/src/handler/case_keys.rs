@@ -0,0 +1,242 @@
+//! Handler which changes the case of object keys in matched data
+//!
+//! `{"Name": {"First": "A"}}` -> `{"name": {"first": "A"}}`
+//!
+//! The matched data are processed using an internal streamer so the
+//! output is emitted as the data are fed and only the keys are changed.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler::{self, case_keys::Case}, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(handler::CaseKeys::new(Case::Lower)));
+//! let matcher = matcher::Simple::new(r#"{"users"}[]"#).unwrap();
+//!
+//! let mut convert = strategy::Convert::new();
+//!
+//! // Set the matcher for convert strategy
+//! convert.add_matcher(Box::new(matcher), handler);
+//!
+//! for input in vec![
+//!     br#"{"users": [{"Name": "first"}, "#.to_vec(),
+//!     br#"{"NAME": "second"}]}"#.to_vec(),
+//! ] {
+//!     for converted_data in convert.process(&input).unwrap() {
+//!         println!("{:?}", converted_data);
+//!     }
+//! }
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    path::{Element, Path},
+    streamer::{escape, unescape, Streamer, Token},
+};
+use std::{any::Any, str::FromStr};
+
+/// Case of the keys in the output
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    /// All keys are lowercased
+    #[default]
+    Lower,
+    /// All keys are uppercased
+    Upper,
+}
+
+impl Case {
+    /// Converts the string to the case
+    fn apply(self, input: &str) -> String {
+        match self {
+            Self::Lower => input.to_lowercase(),
+            Self::Upper => input.to_uppercase(),
+        }
+    }
+}
+
+impl FromStr for Case {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "lower" => Ok(Self::Lower),
+            "upper" => Ok(Self::Upper),
+            _ => Err(error::Handler::new(format!("Unknown case '{}'", input))),
+        }
+    }
+}
+
+/// Handler which lowercases or uppercases all object keys in matched data
+///
+/// Keys of nested objects are changed as well, values are passed through.
+#[derive(Debug)]
+pub struct CaseKeys {
+    /// Case of the output keys
+    case: Case,
+    /// Streamer which processes the matched data
+    streamer: Streamer,
+    /// Data which were fed but not emitted yet
+    buffer: Vec<u8>,
+    /// Index of the start of the buffer within the matched data
+    offset: usize,
+}
+
+impl CaseKeys {
+    /// Creates a new handler which changes the case of object keys
+    ///
+    /// # Arguments
+    /// * `case` - case of the keys in the output
+    pub fn new(case: Case) -> Self {
+        Self {
+            case,
+            streamer: Streamer::new(),
+            buffer: vec![],
+            offset: 0,
+        }
+    }
+
+    /// Removes the data till `idx` from the buffer and appends them to the output
+    ///
+    /// If the data precede an object value, the case of the key within the data is changed.
+    fn emit(&mut self, idx: usize, is_key: bool, output: &mut Vec<u8>) {
+        let data: Vec<u8> = self.buffer.drain(..idx - self.offset).collect();
+        self.offset = idx;
+
+        // data are supposed to look like `, "key": `
+        if is_key {
+            let first = data.iter().position(|byte| *byte == b'"');
+            let last = data.iter().rposition(|byte| *byte == b'"');
+            if let (Some(first), Some(last)) = (first, last) {
+                if let Some(key) = unescape(&data[first + 1..last]) {
+                    output.extend(&data[..first]);
+                    output.push(b'"');
+                    output.extend(escape(&self.case.apply(&key)).as_bytes());
+                    output.push(b'"');
+                    output.extend(&data[last + 1..]);
+                    return;
+                }
+            }
+        }
+        output.extend(data);
+    }
+
+    /// Processes the buffered data and returns the converted part
+    fn process(&mut self) -> Result<Vec<u8>, error::Handler> {
+        let mut output = vec![];
+        loop {
+            match self.streamer.read().map_err(error::Handler::new)? {
+                Token::Start(idx, _) => {
+                    let is_key = matches!(
+                        self.streamer.current_path().get_path().last(),
+                        Some(Element::Key(_))
+                    );
+                    self.emit(idx, is_key, &mut output);
+                }
+                Token::End(idx, _) => self.emit(idx, false, &mut output),
+                Token::Separator(_, _) | Token::Finished(_) | Token::Discarded(_) => {}
+                Token::Pending => return Ok(output),
+            }
+        }
+    }
+}
+
+impl FromStr for CaseKeys {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(input.parse()?))
+    }
+}
+
+impl Handler for CaseKeys {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        self.streamer = Streamer::new();
+        self.buffer.clear();
+        self.offset = 0;
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.buffer.extend(data);
+        self.streamer.feed(data);
+        let output = self.process()?;
+        if output.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(output))
+        }
+    }
+
+    fn end(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        // the rest can't contain any keys
+        self.offset = 0;
+        Ok(Some(self.buffer.drain(..).collect()))
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Case, CaseKeys};
+    use crate::{
+        matcher::Simple,
+        strategy::{Convert, OutputConverter, Strategy},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    fn convert(handler: CaseKeys, input: &[u8], chunk_size: usize) -> String {
+        let mut convert = Convert::new();
+        convert.add_matcher(
+            Box::new(Simple::new(r#"{"items"}[]"#).unwrap()),
+            Arc::new(Mutex::new(handler)),
+        );
+
+        let mut output = vec![];
+        let mut converter = OutputConverter::new();
+        for part in input.chunks(chunk_size) {
+            for (_, data) in converter.convert(&convert.process(part).unwrap()) {
+                output.extend(data);
+            }
+        }
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn lower() {
+        let input = br#"{"items": [{"Name": "First", "NeStEd": {"KEY": [{"Inner": 1}], "x": "Y"}}, "Value", 3, {}], "Other": 1}"#;
+        for chunk_size in 1..input.len() {
+            assert_eq!(
+                convert(CaseKeys::new(Case::Lower), input, chunk_size),
+                r#"{"items": [{"name": "First", "nested": {"key": [{"inner": 1}], "x": "Y"}}, "Value", 3, {}], "Other": 1}"#
+            );
+        }
+    }
+
+    #[test]
+    fn upper() {
+        assert_eq!(
+            convert(
+                CaseKeys::from_str("upper").unwrap(),
+                r#"{"items": [{ "a\"b" : 1 , "č": {"éx": null}}]}"#.as_bytes(),
+                4
+            ),
+            r#"{"items": [{ "A\"B" : 1 , "Č": {"ÉX": null}}]}"#
+        );
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Case::from_str("lower").unwrap(), Case::Lower);
+        assert!(CaseKeys::from_str("camel").is_err());
+    }
+}
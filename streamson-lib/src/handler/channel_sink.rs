@@ -0,0 +1,249 @@
+//! Handler which sends matched data through a channel
+//!
+//! It can be used to decouple JSON processing from the threads
+//! which consume the matched data.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::{sync::{mpsc, Arc, Mutex}, thread};
+//!
+//! let (sender, receiver) = mpsc::channel::<(Option<String>, Vec<u8>)>();
+//! let consumer = thread::spawn(move || {
+//!     for (path, data) in receiver {
+//!         // Do something with the data
+//!         println!("{} (len {})", path.unwrap(), data.len());
+//!     }
+//! });
+//!
+//! let sink_handler = Arc::new(Mutex::new(
+//!     handler::ChannelSink::new(sender).set_use_path(true)
+//! ));
+//! let matcher = matcher::Simple::new(r#"{"users"}[]{"name"}"#).unwrap();
+//!
+//! let mut trigger = strategy::Trigger::new();
+//!
+//! // Set the matcher for trigger strategy
+//! trigger.add_matcher(Box::new(matcher), sink_handler);
+//!
+//! for input in vec![
+//!     br#"{"users": [{"id": 1, "name": "first"}, {"#.to_vec(),
+//!     br#""id": 2, "name": "second"}]}"#.to_vec(),
+//! ] {
+//!     trigger.process(&input).unwrap();
+//! }
+//!
+//! // Sender is dropped with the trigger which terminates the consumer
+//! drop(trigger);
+//! consumer.join().unwrap();
+//! ```
+
+use super::{buffer::Buff, Handler, HandlerOutput};
+use crate::{error, path::Path, streamer::Token};
+use std::{any::Any, sync::mpsc::Sender};
+
+/// Handler which sends every matched data to a channel in (path, data) format
+pub struct ChannelSink {
+    /// Where the matched data are sent
+    sender: Sender<(Option<String>, Vec<u8>)>,
+    /// For storing unterminated data
+    buffer: Vec<u8>,
+    /// Buffer idx to total index
+    buffer_idx: usize,
+    /// Indexes for the Path and size
+    buffer_parts: Vec<usize>,
+    /// Not to send path will spare some allocation
+    use_path: bool,
+    /// Current buffer size (in bytes)
+    current_buffer_size: usize,
+    /// Max buffer size
+    max_buffer_size: Option<usize>,
+    /// Whether the receiving part of the channel was disconnected
+    disconnected: bool,
+}
+
+impl ChannelSink {
+    /// Creates a new handler which sends matched data to a channel
+    ///
+    /// # Arguments
+    /// * `sender` - sending part of the channel
+    pub fn new(sender: Sender<(Option<String>, Vec<u8>)>) -> Self {
+        Self {
+            sender,
+            buffer: vec![],
+            buffer_idx: 0,
+            buffer_parts: vec![],
+            use_path: false,
+            current_buffer_size: 0,
+            max_buffer_size: None,
+            disconnected: false,
+        }
+    }
+
+    /// Set whether to send path
+    ///
+    /// # Arguments
+    /// * `use_path` - should path be sent with data
+    pub fn set_use_path(mut self, use_path: bool) -> Self {
+        self.use_path = use_path;
+        self
+    }
+
+    /// Sets max size of data which are being collected
+    ///
+    /// # Arguments
+    /// * `max_size` - max size of unterminated data (in bytes)
+    pub fn set_max_buffer_size(mut self, max_size: Option<usize>) -> Self {
+        self.max_buffer_size = max_size;
+        self
+    }
+
+    /// Indicates that the receiving part of the channel is gone
+    /// and the matched data can't be delivered anymore
+    pub fn is_disconnected(&self) -> bool {
+        self.disconnected
+    }
+}
+
+impl Handler for ChannelSink {
+    fn start(&mut self, path: &Path, matcher_idx: usize, token: Token) -> HandlerOutput {
+        self._start(path, matcher_idx, token)
+    }
+
+    fn feed(&mut self, data: &[u8], matcher_idx: usize) -> HandlerOutput {
+        self._feed(data, matcher_idx)
+    }
+
+    fn end(&mut self, path: &Path, matcher_idx: usize, token: Token) -> HandlerOutput {
+        self._end(path, matcher_idx, token)?;
+        // sent data are no longer kept in the buffer
+        self.current_buffer_size = self.buffer.len();
+        if self.disconnected {
+            Err(error::Handler::new("Channel receiver disconnected"))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Buff for ChannelSink {
+    fn store_result(&mut self, path: &Path, data: Vec<u8>) {
+        let path = if self.use_path {
+            Some(path.to_string())
+        } else {
+            None
+        };
+        if self.sender.send((path, data)).is_err() {
+            self.disconnected = true;
+        }
+    }
+
+    fn buffer(&mut self) -> &mut Vec<u8> {
+        &mut self.buffer
+    }
+
+    fn buffer_parts(&mut self) -> &mut Vec<usize> {
+        &mut self.buffer_parts
+    }
+
+    fn buffer_idx(&mut self) -> &mut usize {
+        &mut self.buffer_idx
+    }
+
+    fn max_buffer_size(&mut self) -> &mut Option<usize> {
+        &mut self.max_buffer_size
+    }
+
+    fn current_buffer_size(&mut self) -> &mut usize {
+        &mut self.current_buffer_size
+    }
+
+    fn use_path(&mut self) -> &mut bool {
+        &mut self.use_path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChannelSink;
+    use crate::{
+        matcher::{Combinator, Simple},
+        strategy::{Strategy, Trigger},
+    };
+    use std::{
+        sync::{mpsc, Arc, Mutex},
+        thread,
+    };
+
+    #[test]
+    fn consumer_thread() {
+        let (sender, receiver) = mpsc::channel();
+        let consumer = thread::spawn(move || receiver.iter().collect::<Vec<_>>());
+
+        let mut trigger = Trigger::new();
+        let sink = Arc::new(Mutex::new(ChannelSink::new(sender).set_use_path(true)));
+        let matcher = Simple::new(r#"{"users"}[]"#).unwrap();
+        trigger.add_matcher(Box::new(matcher), sink);
+
+        trigger.process(br#"{"users": [1, "tw"#).unwrap();
+        trigger.process(br#"o", {"three": 3}]}"#).unwrap();
+        trigger.terminate().unwrap();
+
+        // drops the sender
+        drop(trigger);
+
+        assert_eq!(
+            consumer.join().unwrap(),
+            vec![
+                (Some(r#"{"users"}[0]"#.to_string()), br#"1"#.to_vec()),
+                (Some(r#"{"users"}[1]"#.to_string()), br#""two""#.to_vec()),
+                (
+                    Some(r#"{"users"}[2]"#.to_string()),
+                    br#"{"three": 3}"#.to_vec()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested() {
+        let (sender, receiver) = mpsc::channel();
+        let consumer = thread::spawn(move || receiver.iter().collect::<Vec<_>>());
+
+        let mut trigger = Trigger::new();
+        let sink = Arc::new(Mutex::new(ChannelSink::new(sender)));
+        let matcher = Combinator::new(Simple::new(r#"{"nested"}"#).unwrap())
+            | Combinator::new(Simple::new(r#"{"nested"}[]"#).unwrap());
+        trigger.add_matcher(Box::new(matcher), sink);
+
+        trigger.process(br#"{"nested": [1, 2]}"#).unwrap();
+        drop(trigger);
+
+        assert_eq!(
+            consumer.join().unwrap(),
+            vec![
+                (None, br#"1"#.to_vec()),
+                (None, br#"2"#.to_vec()),
+                (None, br#"[1, 2]"#.to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn disconnected() {
+        let (sender, receiver) = mpsc::channel();
+        drop(receiver);
+
+        let mut trigger = Trigger::new();
+        let sink = Arc::new(Mutex::new(ChannelSink::new(sender)));
+        let matcher = Simple::new(r#"[]"#).unwrap();
+        trigger.add_matcher(Box::new(matcher), sink.clone());
+
+        assert!(trigger.process(br#"[1, 2]"#).is_err());
+        assert!(sink.lock().unwrap().is_disconnected());
+    }
+}
@@ -0,0 +1,415 @@
+//! Handler which emits matched values as CSV rows
+//!
+//! Columns can be defined in two ways:
+//! * by matcher indexes - values matched within a single JSON are collected
+//!   into a row (the same way as in `Tsv` handler)
+//! * by `Simple` paths relative to the match - each matched value is a row
+//!   and nested values are flattened into the columns
+//!   (e.g. `{"address"}{"street"}`)
+//!
+//! Strings are unquoted, other values are kept as they are.
+//! Fields are quoted according to RFC 4180 (fields which contain
+//! the delimiter, the quote or a line break are quoted and quotes are doubled),
+//! the delimiter, the quote and an escape character can be configured.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::{io, sync::{Arc, Mutex}};
+//!
+//! let handler = Arc::new(Mutex::new(
+//!     handler::Csv::with_paths(
+//!         io::stdout(),
+//!         vec![
+//!             matcher::Simple::new(r#"{"name"}"#).unwrap(),
+//!             matcher::Simple::new(r#"{"address"}{"street"}"#).unwrap(),
+//!         ],
+//!     )
+//!     .set_header(vec!["name".into(), "street".into()])
+//! ));
+//!
+//! let mut trigger = strategy::Trigger::new();
+//! trigger.add_matcher(Box::new(matcher::Simple::new(r#"{"users"}[]"#).unwrap()), handler);
+//!
+//! // writes "name,street\nfirst,\"Main St, 1\"\n" to stdout
+//! trigger.process(br#"{"users": [{"name": "first", "address": {"street": "Main St, 1"}}]}"#).unwrap();
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    matcher::Simple,
+    path::Path,
+    streamer::{unescape, ParsedKind, Streamer, Token},
+};
+use std::{any::Any, io, mem};
+
+/// How the columns are defined
+enum Columns {
+    /// Number of columns (matcher indexes `0..columns`)
+    Matchers(usize),
+    /// Paths relative to the match
+    Paths(Vec<Simple>),
+}
+
+/// Handler which writes matched values as CSV rows
+pub struct Csv<W>
+where
+    W: io::Write,
+{
+    /// Writable output
+    output: W,
+    /// Definition of the columns
+    columns: Columns,
+    /// Field delimiter
+    delimiter: u8,
+    /// Quote character
+    quote: u8,
+    /// Character which escapes quotes (quotes are doubled when not set)
+    escape: Option<u8>,
+    /// Optional header row
+    header: Option<Vec<String>>,
+    /// Header was already emitted
+    header_written: bool,
+    /// Rows of the current JSON
+    rows: Vec<Vec<Option<Vec<u8>>>>,
+    /// Data of currently matched value
+    buffer: Vec<u8>,
+    /// Kind of currently matched value
+    kind: Option<ParsedKind>,
+}
+
+impl<W> Csv<W>
+where
+    W: io::Write,
+{
+    fn with_columns(output: W, columns: Columns) -> Self {
+        Self {
+            output,
+            columns,
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            header: None,
+            header_written: false,
+            rows: vec![],
+            buffer: vec![],
+            kind: None,
+        }
+    }
+
+    /// Creates a new CSV handler with columns defined by matcher indexes
+    ///
+    /// # Arguments
+    /// * `output` - structure which implements `io::Write`
+    /// * `columns` - number of columns (matcher indexes `0..columns`)
+    pub fn new(output: W, columns: usize) -> Self {
+        Self::with_columns(output, Columns::Matchers(columns))
+    }
+
+    /// Creates a new CSV handler with columns defined by paths
+    ///
+    /// Each matched value is written as a row.
+    ///
+    /// # Arguments
+    /// * `output` - structure which implements `io::Write`
+    /// * `paths` - paths of the columns relative to the match
+    pub fn with_paths(output: W, paths: Vec<Simple>) -> Self {
+        Self::with_columns(output, Columns::Paths(paths))
+    }
+
+    /// Returns the output
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+
+    /// Sets the header which is emitted before the first row
+    ///
+    /// # Arguments
+    /// * `header` - names of the columns
+    pub fn set_header(mut self, header: Vec<String>) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /// Sets the field delimiter (default `,`)
+    ///
+    /// # Arguments
+    /// * `delimiter` - character which separates the fields
+    pub fn set_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Sets the quote character (default `"`)
+    ///
+    /// # Arguments
+    /// * `quote` - character which quotes the fields
+    pub fn set_quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// Sets the character which escapes quotes within quoted fields
+    ///
+    /// # Arguments
+    /// * `escape` - escape character (`None` means that quotes are doubled)
+    pub fn set_escape(mut self, escape: Option<u8>) -> Self {
+        self.escape = escape;
+        self
+    }
+
+    /// Stores the value into the current row
+    fn store(&mut self, columns: usize, column: usize, value: Vec<u8>) {
+        let new_row = match self.rows.last() {
+            Some(row) => row[column].is_some(),
+            None => true,
+        };
+        if new_row {
+            self.rows.push(vec![None; columns]);
+        }
+        self.rows.last_mut().unwrap()[column] = Some(value);
+    }
+
+    /// Quotes the field when needed
+    fn field(&self, data: &[u8], output: &mut Vec<u8>) {
+        let needs_quotes = data
+            .iter()
+            .any(|byte| [self.delimiter, self.quote, b'\n', b'\r'].contains(byte));
+        if !needs_quotes {
+            output.extend(data);
+            return;
+        }
+        output.push(self.quote);
+        for byte in data {
+            if *byte == self.quote {
+                output.push(self.escape.unwrap_or(self.quote));
+            } else if Some(*byte) == self.escape {
+                output.push(*byte);
+            }
+            output.push(*byte);
+        }
+        output.push(self.quote);
+    }
+
+    /// Writes a single row
+    fn write_row<'a, I>(&self, output: &mut Vec<u8>, fields: I)
+    where
+        I: Iterator<Item = &'a [u8]>,
+    {
+        for (idx, field) in fields.enumerate() {
+            if idx != 0 {
+                output.push(self.delimiter);
+            }
+            self.field(field, output);
+        }
+        output.push(b'\n');
+    }
+
+    /// Writes the header (if not written yet) and the rows
+    fn flush(&mut self) -> Result<(), error::Handler> {
+        let mut output = vec![];
+        if !self.header_written {
+            if let Some(header) = self.header.as_ref() {
+                self.write_row(&mut output, header.iter().map(|e| e.as_bytes()));
+            }
+            self.header_written = true;
+        }
+        for row in mem::take(&mut self.rows) {
+            self.write_row(
+                &mut output,
+                row.iter().map(|e| e.as_ref().map_or(&b""[..], |e| &e[..])),
+            );
+        }
+        self.output.write_all(&output).map_err(error::Handler::new)
+    }
+}
+
+/// Converts JSON data into a field value
+fn value(data: &[u8], kind: ParsedKind) -> Vec<u8> {
+    if kind == ParsedKind::Str && data.len() >= 2 {
+        let content = &data[1..data.len() - 1];
+        unescape(content).map_or_else(|| content.to_vec(), String::into_bytes)
+    } else {
+        data.to_vec()
+    }
+}
+
+/// Extracts the values of the paths from the matched data
+fn flatten(paths: &[Simple], data: &[u8]) -> Result<Vec<Option<Vec<u8>>>, error::Handler> {
+    let mut row = vec![None; paths.len()];
+    let mut streamer = Streamer::new();
+    streamer.feed_final(data);
+    // start idx and matched columns of the opened elements
+    let mut stack: Vec<(usize, Vec<usize>)> = vec![];
+    loop {
+        match streamer.read().map_err(error::Handler::new)? {
+            Token::Start(idx, kind) => {
+                let path = streamer.current_path();
+                let columns = paths
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, column)| column.matches_elements(path.get_path(), kind))
+                    .map(|(column, _)| column)
+                    .collect();
+                stack.push((idx, columns));
+            }
+            Token::End(idx, kind) => {
+                let (start, columns) = stack.pop().unwrap();
+                for column in columns {
+                    // the first value is used
+                    if row[column].is_none() {
+                        row[column] = Some(value(&data[start..idx], kind));
+                    }
+                }
+                if stack.is_empty() {
+                    return Ok(row);
+                }
+            }
+            Token::Pending => return Err(error::Handler::new("Matched data are not complete")),
+            Token::Separator(_, _) | Token::Finished(_) | Token::Discarded(_) => {}
+        }
+    }
+}
+
+impl<W> Handler for Csv<W>
+where
+    W: io::Write + Send + 'static,
+{
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, token: Token) -> HandlerOutput {
+        self.buffer.clear();
+        if let Token::Start(_, kind) = token {
+            self.kind = Some(kind);
+        }
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(&mut self, _path: &Path, matcher_idx: usize, _token: Token) -> HandlerOutput {
+        let data = mem::take(&mut self.buffer);
+        let kind = self.kind.take().unwrap_or(ParsedKind::Null);
+        match &self.columns {
+            Columns::Matchers(columns) => {
+                let columns = *columns;
+                if matcher_idx >= columns {
+                    return Err(error::Handler::new(format!(
+                        "CSV column {} is out of range",
+                        matcher_idx
+                    )));
+                }
+                self.store(columns, matcher_idx, value(&data, kind));
+            }
+            Columns::Paths(paths) => {
+                let row = flatten(paths, &data)?;
+                self.rows.push(row);
+                self.flush()?;
+            }
+        }
+        Ok(None)
+    }
+
+    fn json_finished(&mut self) -> HandlerOutput {
+        if !self.rows.is_empty() {
+            self.flush()?;
+        }
+        Ok(None)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Csv;
+    use crate::{
+        matcher::Simple,
+        strategy::{Strategy, Trigger},
+    };
+    use std::sync::{Arc, Mutex};
+
+    fn csv(handler: Csv<Vec<u8>>, matchers: &[&str], input: &[u8]) -> String {
+        let handler = Arc::new(Mutex::new(handler));
+        let mut trigger = Trigger::new();
+        for matcher in matchers {
+            trigger.add_matcher(Box::new(Simple::new(matcher).unwrap()), handler.clone());
+        }
+        for part in input.chunks(4) {
+            trigger.process(part).unwrap();
+        }
+        let guard = handler.lock().unwrap();
+        String::from_utf8(guard.output().clone()).unwrap()
+    }
+
+    #[test]
+    fn matchers() {
+        let output = csv(
+            Csv::new(vec![], 2).set_header(vec!["id".into(), "name".into()]),
+            &[r#"[]{"id"}"#, r#"[]{"name"}"#],
+            br#"[{"id": 1, "name": "fi,rst"}, {"name": "sec\"ond", "id": 2}] [{"id": 3}]"#,
+        );
+        assert_eq!(
+            output,
+            "id,name\n\
+             1,\"fi,rst\"\n\
+             2,\"sec\"\"ond\"\n\
+             3,\n"
+        );
+    }
+
+    #[test]
+    fn paths() {
+        let paths = vec![
+            Simple::new(r#"{"name"}"#).unwrap(),
+            Simple::new(r#"{"address"}{"street"}"#).unwrap(),
+            Simple::new(r#"{"tags"}[0]"#).unwrap(),
+            Simple::new(r#"{"tags"}"#).unwrap(),
+        ];
+        let output = csv(
+            Csv::with_paths(vec![], paths).set_header(vec![
+                "name".into(),
+                "street".into(),
+                "tag".into(),
+                "tags".into(),
+            ]),
+            &[r#"{"users"}[]"#],
+            br#"{"users": [
+                {"name": "first", "address": {"street": "Main\nSt"}, "tags": ["a", 1]},
+                {"tags": [], "name": null}
+            ]}"#,
+        );
+        assert_eq!(
+            output,
+            "name,street,tag,tags\n\
+             first,\"Main\nSt\",a,\"[\"\"a\"\", 1]\"\n\
+             null,,,[]\n"
+        );
+    }
+
+    #[test]
+    fn custom_characters() {
+        let output = csv(
+            Csv::new(vec![], 2)
+                .set_delimiter(b';')
+                .set_quote(b'\'')
+                .set_escape(Some(b'\\')),
+            &[r#"{"a"}"#, r#"{"b"}"#],
+            br#"{"a": "x;y", "b": "it's \\ ok"}"#,
+        );
+        assert_eq!(output, "'x;y';'it\\'s \\\\ ok'\n");
+    }
+
+    #[test]
+    fn out_of_range() {
+        let handler = Arc::new(Mutex::new(Csv::new(vec![], 1)));
+        let mut trigger = Trigger::new();
+        trigger.add_matcher(Box::new(Simple::new(r#"{"a"}"#).unwrap()), handler.clone());
+        trigger.add_matcher(Box::new(Simple::new(r#"{"b"}"#).unwrap()), handler);
+        assert!(trigger.process(br#"{"a": 1, "b": 2}"#).is_err());
+    }
+}
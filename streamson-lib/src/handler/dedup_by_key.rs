@@ -0,0 +1,299 @@
+//! Handler which stores matched objects only when they were changed
+//!
+//! Objects are identified by a value of a key field. An object
+//! is stored only if it differs from the last object with the same key.
+//! It can be used e.g. for incremental synchronization.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let dedup_handler = Arc::new(Mutex::new(handler::DedupByKey::new("id")));
+//!
+//! let matcher = matcher::Simple::new(r#"[]"#).unwrap();
+//!
+//! let mut trigger = strategy::Trigger::new();
+//!
+//! // Set the matcher for trigger strategy
+//! trigger.add_matcher(Box::new(matcher), dedup_handler.clone());
+//!
+//! for input in vec![
+//!     br#"[{"id": 1, "name": "first"}, {"id": 1, "name": "first"}, "#.to_vec(),
+//!     br#"{"id": 1, "name": "changed"}]"#.to_vec(),
+//! ] {
+//!     trigger.process(&input).unwrap();
+//!     let mut guard = dedup_handler.lock().unwrap();
+//!     while let Some((_, data)) = guard.pop() {
+//!         // Do something with the changed data
+//!         println!("{}", String::from_utf8(data).unwrap());
+//!     }
+//! }
+//! ```
+
+use super::{buffer::Buff, Handler, HandlerOutput};
+use crate::{
+    error,
+    path::{Element, Path},
+    streamer::{Streamer, Token},
+};
+use std::{
+    any::Any,
+    collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+    hash::{Hash, Hasher},
+    slice,
+    str::FromStr,
+};
+
+/// Handler which stores only changed objects identified by a key field
+pub struct DedupByKey {
+    /// Name of the field which identifies the object
+    key_field: String,
+    /// Hashes of the last seen objects by the key value
+    seen: HashMap<Vec<u8>, u64>,
+    /// For storing unterminated data
+    buffer: Vec<u8>,
+    /// Buffer idx to total index
+    buffer_idx: usize,
+    /// Indexes for the Path and size
+    buffer_parts: Vec<usize>,
+    /// Queue with changed objects in (path, data) format
+    results: VecDeque<(Option<String>, Vec<u8>)>,
+    /// Not to show path will spare some allocation
+    use_path: bool,
+    /// Current buffer size (in bytes)
+    current_buffer_size: usize,
+    /// Max buffer size
+    max_buffer_size: Option<usize>,
+}
+
+impl FromStr for DedupByKey {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() {
+            Err(error::Handler::new("Key field has to be set"))
+        } else {
+            Ok(Self::new(input))
+        }
+    }
+}
+
+impl DedupByKey {
+    /// Creates a new handler which stores only changed objects
+    ///
+    /// # Arguments
+    /// * `key_field` - name of the field which identifies the object
+    pub fn new<S>(key_field: S) -> Self
+    where
+        S: ToString,
+    {
+        Self {
+            key_field: key_field.to_string(),
+            seen: HashMap::new(),
+            buffer: vec![],
+            buffer_idx: 0,
+            buffer_parts: vec![],
+            results: VecDeque::new(),
+            use_path: false,
+            current_buffer_size: 0,
+            max_buffer_size: None,
+        }
+    }
+
+    /// Set whether to show path
+    ///
+    /// # Arguments
+    /// * `use_path` - should path be store with data
+    pub fn set_use_path(mut self, use_path: bool) -> Self {
+        self.use_path = use_path;
+        self
+    }
+
+    /// Sets max buffer size
+    ///
+    /// # Arguments
+    /// * `max_size` - max size of buffered data (in bytes)
+    pub fn set_max_buffer_size(mut self, max_size: Option<usize>) -> Self {
+        self.max_buffer_size = max_size;
+        self
+    }
+
+    /// Pops the oldest changed object
+    ///
+    /// # Returns
+    /// * `None` - queue is empty
+    /// * `Some((path, data))` - stored data remove from the queue and returned
+    pub fn pop(&mut self) -> Option<(Option<String>, Vec<u8>)> {
+        let popped = self.results.pop_front();
+        if popped.is_some() {
+            self.current_buffer_size =
+                self.results.iter().fold(0, |e, y| e + y.1.len()) + self.buffer.len();
+        }
+        popped
+    }
+}
+
+/// Extracts raw data of the key field from an object
+///
+/// # Arguments
+/// * `data` - raw data of the object
+/// * `key_field` - name of the field
+pub(super) fn key_value(data: &[u8], key_field: &str) -> Option<Vec<u8>> {
+    let mut streamer = Streamer::new();
+    streamer.feed(data);
+    let key_path = Element::Key(key_field.to_string());
+    let mut start = None;
+    loop {
+        match streamer.read().ok()? {
+            Token::Start(idx, _) => {
+                if streamer.current_path().get_path() == slice::from_ref(&key_path) {
+                    start = Some(idx);
+                }
+            }
+            Token::End(idx, _) => {
+                if streamer.current_path().get_path() == slice::from_ref(&key_path) {
+                    return Some(data[start?..idx].to_vec());
+                }
+            }
+            Token::Separator(_, _) | Token::Finished(_) | Token::Discarded(_) => {}
+            Token::Pending => return None,
+        }
+    }
+}
+
+impl Handler for DedupByKey {
+    fn start(&mut self, path: &Path, matcher_idx: usize, token: Token) -> HandlerOutput {
+        self._start(path, matcher_idx, token)
+    }
+
+    fn feed(&mut self, data: &[u8], matcher_idx: usize) -> HandlerOutput {
+        self._feed(data, matcher_idx)
+    }
+
+    fn end(&mut self, path: &Path, matcher_idx: usize, token: Token) -> HandlerOutput {
+        self._end(path, matcher_idx, token)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Buff for DedupByKey {
+    fn store_result(&mut self, path: &Path, data: Vec<u8>) {
+        // objects without the key field can't be compared
+        if let Some(key) = key_value(&data, &self.key_field) {
+            let mut hasher = DefaultHasher::new();
+            data.hash(&mut hasher);
+            let hash = hasher.finish();
+            if self.seen.insert(key, hash) == Some(hash) {
+                // not changed
+                return;
+            }
+        }
+        let use_path = *self.use_path();
+        self.results.push_back((
+            if use_path {
+                Some(path.to_string())
+            } else {
+                None
+            },
+            data,
+        ));
+    }
+
+    fn buffer(&mut self) -> &mut Vec<u8> {
+        &mut self.buffer
+    }
+
+    fn buffer_parts(&mut self) -> &mut Vec<usize> {
+        &mut self.buffer_parts
+    }
+
+    fn buffer_idx(&mut self) -> &mut usize {
+        &mut self.buffer_idx
+    }
+
+    fn max_buffer_size(&mut self) -> &mut Option<usize> {
+        &mut self.max_buffer_size
+    }
+
+    fn current_buffer_size(&mut self) -> &mut usize {
+        &mut self.current_buffer_size
+    }
+
+    fn use_path(&mut self) -> &mut bool {
+        &mut self.use_path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DedupByKey;
+    use crate::{
+        matcher::Simple,
+        strategy::{Strategy, Trigger},
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn changed_records() {
+        let mut trigger = Trigger::new();
+        let handler = Arc::new(Mutex::new(DedupByKey::new("id").set_use_path(true)));
+        let matcher = Simple::new(r#"{"records"}[]"#).unwrap();
+        trigger.add_matcher(Box::new(matcher), handler.clone());
+
+        trigger
+            .process(br#"{"records": [{"id": 1, "val": "a"}, {"id": 2, "val": "b"}, "#)
+            .unwrap();
+        trigger
+            .process(br#"{"id": 1, "val": "a"}, {"id": 2, "val": "c"}, {"id": 1, "val": "a"}]}"#)
+            .unwrap();
+
+        let mut guard = handler.lock().unwrap();
+        assert_eq!(
+            guard.pop().unwrap(),
+            (
+                Some(r#"{"records"}[0]"#.to_string()),
+                br#"{"id": 1, "val": "a"}"#.to_vec()
+            )
+        );
+        assert_eq!(
+            guard.pop().unwrap(),
+            (
+                Some(r#"{"records"}[1]"#.to_string()),
+                br#"{"id": 2, "val": "b"}"#.to_vec()
+            )
+        );
+        assert_eq!(
+            guard.pop().unwrap(),
+            (
+                Some(r#"{"records"}[3]"#.to_string()),
+                br#"{"id": 2, "val": "c"}"#.to_vec()
+            )
+        );
+        assert_eq!(guard.pop(), None);
+    }
+
+    #[test]
+    fn changed_back() {
+        let mut trigger = Trigger::new();
+        let handler = Arc::new(Mutex::new(DedupByKey::new("id")));
+        let matcher = Simple::new(r#"[]"#).unwrap();
+        trigger.add_matcher(Box::new(matcher), handler.clone());
+
+        trigger
+            .process(
+                br#"[{"id": "x", "v": 1}, {"id": "x", "v": 2}, {"id": "x", "v": 1}, {"v": 1}]"#,
+            )
+            .unwrap();
+
+        let mut guard = handler.lock().unwrap();
+        assert_eq!(guard.pop().unwrap().1, br#"{"id": "x", "v": 1}"#.to_vec());
+        assert_eq!(guard.pop().unwrap().1, br#"{"id": "x", "v": 2}"#.to_vec());
+        assert_eq!(guard.pop().unwrap().1, br#"{"id": "x", "v": 1}"#.to_vec());
+        // records without the key are always stored
+        assert_eq!(guard.pop().unwrap().1, br#"{"v": 1}"#.to_vec());
+        assert_eq!(guard.pop(), None);
+    }
+}
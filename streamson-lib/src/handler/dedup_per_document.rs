@@ -0,0 +1,228 @@
+//! Handler which stores matched values which are distinct within a document
+//!
+//! Seen values are forgotten when the document (top level value) ends,
+//! so it can be used e.g. for NDJSON inputs where each line should
+//! be deduplicated separately.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let dedup_handler = Arc::new(Mutex::new(handler::DedupPerDocument::new()));
+//!
+//! let matcher = matcher::Simple::new(r#"{"tags"}[]"#).unwrap();
+//!
+//! let mut trigger = strategy::Trigger::new();
+//!
+//! // Set the matcher for trigger strategy
+//! trigger.add_matcher(Box::new(matcher), dedup_handler.clone());
+//!
+//! for input in vec![
+//!     br#"{"tags": ["a", "b", "a"]}"#.to_vec(),
+//!     br#"{"tags": ["a"]}"#.to_vec(),
+//! ] {
+//!     trigger.process(&input).unwrap();
+//!     let mut guard = dedup_handler.lock().unwrap();
+//!     while let Some((_, data)) = guard.pop() {
+//!         // Do something with the distinct data
+//!         println!("{}", String::from_utf8(data).unwrap());
+//!     }
+//! }
+//! ```
+
+use super::{buffer::Buff, Handler, HandlerOutput};
+use crate::{error, path::Path, streamer::Token};
+use std::{
+    any::Any,
+    collections::{HashSet, VecDeque},
+    str::FromStr,
+};
+
+/// Handler which stores only the first occurrence of a value within a document
+///
+/// Values are compared as they are written in the input
+/// so e.g. `1` and `1.0` are distinct values.
+#[derive(Debug, Default)]
+pub struct DedupPerDocument {
+    /// Values seen in the current document
+    seen: HashSet<Vec<u8>>,
+    /// For storing unterminated data
+    buffer: Vec<u8>,
+    /// Buffer idx to total index
+    buffer_idx: usize,
+    /// Indexes for the Path and size
+    buffer_parts: Vec<usize>,
+    /// Queue with distinct values in (path, data) format
+    results: VecDeque<(Option<String>, Vec<u8>)>,
+    /// Not to show path will spare some allocation
+    use_path: bool,
+    /// Current buffer size (in bytes)
+    current_buffer_size: usize,
+    /// Max buffer size
+    max_buffer_size: Option<usize>,
+}
+
+impl FromStr for DedupPerDocument {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() {
+            Ok(Self::default())
+        } else {
+            Ok(Self::default().set_use_path(input.parse().map_err(error::Handler::new)?))
+        }
+    }
+}
+
+impl DedupPerDocument {
+    /// Creates a new handler which stores distinct values of each document
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to show path
+    ///
+    /// # Arguments
+    /// * `use_path` - should path be store with data
+    pub fn set_use_path(mut self, use_path: bool) -> Self {
+        self.use_path = use_path;
+        self
+    }
+
+    /// Sets max buffer size
+    ///
+    /// # Arguments
+    /// * `max_size` - max size of buffered data (in bytes)
+    pub fn set_max_buffer_size(mut self, max_size: Option<usize>) -> Self {
+        self.max_buffer_size = max_size;
+        self
+    }
+
+    /// Pops the oldest distinct value
+    ///
+    /// # Returns
+    /// * `None` - queue is empty
+    /// * `Some((path, data))` - stored data remove from the queue and returned
+    pub fn pop(&mut self) -> Option<(Option<String>, Vec<u8>)> {
+        let popped = self.results.pop_front();
+        if popped.is_some() {
+            self.current_buffer_size =
+                self.results.iter().fold(0, |e, y| e + y.1.len()) + self.buffer.len();
+        }
+        popped
+    }
+}
+
+impl Handler for DedupPerDocument {
+    fn start(&mut self, path: &Path, matcher_idx: usize, token: Token) -> HandlerOutput {
+        self._start(path, matcher_idx, token)
+    }
+
+    fn feed(&mut self, data: &[u8], matcher_idx: usize) -> HandlerOutput {
+        self._feed(data, matcher_idx)
+    }
+
+    fn end(&mut self, path: &Path, matcher_idx: usize, token: Token) -> HandlerOutput {
+        self._end(path, matcher_idx, token)
+    }
+
+    fn json_finished(&mut self) -> HandlerOutput {
+        self.seen.clear();
+        Ok(None)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Buff for DedupPerDocument {
+    fn store_result(&mut self, path: &Path, data: Vec<u8>) {
+        if self.seen.contains(&data) {
+            return;
+        }
+        self.seen.insert(data.clone());
+        let use_path = *self.use_path();
+        self.results.push_back((
+            if use_path {
+                Some(path.to_string())
+            } else {
+                None
+            },
+            data,
+        ));
+    }
+
+    fn buffer(&mut self) -> &mut Vec<u8> {
+        &mut self.buffer
+    }
+
+    fn buffer_parts(&mut self) -> &mut Vec<usize> {
+        &mut self.buffer_parts
+    }
+
+    fn buffer_idx(&mut self) -> &mut usize {
+        &mut self.buffer_idx
+    }
+
+    fn max_buffer_size(&mut self) -> &mut Option<usize> {
+        &mut self.max_buffer_size
+    }
+
+    fn current_buffer_size(&mut self) -> &mut usize {
+        &mut self.current_buffer_size
+    }
+
+    fn use_path(&mut self) -> &mut bool {
+        &mut self.use_path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DedupPerDocument;
+    use crate::{
+        matcher::Simple,
+        strategy::{Strategy, Trigger},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    #[test]
+    fn two_documents() {
+        let mut trigger = Trigger::new();
+        let handler = Arc::new(Mutex::new(DedupPerDocument::from_str("true").unwrap()));
+        let matcher = Simple::new(r#"[]"#).unwrap();
+        trigger.add_matcher(Box::new(matcher), handler.clone());
+
+        let input = b"[1, 2, 1, {\"a\": 1}, {\"a\": 1}]\n[2, 2, 1.0, 1]\n";
+        for part in input.chunks(4) {
+            trigger.process(part).unwrap();
+        }
+
+        let mut output = vec![];
+        let mut guard = handler.lock().unwrap();
+        while let Some((path, data)) = guard.pop() {
+            output.push((path.unwrap(), String::from_utf8(data).unwrap()));
+        }
+        assert_eq!(
+            output,
+            vec![
+                ("[0]".to_string(), "1".to_string()),
+                ("[1]".to_string(), "2".to_string()),
+                ("[3]".to_string(), r#"{"a": 1}"#.to_string()),
+                ("[0]".to_string(), "2".to_string()),
+                ("[2]".to_string(), "1.0".to_string()),
+                ("[3]".to_string(), "1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse() {
+        assert!(DedupPerDocument::from_str("").is_ok());
+        assert!(DedupPerDocument::from_str("x").is_err());
+    }
+}
@@ -0,0 +1,181 @@
+//! Handler which wraps matched data into an envelope with metadata
+//!
+//! `{"a": 1}` -> `{"ts":1600000000,"source":"input","payload":{"a": 1}}`
+//!
+//! The payload is streamed as it is, so it doesn't need to be buffered.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(handler::Envelope::new("users").set_timestamp(true)));
+//! let matcher = matcher::Simple::new(r#"{"users"}[]"#).unwrap();
+//!
+//! let mut convert = strategy::Convert::new();
+//!
+//! // Set the matcher for convert strategy
+//! convert.add_matcher(Box::new(matcher), handler);
+//!
+//! for input in vec![
+//!     br#"{"users": [{"name": "first"}, "#.to_vec(),
+//!     br#"{"name": "second"}]}"#.to_vec(),
+//! ] {
+//!     for converted_data in convert.process(&input).unwrap() {
+//!         println!("{:?}", converted_data);
+//!     }
+//! }
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    path::Path,
+    streamer::{escape, Token},
+};
+use std::{
+    any::Any,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Handler which wraps matched data into
+/// `{"ts": <unix timestamp>, "source": "<source>", "payload": <data>}`
+///
+/// The `ts` field is present only when timestamps are enabled.
+#[derive(Debug, Default)]
+pub struct Envelope {
+    /// Source of the data
+    source: String,
+    /// Whether to add the timestamp (in seconds)
+    add_timestamp: bool,
+}
+
+impl Envelope {
+    /// Creates a new handler which wraps matched data into an envelope
+    ///
+    /// # Arguments
+    /// * `source` - source which is written to the envelope
+    pub fn new<T>(source: T) -> Self
+    where
+        T: ToString,
+    {
+        Self {
+            source: source.to_string(),
+            add_timestamp: false,
+        }
+    }
+
+    /// Sets whether the timestamp is added to the envelope
+    ///
+    /// # Arguments
+    /// * `add_timestamp` - add seconds since the unix epoch
+    pub fn set_timestamp(mut self, add_timestamp: bool) -> Self {
+        self.add_timestamp = add_timestamp;
+        self
+    }
+}
+
+impl FromStr for Envelope {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(input))
+    }
+}
+
+impl Handler for Envelope {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        let mut prefix = String::from("{");
+        if self.add_timestamp {
+            let ts = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_err(error::Handler::new)?;
+            prefix.push_str(&format!(r#""ts":{},"#, ts.as_secs()));
+        }
+        prefix.push_str(&format!(
+            r#""source":"{}","payload":"#,
+            escape(&self.source)
+        ));
+        Ok(Some(prefix.into_bytes()))
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        Ok(Some(data.to_vec()))
+    }
+
+    fn end(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        Ok(Some(b"}".to_vec()))
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Envelope;
+    use crate::{
+        matcher::Simple,
+        strategy::{Convert, OutputConverter, Strategy},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    fn convert(envelope: Envelope, input: &[u8]) -> String {
+        let mut convert = Convert::new();
+        convert.add_matcher(
+            Box::new(Simple::new(r#"{"items"}[]"#).unwrap()),
+            Arc::new(Mutex::new(envelope)),
+        );
+
+        let mut output = vec![];
+        let mut converter = OutputConverter::new();
+        for part in input.chunks(3) {
+            for (_, data) in converter.convert(&convert.process(part).unwrap()) {
+                output.extend(data);
+            }
+        }
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn envelope() {
+        assert_eq!(
+            convert(
+                Envelope::from_str(r#"my "src""#).unwrap(),
+                br#"{"items": [{"a": [1]}, 2]}"#
+            ),
+            r#"{"items": [{"source":"my \"src\"","payload":{"a": [1]}}, {"source":"my \"src\"","payload":2}]}"#
+        );
+    }
+
+    #[test]
+    fn timestamp() {
+        let before = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let output = convert(
+            Envelope::new("src").set_timestamp(true),
+            br#"{"items": ["x"]}"#,
+        );
+        let after = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let rest = output.strip_prefix(r#"{"items": [{"ts":"#).unwrap();
+        let (ts, rest) = rest.split_at(rest.find(',').unwrap());
+        let ts: u64 = ts.parse().unwrap();
+        assert!(before <= ts && ts <= after);
+        assert_eq!(rest, r#","source":"src","payload":"x"}]}"#);
+    }
+}
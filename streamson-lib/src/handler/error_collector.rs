@@ -0,0 +1,231 @@
+//! Handler which collects contexts of the recoverable input errors
+//!
+//! It is meant to be set to a strategy which can recover from
+//! an incorrect input (see `strategy::Trigger::set_error_collector`).
+//! Only the last N errors are kept and they are emitted
+//! as a JSON array when the input is finished.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let collector = Arc::new(Mutex::new(handler::ErrorCollector::new(10)));
+//! let buffer = Arc::new(Mutex::new(handler::Buffer::new()));
+//! let matcher = matcher::Simple::new(r#"{"id"}"#).unwrap();
+//!
+//! let mut trigger = strategy::Trigger::new();
+//! trigger.add_matcher(Box::new(matcher), buffer.clone());
+//! trigger.set_error_collector(collector.clone());
+//!
+//! for input in vec![
+//!     b"{\"id\": 1}\n{\"id\": 2,,}\n".to_vec(),
+//!     b"{\"id\": 3}\n".to_vec(),
+//! ] {
+//!     trigger.process(&input).unwrap();
+//! }
+//!
+//! let guard = collector.lock().unwrap();
+//! println!("{}", String::from_utf8(guard.to_json()).unwrap());
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    path::Path,
+    streamer::{escape, Token},
+};
+use std::{any::Any, collections::VecDeque, str::FromStr};
+
+/// Context of an error
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorContext {
+    /// Total index of the incorrect byte
+    pub idx: usize,
+    /// Incorrect byte
+    pub byte: u8,
+    /// Path where the error occured
+    pub path: String,
+    /// Bytes surrounding the incorrect byte
+    pub context: Vec<u8>,
+}
+
+/// Handler which keeps the contexts of the last errors
+#[derive(Debug)]
+pub struct ErrorCollector {
+    /// Max number of the kept errors
+    max_errors: usize,
+    /// Number of bytes stored before and after the incorrect byte
+    context_size: usize,
+    /// Collected errors
+    errors: VecDeque<ErrorContext>,
+}
+
+impl FromStr for ErrorCollector {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(input.parse().map_err(error::Handler::new)?))
+    }
+}
+
+impl ErrorCollector {
+    /// Creates a new handler which collects errors
+    ///
+    /// # Arguments
+    /// * `max_errors` - number of the last errors which are kept
+    pub fn new(max_errors: usize) -> Self {
+        Self {
+            max_errors,
+            context_size: 16,
+            errors: VecDeque::new(),
+        }
+    }
+
+    /// Sets the size of the context
+    ///
+    /// # Arguments
+    /// * `context_size` - number of bytes stored before and after the incorrect byte
+    pub fn set_context_size(mut self, context_size: usize) -> Self {
+        self.context_size = context_size;
+        self
+    }
+
+    /// Records an error
+    ///
+    /// # Arguments
+    /// * `error` - incorrect input error
+    /// * `path` - path where the error occured
+    /// * `input` - input data which contain the incorrect byte
+    /// * `input_start` - total index of the input data start
+    pub fn record(
+        &mut self,
+        error: &error::IncorrectInput,
+        path: &Path,
+        input: &[u8],
+        input_start: usize,
+    ) {
+        let idx = error.idx().saturating_sub(input_start).min(input.len());
+        let start = idx.saturating_sub(self.context_size);
+        let end = (idx + self.context_size + 1).min(input.len());
+        self.errors.push_back(ErrorContext {
+            idx: error.idx(),
+            byte: error.byte(),
+            path: path.to_string(),
+            context: input[start..end].to_vec(),
+        });
+        while self.errors.len() > self.max_errors {
+            self.errors.pop_front();
+        }
+    }
+
+    /// Returns the collected errors (the oldest first)
+    pub fn errors(&self) -> &VecDeque<ErrorContext> {
+        &self.errors
+    }
+
+    /// Returns the collected errors as a JSON array
+    pub fn to_json(&self) -> Vec<u8> {
+        let items: Vec<String> = self
+            .errors
+            .iter()
+            .map(|error| {
+                format!(
+                    r#"{{"idx":{},"byte":"{}","path":"{}","context":"{}"}}"#,
+                    error.idx,
+                    escape(&(error.byte as char).to_string()),
+                    escape(&error.path),
+                    escape(&String::from_utf8_lossy(&error.context)),
+                )
+            })
+            .collect();
+        format!("[{}]", items.join(",")).into_bytes()
+    }
+}
+
+impl Handler for ErrorCollector {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        Ok(None)
+    }
+
+    fn feed(&mut self, _data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        Ok(None)
+    }
+
+    fn end(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        Ok(None)
+    }
+
+    fn input_finished(&mut self) -> HandlerOutput {
+        Ok(Some(self.to_json()))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ErrorCollector;
+    use crate::{
+        handler::Buffer,
+        matcher::Simple,
+        strategy::{Output, Strategy, Trigger},
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn two_errors() {
+        let collector = Arc::new(Mutex::new(ErrorCollector::new(5).set_context_size(4)));
+        let buffer = Arc::new(Mutex::new(Buffer::new()));
+        let mut trigger = Trigger::new();
+        trigger.add_matcher(Box::new(Simple::new(r#"{"a"}"#).unwrap()), buffer.clone());
+        trigger.set_error_collector(collector.clone());
+
+        let input =
+            b"{\"a\": 1}\n{\"a\": 2, \"b\": [1, x]}\n{\"a\": 3}\n{\"a\": 4 5}\n{\"a\": 6}\n";
+        for part in input.chunks(5) {
+            trigger.process(part).unwrap();
+        }
+
+        // valid data still flows
+        let mut values = vec![];
+        while let Some((_, data)) = buffer.lock().unwrap().pop() {
+            values.push(String::from_utf8(data).unwrap());
+        }
+        assert_eq!(values, vec!["1", "2", "3", "4", "6"]);
+
+        let output = trigger.terminate().unwrap();
+        let guard = collector.lock().unwrap();
+        let errors = guard.errors();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].idx, 27);
+        assert_eq!(errors[0].byte, b'x');
+        assert_eq!(errors[0].path, r#"{"b"}"#);
+        assert_eq!(errors[1].idx, 48);
+        assert_eq!(errors[1].byte, b'5');
+        assert_eq!(errors[1].path, "");
+
+        assert_eq!(output.len(), 1);
+        if let Output::Data(data) = &output[0] {
+            assert_eq!(data, &guard.to_json());
+        } else {
+            unreachable!();
+        }
+    }
+
+    #[test]
+    fn last_errors() {
+        let collector = Arc::new(Mutex::new(ErrorCollector::new(1).set_context_size(2)));
+        let mut trigger = Trigger::new();
+        trigger.set_error_collector(collector.clone());
+
+        trigger.process(b"[1 2]\n[\"\\\"\" 3]\n[4]").unwrap();
+
+        let guard = collector.lock().unwrap();
+        assert_eq!(
+            String::from_utf8(guard.to_json()).unwrap(),
+            r#"[{"idx":12,"byte":"3","path":"","context":"\" 3]\n"}]"#
+        );
+    }
+}
@@ -35,9 +35,15 @@ use crate::{error, path::Path, streamer::Token};
 use super::{Handler, HandlerOutput};
 
 /// A structure which groups handlers and determines a way how handlers are triggered
+///
+/// By default the handlers are chained - output of a converter
+/// is passed to the following handlers.
+/// In fan-out mode all handlers receive the same original data.
 #[derive(Default, Clone)]
 pub struct Group {
     handlers: Vec<Arc<Mutex<dyn Handler>>>,
+    /// Feed all handlers with the original data
+    fan_out: bool,
 }
 
 impl Group {
@@ -65,6 +71,20 @@ impl Group {
         self.handlers.push(handler);
     }
 
+    /// Sets fan-out mode (builder pattern)
+    ///
+    /// In fan-out mode every handler receives the same original data
+    /// and handlers are not chained. Output of converters is dropped
+    /// so the group itself is never a converter. Outputs emitted when
+    /// a JSON or the input is finished are concatenated.
+    ///
+    /// # Returns
+    /// * Group handler
+    pub fn fan_out(mut self) -> Self {
+        self.fan_out = true;
+        self
+    }
+
     /// Iterates through handlers
     pub fn subhandlers(&self) -> &[Arc<Mutex<dyn Handler>>] {
         &self.handlers
@@ -78,6 +98,15 @@ impl Handler for Group {
         matcher_idx: usize,
         token: Token,
     ) -> Result<Option<Vec<u8>>, error::Handler> {
+        if self.fan_out {
+            for handler in self.handlers.iter() {
+                handler
+                    .lock()
+                    .unwrap()
+                    .start(path, matcher_idx, token.clone())?;
+            }
+            return Ok(None);
+        }
         let mut result = None;
         for handler in self.handlers.iter() {
             let mut guard = handler.lock().unwrap();
@@ -106,6 +135,12 @@ impl Handler for Group {
     }
 
     fn feed(&mut self, data: &[u8], matcher_idx: usize) -> Result<Option<Vec<u8>>, error::Handler> {
+        if self.fan_out {
+            for handler in self.handlers.iter() {
+                handler.lock().unwrap().feed(data, matcher_idx)?;
+            }
+            return Ok(None);
+        }
         let mut result = Some(data.to_vec());
         for handler in self.handlers.iter() {
             let mut guard = handler.lock().unwrap();
@@ -130,6 +165,15 @@ impl Handler for Group {
         matcher_idx: usize,
         token: Token,
     ) -> Result<Option<Vec<u8>>, error::Handler> {
+        if self.fan_out {
+            for handler in self.handlers.iter() {
+                handler
+                    .lock()
+                    .unwrap()
+                    .end(path, matcher_idx, token.clone())?;
+            }
+            return Ok(None);
+        }
         let mut result: Option<Vec<u8>> = None;
         for handler in self.handlers.iter() {
             let mut guard = handler.lock().unwrap();
@@ -158,9 +202,11 @@ impl Handler for Group {
     }
 
     fn is_converter(&self) -> bool {
-        self.handlers
-            .iter()
-            .any(|e| e.lock().unwrap().is_converter())
+        !self.fan_out
+            && self
+                .handlers
+                .iter()
+                .any(|e| e.lock().unwrap().is_converter())
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -168,6 +214,15 @@ impl Handler for Group {
     }
 
     fn json_finished(&mut self) -> HandlerOutput {
+        if self.fan_out {
+            let mut result: Option<Vec<u8>> = None;
+            for handler in self.handlers.iter() {
+                if let Some(data) = handler.lock().unwrap().json_finished()? {
+                    result.get_or_insert_with(Vec::new).extend(data);
+                }
+            }
+            return Ok(result);
+        }
         let mut input: Option<Vec<u8>> = None;
         for handler in self.handlers.iter() {
             // If there are some input data feed the handler
@@ -191,6 +246,15 @@ impl Handler for Group {
     }
 
     fn input_finished(&mut self) -> HandlerOutput {
+        if self.fan_out {
+            let mut result: Option<Vec<u8>> = None;
+            for handler in self.handlers.iter() {
+                if let Some(data) = handler.lock().unwrap().input_finished()? {
+                    result.get_or_insert_with(Vec::new).extend(data);
+                }
+            }
+            return Ok(result);
+        }
         let mut input: Option<Vec<u8>> = None;
         for handler in self.handlers.iter() {
             // If there are some input data feed the handler
@@ -212,12 +276,45 @@ impl Handler for Group {
         }
         Ok(input)
     }
+
+    fn flush(&mut self) -> HandlerOutput {
+        if self.fan_out {
+            let mut result: Option<Vec<u8>> = None;
+            for handler in self.handlers.iter() {
+                if let Some(data) = handler.lock().unwrap().flush()? {
+                    result.get_or_insert_with(Vec::new).extend(data);
+                }
+            }
+            return Ok(result);
+        }
+        let mut input: Option<Vec<u8>> = None;
+        for handler in self.handlers.iter() {
+            // If there are some input data feed the handler
+            if let Some(input_data) = input.take() {
+                let mut guard = handler.lock().unwrap();
+                input = guard.feed(&input_data, 0)?;
+            }
+
+            // Trigger flush
+            let flushed_data = handler.lock().unwrap().flush()?;
+            if let Some(new_data) = flushed_data {
+                if let Some(mut prev_input) = input.take() {
+                    prev_input.extend(new_data);
+                    input = Some(prev_input);
+                } else {
+                    input = Some(new_data);
+                }
+            }
+        }
+        Ok(input)
+    }
 }
 
 impl ops::Add for Group {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
         let mut joined = Self::new();
+        joined.fan_out = self.fan_out && rhs.fan_out;
         self.subhandlers()
             .iter()
             .for_each(|h| joined.add_handler_mut(h.clone()));
@@ -233,7 +330,7 @@ impl ops::Add for Group {
 mod tests {
     use super::Group;
     use crate::{
-        handler::{Buffer, Replace, Shorten},
+        handler::{AnalyserJson, Buffer, Handler, Replace, Shorten},
         matcher::Simple,
         strategy::{Convert, Extract, Filter, OutputConverter, Strategy, Trigger},
     };
@@ -494,4 +591,75 @@ mod tests {
         );
         assert!(buffer3.lock().unwrap().pop().is_none());
     }
+
+    #[test]
+    fn test_fan_out() {
+        let (buffer1, buffer2, _, replace, shorten) = prepare_handlers();
+        let group = Group::new()
+            .fan_out()
+            .add_handler(buffer1.clone())
+            .add_handler(replace)
+            .add_handler(shorten)
+            .add_handler(buffer2.clone());
+
+        let mut trigger = Trigger::new();
+        trigger.add_matcher(
+            Box::new(Simple::new(r#"{"users"}[]"#).unwrap()),
+            Arc::new(Mutex::new(group)),
+        );
+        trigger.process(br#"{"users": ["first", "sec"#).unwrap();
+        trigger.process(br#"ond", "third"]}"#).unwrap();
+
+        // both buffers receive the original data
+        for buffer in &[buffer1, buffer2] {
+            let data: Vec<Vec<u8>> = buffer.lock().unwrap().drain_iter().map(|e| e.1).collect();
+            assert_eq!(
+                data,
+                vec![
+                    br#""first""#.to_vec(),
+                    br#""second""#.to_vec(),
+                    br#""third""#.to_vec()
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_fan_out_convert() {
+        let (buffer1, buffer2, _, replace, _) = prepare_handlers();
+        let group = Group::new()
+            .fan_out()
+            .add_handler(replace)
+            .add_handler(buffer1.clone())
+            .add_handler(buffer2.clone());
+
+        let mut convert = Convert::new();
+        convert.add_matcher(
+            Box::new(Simple::new(r#"[]"#).unwrap()),
+            Arc::new(Mutex::new(group)),
+        );
+        let output: Vec<u8> = OutputConverter::new()
+            .convert(&convert.process(br#"[1, "a"]"#).unwrap())
+            .into_iter()
+            .flat_map(|e| e.1)
+            .collect();
+
+        // fan-out group doesn't produce any output
+        assert_eq!(output, br#"[, ]"#.to_vec());
+        assert_eq!(buffer1.lock().unwrap().len(), 2);
+        assert_eq!(buffer2.lock().unwrap().pop().unwrap().1, b"1".to_vec());
+    }
+
+    #[test]
+    fn test_fan_out_finished() {
+        let mut group = Group::new()
+            .fan_out()
+            .add_handler(Arc::new(Mutex::new(AnalyserJson::new())))
+            .add_handler(Arc::new(Mutex::new(Buffer::new())))
+            .add_handler(Arc::new(Mutex::new(AnalyserJson::new())));
+
+        // outputs are concatenated
+        assert_eq!(group.input_finished().unwrap().unwrap(), b"{}{}".to_vec());
+        assert_eq!(group.json_finished().unwrap(), None);
+    }
 }
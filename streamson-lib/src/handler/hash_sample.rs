@@ -0,0 +1,270 @@
+//! Handler which stores a deterministic sample of matched data
+//!
+//! Whether the matched data are sampled depends only on the hash
+//! of the data. So the same values are always sampled regardless
+//! of their order or of the number of processed values.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! // roughly 10% of users
+//! let sample_handler = Arc::new(Mutex::new(handler::HashSample::new(0.1)));
+//!
+//! let matcher = matcher::Simple::new(r#"{"users"}[]"#).unwrap();
+//!
+//! let mut trigger = strategy::Trigger::new();
+//!
+//! // Set the matcher for trigger strategy
+//! trigger.add_matcher(Box::new(matcher), sample_handler.clone());
+//!
+//! for input in vec![
+//!     br#"{"users": [{"id": 1}, {"id": 2}, "#.to_vec(),
+//!     br#"{"id": 3}, {"id": 4}]}"#.to_vec(),
+//! ] {
+//!     trigger.process(&input).unwrap();
+//!     let mut guard = sample_handler.lock().unwrap();
+//!     while let Some((_, data)) = guard.pop() {
+//!         // Do something with the sampled data
+//!         println!("{}", String::from_utf8(data).unwrap());
+//!     }
+//! }
+//! ```
+
+use super::{buffer::Buff, Handler, HandlerOutput};
+use crate::{error, path::Path, streamer::Token};
+use std::{any::Any, collections::VecDeque, str::FromStr};
+
+/// Number of buckets the hashes are split into
+const BUCKETS: u64 = 1_000_000;
+
+/// FNV-1a hash (it doesn't depend on the platform nor on the compiler version)
+pub(super) fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Handler which stores only values with a hash within the sampled range
+pub struct HashSample {
+    /// Part of the values which should be sampled (0.0 - 1.0)
+    rate: f64,
+    /// For storing unterminated data
+    buffer: Vec<u8>,
+    /// Buffer idx to total index
+    buffer_idx: usize,
+    /// Indexes for the Path and size
+    buffer_parts: Vec<usize>,
+    /// Queue with sampled values in (path, data) format
+    results: VecDeque<(Option<String>, Vec<u8>)>,
+    /// Not to show path will spare some allocation
+    use_path: bool,
+    /// Current buffer size (in bytes)
+    current_buffer_size: usize,
+    /// Max buffer size
+    max_buffer_size: Option<usize>,
+}
+
+impl FromStr for HashSample {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let rate: f64 = input.parse().map_err(error::Handler::new)?;
+        if !(0.0..=1.0).contains(&rate) {
+            return Err(error::Handler::new(format!(
+                "Sample rate {} is not within 0.0 and 1.0",
+                rate
+            )));
+        }
+        Ok(Self::new(rate))
+    }
+}
+
+impl HashSample {
+    /// Creates a new handler which stores a sample of matched values
+    ///
+    /// # Arguments
+    /// * `rate` - part of the values which should be sampled (0.0 - 1.0)
+    pub fn new(rate: f64) -> Self {
+        assert!((0.0..=1.0).contains(&rate));
+        Self {
+            rate,
+            buffer: vec![],
+            buffer_idx: 0,
+            buffer_parts: vec![],
+            results: VecDeque::new(),
+            use_path: false,
+            current_buffer_size: 0,
+            max_buffer_size: None,
+        }
+    }
+
+    /// Set whether to show path
+    ///
+    /// # Arguments
+    /// * `use_path` - should path be store with data
+    pub fn set_use_path(mut self, use_path: bool) -> Self {
+        self.use_path = use_path;
+        self
+    }
+
+    /// Sets max buffer size
+    ///
+    /// # Arguments
+    /// * `max_size` - max size of buffered data (in bytes)
+    pub fn set_max_buffer_size(mut self, max_size: Option<usize>) -> Self {
+        self.max_buffer_size = max_size;
+        self
+    }
+
+    /// Pops the oldest sampled value
+    ///
+    /// # Returns
+    /// * `None` - queue is empty
+    /// * `Some((path, data))` - stored data remove from the queue and returned
+    pub fn pop(&mut self) -> Option<(Option<String>, Vec<u8>)> {
+        let popped = self.results.pop_front();
+        if popped.is_some() {
+            self.current_buffer_size =
+                self.results.iter().fold(0, |e, y| e + y.1.len()) + self.buffer.len();
+        }
+        popped
+    }
+
+    /// Decides whether the value should be sampled
+    ///
+    /// # Arguments
+    /// * `data` - raw data of the value
+    pub fn is_sampled(&self, data: &[u8]) -> bool {
+        fnv1a(data) % BUCKETS < (self.rate * BUCKETS as f64) as u64
+    }
+}
+
+impl Handler for HashSample {
+    fn start(&mut self, path: &Path, matcher_idx: usize, token: Token) -> HandlerOutput {
+        self._start(path, matcher_idx, token)
+    }
+
+    fn feed(&mut self, data: &[u8], matcher_idx: usize) -> HandlerOutput {
+        self._feed(data, matcher_idx)
+    }
+
+    fn end(&mut self, path: &Path, matcher_idx: usize, token: Token) -> HandlerOutput {
+        self._end(path, matcher_idx, token)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Buff for HashSample {
+    fn store_result(&mut self, path: &Path, data: Vec<u8>) {
+        if !self.is_sampled(&data) {
+            return;
+        }
+        let use_path = *self.use_path();
+        self.results.push_back((
+            if use_path {
+                Some(path.to_string())
+            } else {
+                None
+            },
+            data,
+        ));
+    }
+
+    fn buffer(&mut self) -> &mut Vec<u8> {
+        &mut self.buffer
+    }
+
+    fn buffer_parts(&mut self) -> &mut Vec<usize> {
+        &mut self.buffer_parts
+    }
+
+    fn buffer_idx(&mut self) -> &mut usize {
+        &mut self.buffer_idx
+    }
+
+    fn max_buffer_size(&mut self) -> &mut Option<usize> {
+        &mut self.max_buffer_size
+    }
+
+    fn current_buffer_size(&mut self) -> &mut usize {
+        &mut self.current_buffer_size
+    }
+
+    fn use_path(&mut self) -> &mut bool {
+        &mut self.use_path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HashSample;
+    use crate::{
+        matcher::Simple,
+        strategy::{Strategy, Trigger},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    fn sample(rate: f64, input: &[u8]) -> Vec<Vec<u8>> {
+        let mut trigger = Trigger::new();
+        let handler = Arc::new(Mutex::new(HashSample::new(rate)));
+        trigger.add_matcher(Box::new(Simple::new(r#"[]"#).unwrap()), handler.clone());
+        trigger.process(input).unwrap();
+
+        let mut guard = handler.lock().unwrap();
+        let mut res = vec![];
+        while let Some((_, data)) = guard.pop() {
+            res.push(data);
+        }
+        res
+    }
+
+    fn numbers<I: Iterator<Item = usize>>(iter: I) -> Vec<u8> {
+        let items: Vec<String> = iter.map(|e| e.to_string()).collect();
+        format!("[{}]", items.join(", ")).into_bytes()
+    }
+
+    #[test]
+    fn stable() {
+        let first = sample(0.3, &numbers(0..1000));
+        assert_eq!(first, sample(0.3, &numbers(0..1000)));
+
+        // order independent
+        let mut reversed = sample(0.3, &numbers((0..1000).rev()));
+        reversed.reverse();
+        assert_eq!(first, reversed);
+
+        // the same values are sampled within a larger input
+        let larger = sample(0.3, &numbers(0..2000));
+        assert_eq!(first[..], larger[..first.len()]);
+
+        // larger rate samples a superset
+        let more = sample(0.6, &numbers(0..1000));
+        assert!(first.iter().all(|e| more.contains(e)));
+    }
+
+    #[test]
+    fn rate() {
+        let input = numbers(0..10000);
+        assert!(sample(0.0, &input).is_empty());
+        assert_eq!(sample(1.0, &input).len(), 10000);
+
+        let count = sample(0.25, &input).len();
+        assert!((2250..=2750).contains(&count), "{}", count);
+    }
+
+    #[test]
+    fn parse() {
+        assert!(HashSample::from_str("0.5").is_ok());
+        assert!(HashSample::from_str("1").is_ok());
+        assert!(HashSample::from_str("1.5").is_err());
+        assert!(HashSample::from_str("-0.1").is_err());
+        assert!(HashSample::from_str("x").is_err());
+    }
+}
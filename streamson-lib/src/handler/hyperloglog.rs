@@ -0,0 +1,240 @@
+//! Handler which estimates the number of distinct matched values
+//!
+//! It uses a HyperLogLog sketch so the values themselves are not stored.
+//! The estimate is emitted once the input is finished.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(handler::HyperLogLog::new()));
+//! let matcher = matcher::Simple::new(r#"{"users"}[]{"name"}"#).unwrap();
+//!
+//! let mut trigger = strategy::Trigger::new();
+//!
+//! trigger.add_matcher(Box::new(matcher), handler.clone());
+//!
+//! for input in vec![
+//!     br#"{"users": [{"name": "first"}, {"name": "second"}, "#.to_vec(),
+//!     br#"{"name": "first"}]}"#.to_vec(),
+//! ] {
+//!     trigger.process(&input).unwrap();
+//! }
+//!
+//! assert_eq!(handler.lock().unwrap().estimate().round(), 2.0);
+//! ```
+
+use super::{hash_sample::fnv1a, Handler, HandlerOutput};
+use crate::{error, path::Path, streamer::Token};
+use std::{any::Any, mem, str::FromStr};
+
+/// Default number of bits used to select a register
+const DEFAULT_PRECISION: u8 = 12;
+
+/// Handler which estimates cardinality of matched values
+///
+/// Matched values are compared as they are written in the input
+/// so e.g. `1` and `1.0` are distinct values.
+/// The standard error of the estimate is about `1.04 / sqrt(2^precision)`.
+#[derive(Debug)]
+pub struct HyperLogLog {
+    /// Number of bits used to select a register
+    precision: u8,
+    /// Maximal ranks per register
+    registers: Vec<u8>,
+    /// Currently matched data
+    buffer: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self {
+            precision: DEFAULT_PRECISION,
+            registers: vec![0; 1 << DEFAULT_PRECISION],
+            buffer: vec![],
+        }
+    }
+}
+
+impl HyperLogLog {
+    /// Creates a new handler with the default precision (12)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the precision of the sketch
+    ///
+    /// Higher precision means more accurate estimate and more memory used
+    /// (`2^precision` bytes).
+    ///
+    /// # Arguments
+    /// * `precision` - number of bits used to select a register (4 - 16)
+    ///
+    /// # Panics
+    /// If the precision is out of the range
+    pub fn set_precision(mut self, precision: u8) -> Self {
+        assert!(
+            (4..=16).contains(&precision),
+            "precision has to be within 4 - 16"
+        );
+        self.precision = precision;
+        self.registers = vec![0; 1 << precision];
+        self
+    }
+
+    /// Adds a value to the sketch
+    fn add(&mut self, data: &[u8]) {
+        let hash = mix(fnv1a(data));
+        let idx = (hash >> (64 - self.precision)) as usize;
+        // the guard bit limits the rank when the remaining bits are zeros
+        let rest = (hash << self.precision) | (1 << (self.precision - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        if self.registers[idx] < rank {
+            self.registers[idx] = rank;
+        }
+    }
+
+    /// Returns the estimated number of distinct values
+    pub fn estimate(&self) -> f64 {
+        let count = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / count),
+        };
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|rank| 2f64.powi(-i32::from(*rank)))
+            .sum();
+        let estimate = alpha * count * count / sum;
+
+        let zeros = self.registers.iter().filter(|rank| **rank == 0).count();
+        if estimate <= 2.5 * count && zeros > 0 {
+            // linear counting is more accurate for small cardinalities
+            count * (count / zeros as f64).ln()
+        } else {
+            estimate
+        }
+    }
+}
+
+/// Spreads the bits of the hash (splitmix64 finalizer)
+///
+/// FNV hashes of similar values differ mostly in lower bits.
+fn mix(mut hash: u64) -> u64 {
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+impl FromStr for HyperLogLog {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() {
+            return Ok(Self::default());
+        }
+        let precision: u8 = input.parse().map_err(error::Handler::new)?;
+        if (4..=16).contains(&precision) {
+            Ok(Self::default().set_precision(precision))
+        } else {
+            Err(error::Handler::new("Precision has to be within 4 - 16"))
+        }
+    }
+}
+
+impl Handler for HyperLogLog {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        self.buffer.clear();
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        let data = mem::take(&mut self.buffer);
+        self.add(&data);
+        Ok(None)
+    }
+
+    fn input_finished(&mut self) -> HandlerOutput {
+        Ok(Some(format!("{}", self.estimate().round()).into_bytes()))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HyperLogLog;
+    use crate::{
+        matcher::Simple,
+        strategy::{Output, Strategy, Trigger},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    fn estimate(handler: HyperLogLog, input: &[u8]) -> (f64, Vec<Output>) {
+        let handler = Arc::new(Mutex::new(handler));
+        let mut trigger = Trigger::new();
+        trigger.add_matcher(Box::new(Simple::new(r#"[]"#).unwrap()), handler.clone());
+        for part in input.chunks(100) {
+            trigger.process(part).unwrap();
+        }
+        let output = trigger.terminate().unwrap();
+        let estimate = handler.lock().unwrap().estimate();
+        (estimate, output)
+    }
+
+    #[test]
+    fn large() {
+        // 20000 distinct values each repeated twice
+        let values: Vec<String> = (0..40000)
+            .map(|idx| format!(r#"{{"id": {}}}"#, idx % 20000))
+            .collect();
+        let input = format!("[{}]", values.join(", "));
+
+        let (value, output) = estimate(HyperLogLog::new(), input.as_bytes());
+        assert!((value - 20000.0).abs() < 20000.0 * 0.05, "{}", value);
+        assert_eq!(
+            output,
+            vec![Output::Data(format!("{}", value.round()).into_bytes())]
+        );
+
+        let (value, _) = estimate(HyperLogLog::new().set_precision(6), input.as_bytes());
+        assert!((value - 20000.0).abs() < 20000.0 * 0.4, "{}", value);
+    }
+
+    #[test]
+    fn small() {
+        let (value, _) = estimate(
+            HyperLogLog::new(),
+            br#"["a", "b", "c", "a", 1, 1, null, {"a": 1}, {"a": 1}]"#,
+        );
+        assert_eq!(value.round(), 6.0);
+
+        let (value, _) = estimate(HyperLogLog::new(), b"[]");
+        assert_eq!(value, 0.0);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(HyperLogLog::from_str("").unwrap().precision, 12);
+        assert_eq!(
+            HyperLogLog::from_str("14").unwrap().registers.len(),
+            1 << 14
+        );
+        assert!(HyperLogLog::from_str("3").is_err());
+        assert!(HyperLogLog::from_str("17").is_err());
+        assert!(HyperLogLog::from_str("x").is_err());
+    }
+}
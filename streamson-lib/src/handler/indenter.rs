@@ -37,6 +37,10 @@ pub struct Indenter {
     spaces: Option<usize>,
     /// Currently processed element on each level
     stack: Option<Vec<(usize, ParsedKind)>>,
+    /// Should object keys be sorted
+    sort_keys: bool,
+    /// Entries (key, data) of currently processed objects (used for sorting only)
+    entries: Vec<Vec<(String, Vec<u8>)>>,
 }
 
 impl Indenter {
@@ -48,9 +52,47 @@ impl Indenter {
         Self {
             spaces,
             stack: None,
+            sort_keys: false,
+            entries: vec![],
         }
     }
 
+    /// Sets whether object keys should be sorted
+    ///
+    /// Note that entire objects has to be buffered in order to sort the keys.
+    ///
+    /// # Arguments
+    /// * `sort_keys` - should keys be sorted
+    pub fn set_sort_keys(mut self, sort_keys: bool) -> Self {
+        self.sort_keys = sort_keys;
+        self
+    }
+
+    /// Writes data to the output or to the currently buffered object entry
+    fn write(&mut self, res: &mut Vec<u8>, data: &[u8]) {
+        if let Some(entry) = self.entries.last_mut().and_then(|e| e.last_mut()) {
+            entry.1.extend(data);
+        } else {
+            res.extend(data);
+        }
+    }
+
+    /// Sorts buffered object entries and joins them together
+    fn sorted_entries(&self, mut entries: Vec<(String, Vec<u8>)>) -> Vec<u8> {
+        entries.sort_by(|first, second| first.0.cmp(&second.0));
+        let mut content = vec![];
+        for (idx, (_, data)) in entries.into_iter().enumerate() {
+            if idx != 0 {
+                content.push(b',');
+            }
+            if self.spaces.is_some() {
+                content.push(b'\n');
+            }
+            content.extend(data);
+        }
+        content
+    }
+
     fn write_indent_level(&self, buff: &mut Vec<u8>) {
         if let Some(stack) = self.stack.as_ref() {
             for _ in 0..(stack.len() - 1) * self.spaces.unwrap_or(0) {
@@ -86,16 +128,16 @@ impl Handler for Indenter {
             unreachable![];
         };
 
-        let mut res = vec![];
+        let mut separator = vec![];
         self.stack = if let Some(mut stack) = self.stack.take() {
             stack.push((0, kind));
             // We need to add separators for nested elements
             if stack.len() > 1 {
                 if stack[stack.len() - 2].0 != 0 {
-                    res.push(b',');
+                    separator.push(b',');
                 }
                 if self.spaces.is_some() {
-                    res.push(b'\n');
+                    separator.push(b'\n');
                 }
             }
             Some(stack)
@@ -104,19 +146,22 @@ impl Handler for Indenter {
             Some(vec![(0, kind)])
         };
 
-        self.write_indent_level(&mut res);
+        let mut body = vec![];
+        let mut parent_key = None;
+        self.write_indent_level(&mut body);
         // stack  should have at least one element now
         let stack = self.stack.as_ref().unwrap();
         if stack.len() > 1 {
             // Write key of parent object
             if matches!(stack[stack.len() - 2].1, ParsedKind::Obj) {
                 if let Element::Key(key) = &path.get_path()[path.depth() - 1] {
-                    res.push(b'"');
-                    res.extend(key.as_bytes());
-                    res.extend(br#"":"#);
+                    body.push(b'"');
+                    body.extend(key.as_bytes());
+                    body.extend(br#"":"#);
                     if self.spaces.is_some() {
-                        res.push(b' ');
+                        body.push(b' ');
                     }
+                    parent_key = Some(key.clone());
                 } else {
                     unreachable!();
                 }
@@ -125,14 +170,33 @@ impl Handler for Indenter {
 
         match kind {
             ParsedKind::Arr => {
-                res.push(b'[');
+                body.push(b'[');
             }
             ParsedKind::Obj => {
-                res.push(b'{');
+                body.push(b'{');
             }
             _ => {}
         }
 
+        let mut res = vec![];
+        match parent_key {
+            Some(key) if self.sort_keys => {
+                // separators are added when the entries are sorted
+                if let Some(entries) = self.entries.last_mut() {
+                    entries.push((key, vec![]));
+                }
+                self.write(&mut res, &body);
+            }
+            _ => {
+                self.write(&mut res, &separator);
+                self.write(&mut res, &body);
+            }
+        }
+
+        if self.sort_keys && matches!(kind, ParsedKind::Obj) {
+            self.entries.push(vec![]);
+        }
+
         if res.is_empty() {
             Ok(None)
         } else {
@@ -151,7 +215,7 @@ impl Handler for Indenter {
                 match kind {
                     ParsedKind::Obj | ParsedKind::Arr => {}
                     _ => {
-                        result.extend(data.to_vec());
+                        self.write(&mut result, data);
                     }
                 }
             }
@@ -171,27 +235,36 @@ impl Handler for Indenter {
             unreachable![];
         };
 
-        let mut res = vec![];
+        let mut closing = vec![];
         if let Some(stack) = self.stack.as_ref() {
             match kind {
                 ParsedKind::Arr => {
                     if stack.last().unwrap().0 != 0 && self.spaces.is_some() {
-                        res.push(b'\n');
-                        self.write_indent_level(&mut res);
+                        closing.push(b'\n');
+                        self.write_indent_level(&mut closing);
                     }
-                    res.push(b']');
+                    closing.push(b']');
                 }
                 ParsedKind::Obj => {
                     if stack.last().unwrap().0 != 0 && self.spaces.is_some() {
-                        res.push(b'\n');
-                        self.write_indent_level(&mut res);
+                        closing.push(b'\n');
+                        self.write_indent_level(&mut closing);
                     }
-                    res.push(b'}');
+                    closing.push(b'}');
                 }
                 _ => {}
             };
         }
 
+        let mut res = vec![];
+        if self.sort_keys && matches!(kind, ParsedKind::Obj) {
+            // write sorted entries of the object
+            let entries = self.entries.pop().unwrap_or_default();
+            let content = self.sorted_entries(entries);
+            self.write(&mut res, &content);
+        }
+        self.write(&mut res, &closing);
+
         if let Some(stack) = self.stack.as_mut() {
             // remove item from stack and increase parent count
             stack.pop();
@@ -202,8 +275,8 @@ impl Handler for Indenter {
 
             // finish newline
             if stack.is_empty() && self.spaces.is_some() {
-                res.push(b'\n');
                 self.stack = None;
+                self.write(&mut res, b"\n");
             }
         }
 
@@ -214,6 +287,22 @@ impl Handler for Indenter {
         }
     }
 
+    fn flush(&mut self) -> Result<Option<Vec<u8>>, error::Handler> {
+        // write entries of the unfinished objects (starting with the innermost one)
+        let mut res = vec![];
+        while let Some(entries) = self.entries.pop() {
+            let content = self.sorted_entries(entries);
+            self.write(&mut res, &content);
+        }
+        self.stack = None;
+
+        if res.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(res))
+        }
+    }
+
     fn is_converter(&self) -> bool {
         true
     }
@@ -400,4 +489,69 @@ mod tests {
             OutputConverter::new().convert(&all.process(&input).unwrap())[0].1
         );
     }
+
+    #[test]
+    fn sort_keys() {
+        let input =
+            br#"{"b": [{"y": 1, "x": 2}], "c": {}, "a": {"d": null, "c": {"f": 1, "e": "2"}}}"#
+                .to_vec();
+
+        // No indentation or spaces
+        let mut all = All::new();
+        all.set_convert(true);
+        all.add_handler(Arc::new(Mutex::new(
+            Indenter::new(None).set_sort_keys(true),
+        )));
+        assert_eq!(
+            String::from_utf8(
+                OutputConverter::new().convert(&all.process(&input).unwrap())[0]
+                    .1
+                    .clone()
+            )
+            .unwrap(),
+            r#"{"a":{"c":{"e":"2","f":1},"d":null},"b":[{"x":2,"y":1}],"c":{}}"#
+        );
+
+        // 2 indentation
+        let mut all = All::new();
+        all.set_convert(true);
+        all.add_handler(Arc::new(Mutex::new(
+            Indenter::new(Some(2)).set_sort_keys(true),
+        )));
+        let mut output = vec![];
+        let mut converter = OutputConverter::new();
+        for part in input.chunks(3) {
+            output.extend(
+                converter
+                    .convert(&all.process(part).unwrap())
+                    .into_iter()
+                    .flat_map(|e| e.1),
+            );
+        }
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\n  \"a\": {\n    \"c\": {\n      \"e\": \"2\",\n      \"f\": 1\n    },\n    \"d\": null\n  },\n  \"b\": [\n    {\n      \"x\": 2,\n      \"y\": 1\n    }\n  ],\n  \"c\": {}\n}\n"
+        );
+    }
+
+    #[test]
+    fn flush() {
+        let mut all = All::new();
+        all.set_convert(true);
+        all.set_flush_incomplete(true);
+        all.add_handler(Arc::new(Mutex::new(
+            Indenter::new(Some(2)).set_sort_keys(true),
+        )));
+        let mut converter = OutputConverter::new();
+        let mut output = converter.convert(
+            &all.process(br#"{"b": 1, "a": {"d": [1, 2], "c": {"#)
+                .unwrap(),
+        );
+        output.extend(converter.convert(&all.terminate().unwrap()));
+        assert_eq!(output.len(), 1);
+        assert_eq!(
+            String::from_utf8(output[0].1.clone()).unwrap(),
+            "{\n  \"a\": {\n    \"c\": {,\n    \"d\": [\n      1,\n      2\n    ],\n  \"b\": 1"
+        );
+    }
 }
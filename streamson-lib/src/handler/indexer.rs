@@ -187,7 +187,7 @@ mod tests {
         );
         assert_eq!(
             guard.pop().unwrap(),
-            (None, Token::End(15, ParsedKind::Num))
+            (None, Token::End(15, ParsedKind::Int))
         );
         assert_eq!(
             guard.pop().unwrap(),
@@ -195,7 +195,7 @@ mod tests {
         );
         assert_eq!(
             guard.pop().unwrap(),
-            (None, Token::End(18, ParsedKind::Num))
+            (None, Token::End(18, ParsedKind::Int))
         );
         assert_eq!(
             guard.pop().unwrap(),
@@ -203,7 +203,7 @@ mod tests {
         );
         assert_eq!(
             guard.pop().unwrap(),
-            (None, Token::End(21, ParsedKind::Num))
+            (None, Token::End(21, ParsedKind::Int))
         );
         assert_eq!(
             guard.pop().unwrap(),
@@ -211,7 +211,7 @@ mod tests {
         );
         assert_eq!(
             guard.pop().unwrap(),
-            (None, Token::End(24, ParsedKind::Num))
+            (None, Token::End(24, ParsedKind::Int))
         );
         assert_eq!(
             guard.pop().unwrap(),
@@ -0,0 +1,232 @@
+//! Handler which converts matched data to JSON Patch (RFC 6902)
+//! against a baseline value
+//!
+//! `{"a": 1, "b": 2}` with baseline `{"a": 1, "b": 1}` -> `[{"op":"replace","path":"/b","value":2}]`
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let baseline = serde_json::json!({"name": "first", "active": true});
+//! let handler = Arc::new(Mutex::new(handler::JsonPatch::new(baseline)));
+//! let matcher = matcher::Simple::new(r#"{"users"}[]"#).unwrap();
+//!
+//! let mut convert = strategy::Convert::new();
+//!
+//! // Set the matcher for convert strategy
+//! convert.add_matcher(Box::new(matcher), handler);
+//!
+//! for input in vec![
+//!     br#"{"users": [{"name": "first", "active": true}, {"#.to_vec(),
+//!     br#""name": "second", "active": true}]}"#.to_vec(),
+//! ] {
+//!     for converted_data in convert.process(&input).unwrap() {
+//!         println!("{:?}", converted_data);
+//!     }
+//! }
+//! ```
+
+use super::Handler;
+use crate::{error, path::Path, streamer::Token};
+use serde_json::{json, Value};
+use std::{any::Any, str::FromStr};
+
+/// Handler which replaces matched data with a JSON Patch
+/// which converts the baseline to the matched data
+#[derive(Debug)]
+pub struct JsonPatch {
+    /// Value against which the patch is computed
+    baseline: Value,
+    /// Buffer to collect matched data
+    buffer: Vec<u8>,
+}
+
+impl JsonPatch {
+    /// Creates a new handler which converts matched data to JSON Patch
+    ///
+    /// # Arguments
+    /// * `baseline` - value against which the patch is computed
+    pub fn new(baseline: Value) -> Self {
+        Self {
+            baseline,
+            buffer: vec![],
+        }
+    }
+}
+
+impl FromStr for JsonPatch {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(
+            serde_json::from_str(input).map_err(error::Handler::new)?,
+        ))
+    }
+}
+
+/// Escapes a key to be used in JSON Pointer
+fn escape_key(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Computes patch operations which convert `source` to `target`
+fn diff(source: &Value, target: &Value, pointer: &str, operations: &mut Vec<Value>) {
+    match (source, target) {
+        (Value::Object(source_map), Value::Object(target_map)) => {
+            for key in source_map.keys() {
+                if !target_map.contains_key(key) {
+                    operations.push(json!({
+                        "op": "remove",
+                        "path": format!("{}/{}", pointer, escape_key(key)),
+                    }));
+                }
+            }
+            for (key, target_value) in target_map {
+                let path = format!("{}/{}", pointer, escape_key(key));
+                if let Some(source_value) = source_map.get(key) {
+                    diff(source_value, target_value, &path, operations);
+                } else {
+                    operations.push(json!({"op": "add", "path": path, "value": target_value}));
+                }
+            }
+        }
+        (Value::Array(source_arr), Value::Array(target_arr)) => {
+            let common = source_arr.len().min(target_arr.len());
+            for idx in 0..common {
+                let path = format!("{}/{}", pointer, idx);
+                diff(&source_arr[idx], &target_arr[idx], &path, operations);
+            }
+            // remove from the end so the indexes remain valid
+            for idx in (common..source_arr.len()).rev() {
+                operations.push(json!({
+                    "op": "remove",
+                    "path": format!("{}/{}", pointer, idx),
+                }));
+            }
+            for (idx, target_value) in target_arr.iter().enumerate().skip(common) {
+                operations.push(json!({
+                    "op": "add",
+                    "path": format!("{}/{}", pointer, idx),
+                    "value": target_value,
+                }));
+            }
+        }
+        (source, target) => {
+            if source != target {
+                operations.push(json!({"op": "replace", "path": pointer, "value": target}));
+            }
+        }
+    }
+}
+
+impl Handler for JsonPatch {
+    fn start(
+        &mut self,
+        _path: &Path,
+        _matcher_idx: usize,
+        _token: Token,
+    ) -> Result<Option<Vec<u8>>, error::Handler> {
+        self.buffer.clear();
+        Ok(None)
+    }
+
+    fn feed(
+        &mut self,
+        data: &[u8],
+        _matcher_idx: usize,
+    ) -> Result<Option<Vec<u8>>, error::Handler> {
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(
+        &mut self,
+        _path: &Path,
+        _matcher_idx: usize,
+        _token: Token,
+    ) -> Result<Option<Vec<u8>>, error::Handler> {
+        let target: Value = serde_json::from_slice(&self.buffer).map_err(error::Handler::new)?;
+        self.buffer.clear();
+
+        let mut operations = vec![];
+        diff(&self.baseline, &target, "", &mut operations);
+
+        Ok(Some(
+            serde_json::to_vec(&Value::Array(operations)).map_err(error::Handler::new)?,
+        ))
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonPatch;
+    use crate::{
+        matcher::Simple,
+        strategy::{Convert, OutputConverter, Strategy},
+    };
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+
+    fn convert(baseline: Value, input: &[u8]) -> Value {
+        let mut convert = Convert::new();
+        let handler = Arc::new(Mutex::new(JsonPatch::new(baseline)));
+        let matcher = Simple::new(r#"{"item"}"#).unwrap();
+        convert.add_matcher(Box::new(matcher), handler);
+
+        let output: Vec<u8> = OutputConverter::new()
+            .convert(&convert.process(input).unwrap())
+            .into_iter()
+            .flat_map(|e| e.1)
+            .collect();
+        serde_json::from_slice(&output).unwrap()
+    }
+
+    #[test]
+    fn add_replace() {
+        let output = convert(
+            json!({"name": "first", "tags": ["a"]}),
+            br#"{"item": {"name": "second", "tags": ["a", "b"], "new/key": 1}}"#,
+        );
+        assert_eq!(
+            output,
+            json!({"item": [
+                {"op": "replace", "path": "/name", "value": "second"},
+                {"op": "add", "path": "/new~1key", "value": 1},
+                {"op": "add", "path": "/tags/1", "value": "b"},
+            ]})
+        );
+    }
+
+    #[test]
+    fn remove() {
+        let output = convert(
+            json!({"name": "first", "tags": ["a", "b", "c"]}),
+            br#"{"item": {"tags": ["a"]}}"#,
+        );
+        assert_eq!(
+            output,
+            json!({"item": [
+                {"op": "remove", "path": "/name"},
+                {"op": "remove", "path": "/tags/2"},
+                {"op": "remove", "path": "/tags/1"},
+            ]})
+        );
+    }
+
+    #[test]
+    fn unchanged() {
+        let output = convert(
+            json!({"a": [1, {"b": null}]}),
+            br#"{"item": {"a": [1, {"b": null}]}}"#,
+        );
+        assert_eq!(output, json!({ "item": [] }));
+    }
+}
@@ -0,0 +1,296 @@
+//! Handler which stores only matched values which are valid against a regex for their kind
+//!
+//! Strings are validated using their decoded (unquoted) value,
+//! other kinds using their raw data.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}, streamer::ParsedKind};
+//! use std::{collections::HashMap, sync::{Arc, Mutex}};
+//!
+//! let mut regexes = HashMap::new();
+//! regexes.insert(ParsedKind::Str, regex::Regex::new(r"^[^@\s]+@[^@\s]+$").unwrap());
+//!
+//! let filter_handler = Arc::new(Mutex::new(handler::KindRegexFilter::new(regexes)));
+//! let matcher = matcher::Simple::new(r#"{"users"}[]{"email"}"#).unwrap();
+//!
+//! let mut trigger = strategy::Trigger::new();
+//!
+//! // Set the matcher for trigger strategy
+//! trigger.add_matcher(Box::new(matcher), filter_handler.clone());
+//!
+//! for input in vec![
+//!     br#"{"users": [{"email": "first@example.com"}, "#.to_vec(),
+//!     br#"{"email": "invalid"}]}"#.to_vec(),
+//! ] {
+//!     trigger.process(&input).unwrap();
+//!     let mut guard = filter_handler.lock().unwrap();
+//!     while let Some((_, data)) = guard.pop() {
+//!         // Do something with the valid data
+//!         println!("{}", String::from_utf8(data).unwrap());
+//!     }
+//! }
+//! ```
+
+use super::{buffer::Buff, Handler, HandlerOutput};
+use crate::{
+    path::Path,
+    streamer::{unescape, ParsedKind, Token},
+};
+use std::{
+    any::Any,
+    collections::{HashMap, VecDeque},
+    str,
+};
+
+/// Handler which stores matched values which are valid against the regex for their kind
+///
+/// Numbers can be validated based on their exact kind (`Int` or `Float`),
+/// `Num` regex is used for all numbers otherwise.
+/// Values of a kind without a regex are never valid.
+pub struct KindRegexFilter {
+    /// Regexes used to validate the values
+    regexes: HashMap<ParsedKind, regex::Regex>,
+    /// Kinds of currently matched values
+    kinds: Vec<ParsedKind>,
+    /// For storing unterminated data
+    buffer: Vec<u8>,
+    /// Buffer idx to total index
+    buffer_idx: usize,
+    /// Indexes for the Path and size
+    buffer_parts: Vec<usize>,
+    /// Queue with valid values in (path, data) format
+    results: VecDeque<(Option<String>, Vec<u8>)>,
+    /// Not to show path will spare some allocation
+    use_path: bool,
+    /// Current buffer size (in bytes)
+    current_buffer_size: usize,
+    /// Max buffer size
+    max_buffer_size: Option<usize>,
+}
+
+impl KindRegexFilter {
+    /// Creates a new handler which stores only valid values
+    ///
+    /// # Arguments
+    /// * `regexes` - regexes which will be used for the given kind
+    pub fn new(regexes: HashMap<ParsedKind, regex::Regex>) -> Self {
+        Self {
+            regexes,
+            kinds: vec![],
+            buffer: vec![],
+            buffer_idx: 0,
+            buffer_parts: vec![],
+            results: VecDeque::new(),
+            use_path: false,
+            current_buffer_size: 0,
+            max_buffer_size: None,
+        }
+    }
+
+    /// Set whether to show path
+    ///
+    /// # Arguments
+    /// * `use_path` - should path be store with data
+    pub fn set_use_path(mut self, use_path: bool) -> Self {
+        self.use_path = use_path;
+        self
+    }
+
+    /// Sets max buffer size
+    ///
+    /// # Arguments
+    /// * `max_size` - max size of buffered data (in bytes)
+    pub fn set_max_buffer_size(mut self, max_size: Option<usize>) -> Self {
+        self.max_buffer_size = max_size;
+        self
+    }
+
+    /// Pops the oldest valid value
+    ///
+    /// # Returns
+    /// * `None` - queue is empty
+    /// * `Some((path, data))` - stored data remove from the queue and returned
+    pub fn pop(&mut self) -> Option<(Option<String>, Vec<u8>)> {
+        let popped = self.results.pop_front();
+        if popped.is_some() {
+            self.current_buffer_size =
+                self.results.iter().fold(0, |e, y| e + y.1.len()) + self.buffer.len();
+        }
+        popped
+    }
+
+    /// Decides whether the value is valid
+    ///
+    /// # Arguments
+    /// * `kind` - kind of the value
+    /// * `data` - raw data of the value
+    pub fn is_valid(&self, kind: ParsedKind, data: &[u8]) -> bool {
+        let regex = if let Some(regex) = self.regexes.get(&kind).or_else(|| match kind {
+            ParsedKind::Int | ParsedKind::Float => self.regexes.get(&ParsedKind::Num),
+            _ => None,
+        }) {
+            regex
+        } else {
+            return false;
+        };
+        if kind == ParsedKind::Str {
+            if data.len() < 2 {
+                return false;
+            }
+            unescape(&data[1..data.len() - 1])
+                .map(|decoded| regex.is_match(&decoded))
+                .unwrap_or(false)
+        } else {
+            str::from_utf8(data)
+                .map(|data| regex.is_match(data))
+                .unwrap_or(false)
+        }
+    }
+}
+
+impl Handler for KindRegexFilter {
+    fn start(&mut self, path: &Path, matcher_idx: usize, token: Token) -> HandlerOutput {
+        if let Token::Start(_, kind) = token {
+            self.kinds.push(kind);
+        }
+        self._start(path, matcher_idx, token)
+    }
+
+    fn feed(&mut self, data: &[u8], matcher_idx: usize) -> HandlerOutput {
+        self._feed(data, matcher_idx)
+    }
+
+    fn end(&mut self, path: &Path, matcher_idx: usize, token: Token) -> HandlerOutput {
+        // exact kind of numbers is known at the end
+        if let (Token::End(_, kind), Some(last)) = (&token, self.kinds.last_mut()) {
+            *last = *kind;
+        }
+        let output = self._end(path, matcher_idx, token);
+        self.kinds.pop();
+        output
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Buff for KindRegexFilter {
+    fn store_result(&mut self, path: &Path, data: Vec<u8>) {
+        let valid = self
+            .kinds
+            .last()
+            .map(|kind| self.is_valid(*kind, &data))
+            .unwrap_or(false);
+        if !valid {
+            return;
+        }
+        let use_path = *self.use_path();
+        self.results.push_back((
+            if use_path {
+                Some(path.to_string())
+            } else {
+                None
+            },
+            data,
+        ));
+    }
+
+    fn buffer(&mut self) -> &mut Vec<u8> {
+        &mut self.buffer
+    }
+
+    fn buffer_parts(&mut self) -> &mut Vec<usize> {
+        &mut self.buffer_parts
+    }
+
+    fn buffer_idx(&mut self) -> &mut usize {
+        &mut self.buffer_idx
+    }
+
+    fn max_buffer_size(&mut self) -> &mut Option<usize> {
+        &mut self.max_buffer_size
+    }
+
+    fn current_buffer_size(&mut self) -> &mut usize {
+        &mut self.current_buffer_size
+    }
+
+    fn use_path(&mut self) -> &mut bool {
+        &mut self.use_path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KindRegexFilter;
+    use crate::{
+        matcher::Simple,
+        strategy::{Strategy, Trigger},
+        streamer::ParsedKind,
+    };
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    fn valid(regexes: &[(ParsedKind, &str)], matcher: &str, input: &[u8]) -> Vec<Vec<u8>> {
+        let regexes: HashMap<ParsedKind, regex::Regex> = regexes
+            .iter()
+            .map(|(kind, regex)| (*kind, regex::Regex::new(regex).unwrap()))
+            .collect();
+        let handler = Arc::new(Mutex::new(KindRegexFilter::new(regexes)));
+        let mut trigger = Trigger::new();
+        trigger.add_matcher(Box::new(Simple::new(matcher).unwrap()), handler.clone());
+        for part in input.chunks(4) {
+            trigger.process(part).unwrap();
+        }
+
+        let mut guard = handler.lock().unwrap();
+        let mut res = vec![];
+        while let Some((_, data)) = guard.pop() {
+            res.push(data);
+        }
+        res
+    }
+
+    #[test]
+    fn emails() {
+        let output = valid(
+            &[(ParsedKind::Str, r"^[^@\s]+@[^@\s]+\.[a-z]+$")],
+            r#"[]{"email"}"#,
+            br#"[{"email": "first@example.com"}, {"email": "second"}, {"email": 3},
+                {"email": "third@example.org"}, {"email": "a b@example.com"}]"#,
+        );
+        assert_eq!(
+            output,
+            vec![
+                br#""first@example.com""#.to_vec(),
+                br#""third@example.org""#.to_vec(),
+            ]
+        );
+    }
+
+    #[test]
+    fn kinds() {
+        let output = valid(
+            &[
+                (ParsedKind::Num, r"^[0-9]$"),
+                (ParsedKind::Float, r"^[0-9]+\.5$"),
+                (ParsedKind::Arr, r"^\[\]$"),
+            ],
+            r#"[]"#,
+            br#"[1, 12, 1.5, 10.5, 2.0, [], [1], {}, null]"#,
+        );
+        assert_eq!(
+            output,
+            vec![
+                b"1".to_vec(),
+                b"1.5".to_vec(),
+                b"10.5".to_vec(),
+                b"[]".to_vec()
+            ]
+        );
+    }
+}
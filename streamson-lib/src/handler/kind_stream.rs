@@ -0,0 +1,136 @@
+//! Handler which records kinds of matched values
+//!
+//! It doesn't need to buffer the matched data.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}, streamer::ParsedKind};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(handler::KindStream::new()));
+//! let matcher = matcher::Simple::new(r#"{"users"}[]{}"#).unwrap();
+//!
+//! let mut trigger = strategy::Trigger::new();
+//!
+//! trigger.add_matcher(Box::new(matcher), handler.clone());
+//!
+//! for input in vec![
+//!     br#"{"users": [{"name": "first", "groups": []}, "#.to_vec(),
+//!     br#"{"name": null}]}"#.to_vec(),
+//! ] {
+//!     trigger.process(&input).unwrap();
+//! }
+//!
+//! assert_eq!(
+//!     handler.lock().unwrap().kinds(),
+//!     &[ParsedKind::Str, ParsedKind::Arr, ParsedKind::Null]
+//! );
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    path::Path,
+    streamer::{ParsedKind, Token},
+};
+use std::{any::Any, mem, str::FromStr};
+
+/// Handler which stores the kind of each match
+///
+/// The kind is taken from the start token so numbers
+/// are recorded as `ParsedKind::Num`.
+#[derive(Debug, Default)]
+pub struct KindStream {
+    /// Kinds of the matched values in the order of appearance
+    kinds: Vec<ParsedKind>,
+}
+
+impl KindStream {
+    /// Creates a new handler which records kinds of matched values
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the recorded kinds
+    pub fn kinds(&self) -> &[ParsedKind] {
+        &self.kinds
+    }
+
+    /// Returns the recorded kinds and clears them
+    pub fn take_kinds(&mut self) -> Vec<ParsedKind> {
+        mem::take(&mut self.kinds)
+    }
+}
+
+impl FromStr for KindStream {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() {
+            Ok(Self::default())
+        } else {
+            Err(error::Handler::new("KindStream has no options"))
+        }
+    }
+}
+
+impl Handler for KindStream {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, token: Token) -> HandlerOutput {
+        if let Token::Start(_, kind) = token {
+            self.kinds.push(kind);
+        }
+        Ok(None)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KindStream;
+    use crate::{
+        matcher::Simple,
+        strategy::{Strategy, Trigger},
+        streamer::ParsedKind,
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    #[test]
+    fn mixed_array() {
+        let handler = Arc::new(Mutex::new(KindStream::new()));
+        let mut trigger = Trigger::new();
+        trigger.add_matcher(Box::new(Simple::new(r#"[]"#).unwrap()), handler.clone());
+
+        let input = br#"[{"a": 1}, [2], "3", 4, 5.0, true, null, {}]"#;
+        for part in input.chunks(3) {
+            trigger.process(part).unwrap();
+        }
+        trigger.terminate().unwrap();
+
+        let mut guard = handler.lock().unwrap();
+        assert_eq!(
+            guard.take_kinds(),
+            vec![
+                ParsedKind::Obj,
+                ParsedKind::Arr,
+                ParsedKind::Str,
+                ParsedKind::Num,
+                ParsedKind::Num,
+                ParsedKind::Bool,
+                ParsedKind::Null,
+                ParsedKind::Obj,
+            ]
+        );
+        assert!(guard.kinds().is_empty());
+    }
+
+    #[test]
+    fn parse() {
+        assert!(KindStream::from_str("").is_ok());
+        assert!(KindStream::from_str("x").is_err());
+    }
+}
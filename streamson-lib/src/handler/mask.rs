@@ -0,0 +1,237 @@
+//! Handler which masks characters of matched strings
+//! it can be used e.g. to hide personal data while
+//! keeping the format of the data
+//! `"abc-123"` -> `"XXX-###"`
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(handler::Mask::new('X', '#')));
+//! let matcher = matcher::Simple::new(r#"{"users"}[]{"phone"}"#).unwrap();
+//!
+//! let mut convert = strategy::Convert::new();
+//!
+//! // Set the matcher for convert strategy
+//! convert.add_matcher(Box::new(matcher), handler);
+//!
+//! for input in vec![
+//!     br#"{"users": [{"phone": "+420 123 456", "name": "first"}, {"#.to_vec(),
+//!     br#""phone": "+420 654 321", "name": "second"}]}"#.to_vec(),
+//! ] {
+//!     for converted_data in convert.process(&input).unwrap() {
+//!         println!("{:?}", converted_data);
+//!     }
+//! }
+//! ```
+
+use super::Handler;
+use crate::{
+    error,
+    path::Path,
+    streamer::{escape, unescape, ParsedKind, Token},
+};
+use std::{any::Any, mem, str::FromStr};
+
+/// Handler which replaces letters and digits of matched strings
+#[derive(Debug)]
+pub struct Mask {
+    /// Character which replaces letters
+    letters: char,
+    /// Character which replaces digits
+    digits: char,
+    /// Buffer to collect the matched string
+    buffer: Vec<u8>,
+}
+
+impl Default for Mask {
+    fn default() -> Self {
+        Self::new('X', '#')
+    }
+}
+
+impl Mask {
+    /// Creates a new handler which masks matched strings
+    ///
+    /// # Arguments
+    /// * `letters` - character which replaces letters
+    /// * `digits` - character which replaces digits
+    pub fn new(letters: char, digits: char) -> Self {
+        Self {
+            letters,
+            digits,
+            buffer: vec![],
+        }
+    }
+}
+
+impl FromStr for Mask {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() {
+            return Ok(Self::default());
+        }
+        let chars: Vec<char> = input.chars().collect();
+        if chars.len() == 3 && chars[1] == ',' {
+            Ok(Self::new(chars[0], chars[2]))
+        } else {
+            Err(error::Handler::new("Failed to parse"))
+        }
+    }
+}
+
+impl Handler for Mask {
+    fn start(
+        &mut self,
+        _path: &Path,
+        _matcher_idx: usize,
+        token: Token,
+    ) -> Result<Option<Vec<u8>>, error::Handler> {
+        self.buffer.clear();
+        if let Token::Start(_, kind) = token {
+            if !matches!(kind, ParsedKind::Str) {
+                return Err(error::Handler::new(
+                    "Masked data is supposed to be a string.",
+                ));
+            }
+            Ok(None)
+        } else {
+            unreachable!();
+        }
+    }
+
+    fn feed(
+        &mut self,
+        data: &[u8],
+        _matcher_idx: usize,
+    ) -> Result<Option<Vec<u8>>, error::Handler> {
+        // the entire string is needed to process multibyte characters
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(
+        &mut self,
+        _path: &Path,
+        _matcher_idx: usize,
+        _token: Token,
+    ) -> Result<Option<Vec<u8>>, error::Handler> {
+        // escape sequences (e.g. `\u0041`) are decoded first
+        // so that only the actual characters are masked
+        let data = mem::take(&mut self.buffer);
+        let input = data
+            .get(1..data.len().saturating_sub(1))
+            .and_then(unescape)
+            .ok_or_else(|| error::Handler::new("Failed to decode matched string"))?;
+
+        let masked: String = input
+            .chars()
+            .map(|chr| {
+                if chr.is_alphabetic() {
+                    self.letters
+                } else if chr.is_numeric() {
+                    self.digits
+                } else {
+                    chr
+                }
+            })
+            .collect();
+        let output = format!(r#""{}""#, escape(&masked));
+
+        Ok(Some(output.into_bytes()))
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Mask;
+    use crate::{
+        matcher::Simple,
+        strategy::{Convert, OutputConverter, Strategy},
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn mask_handler() {
+        let mut convert = Convert::new();
+        let mask_handler = Arc::new(Mutex::new(Mask::new('X', '#')));
+        let matcher = Simple::new(r#"[]{"id"}"#).unwrap();
+
+        convert.add_matcher(Box::new(matcher), mask_handler);
+        let mut output = convert.process(br#"[{"id": "abc-1"#).unwrap();
+        output.extend(
+            convert
+                .process(r#"23"}, {"id": "čř \"5\""}, {"id": "x"}]"#.as_bytes())
+                .unwrap(),
+        );
+
+        let output: Vec<u8> = OutputConverter::new()
+            .convert(&output)
+            .into_iter()
+            .flat_map(|e| e.1)
+            .collect();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r##"[{"id": "XXX-###"}, {"id": "XX \"#\""}, {"id": "X"}]"##
+        );
+    }
+
+    #[test]
+    fn mask_escaped_chars() {
+        let mut convert = Convert::new();
+        let mask_handler = Arc::new(Mutex::new(Mask::new('"', '\\')));
+        let matcher = Simple::new(r#"[]"#).unwrap();
+
+        convert.add_matcher(Box::new(matcher), mask_handler);
+        let output: Vec<u8> = OutputConverter::new()
+            .convert(&convert.process(br#"["a1"]"#).unwrap())
+            .into_iter()
+            .flat_map(|e| e.1)
+            .collect();
+
+        assert_eq!(String::from_utf8(output).unwrap(), r#"["\"\\"]"#);
+    }
+
+    #[test]
+    fn mask_unicode_escapes() {
+        let mut convert = Convert::new();
+        let mask_handler = Arc::new(Mutex::new(Mask::default()));
+        let matcher = Simple::new(r#"[]"#).unwrap();
+
+        convert.add_matcher(Box::new(matcher), mask_handler);
+        let output: Vec<u8> = OutputConverter::new()
+            .convert(
+                &convert
+                    .process(br#"["a\u0041b", "\u0031-\ud83d\ude00\n"]"#)
+                    .unwrap(),
+            )
+            .into_iter()
+            .flat_map(|e| e.1)
+            .collect();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "[\"XXX\", \"#-\u{1F600}\\n\"]"
+        );
+    }
+
+    #[test]
+    fn mask_not_string() {
+        let mut convert = Convert::new();
+        let mask_handler = Arc::new(Mutex::new(Mask::default()));
+        let matcher = Simple::new(r#"[]"#).unwrap();
+
+        convert.add_matcher(Box::new(matcher), mask_handler);
+        assert!(convert.process(br#"[1]"#).is_err());
+    }
+}
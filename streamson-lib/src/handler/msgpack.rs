@@ -0,0 +1,366 @@
+//! Handler which converts matched JSON values into MessagePack
+//!
+//! `{"a": [1, "b"]}` -> `0x81 0xa1 0x61 0x92 0x01 0xa1 0x62`
+//!
+//! Only the matched values are buffered (not the entire document).
+//! Integers are stored in the smallest possible format, other numbers
+//! as 64-bit floats.
+//!
+//! When used as a converter (e.g. within `Convert` strategy) the converted
+//! data are returned as the output of the handler. With other strategies
+//! (e.g. `Extract` or `Trigger`) the converted data can be stored and popped
+//! from the handler (see `MsgPack::set_buffered`).
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(handler::MsgPack::new()));
+//! let matcher = matcher::Simple::new(r#"{"users"}[]"#).unwrap();
+//!
+//! let mut convert = strategy::Convert::new();
+//!
+//! // Set the matcher for convert strategy
+//! convert.add_matcher(Box::new(matcher), handler);
+//!
+//! for input in vec![
+//!     br#"{"users": [{"name": "first"}, "#.to_vec(),
+//!     br#"{"name": "second"}]}"#.to_vec(),
+//! ] {
+//!     for converted_data in convert.process(&input).unwrap() {
+//!         println!("{:?}", converted_data);
+//!     }
+//! }
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    path::Path,
+    streamer::{unescape, Token},
+};
+use rmp::encode;
+use std::{any::Any, collections::VecDeque, convert::TryFrom, mem, str, str::FromStr};
+
+/// Converts JSON values into MessagePack
+#[derive(Debug, Default)]
+pub struct MsgPack {
+    /// Data of currently matched value
+    buffer: Vec<u8>,
+    /// Store the converted data instead of returning them
+    buffered: bool,
+    /// Queue with converted values in (path, data) format
+    results: VecDeque<(Option<String>, Vec<u8>)>,
+}
+
+impl MsgPack {
+    /// Creates a new MessagePack converter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the converted data are stored in the handler
+    ///
+    /// Stored data can be obtained using `MsgPack::pop`.
+    /// It is meant to be used with strategies which don't use
+    /// the output of the handlers (e.g. `Extract` or `Trigger`).
+    ///
+    /// # Arguments
+    /// * `buffered` - store the converted data
+    pub fn set_buffered(mut self, buffered: bool) -> Self {
+        self.buffered = buffered;
+        self
+    }
+
+    /// Pops the oldest converted value
+    ///
+    /// # Returns
+    /// * `None` - queue is empty
+    /// * `Some((path, data))` - stored data remove from the queue and returned
+    pub fn pop(&mut self) -> Option<(Option<String>, Vec<u8>)> {
+        self.results.pop_front()
+    }
+}
+
+/// Converts a single JSON value into MessagePack
+///
+/// # Arguments
+/// * `data` - JSON value
+pub fn convert(data: &[u8]) -> Result<Vec<u8>, error::Handler> {
+    let mut encoder = Encoder { data, idx: 0 };
+    let mut output = vec![];
+    encoder.value(&mut output)?;
+    encoder.skip_whitespaces();
+    if encoder.idx < data.len() {
+        return Err(encoder.error());
+    }
+    Ok(output)
+}
+
+/// Recursive encoder of the JSON data
+struct Encoder<'a> {
+    data: &'a [u8],
+    idx: usize,
+}
+
+impl<'a> Encoder<'a> {
+    fn error(&self) -> error::Handler {
+        error::Handler::new(format!(
+            "Failed to convert JSON to MessagePack (idx {})",
+            self.idx
+        ))
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while self.idx < self.data.len() && self.data[self.idx].is_ascii_whitespace() {
+            self.idx += 1;
+        }
+    }
+
+    /// Returns next non-whitespace byte
+    fn peek(&mut self) -> Result<u8, error::Handler> {
+        self.skip_whitespaces();
+        self.data.get(self.idx).copied().ok_or_else(|| self.error())
+    }
+
+    fn value(&mut self, output: &mut Vec<u8>) -> Result<(), error::Handler> {
+        match self.peek()? {
+            b'{' => self.container(output, b'}'),
+            b'[' => self.container(output, b']'),
+            b'"' => {
+                let string = self.string()?;
+                encode::write_str(output, &string).map_err(error::Handler::new)
+            }
+            b't' => self.literal(b"true", || encode::write_bool(output, true)),
+            b'f' => self.literal(b"false", || encode::write_bool(output, false)),
+            b'n' => self.literal(b"null", || encode::write_nil(output)),
+            _ => self.number(output),
+        }
+    }
+
+    fn literal<F, E>(&mut self, literal: &[u8], write: F) -> Result<(), error::Handler>
+    where
+        F: FnOnce() -> Result<(), E>,
+        E: ToString,
+    {
+        if !self.data[self.idx..].starts_with(literal) {
+            return Err(self.error());
+        }
+        self.idx += literal.len();
+        write().map_err(error::Handler::new)
+    }
+
+    /// Parses a string and returns its decoded content
+    fn string(&mut self) -> Result<String, error::Handler> {
+        let start = self.idx + 1;
+        let mut idx = start;
+        while idx < self.data.len() {
+            match self.data[idx] {
+                b'\\' => idx += 2,
+                b'"' => {
+                    self.idx = idx + 1;
+                    return unescape(&self.data[start..idx]).ok_or_else(|| self.error());
+                }
+                _ => idx += 1,
+            }
+        }
+        Err(self.error())
+    }
+
+    fn number(&mut self, output: &mut Vec<u8>) -> Result<(), error::Handler> {
+        let start = self.idx;
+        while self.idx < self.data.len()
+            && matches!(
+                self.data[self.idx],
+                b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E'
+            )
+        {
+            self.idx += 1;
+        }
+        let number = str::from_utf8(&self.data[start..self.idx]).map_err(error::Handler::new)?;
+        if let Ok(number) = number.parse::<u64>() {
+            encode::write_uint(output, number).map_err(error::Handler::new)?;
+        } else if let Ok(number) = number.parse::<i64>() {
+            encode::write_sint(output, number).map_err(error::Handler::new)?;
+        } else {
+            let number: f64 = number.parse().map_err(|_| self.error())?;
+            encode::write_f64(output, number).map_err(error::Handler::new)?;
+        }
+        Ok(())
+    }
+
+    /// Encodes an array or an object (the items are counted first)
+    fn container(&mut self, output: &mut Vec<u8>, end: u8) -> Result<(), error::Handler> {
+        let is_object = end == b'}';
+        self.idx += 1;
+        let mut items = vec![];
+        let mut count: usize = 0;
+        if self.peek()? == end {
+            self.idx += 1;
+        } else {
+            loop {
+                if is_object {
+                    if self.peek()? != b'"' {
+                        return Err(self.error());
+                    }
+                    let key = self.string()?;
+                    encode::write_str(&mut items, &key).map_err(error::Handler::new)?;
+                    if self.peek()? != b':' {
+                        return Err(self.error());
+                    }
+                    self.idx += 1;
+                }
+                self.value(&mut items)?;
+                count += 1;
+                match self.peek()? {
+                    b',' => self.idx += 1,
+                    byte if byte == end => {
+                        self.idx += 1;
+                        break;
+                    }
+                    _ => return Err(self.error()),
+                }
+            }
+        }
+        let count = u32::try_from(count).map_err(|_| self.error())?;
+        if is_object {
+            encode::write_map_len(output, count).map_err(error::Handler::new)?;
+        } else {
+            encode::write_array_len(output, count).map_err(error::Handler::new)?;
+        }
+        output.extend(items);
+        Ok(())
+    }
+}
+
+impl Handler for MsgPack {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        self.buffer.clear();
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(&mut self, path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        let converted = convert(&mem::take(&mut self.buffer))?;
+        if self.buffered {
+            self.results.push_back((Some(path.to_string()), converted));
+            Ok(None)
+        } else {
+            Ok(Some(converted))
+        }
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl FromStr for MsgPack {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() {
+            Ok(Self::default())
+        } else {
+            Ok(Self::default().set_buffered(input.parse().map_err(error::Handler::new)?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert, MsgPack};
+    use crate::{
+        matcher::Simple,
+        strategy::{Convert, Extract, OutputConverter, Strategy},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    #[test]
+    fn values() {
+        assert_eq!(convert(b"1").unwrap(), vec![0x01]);
+        assert_eq!(convert(b"300").unwrap(), vec![0xcd, 0x01, 0x2c]);
+        assert_eq!(convert(b"-1").unwrap(), vec![0xff]);
+        assert_eq!(
+            convert(b"1.5").unwrap(),
+            vec![0xcb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(convert(b" true ").unwrap(), vec![0xc3]);
+        assert_eq!(convert(b"false").unwrap(), vec![0xc2]);
+        assert_eq!(convert(b"null").unwrap(), vec![0xc0]);
+        assert_eq!(
+            convert(br#""a\"\u00e9""#).unwrap(),
+            vec![0xa4, b'a', b'"', 0xc3, 0xa9]
+        );
+        assert_eq!(
+            convert(br#"{"a": [1, "b"], "c": {}}"#).unwrap(),
+            vec![0x82, 0xa1, b'a', 0x92, 0x01, 0xa1, b'b', 0xa1, b'c', 0x80]
+        );
+        assert_eq!(convert(b"[]").unwrap(), vec![0x90]);
+    }
+
+    #[test]
+    fn invalid() {
+        for input in &[&b"[1,"[..], b"{1: 2}", b"nul", b"\"a", b"1 2", b"[1 2]"] {
+            assert!(convert(input).is_err());
+        }
+    }
+
+    #[test]
+    fn convert_strategy() {
+        let mut convert = Convert::new();
+        convert.add_matcher(
+            Box::new(Simple::new(r#"{"a"}[]"#).unwrap()),
+            Arc::new(Mutex::new(MsgPack::new())),
+        );
+        let mut output = vec![];
+        let mut converter = OutputConverter::new();
+        for part in br#"{"a": [[1, 2], "x"]}"#.chunks(3) {
+            for (_, data) in converter.convert(&convert.process(part).unwrap()) {
+                output.extend(data);
+            }
+        }
+        let mut expected = br#"{"a": ["#.to_vec();
+        expected.extend(&[0x92, 0x01, 0x02]);
+        expected.extend(b", ");
+        expected.extend(&[0xa1, b'x']);
+        expected.extend(b"]}");
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn extract_buffered() {
+        let handler = Arc::new(Mutex::new(MsgPack::from_str("true").unwrap()));
+        let mut extract = Extract::new();
+        extract.add_matcher(
+            Box::new(Simple::new(r#"{"a"}[]"#).unwrap()),
+            Some(handler.clone()),
+        );
+        extract.process(br#"{"a": [null, {"b": true}]}"#).unwrap();
+
+        let mut guard = handler.lock().unwrap();
+        assert_eq!(
+            guard.pop(),
+            Some((Some(r#"{"a"}[0]"#.to_string()), vec![0xc0]))
+        );
+        assert_eq!(
+            guard.pop(),
+            Some((
+                Some(r#"{"a"}[1]"#.to_string()),
+                vec![0x81, 0xa1, b'b', 0xc3]
+            ))
+        );
+        assert_eq!(guard.pop(), None);
+    }
+}
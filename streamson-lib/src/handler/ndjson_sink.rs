@@ -0,0 +1,166 @@
+//! Handler which writes matched data as JSON Lines (NDJSON)
+//!
+//! Each match is minified (whitespaces outside of strings are removed)
+//! and written to the writer as a single line when the match ends.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::{io::stdout, sync::{Arc, Mutex}};
+//!
+//! let handler = Arc::new(Mutex::new(handler::NdjsonSink::new(stdout())));
+//! let matcher = matcher::Simple::new(r#"{"users"}[]"#).unwrap();
+//!
+//! let mut trigger = strategy::Trigger::new();
+//!
+//! // Set the matcher for trigger strategy
+//! trigger.add_matcher(Box::new(matcher), handler);
+//!
+//! for input in vec![
+//!     br#"{"users": [{"name": "first"}, "#.to_vec(),
+//!     br#"{"name": "second"}]}"#.to_vec(),
+//! ] {
+//!     trigger.process(&input).unwrap();
+//! }
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{error, path::Path, streamer::Token};
+use std::{any::Any, fs, io, str::FromStr};
+
+/// Handler which writes each match as a minified line
+pub struct NdjsonSink<W>
+where
+    W: io::Write,
+{
+    /// Writable output
+    writer: W,
+    /// Minified data of the current match
+    line: Vec<u8>,
+    /// Whether the minifier is within a string
+    in_string: bool,
+    /// Whether the previous character was an escape within a string
+    escaped: bool,
+    /// Number of currently active matches
+    level: usize,
+}
+
+impl FromStr for NdjsonSink<fs::File> {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(
+            fs::File::create(input).map_err(error::Handler::new)?,
+        ))
+    }
+}
+
+impl<W> NdjsonSink<W>
+where
+    W: io::Write,
+{
+    /// Creates a new handler which writes JSON Lines
+    ///
+    /// # Arguments
+    /// * `writer` - structure which implements `io::Write`
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            line: vec![],
+            in_string: false,
+            escaped: false,
+            level: 0,
+        }
+    }
+
+    /// Returns the underlying writer
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    /// Appends minified data to the current line
+    fn minify(&mut self, data: &[u8]) {
+        for byte in data {
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if *byte == b'\\' {
+                    self.escaped = true;
+                } else if *byte == b'"' {
+                    self.in_string = false;
+                }
+            } else if *byte == b'"' {
+                self.in_string = true;
+            } else if byte.is_ascii_whitespace() {
+                continue;
+            }
+            self.line.push(*byte);
+        }
+    }
+}
+
+impl<W> Handler for NdjsonSink<W>
+where
+    W: io::Write + Send + 'static,
+{
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        // nested matches are already a part of the outer line
+        self.level += 1;
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.minify(data);
+        Ok(None)
+    }
+
+    fn end(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        self.level -= 1;
+        if self.level == 0 {
+            self.line.push(b'\n');
+            self.writer
+                .write_all(&self.line)
+                .map_err(error::Handler::new)?;
+            self.line.clear();
+        }
+        Ok(None)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NdjsonSink;
+    use crate::{
+        matcher::Simple,
+        strategy::{Strategy, Trigger},
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn lines() {
+        let handler = Arc::new(Mutex::new(NdjsonSink::new(vec![])));
+        let mut trigger = Trigger::new();
+        trigger.add_matcher(
+            Box::new(Simple::new(r#"{"items"}[]"#).unwrap()),
+            handler.clone(),
+        );
+
+        let input = br#"{"items": [
+            {"name": "a b", "tags": [ 1, 2 ]},
+            "x \" y\\",
+            { "nested": { "k" : null } }
+        ]}"#;
+        for part in input.chunks(3) {
+            trigger.process(part).unwrap();
+        }
+
+        let guard = handler.lock().unwrap();
+        assert_eq!(
+            String::from_utf8(guard.writer().clone()).unwrap(),
+            "{\"name\":\"a b\",\"tags\":[1,2]}\n\"x \\\" y\\\\\"\n{\"nested\":{\"k\":null}}\n"
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! Handler which converts matched arrays and objects
+//! into objects keyed by incrementing ids
+//!
+//! `[1, "two"]` -> `{"0":1,"1":"two"}`
+//! `{"a": 1, "b": [2]}` -> `{"0":1,"1":[2]}`
+//!
+//! It is supposed to be used with the `Convert` strategy. The matched data
+//! are processed using an internal streamer, so the values are emitted
+//! as they are fed and only the keys and the separators are changed.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(handler::ObjectWrap::new()));
+//! let matcher = matcher::Simple::new(r#"{"users"}"#).unwrap();
+//!
+//! let mut convert = strategy::Convert::new();
+//!
+//! // Set the matcher for convert strategy
+//! convert.add_matcher(Box::new(matcher), handler);
+//!
+//! for input in vec![
+//!     br#"{"users": [{"name": "first"}, "#.to_vec(),
+//!     br#"{"name": "second"}]}"#.to_vec(),
+//! ] {
+//!     for converted_data in convert.process(&input).unwrap() {
+//!         println!("{:?}", converted_data);
+//!     }
+//! }
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    path::Path,
+    streamer::{ParsedKind, Streamer, Token},
+};
+use std::{any::Any, str::FromStr};
+
+/// Handler which converts matched arrays and objects into `{"0": .., "1": .., ...}`
+#[derive(Debug, Default)]
+pub struct ObjectWrap {
+    /// Streamer which processes the matched data
+    streamer: Streamer,
+    /// Data which were fed but not processed yet
+    buffer: Vec<u8>,
+    /// Index of the start of the buffer within the matched data
+    offset: usize,
+    /// Current level within the matched data
+    level: usize,
+    /// Number of already wrapped values
+    count: usize,
+}
+
+impl ObjectWrap {
+    /// Creates a new handler which wraps values into an object
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes the data till `idx` from the buffer
+    fn take(&mut self, idx: usize) -> Vec<u8> {
+        let data = self.buffer.drain(..idx - self.offset).collect();
+        self.offset = idx;
+        data
+    }
+
+    /// Processes the buffered data and returns the converted part
+    fn process(&mut self) -> Result<Vec<u8>, error::Handler> {
+        let mut output = vec![];
+        loop {
+            match self.streamer.read().map_err(error::Handler::new)? {
+                Token::Start(idx, kind) => {
+                    self.level += 1;
+                    match self.level {
+                        1 => {
+                            if !matches!(kind, ParsedKind::Obj | ParsedKind::Arr) {
+                                return Err(error::Handler::new(
+                                    "Wrapped data are supposed to be an array or an object.",
+                                ));
+                            }
+                            // drop the opening bracket
+                            self.take(idx + 1);
+                            output.push(b'{');
+                        }
+                        2 => {
+                            // drop the separator (and the key)
+                            self.take(idx);
+                            if self.count > 0 {
+                                output.push(b',');
+                            }
+                            output.extend(format!(r#""{}":"#, self.count).as_bytes());
+                            self.count += 1;
+                        }
+                        _ => {}
+                    }
+                }
+                Token::End(idx, _) => {
+                    match self.level {
+                        1 => {
+                            self.take(idx);
+                            output.push(b'}');
+                        }
+                        2 => output.extend(self.take(idx)),
+                        _ => {}
+                    }
+                    self.level -= 1;
+                }
+                Token::Separator(_, _) | Token::Finished(_) | Token::Discarded(_) => {}
+                Token::Pending => {
+                    // data within the values can be emitted right away
+                    if self.level > 1 {
+                        let idx = self.offset + self.buffer.len();
+                        output.extend(self.take(idx));
+                    }
+                    return Ok(output);
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for ObjectWrap {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() {
+            Ok(Self::default())
+        } else {
+            Err(error::Handler::new(
+                "ObjectWrap handler accepts no argument",
+            ))
+        }
+    }
+}
+
+impl Handler for ObjectWrap {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        *self = Self::default();
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.buffer.extend(data);
+        self.streamer.feed(data);
+        let output = self.process()?;
+        if output.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(output))
+        }
+    }
+
+    fn end(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        // the object was closed when the data were fed
+        self.buffer.clear();
+        Ok(None)
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ObjectWrap;
+    use crate::{
+        matcher::Simple,
+        strategy::{Convert, OutputConverter, Strategy},
+        streamer::{Streamer, Token},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    fn convert(matcher: &str, input: &[u8], chunk_size: usize) -> Result<String, String> {
+        let mut convert = Convert::new();
+        convert.add_matcher(
+            Box::new(Simple::new(matcher).unwrap()),
+            Arc::new(Mutex::new(ObjectWrap::new())),
+        );
+
+        let mut output = vec![];
+        let mut converter = OutputConverter::new();
+        for part in input.chunks(chunk_size) {
+            let converted = convert.process(part).map_err(|e| e.to_string())?;
+            for (_, data) in converter.convert(&converted) {
+                output.extend(data);
+            }
+        }
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    /// Checks that the entire output is a single valid JSON
+    fn assert_valid(output: &str) {
+        let mut streamer = Streamer::new();
+        streamer.set_strict(true);
+        streamer.feed_final(output.as_bytes());
+        loop {
+            match streamer.read().unwrap() {
+                Token::Finished(idx) => {
+                    assert_eq!(idx, output.len());
+                    break;
+                }
+                Token::Pending => panic!("JSON is not finished"),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn wrapped_array() {
+        let input = br#"{"items": [{"a": [1, 2]}, "two" , 3, [], null], "other": []}"#;
+        for chunk_size in 1..input.len() {
+            let output = convert(r#"{"items"}"#, input, chunk_size).unwrap();
+            assert_eq!(
+                output,
+                r#"{"items": {"0":{"a": [1, 2]},"1":"two","2":3,"3":[],"4":null}, "other": []}"#
+            );
+            assert_valid(&output);
+        }
+    }
+
+    #[test]
+    fn wrapped_object() {
+        let input = br#"[{"a": 1, "b": {"c": "x"}}, {}, []]"#;
+        for chunk_size in 1..input.len() {
+            let output = convert("[]", input, chunk_size).unwrap();
+            assert_eq!(output, r#"[{"0":1,"1":{"c": "x"}}, {}, {}]"#);
+            assert_valid(&output);
+        }
+    }
+
+    #[test]
+    fn not_container() {
+        assert!(convert("[]", b"[1]", 3).is_err());
+        assert!(ObjectWrap::from_str("").is_ok());
+        assert!(ObjectWrap::from_str("x").is_err());
+    }
+}
@@ -1,8 +1,69 @@
 //! Handler which puts output into writeable struct
+//!
+//! Records can be framed so that the consumers are able to split the output:
+//! * separator - each record is followed by a separator (default `\n`)
+//! * length prefix - each record is preceded by its length (4 bytes, big endian)
+//! * envelope - each record is wrapped into `{"path": <path>, "data": <data>}`
+//!   and followed by a separator
 
 use super::Handler;
-use crate::{error, path::Path, streamer::Token};
-use std::{any::Any, fs, io, str::FromStr};
+use crate::{
+    error,
+    path::Path,
+    streamer::{escape, Token},
+};
+use std::{any::Any, convert::TryFrom, fs, io, mem, str::FromStr};
+
+/// Format of the path which is written before the data
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PathFormat {
+    /// Streamson path format (e.g. `{"users"}[0]`)
+    #[default]
+    Streamson,
+    /// JSON Pointer format (e.g. `/users/0`)
+    JsonPointer,
+}
+
+impl FromStr for PathFormat {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "streamson" => Ok(Self::Streamson),
+            "pointer" => Ok(Self::JsonPointer),
+            _ => Err(error::Handler::new(format!(
+                "Unknown path format '{}'",
+                input
+            ))),
+        }
+    }
+}
+
+/// How the records are framed in the output
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    /// Records are followed by a separator
+    #[default]
+    Separator,
+    /// Records are preceded by their length (u32, big endian)
+    ///
+    /// Note that the records have to be buffered
+    LengthPrefix,
+    /// Records are wrapped into `{"path": <path>, "data": <data>}`
+    /// and followed by a separator
+    Envelope,
+}
+
+impl FromStr for Framing {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "separator" => Ok(Self::Separator),
+            "length" => Ok(Self::LengthPrefix),
+            "envelope" => Ok(Self::Envelope),
+            _ => Err(error::Handler::new(format!("Unknown framing '{}'", input))),
+        }
+    }
+}
 
 /// File handler responsible for storing data to a file.
 pub struct Output<W>
@@ -16,9 +77,18 @@ where
     /// e.g. `{"items"}: {"sub": 4}` vs `{"sub": 4}`
     write_path: bool,
 
+    /// Format of the written path
+    path_format: PathFormat,
+
     /// String which will be appended to the end of each record
     /// to separate it with the next record (default '\n')
     separator: String,
+
+    /// How the records are framed
+    framing: Framing,
+
+    /// Currently processed record (used for length prefixed records)
+    buffer: Vec<u8>,
 }
 
 impl FromStr for Output<fs::File> {
@@ -43,7 +113,10 @@ where
         Self {
             output,
             write_path: false,
+            path_format: PathFormat::default(),
             separator: "\n".into(),
+            framing: Framing::default(),
+            buffer: vec![],
         }
     }
 
@@ -64,6 +137,24 @@ where
         self
     }
 
+    /// Set the format of the written path
+    ///
+    /// # Arguments
+    /// * `path_format` - format of the path (used only when path is written)
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::stdout;
+    /// use streamson_lib::handler::{self, output::PathFormat};
+    /// let output = handler::Output::new(stdout())
+    ///     .set_write_path(true)
+    ///     .set_path_format(PathFormat::JsonPointer);
+    /// ```
+    pub fn set_path_format(mut self, path_format: PathFormat) -> Self {
+        self.path_format = path_format;
+        self
+    }
+
     /// Set which separator will be used in the output
     ///
     /// Note that every separator will be extended to every found item.
@@ -85,6 +176,43 @@ where
         self.separator = separator.to_string();
         self
     }
+
+    /// Set how the records are framed
+    ///
+    /// # Arguments
+    /// * `framing` - framing of the records
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::stdout;
+    /// use streamson_lib::handler::{self, output::Framing};
+    /// let output = handler::Output::new(stdout())
+    ///     .set_framing(Framing::LengthPrefix);
+    /// ```
+    pub fn set_framing(mut self, framing: Framing) -> Self {
+        self.framing = framing;
+        self
+    }
+
+    /// Formats the path
+    fn format_path(&self, path: &Path) -> String {
+        match self.path_format {
+            PathFormat::Streamson => path.to_string(),
+            PathFormat::JsonPointer => path.to_pointer(),
+        }
+    }
+
+    /// Writes the data to the output (or to the buffer when the record is buffered)
+    fn write(&mut self, data: &[u8]) -> Result<(), error::Handler> {
+        if self.framing == Framing::LengthPrefix {
+            self.buffer.extend(data);
+            Ok(())
+        } else {
+            self.output
+                .write_all(data)
+                .map_err(|err| error::Handler::new(err.to_string()))
+        }
+    }
 }
 
 impl<W> Handler for Output<W>
@@ -97,10 +225,12 @@ where
         _matcher_idx: usize,
         _token: Token,
     ) -> Result<Option<Vec<u8>>, error::Handler> {
-        if self.write_path {
-            self.output
-                .write(format!("{}: ", path).as_bytes())
-                .map_err(|err| error::Handler::new(err.to_string()))?;
+        if self.framing == Framing::Envelope {
+            let envelope = format!(r#"{{"path":"{}","data":"#, escape(&self.format_path(path)));
+            self.write(envelope.as_bytes())?;
+        } else if self.write_path {
+            let path = format!("{}: ", self.format_path(path));
+            self.write(path.as_bytes())?;
         }
         Ok(None)
     }
@@ -110,9 +240,7 @@ where
         data: &[u8],
         _matcher_idx: usize,
     ) -> Result<Option<Vec<u8>>, error::Handler> {
-        self.output
-            .write(data)
-            .map_err(|err| error::Handler::new(err.to_string()))?;
+        self.write(data)?;
         Ok(None)
     }
 
@@ -122,10 +250,25 @@ where
         _matcher_idx: usize,
         _token: Token,
     ) -> Result<Option<Vec<u8>>, error::Handler> {
-        let separator = self.separator.to_string();
-        self.output
-            .write(separator.as_bytes())
-            .map_err(|err| error::Handler::new(err.to_string()))?;
+        match self.framing {
+            Framing::Separator => {
+                let separator = self.separator.to_string();
+                self.write(separator.as_bytes())?;
+            }
+            Framing::LengthPrefix => {
+                let record = mem::take(&mut self.buffer);
+                let length = u32::try_from(record.len())
+                    .map_err(|_| error::Handler::new("Record is too long"))?;
+                self.output
+                    .write_all(&length.to_be_bytes())
+                    .and_then(|_| self.output.write_all(&record))
+                    .map_err(|err| error::Handler::new(err.to_string()))?;
+            }
+            Framing::Envelope => {
+                let end = format!("}}{}", self.separator);
+                self.write(end.as_bytes())?;
+            }
+        }
         Ok(None)
     }
 
@@ -136,12 +279,14 @@ where
 
 #[cfg(test)]
 mod tests {
+    use super::{Framing, PathFormat};
     use crate::{
         handler, matcher,
         strategy::{self, Strategy},
     };
     use std::{
-        fs, str,
+        fs,
+        str::{self, FromStr},
         sync::{Arc, Mutex},
     };
     use tempfile::NamedTempFile;
@@ -234,4 +379,83 @@ mod tests {
             .unwrap()
         );
     }
+
+    #[test]
+    fn pointer_path() {
+        let tmp_path = NamedTempFile::new().unwrap().into_temp_path();
+        let str_path = tmp_path.to_str().unwrap();
+
+        let matcher = matcher::Simple::new(r#"{"aa"}[]"#).unwrap();
+        let file = fs::File::create(str_path).unwrap();
+        let handler = handler::Output::new(file)
+            .set_write_path(true)
+            .set_path_format(PathFormat::from_str("pointer").unwrap());
+
+        let output = make_output(
+            str_path,
+            matcher,
+            handler,
+            br#"{"aa": [1, {"a/b": 2}], "b": true}"#,
+        );
+
+        assert_eq!(output, "/aa/0: 1\n/aa/1: {\"a/b\": 2}\n");
+    }
+
+    #[test]
+    fn length_prefix() {
+        let tmp_path = NamedTempFile::new().unwrap().into_temp_path();
+        let str_path = tmp_path.to_str().unwrap();
+
+        let matcher = matcher::Simple::new(r#"{"aa"}[]"#).unwrap();
+        let file = fs::File::create(str_path).unwrap();
+        let handler = handler::Output::new(file)
+            .set_write_path(true)
+            .set_framing(Framing::from_str("length").unwrap());
+
+        let handler = Arc::new(Mutex::new(handler));
+        let mut trigger = strategy::Trigger::new();
+        trigger.add_matcher(Box::new(matcher), handler);
+        for part in br#"{"aa": [1, "uv"], "b": true}"#.chunks(3) {
+            trigger.process(part).unwrap();
+        }
+
+        assert_eq!(
+            fs::read(str_path).unwrap(),
+            b"\x00\x00\x00\x0c{\"aa\"}[0]: 1\x00\x00\x00\x0f{\"aa\"}[1]: \"uv\"".to_vec()
+        );
+    }
+
+    #[test]
+    fn envelope() {
+        let tmp_path = NamedTempFile::new().unwrap().into_temp_path();
+        let str_path = tmp_path.to_str().unwrap();
+
+        let matcher = matcher::Simple::new(r#"{"aa"}[]"#).unwrap();
+        let file = fs::File::create(str_path).unwrap();
+        let handler = handler::Output::new(file).set_framing(Framing::Envelope);
+
+        let output = make_output(
+            str_path,
+            matcher,
+            handler,
+            br#"{"aa": [1, {"b": 2}], "b": true}"#,
+        );
+
+        assert_eq!(
+            output,
+            concat!(
+                r#"{"path":"{\"aa\"}[0]","data":1}"#,
+                "\n",
+                r#"{"path":"{\"aa\"}[1]","data":{"b": 2}}"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn framing_from_str() {
+        assert_eq!(Framing::from_str("separator").unwrap(), Framing::Separator);
+        assert_eq!(Framing::from_str("envelope").unwrap(), Framing::Envelope);
+        assert!(Framing::from_str("unknown").is_err());
+    }
 }
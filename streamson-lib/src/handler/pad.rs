@@ -0,0 +1,248 @@
+//! Handler which pads matched scalar values to a fixed width
+//! `"ab"` -> `"   ab"`
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler::{self, pad::Align}, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(handler::Pad::new(8, Align::Right, '.')));
+//! let matcher = matcher::Simple::new(r#"{"users"}[]{"name"}"#).unwrap();
+//!
+//! let mut convert = strategy::Convert::new();
+//!
+//! // Set the matcher for convert strategy
+//! convert.add_matcher(Box::new(matcher), handler);
+//!
+//! for input in vec![
+//!     br#"{"users": [{"name": "first"}, "#.to_vec(),
+//!     br#"{"name": "second"}]}"#.to_vec(),
+//! ] {
+//!     for converted_data in convert.process(&input).unwrap() {
+//!         println!("{:?}", converted_data);
+//!     }
+//! }
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    path::Path,
+    streamer::{escape, unescape, ParsedKind, Token},
+};
+use std::{any::Any, mem, str::FromStr};
+
+/// Where the value is placed within the padded field
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    /// Fill characters are appended
+    #[default]
+    Left,
+    /// Fill characters are prepended
+    Right,
+    /// Fill characters are split around the value (the extra one goes right)
+    Center,
+}
+
+impl FromStr for Align {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "left" => Ok(Self::Left),
+            "right" => Ok(Self::Right),
+            "center" => Ok(Self::Center),
+            _ => Err(error::Handler::new(format!(
+                "Unknown alignment '{}'",
+                input
+            ))),
+        }
+    }
+}
+
+/// Handler which pads matched scalar values to a fixed width
+///
+/// Strings are decoded first and the width is counted in characters.
+/// Other scalars (numbers, booleans and null) are padded as they are written
+/// so the output is always a string. Values which are longer than the width
+/// are kept as they are. Matched arrays and objects are not changed.
+#[derive(Debug)]
+pub struct Pad {
+    /// Width of the output in characters
+    width: usize,
+    /// Alignment of the value
+    align: Align,
+    /// Character used for padding
+    fill: char,
+    /// Kind of the currently matched data
+    kind: Option<ParsedKind>,
+    /// Matched data
+    buffer: Vec<u8>,
+}
+
+impl Default for Pad {
+    fn default() -> Self {
+        Self::new(0, Align::default(), ' ')
+    }
+}
+
+impl Pad {
+    /// Creates a new handler which pads matched values
+    ///
+    /// # Arguments
+    /// * `width` - minimal width of the value in characters
+    /// * `align` - alignment of the value
+    /// * `fill` - character used for padding
+    pub fn new(width: usize, align: Align, fill: char) -> Self {
+        Self {
+            width,
+            align,
+            fill,
+            kind: None,
+            buffer: vec![],
+        }
+    }
+
+    /// Pads the decoded value
+    fn pad(&self, value: &str) -> String {
+        let missing = self.width.saturating_sub(value.chars().count());
+        let (left, right) = match self.align {
+            Align::Left => (0, missing),
+            Align::Right => (missing, 0),
+            Align::Center => (missing / 2, missing - missing / 2),
+        };
+        let mut output = String::with_capacity(value.len() + missing);
+        output.extend((0..left).map(|_| self.fill));
+        output.push_str(value);
+        output.extend((0..right).map(|_| self.fill));
+        output
+    }
+}
+
+impl FromStr for Pad {
+    type Err = error::Handler;
+    /// Parses `width[,align[,fill]]` (e.g. `5,right,-`)
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let splitted: Vec<_> = input.splitn(3, ',').collect();
+        let width = splitted[0].parse().map_err(error::Handler::new)?;
+        let align = if let Some(align) = splitted.get(1) {
+            align.parse()?
+        } else {
+            Align::default()
+        };
+        let fill = if let Some(fill) = splitted.get(2) {
+            let mut chars = fill.chars();
+            match (chars.next(), chars.next()) {
+                (Some(fill), None) => fill,
+                _ => return Err(error::Handler::new("Fill has to be a single character")),
+            }
+        } else {
+            ' '
+        };
+        Ok(Self::new(width, align, fill))
+    }
+}
+
+impl Handler for Pad {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, token: Token) -> HandlerOutput {
+        if let Token::Start(_, kind) = token {
+            self.kind = Some(kind);
+        }
+        self.buffer.clear();
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        let data = mem::take(&mut self.buffer);
+        let decoded = match self.kind.take() {
+            Some(ParsedKind::Str) => {
+                if data.len() < 2 {
+                    return Err(error::Handler::new("Padded string is not terminated"));
+                }
+                unescape(&data[1..data.len() - 1])
+                    .ok_or_else(|| error::Handler::new("Padded string is not valid"))?
+            }
+            Some(ParsedKind::Arr) | Some(ParsedKind::Obj) | None => return Ok(Some(data)),
+            Some(_) => String::from_utf8(data).map_err(error::Handler::new)?,
+        };
+        Ok(Some(
+            format!(r#""{}""#, escape(&self.pad(&decoded))).into_bytes(),
+        ))
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Align, Pad};
+    use crate::{
+        matcher::Simple,
+        strategy::{Convert, OutputConverter, Strategy},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    fn convert(pad: Pad, input: &[u8]) -> String {
+        let mut convert = Convert::new();
+        convert.add_matcher(
+            Box::new(Simple::new(r#"[]"#).unwrap()),
+            Arc::new(Mutex::new(pad)),
+        );
+
+        let mut output = vec![];
+        let mut converter = OutputConverter::new();
+        for part in input.chunks(3) {
+            for (_, data) in converter.convert(&convert.process(part).unwrap()) {
+                output.extend(data);
+            }
+        }
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn pad() {
+        assert_eq!(
+            convert(Pad::new(5, Align::Right, ' '), br#"["ab"]"#),
+            r#"["   ab"]"#
+        );
+        assert_eq!(
+            convert(Pad::new(5, Align::Left, ' '), br#"["ab"]"#),
+            r#"["ab   "]"#
+        );
+        assert_eq!(
+            convert(Pad::new(5, Align::Center, '*'), br#"["ab"]"#),
+            r#"["*ab**"]"#
+        );
+        assert_eq!(
+            convert(
+                Pad::new(4, Align::Right, '0'),
+                br#"[12, "\"", "toolong", null, {"a": 1}]"#
+            ),
+            r#"["0012", "000\"", "toolong", "null", {"a": 1}]"#
+        );
+    }
+
+    #[test]
+    fn parse() {
+        let pad = Pad::from_str("5").unwrap();
+        assert_eq!((pad.width, pad.align, pad.fill), (5, Align::Left, ' '));
+        let pad = Pad::from_str("3,center,,").unwrap();
+        assert_eq!((pad.width, pad.align, pad.fill), (3, Align::Center, ','));
+        assert!(Pad::from_str("").is_err());
+        assert!(Pad::from_str("5,up").is_err());
+        assert!(Pad::from_str("5,right,ab").is_err());
+    }
+}
@@ -0,0 +1,262 @@
+//! Handler which converts matched integers between bases
+//!
+//! `"0xff"` -> `255` (from 16 to 10) and `255` -> `"0xff"` (from 10 to 16)
+//!
+//! Matched strings and numbers are parsed in the source base.
+//! Decimal output is emitted as a JSON number, other bases are
+//! emitted as strings (prefixed with `0x`, `0o` or `0b` for bases 16, 8 and 2).
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(handler::Radix::new(16, 10)));
+//! let matcher = matcher::Simple::new(r#"{"colors"}[]"#).unwrap();
+//!
+//! let mut convert = strategy::Convert::new();
+//!
+//! // Set the matcher for convert strategy
+//! convert.add_matcher(Box::new(matcher), handler);
+//!
+//! for input in vec![
+//!     br#"{"colors": ["0xff00", "#.to_vec(),
+//!     br#""00ff"]}"#.to_vec(),
+//! ] {
+//!     for converted_data in convert.process(&input).unwrap() {
+//!         println!("{:?}", converted_data);
+//!     }
+//! }
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    path::Path,
+    streamer::{unescape, ParsedKind, Token},
+};
+use std::{any::Any, mem, str::FromStr};
+
+/// Handler which converts matched integers from one base to another
+#[derive(Debug)]
+pub struct Radix {
+    /// Base of the matched values
+    from: u32,
+    /// Base of the output
+    to: u32,
+    /// Kind of the currently matched data
+    kind: Option<ParsedKind>,
+    /// Matched data
+    buffer: Vec<u8>,
+}
+
+/// Returns the prefix which is used for the base
+fn prefix(base: u32) -> &'static str {
+    match base {
+        2 => "0b",
+        8 => "0o",
+        16 => "0x",
+        _ => "",
+    }
+}
+
+impl Radix {
+    /// Creates a new handler which converts integers between bases
+    ///
+    /// # Arguments
+    /// * `from` - base of the matched values (2 - 36)
+    /// * `to` - base of the output (2 - 36)
+    ///
+    /// # Panics
+    /// If any of the bases is out of the range
+    pub fn new(from: u32, to: u32) -> Self {
+        assert!(
+            (2..=36).contains(&from) && (2..=36).contains(&to),
+            "base has to be within 2 - 36"
+        );
+        Self {
+            from,
+            to,
+            kind: None,
+            buffer: vec![],
+        }
+    }
+
+    /// Parses the value in the source base
+    fn parse(&self, value: &str) -> Option<i128> {
+        let value = value.trim();
+        let (negative, value) = if let Some(value) = value.strip_prefix('-') {
+            (true, value)
+        } else {
+            (false, value)
+        };
+        let prefix = prefix(self.from);
+        let value = if !prefix.is_empty()
+            && value.len() > prefix.len()
+            && value[..prefix.len()].eq_ignore_ascii_case(prefix)
+        {
+            &value[prefix.len()..]
+        } else {
+            value
+        };
+        // sign is already processed
+        if value.starts_with('+') || value.starts_with('-') {
+            return None;
+        }
+        let number = i128::from_str_radix(value, self.from).ok()?;
+        Some(if negative { -number } else { number })
+    }
+
+    /// Formats the value in the target base
+    fn format(&self, value: i128) -> String {
+        let mut digits = vec![];
+        let mut rest = value.unsigned_abs();
+        loop {
+            digits.push(std::char::from_digit((rest % self.to as u128) as u32, self.to).unwrap());
+            rest /= self.to as u128;
+            if rest == 0 {
+                break;
+            }
+        }
+        let sign = if value < 0 { "-" } else { "" };
+        let digits: String = digits.into_iter().rev().collect();
+        if self.to == 10 {
+            format!("{}{}", sign, digits)
+        } else {
+            format!(r#""{}{}{}""#, sign, prefix(self.to), digits)
+        }
+    }
+}
+
+impl FromStr for Radix {
+    type Err = error::Handler;
+    /// Parses `from,to` (e.g. `16,10`)
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let splitted: Vec<_> = input.split(',').collect();
+        if splitted.len() != 2 {
+            return Err(error::Handler::new("Failed to parse"));
+        }
+        let from: u32 = splitted[0].parse().map_err(error::Handler::new)?;
+        let to: u32 = splitted[1].parse().map_err(error::Handler::new)?;
+        if (2..=36).contains(&from) && (2..=36).contains(&to) {
+            Ok(Self::new(from, to))
+        } else {
+            Err(error::Handler::new("Base has to be within 2 - 36"))
+        }
+    }
+}
+
+impl Handler for Radix {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, token: Token) -> HandlerOutput {
+        if let Token::Start(_, kind) = token {
+            self.kind = Some(kind);
+        }
+        self.buffer.clear();
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        let data = mem::take(&mut self.buffer);
+        let value = match self.kind.take() {
+            Some(ParsedKind::Str) if data.len() >= 2 => unescape(&data[1..data.len() - 1]),
+            Some(ParsedKind::Num) | Some(ParsedKind::Int) | Some(ParsedKind::Float) => {
+                String::from_utf8(data).ok()
+            }
+            _ => {
+                return Err(error::Handler::new(
+                    "Matched data is not a string or a number",
+                ))
+            }
+        };
+        let number = value
+            .and_then(|value| self.parse(&value))
+            .ok_or_else(|| error::Handler::new("Matched data is not a valid number"))?;
+        Ok(Some(self.format(number).into_bytes()))
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Radix;
+    use crate::{
+        matcher::Simple,
+        strategy::{Convert, OutputConverter, Strategy},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    fn convert(radix: Radix, input: &[u8]) -> Result<String, ()> {
+        let mut convert = Convert::new();
+        convert.add_matcher(
+            Box::new(Simple::new(r#"[]"#).unwrap()),
+            Arc::new(Mutex::new(radix)),
+        );
+
+        let mut output = vec![];
+        let mut converter = OutputConverter::new();
+        for part in input.chunks(2) {
+            for (_, data) in converter.convert(&convert.process(part).map_err(|_| ())?) {
+                output.extend(data);
+            }
+        }
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn hex_to_decimal() {
+        assert_eq!(
+            convert(
+                Radix::new(16, 10),
+                br#"["0xff", "FF", "-0x10", 10, "0", "0XaB"]"#
+            )
+            .unwrap(),
+            r#"[255, 255, -16, 16, 0, 171]"#
+        );
+    }
+
+    #[test]
+    fn decimal_to_hex() {
+        assert_eq!(
+            convert(Radix::new(10, 16), br#"[255, "255", -16, 0]"#).unwrap(),
+            r#"["0xff", "0xff", "-0x10", "0x0"]"#
+        );
+        assert_eq!(
+            convert(Radix::from_str("10,2").unwrap(), br#"[5]"#).unwrap(),
+            r#"["0b101"]"#
+        );
+        assert_eq!(
+            convert(Radix::from_str("10,36").unwrap(), br#"[71]"#).unwrap(),
+            r#"["1z"]"#
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        for input in &[&br#"["0xfg"]"#[..], br#"[1.5]"#, br#"[[1]]"#, br#"["--1"]"#] {
+            assert!(convert(Radix::new(16, 10), input).is_err());
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert!(Radix::from_str("16,10").is_ok());
+        assert!(Radix::from_str("1,10").is_err());
+        assert!(Radix::from_str("16,37").is_err());
+        assert!(Radix::from_str("16").is_err());
+    }
+}
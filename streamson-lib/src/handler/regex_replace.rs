@@ -0,0 +1,227 @@
+//! Handler which replaces matched data using a regex with capture groups
+//!
+//! The regex is applied to the entire matched value and all the occurences
+//! are replaced (`$1`, `${name}`, ... can be used in the replacement).
+//!
+//! When `strings_only` is set, other values than strings are passed through
+//! and the regex is applied to the decoded content of the string
+//! (the result is escaped and quoted again).
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let converter = Arc::new(Mutex::new(
+//!     handler::RegexReplace::new(
+//!         regex::Regex::new(r"^(\w+)@(\w+)\.com$").unwrap(),
+//!         "$1 at $2".to_string(),
+//!     )
+//!     .set_strings_only(true),
+//! ));
+//! let matcher = matcher::Simple::new(r#"{"users"}[]{"email"}"#).unwrap();
+//!
+//! let mut convert = strategy::Convert::new();
+//!
+//! // Set the matcher for convert strategy
+//! convert.add_matcher(Box::new(matcher), converter);
+//!
+//! for input in vec![
+//!     br#"{"users": [{"email": "first@example.com"}, {"#.to_vec(),
+//!     br#""email": "second@example.com"}]}"#.to_vec(),
+//! ] {
+//!     for converted_data in convert.process(&input).unwrap() {
+//!         println!("{:?}", converted_data);
+//!     }
+//! }
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    path::Path,
+    streamer::{escape, unescape, ParsedKind, Token},
+};
+use std::{any::Any, mem, str};
+
+/// Replaces matched data using a regex
+pub struct RegexReplace {
+    /// Regex which is searched in the data
+    regex: regex::Regex,
+    /// Replacement (can contain capture groups)
+    replacement: String,
+    /// Only strings are converted
+    strings_only: bool,
+    /// Fail when the data are not valid UTF-8 (pass through otherwise)
+    fail_on_invalid: bool,
+    /// Currently matched value is a string
+    is_string: bool,
+    /// Buffer to collect input
+    buffer: Vec<u8>,
+}
+
+impl RegexReplace {
+    /// Creates a new regex replace converter
+    ///
+    /// # Arguments
+    /// * `regex` - regex which is searched in the data
+    /// * `replacement` - replacement which can contain capture groups (e.g. `$1`)
+    pub fn new(regex: regex::Regex, replacement: String) -> Self {
+        Self {
+            regex,
+            replacement,
+            strings_only: false,
+            fail_on_invalid: true,
+            is_string: false,
+            buffer: vec![],
+        }
+    }
+
+    /// Sets whether only strings should be converted
+    ///
+    /// # Arguments
+    /// * `strings_only` - convert only the content of strings
+    pub fn set_strings_only(mut self, strings_only: bool) -> Self {
+        self.strings_only = strings_only;
+        self
+    }
+
+    /// Sets whether invalid UTF-8 data should fail
+    ///
+    /// # Arguments
+    /// * `fail_on_invalid` - fail (default) or pass the invalid data through
+    pub fn set_fail_on_invalid(mut self, fail_on_invalid: bool) -> Self {
+        self.fail_on_invalid = fail_on_invalid;
+        self
+    }
+
+    /// Converts the data
+    ///
+    /// # Returns
+    /// * `None` - data are passed through
+    fn convert(&self, data: &[u8]) -> Result<Option<Vec<u8>>, error::Handler> {
+        if self.strings_only {
+            if !self.is_string {
+                return Ok(None);
+            }
+            let content = match unescape(&data[1..data.len() - 1]) {
+                Some(content) => content,
+                None if self.fail_on_invalid => {
+                    return Err(error::Handler::new("Matched string is not valid"))
+                }
+                None => return Ok(None),
+            };
+            let replaced = self.regex.replace_all(&content, self.replacement.as_str());
+            Ok(Some(format!(r#""{}""#, escape(&replaced)).into_bytes()))
+        } else {
+            let content = match str::from_utf8(data) {
+                Ok(content) => content,
+                Err(err) if self.fail_on_invalid => return Err(error::Handler::new(err)),
+                Err(_) => return Ok(None),
+            };
+            let replaced = self.regex.replace_all(content, self.replacement.as_str());
+            Ok(Some(replaced.as_bytes().to_vec()))
+        }
+    }
+}
+
+impl Handler for RegexReplace {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, token: Token) -> HandlerOutput {
+        self.is_string = matches!(token, Token::Start(_, ParsedKind::Str));
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        let data = mem::take(&mut self.buffer);
+        match self.convert(&data)? {
+            Some(converted) => Ok(Some(converted)),
+            None => Ok(Some(data)),
+        }
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RegexReplace;
+    use crate::{
+        matcher::Simple,
+        strategy::{All, Convert, OutputConverter, Strategy},
+    };
+    use std::sync::{Arc, Mutex};
+
+    fn convert(handler: RegexReplace, input: &[u8]) -> Result<String, String> {
+        let mut convert = Convert::new();
+        let matcher = Simple::new(r#"[]"#).unwrap();
+        convert.add_matcher(Box::new(matcher), Arc::new(Mutex::new(handler)));
+        let output = convert.process(input).map_err(|e| e.to_string())?;
+        let data: Vec<u8> = OutputConverter::new()
+            .convert(&output)
+            .into_iter()
+            .flat_map(|e| e.1)
+            .collect();
+        Ok(String::from_utf8_lossy(&data).to_string())
+    }
+
+    #[test]
+    fn entire_value() {
+        let handler = RegexReplace::new(
+            regex::Regex::new(r"(\d+)-(\d+)").unwrap(),
+            "$2-$1".to_string(),
+        );
+        assert_eq!(
+            convert(handler, br#"["12-34", {"a": "1-2 3-4"}, 5]"#).unwrap(),
+            r#"["34-12", {"a": "2-1 4-3"}, 5]"#
+        );
+    }
+
+    #[test]
+    fn strings_only() {
+        let handler = RegexReplace::new(regex::Regex::new(r"^(.*)$").unwrap(), "<$1>".to_string())
+            .set_strings_only(true);
+        assert_eq!(
+            convert(handler, br#"["a\"b", 1, null, "A"]"#).unwrap(),
+            r#"["<a\"b>", 1, null, "<A>"]"#
+        );
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let input = b"[\"\xff\", \"a\"]";
+
+        let handler = RegexReplace::new(regex::Regex::new("a").unwrap(), "b".to_string());
+        assert!(convert(handler, input).is_err());
+
+        let handler = RegexReplace::new(regex::Regex::new("a").unwrap(), "b".to_string())
+            .set_fail_on_invalid(false);
+        assert_eq!(convert(handler, input).unwrap(), "[\"\u{fffd}\", \"b\"]");
+    }
+
+    #[test]
+    fn all() {
+        let mut all = All::new();
+        all.set_convert(true);
+        all.add_handler(Arc::new(Mutex::new(RegexReplace::new(
+            regex::Regex::new(r"\s+").unwrap(),
+            "".to_string(),
+        ))));
+        let output: Vec<u8> = OutputConverter::new()
+            .convert(&all.process(br#"{"a": [1, 2]}"#).unwrap())
+            .into_iter()
+            .flat_map(|e| e.1)
+            .collect();
+        assert_eq!(String::from_utf8(output).unwrap(), r#"{"a":[1,2]}"#);
+    }
+}
@@ -0,0 +1,237 @@
+//! Handler which re-orders keys of matched objects
+//!
+//! Keys are emitted in the specified order, the keys which are not
+//! present in the order are appended in their original order.
+//! Note that the output is compressed (no whitespaces between the items).
+//!
+//! `{"b": 1, "c": 3, "a": 2}` with order `["a", "b"]` -> `{"a":2,"b":1,"c":3}`
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(
+//!     handler::ReorderKeys::new(vec!["id".to_string(), "name".to_string()])
+//! ));
+//! let matcher = matcher::Simple::new(r#"{"users"}[]"#).unwrap();
+//!
+//! let mut convert = strategy::Convert::new();
+//!
+//! // Set the matcher for convert strategy
+//! convert.add_matcher(Box::new(matcher), handler);
+//!
+//! for input in vec![
+//!     br#"{"users": [{"name": "first", "id": 1}, {"#.to_vec(),
+//!     br#""name": "second", "id": 2}]}"#.to_vec(),
+//! ] {
+//!     for converted_data in convert.process(&input).unwrap() {
+//!         println!("{:?}", converted_data);
+//!     }
+//! }
+//! ```
+
+use super::Handler;
+use crate::{
+    error,
+    path::{Element, Path},
+    streamer::{ParsedKind, Streamer, Token},
+};
+use std::{any::Any, str::FromStr};
+
+/// Handler which emits keys of matched objects in the specified order
+#[derive(Debug)]
+pub struct ReorderKeys {
+    /// Order of the keys
+    order: Vec<String>,
+    /// Buffer to collect the matched object
+    buffer: Vec<u8>,
+}
+
+impl ReorderKeys {
+    /// Creates a new handler which re-orders object keys
+    ///
+    /// # Arguments
+    /// * `order` - order of the keys (unknown keys are appended)
+    pub fn new(order: Vec<String>) -> Self {
+        Self {
+            order,
+            buffer: vec![],
+        }
+    }
+
+    /// Splits the object into (key, raw_value) items
+    fn items(data: &[u8]) -> Result<Vec<(String, &[u8])>, error::Handler> {
+        let mut streamer = Streamer::new();
+        streamer.feed(data);
+        let mut items = vec![];
+        let mut start = 0;
+        loop {
+            match streamer.read().map_err(error::Handler::new)? {
+                Token::Start(idx, _) => {
+                    if streamer.current_path().depth() == 1 {
+                        start = idx;
+                    }
+                }
+                Token::End(idx, _) => {
+                    let path = streamer.current_path();
+                    match path.depth() {
+                        0 => return Ok(items),
+                        1 => {
+                            if let Element::Key(key) = &path.get_path()[0] {
+                                items.push((key.clone(), &data[start..idx]));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Token::Separator(_, _) | Token::Finished(_) | Token::Discarded(_) => {}
+                Token::Pending => {
+                    return Err(error::Handler::new("Matched object is not complete"));
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for ReorderKeys {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() {
+            Ok(Self::new(vec![]))
+        } else {
+            Ok(Self::new(input.split(',').map(String::from).collect()))
+        }
+    }
+}
+
+impl Handler for ReorderKeys {
+    fn start(
+        &mut self,
+        _path: &Path,
+        _matcher_idx: usize,
+        token: Token,
+    ) -> Result<Option<Vec<u8>>, error::Handler> {
+        self.buffer.clear();
+        if let Token::Start(_, kind) = token {
+            if !matches!(kind, ParsedKind::Obj) {
+                return Err(error::Handler::new(
+                    "Re-ordered data are supposed to be an object.",
+                ));
+            }
+            Ok(None)
+        } else {
+            unreachable!();
+        }
+    }
+
+    fn feed(
+        &mut self,
+        data: &[u8],
+        _matcher_idx: usize,
+    ) -> Result<Option<Vec<u8>>, error::Handler> {
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(
+        &mut self,
+        _path: &Path,
+        _matcher_idx: usize,
+        _token: Token,
+    ) -> Result<Option<Vec<u8>>, error::Handler> {
+        let mut items: Vec<Option<(String, &[u8])>> =
+            Self::items(&self.buffer)?.into_iter().map(Some).collect();
+
+        let mut ordered = vec![];
+        for key in &self.order {
+            for item in items.iter_mut() {
+                if matches!(item, Some((item_key, _)) if item_key == key) {
+                    ordered.push(item.take().unwrap());
+                }
+            }
+        }
+        // unknown keys are appended
+        ordered.extend(items.into_iter().flatten());
+
+        let mut res = vec![b'{'];
+        for (idx, (key, value)) in ordered.into_iter().enumerate() {
+            if idx != 0 {
+                res.push(b',');
+            }
+            res.push(b'"');
+            res.extend(key.as_bytes());
+            res.extend(br#"":"#);
+            res.extend(value);
+        }
+        res.push(b'}');
+
+        self.buffer.clear();
+        Ok(Some(res))
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReorderKeys;
+    use crate::{
+        matcher::Simple,
+        strategy::{Convert, OutputConverter, Strategy},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    fn convert(handler: ReorderKeys, input: &[u8]) -> String {
+        let mut convert = Convert::new();
+        let matcher = Simple::new(r#"[]"#).unwrap();
+        convert.add_matcher(Box::new(matcher), Arc::new(Mutex::new(handler)));
+
+        let output: Vec<u8> = OutputConverter::new()
+            .convert(&convert.process(input).unwrap())
+            .into_iter()
+            .flat_map(|e| e.1)
+            .collect();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn reorder() {
+        let handler = ReorderKeys::new(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(
+            convert(
+                handler,
+                br#"[{"b":1,"a":2}, {"b": [1, 2], "c": {"a": 3}, "a": 4}]"#
+            ),
+            r#"[{"a":2,"b":1}, {"a":4,"b":[1, 2],"c":{"a": 3}}]"#
+        );
+    }
+
+    #[test]
+    fn missing_and_empty() {
+        let handler = ReorderKeys::from_str("x,b").unwrap();
+        assert_eq!(
+            convert(handler, br#"[{}, {"c": null, "b": "\"a\""}]"#),
+            r#"[{}, {"b":"\"a\"","c":null}]"#
+        );
+    }
+
+    #[test]
+    fn not_object() {
+        let mut convert = Convert::new();
+        let handler = Arc::new(Mutex::new(ReorderKeys::new(vec![])));
+        let matcher = Simple::new(r#"[]"#).unwrap();
+
+        convert.add_matcher(Box::new(matcher), handler);
+        assert!(convert.process(br#"[[1]]"#).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+//! Handler which replaces output by fixed data based on its kind
+//!
+//! It can be used e.g. to anonymize the data while keeping
+//! the structure of JSON valid
+//! `{"name": "Jane", "age": 33}` -> `{"name": "", "age": 0}`
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}, streamer::ParsedKind};
+//! use std::{collections::HashMap, sync::{Arc, Mutex}};
+//!
+//! let mut replacements = HashMap::new();
+//! replacements.insert(ParsedKind::Num, b"0".to_vec());
+//! replacements.insert(ParsedKind::Str, br#""""#.to_vec());
+//! replacements.insert(ParsedKind::Bool, b"false".to_vec());
+//!
+//! let handler = Arc::new(Mutex::new(handler::ReplaceByKind::new(replacements)));
+//! let matcher = matcher::Simple::new(r#"{"users"}[]{}"#).unwrap();
+//!
+//! let mut convert = strategy::Convert::new();
+//!
+//! // Set the matcher for convert strategy
+//! convert.add_matcher(Box::new(matcher), handler);
+//!
+//! for input in vec![
+//!     br#"{"users": [{"name": "first", "age": 33, "#.to_vec(),
+//!     br#""admin": true, "groups": ["a"]}]}"#.to_vec(),
+//! ] {
+//!     for converted_data in convert.process(&input).unwrap() {
+//!         println!("{:?}", converted_data);
+//!     }
+//! }
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    path::Path,
+    streamer::{ParsedKind, Token},
+};
+use std::{any::Any, collections::HashMap, mem};
+
+/// Handler which converts matched data to fixed output based on the kind of the data
+///
+/// Numbers can be replaced based on their exact kind (`Int` or `Float`),
+/// `Num` replacement is used for all numbers otherwise.
+/// Data of a kind without a replacement are kept as they are.
+#[derive(Debug)]
+pub struct ReplaceByKind {
+    /// Data which will be returned instead of matched data
+    replacements: HashMap<ParsedKind, Vec<u8>>,
+    /// Kind of the currently matched data
+    kind: Option<ParsedKind>,
+    /// Original data (used when there is no replacement)
+    buffer: Vec<u8>,
+}
+
+impl ReplaceByKind {
+    /// Creates a new handler which replaces matched data by fixed output
+    ///
+    /// # Arguments
+    /// * `replacements` - data which will be used for the given kind
+    pub fn new(replacements: HashMap<ParsedKind, Vec<u8>>) -> Self {
+        Self {
+            replacements,
+            kind: None,
+            buffer: vec![],
+        }
+    }
+
+    /// Finds the replacement for the kind
+    fn replacement(&self, kind: ParsedKind) -> Option<&Vec<u8>> {
+        self.replacements.get(&kind).or_else(|| match kind {
+            ParsedKind::Int | ParsedKind::Float => self.replacements.get(&ParsedKind::Num),
+            _ => None,
+        })
+    }
+}
+
+impl Handler for ReplaceByKind {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, token: Token) -> HandlerOutput {
+        if let Token::Start(_, kind) = token {
+            self.kind = Some(kind);
+        }
+        self.buffer.clear();
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(&mut self, _path: &Path, _matcher_idx: usize, token: Token) -> HandlerOutput {
+        let data = mem::take(&mut self.buffer);
+        // exact kind of numbers is known at the end
+        let kind = match token {
+            Token::End(_, kind) => Some(kind),
+            _ => self.kind,
+        };
+        self.kind = None;
+        if let Some(replacement) = kind.and_then(|kind| self.replacement(kind)) {
+            Ok(Some(replacement.clone()))
+        } else {
+            Ok(Some(data))
+        }
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReplaceByKind;
+    use crate::{
+        matcher::Simple,
+        strategy::{Convert, OutputConverter, Strategy},
+        streamer::ParsedKind,
+    };
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    fn convert(replacements: &[(ParsedKind, &[u8])], input: &[u8]) -> String {
+        let replacements: HashMap<ParsedKind, Vec<u8>> = replacements
+            .iter()
+            .map(|(kind, data)| (*kind, data.to_vec()))
+            .collect();
+        let mut convert = Convert::new();
+        convert.add_matcher(
+            Box::new(Simple::new(r#"[]"#).unwrap()),
+            Arc::new(Mutex::new(ReplaceByKind::new(replacements))),
+        );
+
+        let mut output = vec![];
+        let mut converter = OutputConverter::new();
+        for part in input.chunks(3) {
+            for (_, data) in converter.convert(&convert.process(part).unwrap()) {
+                output.extend(data);
+            }
+        }
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn mixed() {
+        let output = convert(
+            &[
+                (ParsedKind::Num, b"0"),
+                (ParsedKind::Str, br#""""#),
+                (ParsedKind::Bool, b"false"),
+            ],
+            br#"[12, "text", true, 1.5, null, {"a": 1}, "\"", false]"#,
+        );
+        assert_eq!(output, r#"[0, "", false, 0, null, {"a": 1}, "", false]"#);
+    }
+
+    #[test]
+    fn exact_number_kind() {
+        let output = convert(
+            &[
+                (ParsedKind::Num, b"0"),
+                (ParsedKind::Float, b"0.0"),
+                (ParsedKind::Arr, b"[]"),
+                (ParsedKind::Null, br#""N/A""#),
+            ],
+            br#"[1, 2.5, -3e2, [1, 2], null]"#,
+        );
+        assert_eq!(output, r#"[0, 0.0, 0.0, [], "N/A"]"#);
+    }
+}
@@ -0,0 +1,295 @@
+//! Handler which infers a JSON Schema of the matched data
+//!
+//! It tracks kinds of the values, object keys and array items
+//! per path (array indexes are reduced) and emits a draft-07
+//! JSON Schema once the input is finished (or after each JSON when `per_json` is set).
+//!
+//! Keys which are present in all the objects are marked as required
+//! and nullable values have `null` among their types.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(handler::SchemaInfer::new()));
+//!
+//! let mut all = strategy::All::new();
+//! all.set_convert(true);
+//! all.add_handler(handler);
+//!
+//! all.process(br#"{"users": [{"name": "first"}, {"name": null, "age": 3}]}"#).unwrap();
+//! for output in all.terminate().unwrap() {
+//!     println!("{:?}", output);
+//! }
+//! ```
+
+use std::{
+    any::Any,
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    path::{Element, Path},
+    streamer::{escape, unescape, ParsedKind, Token},
+};
+
+/// Inferred schema of a single (reduced) path
+#[derive(Debug, Default)]
+struct Node {
+    /// JSON Schema types of the values
+    types: BTreeSet<&'static str>,
+    /// Number of objects which ended here
+    objects: usize,
+    /// Object properties with number of their occurences
+    properties: BTreeMap<String, (usize, Node)>,
+    /// Schema of the array items
+    items: Option<Box<Node>>,
+}
+
+/// Converts the key from the path (keys are stored escaped)
+fn key(key: &str) -> String {
+    unescape(key.as_bytes()).unwrap_or_else(|| key.to_string())
+}
+
+impl Node {
+    /// Returns the node of the nested element (it is created if missing)
+    fn nested(&mut self, elements: &[Element]) -> &mut Node {
+        let mut node = self;
+        for element in elements {
+            node = match element {
+                Element::Key(name) => &mut node.properties.entry(key(name)).or_default().1,
+                Element::Index(_) => node.items.get_or_insert_with(Default::default),
+            };
+        }
+        node
+    }
+
+    /// Serializes the node into JSON Schema
+    fn write(&self, res: &mut String) {
+        let mut types: Vec<&str> = self.types.iter().copied().collect();
+        if self.types.contains("number") {
+            // integer is a subset of number
+            types.retain(|tp| *tp != "integer");
+        }
+        let mut items = vec![];
+        match types.len() {
+            0 => {}
+            1 => items.push(format!(r#""type":"{}""#, types[0])),
+            _ => items.push(format!(r#""type":["{}"]"#, types.join(r#"",""#))),
+        }
+        if self.types.contains("object") {
+            let mut properties = String::new();
+            let mut required = vec![];
+            for (idx, (key, (count, node))) in self.properties.iter().enumerate() {
+                if idx != 0 {
+                    properties.push(',');
+                }
+                properties.push_str(&format!(r#""{}":"#, escape(key)));
+                node.write(&mut properties);
+                if *count == self.objects {
+                    required.push(format!(r#""{}""#, escape(key)));
+                }
+            }
+            items.push(format!(r#""properties":{{{}}}"#, properties));
+            if !required.is_empty() {
+                items.push(format!(r#""required":[{}]"#, required.join(",")));
+            }
+        }
+        if let Some(node) = self.items.as_ref() {
+            let mut node_str = String::new();
+            node.write(&mut node_str);
+            items.push(format!(r#""items":{}"#, node_str));
+        }
+        res.push('{');
+        res.push_str(&items.join(","));
+        res.push('}');
+    }
+}
+
+/// Handler which infers a JSON Schema
+#[derive(Debug, Default)]
+pub struct SchemaInfer {
+    /// Schema of the root element
+    root: Node,
+    /// Emit (and reset) the schema after each JSON
+    per_json: bool,
+}
+
+impl SchemaInfer {
+    /// Creates a new handler which infers a JSON Schema
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the schema should be emitted after each JSON
+    pub fn set_per_json(mut self, per_json: bool) -> Self {
+        self.per_json = per_json;
+        self
+    }
+
+    /// Returns the JSON Schema inferred so far
+    pub fn schema(&self) -> String {
+        let mut res = String::from(r#"{"$schema":"http://json-schema.org/draft-07/schema#""#);
+        let mut root = String::new();
+        self.root.write(&mut root);
+        if root.len() > 2 {
+            res.push(',');
+            res.push_str(&root[1..root.len() - 1]);
+        }
+        res.push('}');
+        res
+    }
+
+    /// Serializes current schema and resets it
+    fn emit(&mut self) -> Vec<u8> {
+        let res = self.schema().into_bytes();
+        self.root = Node::default();
+        res
+    }
+}
+
+impl Handler for SchemaInfer {
+    fn end(&mut self, path: &Path, _matcher_idx: usize, token: Token) -> HandlerOutput {
+        let kind = if let Token::End(_, kind) = token {
+            kind
+        } else {
+            unreachable!();
+        };
+
+        let elements = path.get_path();
+        let node = self.root.nested(elements);
+        node.types.insert(match kind {
+            ParsedKind::Obj => {
+                node.objects += 1;
+                "object"
+            }
+            ParsedKind::Arr => "array",
+            ParsedKind::Str => "string",
+            ParsedKind::Int => "integer",
+            ParsedKind::Num | ParsedKind::Float => "number",
+            ParsedKind::Null => "null",
+            ParsedKind::Bool => "boolean",
+        });
+
+        // count the key within the parent object
+        if let Some(Element::Key(name)) = elements.last() {
+            let parent = self.root.nested(&elements[..elements.len() - 1]);
+            parent.properties.entry(key(name)).or_default().0 += 1;
+        }
+        Ok(None)
+    }
+
+    fn input_finished(&mut self) -> HandlerOutput {
+        if self.per_json {
+            Ok(None)
+        } else {
+            Ok(Some(self.emit()))
+        }
+    }
+
+    fn json_finished(&mut self) -> HandlerOutput {
+        if self.per_json {
+            Ok(Some(self.emit()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl FromStr for SchemaInfer {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() {
+            Ok(Self::default())
+        } else {
+            let per_json: bool =
+                bool::from_str(input).map_err(|e| Self::Err::new(e.to_string()))?;
+            Ok(Self::default().set_per_json(per_json))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SchemaInfer;
+    use crate::strategy::{All, Output, Strategy};
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    fn infer(handler: SchemaInfer, inputs: &[&[u8]]) -> String {
+        let mut all = All::new();
+        all.set_convert(true);
+        all.add_handler(Arc::new(Mutex::new(handler)));
+
+        let mut output = vec![];
+        for input in inputs {
+            output.extend(all.process(input).unwrap());
+        }
+        output.extend(all.terminate().unwrap());
+        let data: Vec<u8> = output
+            .into_iter()
+            .flat_map(|e| match e {
+                Output::Data(data) => data,
+                _ => vec![],
+            })
+            .collect();
+        String::from_utf8(data).unwrap()
+    }
+
+    #[test]
+    fn objects() {
+        let output = infer(
+            SchemaInfer::new(),
+            &[
+                br#"{"users": [{"name": "first", "age": 1}, {"na"#,
+                br#"me": null, "age": 2.5, "tags": []}], "total": 2}"#,
+            ],
+        );
+        assert_eq!(
+            output,
+            concat!(
+                r#"{"$schema":"http://json-schema.org/draft-07/schema#","type":"object","properties":{"#,
+                r#""total":{"type":"integer"},"users":{"type":"array","items":{"type":"object","properties":{"#,
+                r#""age":{"type":"number"},"name":{"type":["null","string"]},"tags":{"type":"array"}"#,
+                r#"},"required":["age","name"]}}},"required":["total","users"]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn per_json() {
+        let output = infer(
+            SchemaInfer::from_str("true").unwrap(),
+            &[br#"[1, "2"] {"a\"b": true}"#],
+        );
+        assert_eq!(
+            output,
+            concat!(
+                r#"{"$schema":"http://json-schema.org/draft-07/schema#","type":"array","items":{"type":["integer","string"]}}"#,
+                r#"{"$schema":"http://json-schema.org/draft-07/schema#","type":"object","properties":{"a\"b":{"type":"boolean"}},"required":["a\"b"]}"#,
+            )
+        );
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(
+            infer(SchemaInfer::new(), &[]),
+            r#"{"$schema":"http://json-schema.org/draft-07/schema#"}"#
+        );
+    }
+}
@@ -0,0 +1,306 @@
+//! Handler which splits matched objects into shards
+//!
+//! The shard is determined by a hash of the value of a key field,
+//! so objects with the same key always end up in the same shard.
+//! It can be used e.g. to process the objects in parallel.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let shard_handler = Arc::new(Mutex::new(handler::ShardByKey::new("id", 2)));
+//!
+//! let matcher = matcher::Simple::new(r#"[]"#).unwrap();
+//!
+//! let mut trigger = strategy::Trigger::new();
+//!
+//! // Set the matcher for trigger strategy
+//! trigger.add_matcher(Box::new(matcher), shard_handler.clone());
+//!
+//! for input in vec![
+//!     br#"[{"id": 1, "name": "first"}, {"id": 2, "name": "second"}, "#.to_vec(),
+//!     br#"{"id": 1, "name": "changed"}]"#.to_vec(),
+//! ] {
+//!     trigger.process(&input).unwrap();
+//!     let mut guard = shard_handler.lock().unwrap();
+//!     for shard in 0..2 {
+//!         while let Some((_, data)) = guard.pop(shard) {
+//!             // Do something with the data of the shard
+//!             println!("{}: {}", shard, String::from_utf8(data).unwrap());
+//!         }
+//!     }
+//! }
+//! ```
+
+use super::{buffer::Buff, dedup_by_key::key_value, hash_sample::fnv1a, Handler, HandlerOutput};
+use crate::{error, path::Path, streamer::Token};
+use std::{any::Any, collections::VecDeque, str::FromStr};
+
+/// Handler which stores matched objects into shards by a key field
+pub struct ShardByKey {
+    /// Name of the field which identifies the object
+    key_field: String,
+    /// For storing unterminated data
+    buffer: Vec<u8>,
+    /// Buffer idx to total index
+    buffer_idx: usize,
+    /// Indexes for the Path and size
+    buffer_parts: Vec<usize>,
+    /// Queues with stored objects in (path, data) format (one per shard)
+    results: Vec<VecDeque<(Option<String>, Vec<u8>)>>,
+    /// Not to show path will spare some allocation
+    use_path: bool,
+    /// Current buffer size (in bytes)
+    current_buffer_size: usize,
+    /// Max buffer size
+    max_buffer_size: Option<usize>,
+}
+
+impl FromStr for ShardByKey {
+    type Err = error::Handler;
+
+    /// Parses `key_field,shards` (e.g. `id,4`)
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let splitted: Vec<&str> = input.rsplitn(2, ',').collect();
+        if splitted.len() != 2 || splitted[1].is_empty() {
+            return Err(error::Handler::new("Key field and shards have to be set"));
+        }
+        let shards: usize = splitted[0].parse().map_err(error::Handler::new)?;
+        if shards == 0 {
+            return Err(error::Handler::new("Number of shards has to be positive"));
+        }
+        Ok(Self::new(splitted[1], shards))
+    }
+}
+
+impl ShardByKey {
+    /// Creates a new handler which splits objects into shards
+    ///
+    /// # Arguments
+    /// * `key_field` - name of the field which identifies the object
+    /// * `shards` - number of shards (has to be greater than 0)
+    pub fn new<S>(key_field: S, shards: usize) -> Self
+    where
+        S: ToString,
+    {
+        assert!(shards > 0);
+        Self {
+            key_field: key_field.to_string(),
+            buffer: vec![],
+            buffer_idx: 0,
+            buffer_parts: vec![],
+            results: vec![VecDeque::new(); shards],
+            use_path: false,
+            current_buffer_size: 0,
+            max_buffer_size: None,
+        }
+    }
+
+    /// Set whether to show path
+    ///
+    /// # Arguments
+    /// * `use_path` - should path be store with data
+    pub fn set_use_path(mut self, use_path: bool) -> Self {
+        self.use_path = use_path;
+        self
+    }
+
+    /// Sets max buffer size
+    ///
+    /// # Arguments
+    /// * `max_size` - max size of buffered data (in bytes)
+    pub fn set_max_buffer_size(mut self, max_size: Option<usize>) -> Self {
+        self.max_buffer_size = max_size;
+        self
+    }
+
+    /// Returns the number of shards
+    pub fn shards(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Computes the shard of an object
+    ///
+    /// Objects without the key field are sharded by its entire data.
+    ///
+    /// # Arguments
+    /// * `data` - raw data of the object
+    pub fn shard(&self, data: &[u8]) -> usize {
+        let hash = match key_value(data, &self.key_field) {
+            Some(key) => fnv1a(&key),
+            None => fnv1a(data),
+        };
+        (hash % self.results.len() as u64) as usize
+    }
+
+    /// Pops the oldest object of the shard
+    ///
+    /// # Arguments
+    /// * `shard` - index of the shard
+    ///
+    /// # Returns
+    /// * `None` - queue of the shard is empty (or shard doesn't exist)
+    /// * `Some((path, data))` - stored data remove from the queue and returned
+    pub fn pop(&mut self, shard: usize) -> Option<(Option<String>, Vec<u8>)> {
+        let popped = self.results.get_mut(shard)?.pop_front();
+        if popped.is_some() {
+            self.current_buffer_size =
+                self.results.iter().flatten().fold(0, |e, y| e + y.1.len()) + self.buffer.len();
+        }
+        popped
+    }
+}
+
+impl Handler for ShardByKey {
+    fn start(&mut self, path: &Path, matcher_idx: usize, token: Token) -> HandlerOutput {
+        self._start(path, matcher_idx, token)
+    }
+
+    fn feed(&mut self, data: &[u8], matcher_idx: usize) -> HandlerOutput {
+        self._feed(data, matcher_idx)
+    }
+
+    fn end(&mut self, path: &Path, matcher_idx: usize, token: Token) -> HandlerOutput {
+        self._end(path, matcher_idx, token)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Buff for ShardByKey {
+    fn store_result(&mut self, path: &Path, data: Vec<u8>) {
+        let shard = self.shard(&data);
+        let use_path = *self.use_path();
+        self.results[shard].push_back((
+            if use_path {
+                Some(path.to_string())
+            } else {
+                None
+            },
+            data,
+        ));
+    }
+
+    fn buffer(&mut self) -> &mut Vec<u8> {
+        &mut self.buffer
+    }
+
+    fn buffer_parts(&mut self) -> &mut Vec<usize> {
+        &mut self.buffer_parts
+    }
+
+    fn buffer_idx(&mut self) -> &mut usize {
+        &mut self.buffer_idx
+    }
+
+    fn max_buffer_size(&mut self) -> &mut Option<usize> {
+        &mut self.max_buffer_size
+    }
+
+    fn current_buffer_size(&mut self) -> &mut usize {
+        &mut self.current_buffer_size
+    }
+
+    fn use_path(&mut self) -> &mut bool {
+        &mut self.use_path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShardByKey;
+    use crate::{
+        matcher::Simple,
+        strategy::{Strategy, Trigger},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    fn shards(handler: ShardByKey, inputs: &[&[u8]]) -> Vec<Vec<Vec<u8>>> {
+        let count = handler.shards();
+        let handler = Arc::new(Mutex::new(handler));
+        let mut trigger = Trigger::new();
+        trigger.add_matcher(Box::new(Simple::new(r#"[]"#).unwrap()), handler.clone());
+        for input in inputs {
+            trigger.process(input).unwrap();
+        }
+
+        let mut guard = handler.lock().unwrap();
+        (0..count)
+            .map(|shard| {
+                let mut res = vec![];
+                while let Some((_, data)) = guard.pop(shard) {
+                    res.push(data);
+                }
+                res
+            })
+            .collect()
+    }
+
+    #[test]
+    fn by_id() {
+        let records: Vec<String> = (0..30)
+            .map(|e| format!(r#"{{"id": {}, "version": {}}}"#, e % 10, e / 10))
+            .collect();
+        let input = format!("[{}]", records.join(", "));
+        let output = shards(
+            ShardByKey::new("id", 3),
+            &[&input.as_bytes()[..100], &input.as_bytes()[100..]],
+        );
+        assert_eq!(output.len(), 3);
+        assert_eq!(output.iter().map(|e| e.len()).sum::<usize>(), 30);
+
+        let shard_of = |id: usize| {
+            output
+                .iter()
+                .position(|shard| {
+                    shard.contains(&format!(r#"{{"id": {}, "version": 0}}"#, id).into_bytes())
+                })
+                .unwrap()
+        };
+        for (shard, data) in output.iter().enumerate() {
+            // all versions of the same record are in the same shard
+            assert_eq!(data.len() % 3, 0);
+            for id in 0..10 {
+                if shard_of(id) == shard {
+                    for version in 0..3 {
+                        let record = format!(r#"{{"id": {}, "version": {}}}"#, id, version);
+                        assert!(data.contains(&record.into_bytes()));
+                    }
+                }
+            }
+        }
+        // records are distributed
+        assert!(output.iter().filter(|e| !e.is_empty()).count() > 1);
+
+        // stable across runs
+        assert_eq!(
+            output,
+            shards(ShardByKey::new("id", 3), &[input.as_bytes()])
+        );
+    }
+
+    #[test]
+    fn without_key() {
+        let output = shards(ShardByKey::new("id", 2), &[br#"[{"a": 1}, {"a": 1}, 3]"#]);
+        assert_eq!(output.iter().map(|e| e.len()).sum::<usize>(), 3);
+        assert!(output.iter().any(|e| e.len() >= 2));
+    }
+
+    #[test]
+    fn parse() {
+        let handler = ShardByKey::from_str("id,4").unwrap();
+        assert_eq!(handler.shards(), 4);
+        assert_eq!(handler.key_field, "id");
+        assert_eq!(ShardByKey::from_str("a,b,2").unwrap().key_field, "a,b");
+        assert!(ShardByKey::from_str("id").is_err());
+        assert!(ShardByKey::from_str(",2").is_err());
+        assert!(ShardByKey::from_str("id,0").is_err());
+        assert!(ShardByKey::from_str("id,x").is_err());
+    }
+}
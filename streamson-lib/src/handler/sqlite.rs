@@ -0,0 +1,307 @@
+//! Handler which inserts matched values into an SQLite table
+//!
+//! Each matcher index represents a column. Values matched within
+//! a single JSON are collected into a row which is inserted
+//! when the JSON is finished. When a column is matched again within the same JSON
+//! a new row is started (the same way as in `Tsv` handler).
+//!
+//! Strings are stored as text, integers as integers, floats as reals,
+//! booleans as `0`/`1` and nulls as `NULL`. Objects and arrays
+//! are stored as JSON text.
+//!
+//! The table is created when it doesn't exist and rows
+//! of a single JSON are inserted within a single transaction.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(
+//!     handler::Sqlite::new(":memory:", "users", vec!["id".into(), "name".into()]).unwrap()
+//! ));
+//!
+//! let mut trigger = strategy::Trigger::new();
+//! trigger.add_matcher(
+//!     Box::new(matcher::Simple::new(r#"{"users"}[]{"id"}"#).unwrap()),
+//!     handler.clone(),
+//! );
+//! trigger.add_matcher(
+//!     Box::new(matcher::Simple::new(r#"{"users"}[]{"name"}"#).unwrap()),
+//!     handler.clone(),
+//! );
+//!
+//! trigger.process(br#"{"users": [{"id": 1, "name": "first"}, {"id": 2, "name": "second"}]}"#).unwrap();
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    path::Path,
+    streamer::{unescape, ParsedKind, Token},
+};
+use rusqlite::{types::Value, Connection, NO_PARAMS};
+use std::{any::Any, mem, str::FromStr};
+
+/// Handler which inserts matched values into an SQLite table
+pub struct Sqlite {
+    /// Database connection
+    connection: Connection,
+    /// Insert statement
+    insert: String,
+    /// Number of columns
+    columns: usize,
+    /// Rows of the current JSON
+    rows: Vec<Vec<Option<Value>>>,
+    /// Data of currently matched value
+    buffer: Vec<u8>,
+}
+
+/// Quotes an SQL identifier
+fn quote(identifier: &str) -> String {
+    format!(r#""{}""#, identifier.replace('"', r#""""#))
+}
+
+impl Sqlite {
+    /// Creates a new SQLite handler
+    ///
+    /// # Arguments
+    /// * `database` - path to the database file (`:memory:` for in-memory database)
+    /// * `table` - name of the table (it is created if it doesn't exist)
+    /// * `columns` - names of the columns (matcher indexes `0..columns.len()`)
+    pub fn new(database: &str, table: &str, columns: Vec<String>) -> Result<Self, error::Handler> {
+        let connection = Connection::open(database).map_err(error::Handler::new)?;
+        Self::with_connection(connection, table, columns)
+    }
+
+    /// Creates a new SQLite handler using an existing connection
+    ///
+    /// # Arguments
+    /// * `connection` - opened database connection
+    /// * `table` - name of the table (it is created if it doesn't exist)
+    /// * `columns` - names of the columns (matcher indexes `0..columns.len()`)
+    pub fn with_connection(
+        connection: Connection,
+        table: &str,
+        columns: Vec<String>,
+    ) -> Result<Self, error::Handler> {
+        if columns.is_empty() {
+            return Err(error::Handler::new("No SQLite columns were set"));
+        }
+        let names: Vec<String> = columns.iter().map(|column| quote(column)).collect();
+        connection
+            .execute(
+                &format!(
+                    "CREATE TABLE IF NOT EXISTS {} ({})",
+                    quote(table),
+                    names.join(", ")
+                ),
+                NO_PARAMS,
+            )
+            .map_err(error::Handler::new)?;
+        let insert = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            quote(table),
+            names.join(", "),
+            vec!["?"; names.len()].join(", ")
+        );
+        Ok(Self {
+            connection,
+            insert,
+            columns: columns.len(),
+            rows: vec![],
+            buffer: vec![],
+        })
+    }
+
+    /// Returns the database connection
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Stores the value into the current row
+    fn store(&mut self, column: usize, value: Value) {
+        let new_row = match self.rows.last() {
+            Some(row) => row[column].is_some(),
+            None => true,
+        };
+        if new_row {
+            self.rows.push(vec![None; self.columns]);
+        }
+        self.rows.last_mut().unwrap()[column] = Some(value);
+    }
+}
+
+/// Converts matched data into an SQLite value
+fn to_value(data: Vec<u8>, kind: ParsedKind) -> Result<Value, error::Handler> {
+    let text = |data: Vec<u8>| String::from_utf8(data).map_err(error::Handler::new);
+    Ok(match kind {
+        ParsedKind::Str => {
+            let content = &data[1..data.len() - 1];
+            Value::Text(
+                unescape(content).ok_or_else(|| error::Handler::new("Invalid JSON string"))?,
+            )
+        }
+        ParsedKind::Int => {
+            let data = text(data)?;
+            match data.parse::<i64>() {
+                Ok(number) => Value::Integer(number),
+                // too large to fit into an integer
+                Err(_) => Value::Real(data.parse().map_err(error::Handler::new)?),
+            }
+        }
+        ParsedKind::Float | ParsedKind::Num => {
+            Value::Real(text(data)?.parse().map_err(error::Handler::new)?)
+        }
+        ParsedKind::Bool => Value::Integer((data == b"true") as i64),
+        ParsedKind::Null => Value::Null,
+        ParsedKind::Obj | ParsedKind::Arr => Value::Text(text(data)?),
+    })
+}
+
+impl Handler for Sqlite {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        self.buffer.clear();
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(&mut self, _path: &Path, matcher_idx: usize, token: Token) -> HandlerOutput {
+        let kind = if let Token::End(_, kind) = token {
+            kind
+        } else {
+            unreachable!();
+        };
+        if matcher_idx >= self.columns {
+            return Err(error::Handler::new(format!(
+                "SQLite column {} is out of range",
+                matcher_idx
+            )));
+        }
+        let value = to_value(mem::take(&mut self.buffer), kind)?;
+        self.store(matcher_idx, value);
+        Ok(None)
+    }
+
+    fn json_finished(&mut self) -> HandlerOutput {
+        if self.rows.is_empty() {
+            return Ok(None);
+        }
+        let transaction = self.connection.transaction().map_err(error::Handler::new)?;
+        {
+            let mut statement = transaction
+                .prepare_cached(&self.insert)
+                .map_err(error::Handler::new)?;
+            for row in self.rows.drain(..) {
+                statement
+                    .execute(row.into_iter().map(|value| value.unwrap_or(Value::Null)))
+                    .map_err(error::Handler::new)?;
+            }
+        }
+        transaction.commit().map_err(error::Handler::new)?;
+        Ok(None)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl FromStr for Sqlite {
+    type Err = error::Handler;
+    /// Parses `database,table,column1,column2,...`
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parts = input.split(',');
+        let database = parts
+            .next()
+            .filter(|database| !database.is_empty())
+            .ok_or_else(|| error::Handler::new("SQLite database is missing"))?;
+        let table = parts
+            .next()
+            .ok_or_else(|| error::Handler::new("SQLite table is missing"))?;
+        Self::new(database, table, parts.map(String::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sqlite;
+    use crate::{
+        matcher::Simple,
+        strategy::{Strategy, Trigger},
+    };
+    use rusqlite::{types::Value, NO_PARAMS};
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    fn rows(handler: &Sqlite, query: &str) -> Vec<Vec<Value>> {
+        let mut statement = handler.connection().prepare(query).unwrap();
+        let columns = statement.column_count();
+        statement
+            .query_map(NO_PARAMS, |row| {
+                (0..columns).map(|idx| row.get::<_, Value>(idx)).collect()
+            })
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn insert() {
+        let handler = Arc::new(Mutex::new(
+            Sqlite::new(":memory:", "users", vec!["id".into(), "name".into()]).unwrap(),
+        ));
+        let mut trigger = Trigger::new();
+        trigger.add_matcher(
+            Box::new(Simple::new(r#"{"users"}[]{"id"}"#).unwrap()),
+            handler.clone(),
+        );
+        trigger.add_matcher(
+            Box::new(Simple::new(r#"{"users"}[]{"name"}"#).unwrap()),
+            handler.clone(),
+        );
+
+        let input =
+            br#"{"users": [{"id": 1, "name": "fi\"rst"}, {"name": ["x"], "id": 2.5}, {"id": null}]}
+            {"users": [{"name": true}]}"#;
+        for part in input.chunks(5) {
+            trigger.process(part).unwrap();
+        }
+
+        let guard = handler.lock().unwrap();
+        assert_eq!(
+            rows(&guard, r#"SELECT "id", "name" FROM "users""#),
+            vec![
+                vec![Value::Integer(1), Value::Text(r#"fi"rst"#.into())],
+                vec![Value::Real(2.5), Value::Text(r#"["x"]"#.into())],
+                vec![Value::Null, Value::Null],
+                vec![Value::Null, Value::Integer(1)],
+            ]
+        );
+    }
+
+    #[test]
+    fn from_str() {
+        assert!(Sqlite::from_str(":memory:,items,a,b").is_ok());
+        assert!(Sqlite::from_str(":memory:,items").is_err());
+        assert!(Sqlite::from_str(":memory:").is_err());
+        assert!(Sqlite::from_str("").is_err());
+    }
+
+    #[test]
+    fn out_of_range() {
+        let handler = Arc::new(Mutex::new(
+            Sqlite::new(":memory:", "items", vec!["a".into()]).unwrap(),
+        ));
+        let mut trigger = Trigger::new();
+        trigger.add_matcher(Box::new(Simple::new(r#"{"a"}"#).unwrap()), handler.clone());
+        trigger.add_matcher(Box::new(Simple::new(r#"{"b"}"#).unwrap()), handler);
+        assert!(trigger.process(br#"{"a": 1, "b": 2}"#).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+//! Handler which stores a stable hash of matched data
+//!
+//! The hash doesn't depend on the order of object keys nor on whitespace,
+//! so `{"a": 1, "b": 2}` and `{"b":2,"a":1}` have the same hash.
+//! It can be used e.g. to create cache keys.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let hash_handler = Arc::new(Mutex::new(handler::StableHash::new().set_use_path(true)));
+//!
+//! let matcher = matcher::Simple::new(r#"{"users"}[]"#).unwrap();
+//!
+//! let mut trigger = strategy::Trigger::new();
+//!
+//! // Set the matcher for trigger strategy
+//! trigger.add_matcher(Box::new(matcher), hash_handler.clone());
+//!
+//! for input in vec![
+//!     br#"{"users": [{"id": 1, "name": "first"}, "#.to_vec(),
+//!     br#"{"name": "first", "id": 1}]}"#.to_vec(),
+//! ] {
+//!     trigger.process(&input).unwrap();
+//!     let mut guard = hash_handler.lock().unwrap();
+//!     while let Some((path, hash)) = guard.pop() {
+//!         // Do something with the hash
+//!         println!("{}: {:016x}", path.unwrap(), hash);
+//!     }
+//! }
+//! ```
+
+use super::{hash_sample::fnv1a, Handler, HandlerOutput};
+use crate::{error, path::Path, streamer::Token};
+use serde_json::Value;
+use std::{any::Any, collections::VecDeque, str::FromStr};
+
+/// Handler which stores hashes of matched data with canonical key order
+#[derive(Debug, Default)]
+pub struct StableHash {
+    /// Buffer to collect matched data
+    buffer: Vec<u8>,
+    /// Queue with stored hashes in (path, hash) format
+    results: VecDeque<(Option<String>, u64)>,
+    /// Not to show path will spare some allocation
+    use_path: bool,
+}
+
+impl FromStr for StableHash {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() {
+            Ok(Self::default())
+        } else {
+            Err(error::Handler::new(
+                "StableHash handler accepts no argument",
+            ))
+        }
+    }
+}
+
+/// Writes the value with object keys sorted
+fn canonical(value: &Value, output: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut items: Vec<(&String, &Value)> = map.iter().collect();
+            items.sort_by(|a, b| a.0.cmp(b.0));
+            output.push('{');
+            for (idx, (key, value)) in items.into_iter().enumerate() {
+                if idx > 0 {
+                    output.push(',');
+                }
+                output.push_str(&Value::String(key.clone()).to_string());
+                output.push(':');
+                canonical(value, output);
+            }
+            output.push('}');
+        }
+        Value::Array(items) => {
+            output.push('[');
+            for (idx, value) in items.iter().enumerate() {
+                if idx > 0 {
+                    output.push(',');
+                }
+                canonical(value, output);
+            }
+            output.push(']');
+        }
+        value => output.push_str(&value.to_string()),
+    }
+}
+
+impl StableHash {
+    /// Creates a new handler which stores stable hashes of matched data
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to show path
+    ///
+    /// # Arguments
+    /// * `use_path` - should path be store with the hash
+    pub fn set_use_path(mut self, use_path: bool) -> Self {
+        self.use_path = use_path;
+        self
+    }
+
+    /// Pops the oldest hash
+    ///
+    /// # Returns
+    /// * `None` - queue is empty
+    /// * `Some((path, hash))` - stored hash remove from the queue and returned
+    pub fn pop(&mut self) -> Option<(Option<String>, u64)> {
+        self.results.pop_front()
+    }
+
+    /// Computes the hash of JSON data
+    ///
+    /// # Arguments
+    /// * `data` - raw JSON data
+    pub fn hash(data: &[u8]) -> Result<u64, error::Handler> {
+        let value: Value = serde_json::from_slice(data).map_err(error::Handler::new)?;
+        let mut output = String::new();
+        canonical(&value, &mut output);
+        Ok(fnv1a(output.as_bytes()))
+    }
+}
+
+impl Handler for StableHash {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        self.buffer.clear();
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(&mut self, path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        let hash = Self::hash(&self.buffer)?;
+        self.buffer.clear();
+        self.results.push_back((
+            if self.use_path {
+                Some(path.to_string())
+            } else {
+                None
+            },
+            hash,
+        ));
+        Ok(None)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StableHash;
+    use crate::{
+        matcher::Simple,
+        strategy::{Strategy, Trigger},
+    };
+    use std::sync::{Arc, Mutex};
+
+    fn hashes(input: &[u8]) -> Vec<(Option<String>, u64)> {
+        let handler = Arc::new(Mutex::new(StableHash::new().set_use_path(true)));
+        let mut trigger = Trigger::new();
+        trigger.add_matcher(Box::new(Simple::new(r#"[]"#).unwrap()), handler.clone());
+        for part in input.chunks(3) {
+            trigger.process(part).unwrap();
+        }
+
+        let mut guard = handler.lock().unwrap();
+        let mut res = vec![];
+        while let Some(item) = guard.pop() {
+            res.push(item);
+        }
+        res
+    }
+
+    #[test]
+    fn key_order() {
+        let output = hashes(
+            br#"[{"a":1,"b":2}, {"b": 2, "a": 1}, {"a": 2, "b": 1},
+                {"x": {"d": [1, 2], "c": "A"}}, {"x": {"c": "A", "d": [1, 2]}},
+                {"x": {"c": "A", "d": [2, 1]}}]"#,
+        );
+        assert_eq!(output.len(), 6);
+        assert_eq!(output[0].0, Some("[0]".to_string()));
+        assert_eq!(output[0].1, output[1].1);
+        assert_ne!(output[0].1, output[2].1);
+        assert_eq!(output[3].1, output[4].1);
+        // order of array items matters
+        assert_ne!(output[4].1, output[5].1);
+    }
+
+    #[test]
+    fn stable() {
+        assert_eq!(
+            StableHash::hash(br#"{"b": [true, null], "a": "x"}"#).unwrap(),
+            StableHash::hash(br#"{"a":"x","b":[true,null]}"#).unwrap(),
+        );
+        assert!(StableHash::hash(br#"{"a": }"#).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+//! Handler which trims whitespace around matched strings
+//! `"  hi  "` -> `"hi"`
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(handler::Trim::new()));
+//! let matcher = matcher::Simple::new(r#"{"users"}[]{"name"}"#).unwrap();
+//!
+//! let mut convert = strategy::Convert::new();
+//!
+//! // Set the matcher for convert strategy
+//! convert.add_matcher(Box::new(matcher), handler);
+//!
+//! for input in vec![
+//!     br#"{"users": [{"name": "  first "}, "#.to_vec(),
+//!     br#"{"name": "\tsecond\n"}]}"#.to_vec(),
+//! ] {
+//!     for converted_data in convert.process(&input).unwrap() {
+//!         println!("{:?}", converted_data);
+//!     }
+//! }
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    path::Path,
+    streamer::{escape, unescape, ParsedKind, Token},
+};
+use std::{any::Any, mem, str::FromStr};
+
+/// Handler which trims leading and trailing whitespace of matched strings
+///
+/// The string is decoded first so that escaped whitespace (e.g. `\n`)
+/// is trimmed as well. Matched data which are not strings are kept as they are.
+#[derive(Debug, Default)]
+pub struct Trim {
+    /// Kind of the currently matched data
+    kind: Option<ParsedKind>,
+    /// Matched data
+    buffer: Vec<u8>,
+}
+
+impl Trim {
+    /// Creates a new handler which trims matched strings
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl FromStr for Trim {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() {
+            Ok(Self::default())
+        } else {
+            Err(error::Handler::new("Trim handler accepts no argument"))
+        }
+    }
+}
+
+impl Handler for Trim {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, token: Token) -> HandlerOutput {
+        if let Token::Start(_, kind) = token {
+            self.kind = Some(kind);
+        }
+        self.buffer.clear();
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        let data = mem::take(&mut self.buffer);
+        if self.kind.take() != Some(ParsedKind::Str) {
+            return Ok(Some(data));
+        }
+        if data.len() < 2 {
+            return Err(error::Handler::new("Trimmed string is not terminated"));
+        }
+        let decoded = unescape(&data[1..data.len() - 1])
+            .ok_or_else(|| error::Handler::new("Trimmed string is not valid"))?;
+        Ok(Some(
+            format!(r#""{}""#, escape(decoded.trim())).into_bytes(),
+        ))
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Trim;
+    use crate::{
+        matcher::Simple,
+        strategy::{Convert, OutputConverter, Strategy},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    fn convert(input: &[u8]) -> String {
+        let mut convert = Convert::new();
+        convert.add_matcher(
+            Box::new(Simple::new(r#"[]"#).unwrap()),
+            Arc::new(Mutex::new(Trim::new())),
+        );
+
+        let mut output = vec![];
+        let mut converter = OutputConverter::new();
+        for part in input.chunks(3) {
+            for (_, data) in converter.convert(&convert.process(part).unwrap()) {
+                output.extend(data);
+            }
+        }
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn trim() {
+        assert_eq!(convert(br#"["  hi  "]"#), r#"["hi"]"#);
+        assert_eq!(
+            convert(br#"["\t a \"b\" \n", "   ", 1, {"a": " x "}]"#),
+            r#"["a \"b\"", "", 1, {"a": " x "}]"#
+        );
+        // unicode whitespace
+        assert_eq!(convert("[\"\u{a0}c\u{2003}\"]".as_bytes()), r#"["c"]"#);
+    }
+
+    #[test]
+    fn parse() {
+        assert!(Trim::from_str("").is_ok());
+        assert!(Trim::from_str("x").is_err());
+    }
+}
@@ -0,0 +1,245 @@
+//! Handler which emits matched values as TSV rows
+//!
+//! Each matcher index represents a column. Values matched within
+//! a single JSON are collected into a row which is written to the output
+//! when the JSON is finished. When a column is matched again within the same JSON
+//! a new row is started.
+//!
+//! Strings are unquoted, other values are kept as they are.
+//! Tabs and newlines within values are replaced by `\t` and `\n`.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::{io, sync::{Arc, Mutex}};
+//!
+//! let handler = Arc::new(Mutex::new(
+//!     handler::Tsv::new(io::stdout(), 2).set_header(vec!["id".into(), "name".into()])
+//! ));
+//!
+//! let mut trigger = strategy::Trigger::new();
+//! trigger.add_matcher(Box::new(matcher::Simple::new(r#"{"id"}"#).unwrap()), handler.clone());
+//! trigger.add_matcher(Box::new(matcher::Simple::new(r#"{"name"}"#).unwrap()), handler);
+//!
+//! // writes "id\tname\n1\tfirst\n" to stdout
+//! trigger.process(br#"{"id": 1, "name": "first"}"#).unwrap();
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    path::Path,
+    streamer::{ParsedKind, Token},
+};
+use std::{any::Any, io, mem};
+
+/// Handler which writes matched values as TSV rows
+pub struct Tsv<W>
+where
+    W: io::Write,
+{
+    /// Writable output
+    output: W,
+    /// Number of columns
+    columns: usize,
+    /// Optional header row
+    header: Option<Vec<String>>,
+    /// Header was already emitted
+    header_written: bool,
+    /// Rows of the current JSON
+    rows: Vec<Vec<Option<Vec<u8>>>>,
+    /// Data of currently matched value
+    buffer: Vec<u8>,
+    /// Currently matched value is a string
+    is_string: bool,
+}
+
+impl<W> Tsv<W>
+where
+    W: io::Write,
+{
+    /// Creates a new TSV handler
+    ///
+    /// # Arguments
+    /// * `output` - structure which implements `io::Write`
+    /// * `columns` - number of columns (matcher indexes `0..columns`)
+    pub fn new(output: W, columns: usize) -> Self {
+        Self {
+            output,
+            columns,
+            header: None,
+            header_written: false,
+            rows: vec![],
+            buffer: vec![],
+            is_string: false,
+        }
+    }
+
+    /// Returns the output
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+
+    /// Sets the header which is emitted before the first row
+    ///
+    /// # Arguments
+    /// * `header` - names of the columns
+    pub fn set_header(mut self, header: Vec<String>) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /// Stores the value into the current row
+    fn store(&mut self, column: usize, value: Vec<u8>) {
+        let new_row = match self.rows.last() {
+            Some(row) => row[column].is_some(),
+            None => true,
+        };
+        if new_row {
+            self.rows.push(vec![None; self.columns]);
+        }
+        self.rows.last_mut().unwrap()[column] = Some(value);
+    }
+}
+
+/// Replaces tabs and newlines so they don't break the TSV structure
+fn escape(data: &[u8]) -> Vec<u8> {
+    let mut res = Vec::with_capacity(data.len());
+    for byte in data {
+        match byte {
+            b'\t' => res.extend(br#"\t"#),
+            b'\n' => res.extend(br#"\n"#),
+            b'\r' => res.extend(br#"\r"#),
+            _ => res.push(*byte),
+        }
+    }
+    res
+}
+
+/// Writes a single row
+fn write_row<'a, I>(output: &mut Vec<u8>, cells: I)
+where
+    I: Iterator<Item = &'a [u8]>,
+{
+    for (idx, cell) in cells.enumerate() {
+        if idx != 0 {
+            output.push(b'\t');
+        }
+        output.extend(escape(cell));
+    }
+    output.push(b'\n');
+}
+
+impl<W> Handler for Tsv<W>
+where
+    W: io::Write + Send + 'static,
+{
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, token: Token) -> HandlerOutput {
+        self.buffer.clear();
+        self.is_string = matches!(token, Token::Start(_, ParsedKind::Str));
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(&mut self, _path: &Path, matcher_idx: usize, _token: Token) -> HandlerOutput {
+        let mut value = mem::take(&mut self.buffer);
+        if matcher_idx >= self.columns {
+            return Err(error::Handler::new(format!(
+                "TSV column {} is out of range",
+                matcher_idx
+            )));
+        }
+        if self.is_string && value.len() >= 2 {
+            value = value[1..value.len() - 1].to_vec();
+        }
+        self.store(matcher_idx, value);
+        Ok(None)
+    }
+
+    fn json_finished(&mut self) -> HandlerOutput {
+        if self.rows.is_empty() {
+            return Ok(None);
+        }
+        let mut output = vec![];
+        if !self.header_written {
+            if let Some(header) = self.header.as_ref() {
+                write_row(&mut output, header.iter().map(|e| e.as_bytes()));
+            }
+            self.header_written = true;
+        }
+        for row in self.rows.drain(..) {
+            write_row(
+                &mut output,
+                row.iter().map(|e| e.as_ref().map_or(&b""[..], |e| &e[..])),
+            );
+        }
+        self.output
+            .write_all(&output)
+            .map_err(error::Handler::new)?;
+        Ok(None)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tsv;
+    use crate::{
+        matcher::Simple,
+        strategy::{Strategy, Trigger},
+    };
+    use std::sync::{Arc, Mutex};
+
+    fn tsv(handler: Tsv<Vec<u8>>, matchers: &[&str], input: &[u8]) -> String {
+        let handler = Arc::new(Mutex::new(handler));
+        let mut trigger = Trigger::new();
+        for matcher in matchers {
+            trigger.add_matcher(Box::new(Simple::new(matcher).unwrap()), handler.clone());
+        }
+        trigger.process(input).unwrap();
+        let guard = handler.lock().unwrap();
+        String::from_utf8(guard.output().clone()).unwrap()
+    }
+
+    #[test]
+    fn rows() {
+        let output = tsv(
+            Tsv::new(vec![], 3).set_header(vec!["id".into(), "name".into(), "tags".into()]),
+            &[r#"[]{"id"}"#, r#"[]{"name"}"#, r#"[]{"tags"}"#],
+            br#"[{"id": 1, "name": "first", "tags": ["a",	"b"]}, {"name": "sec\tond", "id": 2}] [{"id": 3}]"#,
+        );
+        assert_eq!(
+            output,
+            "id\tname\ttags\n\
+             1\tfirst\t[\"a\",\\t\"b\"]\n\
+             2\tsec\\tond\t\n\
+             3\t\t\n"
+        );
+    }
+
+    #[test]
+    fn no_header() {
+        let output = tsv(
+            Tsv::new(vec![], 2),
+            &[r#"{"b"}"#, r#"{"a"}"#],
+            br#"{"a": null, "b": true} {"c": 1}"#,
+        );
+        assert_eq!(output, "true\tnull\n");
+    }
+
+    #[test]
+    fn out_of_range() {
+        let handler = Arc::new(Mutex::new(Tsv::new(vec![], 1)));
+        let mut trigger = Trigger::new();
+        trigger.add_matcher(Box::new(Simple::new(r#"{"a"}"#).unwrap()), handler.clone());
+        trigger.add_matcher(Box::new(Simple::new(r#"{"b"}"#).unwrap()), handler);
+        assert!(trigger.process(br#"{"a": 1, "b": 2}"#).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+//! Handler which decodes URL-encoded strings
+//! `"a%20b%2Fc"` -> `"a b/c"`
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(handler::UrlDecode::new()));
+//! let matcher = matcher::Simple::new(r#"[]{"query"}"#).unwrap();
+//!
+//! let mut convert = strategy::Convert::new();
+//!
+//! // Set the matcher for convert strategy
+//! convert.add_matcher(Box::new(matcher), handler);
+//!
+//! for input in vec![
+//!     br#"[{"query": "name%3Dfirst%26"#.to_vec(),
+//!     br#"id%3D1"}, {"query": "%C5%A1"}]"#.to_vec(),
+//! ] {
+//!     for converted_data in convert.process(&input).unwrap() {
+//!         println!("{:?}", converted_data);
+//!     }
+//! }
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    path::Path,
+    streamer::{escape, unescape, ParsedKind, Token},
+};
+use std::{any::Any, mem, str::FromStr};
+
+/// Handler which decodes `%XX` sequences in matched strings
+///
+/// The whole string is buffered, so the sequences can be split
+/// across several inputs. Invalid sequences are kept as they are
+/// and matched data which are not strings are not changed.
+/// Note that `+` is not decoded to a space.
+#[derive(Debug, Default)]
+pub struct UrlDecode {
+    /// Kind of the currently matched data
+    kind: Option<ParsedKind>,
+    /// Matched data
+    buffer: Vec<u8>,
+}
+
+impl UrlDecode {
+    /// Creates a new handler which decodes URL-encoded strings
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Converts a hex digit to its value
+fn hex_value(digit: u8) -> Option<u8> {
+    match digit {
+        b'0'..=b'9' => Some(digit - b'0'),
+        b'a'..=b'f' => Some(digit - b'a' + 10),
+        b'A'..=b'F' => Some(digit - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Decodes `%XX` sequences
+fn percent_decode(input: &[u8]) -> Vec<u8> {
+    let mut res = Vec::with_capacity(input.len());
+    let mut idx = 0;
+    while idx < input.len() {
+        if input[idx] == b'%' {
+            if let (Some(high), Some(low)) = (
+                input.get(idx + 1).and_then(|e| hex_value(*e)),
+                input.get(idx + 2).and_then(|e| hex_value(*e)),
+            ) {
+                res.push(high << 4 | low);
+                idx += 3;
+                continue;
+            }
+        }
+        res.push(input[idx]);
+        idx += 1;
+    }
+    res
+}
+
+impl FromStr for UrlDecode {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() {
+            Ok(Self::default())
+        } else {
+            Err(error::Handler::new("UrlDecode handler accepts no argument"))
+        }
+    }
+}
+
+impl Handler for UrlDecode {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, token: Token) -> HandlerOutput {
+        if let Token::Start(_, kind) = token {
+            self.kind = Some(kind);
+        }
+        self.buffer.clear();
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        let data = mem::take(&mut self.buffer);
+        if self.kind.take() != Some(ParsedKind::Str) {
+            return Ok(Some(data));
+        }
+        if data.len() < 2 {
+            return Err(error::Handler::new("Decoded string is not terminated"));
+        }
+        let unescaped = unescape(&data[1..data.len() - 1])
+            .ok_or_else(|| error::Handler::new("Decoded string is not valid"))?;
+        let decoded =
+            String::from_utf8(percent_decode(unescaped.as_bytes())).map_err(error::Handler::new)?;
+        Ok(Some(format!(r#""{}""#, escape(&decoded)).into_bytes()))
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UrlDecode;
+    use crate::{
+        matcher::Simple,
+        strategy::{Convert, OutputConverter, Strategy},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    fn convert(input: &[u8]) -> Result<String, crate::error::General> {
+        let mut convert = Convert::new();
+        convert.add_matcher(
+            Box::new(Simple::new(r#"[]"#).unwrap()),
+            Arc::new(Mutex::new(UrlDecode::new())),
+        );
+
+        let mut output = vec![];
+        let mut converter = OutputConverter::new();
+        // split to single bytes so the sequences are split as well
+        for part in input.chunks(1) {
+            for (_, data) in converter.convert(&convert.process(part)?) {
+                output.extend(data);
+            }
+        }
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn decode() {
+        assert_eq!(convert(br#"["a%20b"]"#).unwrap(), r#"["a b"]"#);
+        assert_eq!(
+            convert(br#"["%C5%A1%c5%a1", "100%", "%2x%", "a+b", 1, {"a": "%20"}]"#).unwrap(),
+            r#"["šš", "100%", "%2x%", "a+b", 1, {"a": "%20"}]"#
+        );
+        // decoded characters are escaped
+        assert_eq!(
+            convert(br#"["%22q%22%5C%0A", "%\/%2F"]"#).unwrap(),
+            r#"["\"q\"\\\n", "%//"]"#
+        );
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        assert!(convert(br#"["%C5"]"#).is_err());
+    }
+
+    #[test]
+    fn parse() {
+        assert!(UrlDecode::from_str("").is_ok());
+        assert!(UrlDecode::from_str("x").is_err());
+    }
+}
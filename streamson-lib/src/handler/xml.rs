@@ -0,0 +1,471 @@
+//! Handler which converts matched JSON values into XML
+//!
+//! `{"a": [1, "b"], "c": null}` -> `<root><a><item>1</item><item>b</item></a><c/></root>`
+//!
+//! Only the matched values are buffered (not the entire document).
+//! Object keys are used as element names (characters which are not valid
+//! in XML names are replaced with `_`) and array items are wrapped
+//! in item elements.
+//!
+//! When attributes are enabled, object members with scalar values
+//! (strings, numbers and booleans) are converted to attributes
+//! of the parent element.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(
+//!     handler::Xml::new()
+//!         .set_root("user".to_string())
+//!         .set_attributes(true)
+//! ));
+//! let matcher = matcher::Simple::new(r#"{"users"}[]"#).unwrap();
+//!
+//! let mut convert = strategy::Convert::new();
+//!
+//! // Set the matcher for convert strategy
+//! convert.add_matcher(Box::new(matcher), handler);
+//!
+//! for input in vec![
+//!     br#"{"users": [{"name": "first"}, "#.to_vec(),
+//!     br#"{"name": "second", "groups": ["admin"]}]}"#.to_vec(),
+//! ] {
+//!     for converted_data in convert.process(&input).unwrap() {
+//!         println!("{:?}", converted_data);
+//!     }
+//! }
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{
+    error,
+    path::Path,
+    streamer::{unescape, Token},
+};
+use std::{any::Any, mem, str, str::FromStr};
+
+/// Converts JSON values into XML elements
+#[derive(Debug)]
+pub struct Xml {
+    /// Name of the element which wraps the matched value
+    root: String,
+    /// Name of the elements which wrap array items
+    item: String,
+    /// Scalar object members are converted to attributes
+    attributes: bool,
+    /// Data of currently matched value
+    buffer: Vec<u8>,
+}
+
+impl Default for Xml {
+    fn default() -> Self {
+        Self {
+            root: "root".into(),
+            item: "item".into(),
+            attributes: false,
+            buffer: vec![],
+        }
+    }
+}
+
+impl Xml {
+    /// Creates a new XML converter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the element which wraps the matched value
+    ///
+    /// # Arguments
+    /// * `root` - name of the element (default `root`)
+    pub fn set_root(mut self, root: String) -> Self {
+        self.root = root;
+        self
+    }
+
+    /// Sets the name of the elements which wrap array items
+    ///
+    /// # Arguments
+    /// * `item` - name of the element (default `item`)
+    pub fn set_item(mut self, item: String) -> Self {
+        self.item = item;
+        self
+    }
+
+    /// Sets whether scalar object members are converted to attributes
+    ///
+    /// # Arguments
+    /// * `attributes` - convert scalars to attributes
+    pub fn set_attributes(mut self, attributes: bool) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// Converts a single JSON value into XML
+    ///
+    /// # Arguments
+    /// * `data` - JSON value
+    pub fn convert(&self, data: &[u8]) -> Result<Vec<u8>, error::Handler> {
+        let mut parser = Parser { data, idx: 0 };
+        let value = parser.value()?;
+        parser.skip_whitespaces();
+        if parser.idx < data.len() {
+            return Err(parser.error());
+        }
+        let mut output = String::new();
+        self.element(&mut output, &self.root, &value);
+        Ok(output.into_bytes())
+    }
+
+    /// Writes the value wrapped in an element
+    fn element(&self, output: &mut String, name: &str, value: &Value) {
+        let name = element_name(name);
+        output.push('<');
+        output.push_str(&name);
+        match value {
+            Value::Null => {
+                output.push_str("/>");
+                return;
+            }
+            Value::Text(text) => {
+                output.push('>');
+                output.push_str(&escape(text));
+            }
+            Value::Array(items) => {
+                output.push('>');
+                for item in items {
+                    self.element(output, &self.item, item);
+                }
+            }
+            Value::Object(members) => {
+                let mut children = vec![];
+                for (key, value) in members {
+                    match value {
+                        Value::Text(text) if self.attributes => {
+                            output.push(' ');
+                            output.push_str(&element_name(key));
+                            output.push_str("=\"");
+                            output.push_str(&escape(text));
+                            output.push('"');
+                        }
+                        _ => children.push((key, value)),
+                    }
+                }
+                if children.is_empty() {
+                    output.push_str("/>");
+                    return;
+                }
+                output.push('>');
+                for (key, value) in children {
+                    self.element(output, key, value);
+                }
+            }
+        }
+        output.push_str("</");
+        output.push_str(&name);
+        output.push('>');
+    }
+}
+
+/// Makes a valid XML name from the input
+fn element_name(name: &str) -> String {
+    let mut result: String = name
+        .chars()
+        .map(|chr| {
+            if chr.is_alphanumeric() || matches!(chr, '_' | '-' | '.') {
+                chr
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !matches!(result.chars().next(), Some(chr) if chr.is_alphabetic() || chr == '_') {
+        result.insert(0, '_');
+    }
+    result
+}
+
+/// Escapes XML special characters
+fn escape(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    for chr in input.chars() {
+        match chr {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            chr => result.push(chr),
+        }
+    }
+    result
+}
+
+/// Parsed JSON value
+enum Value {
+    Null,
+    /// Strings, numbers and booleans
+    Text(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+/// Recursive parser of the JSON data
+struct Parser<'a> {
+    data: &'a [u8],
+    idx: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self) -> error::Handler {
+        error::Handler::new(format!("Failed to convert JSON to XML (idx {})", self.idx))
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while self.idx < self.data.len() && self.data[self.idx].is_ascii_whitespace() {
+            self.idx += 1;
+        }
+    }
+
+    /// Returns next non-whitespace byte
+    fn peek(&mut self) -> Result<u8, error::Handler> {
+        self.skip_whitespaces();
+        self.data.get(self.idx).copied().ok_or_else(|| self.error())
+    }
+
+    fn value(&mut self) -> Result<Value, error::Handler> {
+        match self.peek()? {
+            b'{' => self.container(b'}'),
+            b'[' => self.container(b']'),
+            b'"' => Ok(Value::Text(self.string()?)),
+            b't' => self.literal(b"true", Value::Text("true".into())),
+            b'f' => self.literal(b"false", Value::Text("false".into())),
+            b'n' => self.literal(b"null", Value::Null),
+            _ => self.number(),
+        }
+    }
+
+    fn literal(&mut self, literal: &[u8], value: Value) -> Result<Value, error::Handler> {
+        if !self.data[self.idx..].starts_with(literal) {
+            return Err(self.error());
+        }
+        self.idx += literal.len();
+        Ok(value)
+    }
+
+    /// Parses a string and returns its decoded content
+    fn string(&mut self) -> Result<String, error::Handler> {
+        let start = self.idx + 1;
+        let mut idx = start;
+        while idx < self.data.len() {
+            match self.data[idx] {
+                b'\\' => idx += 2,
+                b'"' => {
+                    self.idx = idx + 1;
+                    return unescape(&self.data[start..idx]).ok_or_else(|| self.error());
+                }
+                _ => idx += 1,
+            }
+        }
+        Err(self.error())
+    }
+
+    fn number(&mut self) -> Result<Value, error::Handler> {
+        let start = self.idx;
+        while self.idx < self.data.len()
+            && matches!(
+                self.data[self.idx],
+                b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E'
+            )
+        {
+            self.idx += 1;
+        }
+        let number = str::from_utf8(&self.data[start..self.idx]).map_err(error::Handler::new)?;
+        if number.parse::<f64>().is_err() {
+            return Err(self.error());
+        }
+        Ok(Value::Text(number.to_string()))
+    }
+
+    fn container(&mut self, end: u8) -> Result<Value, error::Handler> {
+        let is_object = end == b'}';
+        self.idx += 1;
+        let mut items = vec![];
+        let mut members = vec![];
+        if self.peek()? == end {
+            self.idx += 1;
+        } else {
+            loop {
+                if is_object {
+                    if self.peek()? != b'"' {
+                        return Err(self.error());
+                    }
+                    let key = self.string()?;
+                    if self.peek()? != b':' {
+                        return Err(self.error());
+                    }
+                    self.idx += 1;
+                    members.push((key, self.value()?));
+                } else {
+                    items.push(self.value()?);
+                }
+                match self.peek()? {
+                    b',' => self.idx += 1,
+                    byte if byte == end => {
+                        self.idx += 1;
+                        break;
+                    }
+                    _ => return Err(self.error()),
+                }
+            }
+        }
+        if is_object {
+            Ok(Value::Object(members))
+        } else {
+            Ok(Value::Array(items))
+        }
+    }
+}
+
+impl Handler for Xml {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        self.buffer.clear();
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        self.buffer.extend(data);
+        Ok(None)
+    }
+
+    fn end(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        let data = mem::take(&mut self.buffer);
+        Ok(Some(self.convert(&data)?))
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl FromStr for Xml {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let splitted: Vec<_> = input.split(',').collect();
+        match splitted.as_slice() {
+            [""] => Ok(Self::default()),
+            [root] => Ok(Self::default().set_root(root.to_string())),
+            [root, attributes] => Ok(Self::default()
+                .set_root(root.to_string())
+                .set_attributes(attributes.parse().map_err(error::Handler::new)?)),
+            _ => Err(error::Handler::new("Failed to parse")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Xml;
+    use crate::{
+        matcher::Simple,
+        strategy::{Convert, OutputConverter, Strategy},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    fn convert(xml: &Xml, input: &str) -> String {
+        String::from_utf8(xml.convert(input.as_bytes()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn values() {
+        let xml = Xml::new();
+        assert_eq!(convert(&xml, "1.5"), "<root>1.5</root>");
+        assert_eq!(convert(&xml, " true "), "<root>true</root>");
+        assert_eq!(convert(&xml, "null"), "<root/>");
+        assert_eq!(
+            convert(&xml, r#""<a & \"b\">""#),
+            "<root>&lt;a &amp; &quot;b&quot;&gt;</root>"
+        );
+        assert_eq!(
+            convert(&xml, r#"{"a": [1, "b"], "c": null, "d": {}}"#),
+            "<root><a><item>1</item><item>b</item></a><c/><d/></root>"
+        );
+        assert_eq!(
+            convert(&xml, r#"{"1st key": []}"#),
+            "<root><_1st_key></_1st_key></root>"
+        );
+    }
+
+    #[test]
+    fn attributes() {
+        let xml = Xml::new()
+            .set_root("user".into())
+            .set_item("group".into())
+            .set_attributes(true);
+        assert_eq!(
+            convert(
+                &xml,
+                r#"{"name": "a\"b", "id": 1, "groups": ["x"], "parent": null}"#
+            ),
+            r#"<user name="a&quot;b" id="1"><groups><group>x</group></groups><parent/></user>"#
+        );
+        assert_eq!(convert(&xml, r#"{"id": 1}"#), r#"<user id="1"/>"#);
+    }
+
+    #[test]
+    fn invalid() {
+        let xml = Xml::new();
+        for input in &[
+            &b"[1,"[..],
+            b"{1: 2}",
+            b"nul",
+            b"\"a",
+            b"1 2",
+            b"[1 2]",
+            b"-",
+        ] {
+            assert!(xml.convert(input).is_err());
+        }
+    }
+
+    #[test]
+    fn from_str() {
+        let xml = Xml::from_str("").unwrap();
+        assert_eq!(xml.root, "root");
+        assert!(!xml.attributes);
+        let xml = Xml::from_str("user,true").unwrap();
+        assert_eq!(xml.root, "user");
+        assert!(xml.attributes);
+        assert!(Xml::from_str("user,maybe").is_err());
+        assert!(Xml::from_str("a,true,b").is_err());
+    }
+
+    #[test]
+    fn convert_strategy() {
+        let mut convert = Convert::new();
+        convert.add_matcher(
+            Box::new(Simple::new(r#"{"a"}[]"#).unwrap()),
+            Arc::new(Mutex::new(Xml::from_str("v").unwrap())),
+        );
+        let mut output = vec![];
+        let mut converter = OutputConverter::new();
+        for part in br#"{"a": [[1, 2], "x"]}"#.chunks(3) {
+            for (_, data) in converter.convert(&convert.process(part).unwrap()) {
+                output.extend(data);
+            }
+        }
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"{"a": [<v><item>1</item><item>2</item></v>, <v>x</v>]}"#
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! Handler which compresses matched data using zstd
+//!
+//! Each match is compressed into a separate zstd frame.
+//! Data are compressed as they are fed and the frame
+//! is finished at the end of the match.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let buffer = Arc::new(Mutex::new(handler::Buffer::new()));
+//! let handler = handler::Group::new()
+//!     .add_handler(Arc::new(Mutex::new(handler::Zstd::new().set_level(3))))
+//!     .add_handler(buffer.clone());
+//! let matcher = matcher::Simple::new(r#"{"users"}[]"#).unwrap();
+//!
+//! let mut trigger = strategy::Trigger::new();
+//!
+//! // Set the matcher for trigger strategy
+//! trigger.add_matcher(Box::new(matcher), Arc::new(Mutex::new(handler)));
+//!
+//! for input in vec![
+//!     br#"{"users": [{"name": "first"}, {"#.to_vec(),
+//!     br#""name": "second"}]}"#.to_vec(),
+//! ] {
+//!     trigger.process(&input).unwrap();
+//! }
+//!
+//! // compressed users are stored in the buffer
+//! while let Some((_, compressed)) = buffer.lock().unwrap().pop() {
+//!     println!("{:?}", compressed);
+//! }
+//! ```
+
+use super::{Handler, HandlerOutput};
+use crate::{error, path::Path, streamer::Token};
+use std::{any::Any, io::Write, mem, str::FromStr};
+use zstd::stream::write::Encoder;
+
+/// Handler which replaces matched data with zstd compressed data
+#[derive(Default)]
+pub struct Zstd {
+    /// Compression level (0 means zstd default level)
+    level: i32,
+    /// Encoder of the currently matched data
+    encoder: Option<Encoder<'static, Vec<u8>>>,
+}
+
+impl Zstd {
+    /// Creates a new handler which compresses matched data
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the compression level
+    ///
+    /// # Arguments
+    /// * `level` - zstd compression level (0 means zstd default level)
+    pub fn set_level(mut self, level: i32) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Takes the data which were already compressed
+    fn take_compressed(&mut self) -> Option<Vec<u8>> {
+        let compressed = mem::take(self.encoder.as_mut()?.get_mut());
+        if compressed.is_empty() {
+            None
+        } else {
+            Some(compressed)
+        }
+    }
+}
+
+impl FromStr for Zstd {
+    type Err = error::Handler;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() {
+            Ok(Self::default())
+        } else {
+            let level: i32 = input.parse().map_err(error::Handler::new)?;
+            Ok(Self::default().set_level(level))
+        }
+    }
+}
+
+impl Handler for Zstd {
+    fn start(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        self.encoder = Some(Encoder::new(vec![], self.level).map_err(error::Handler::new)?);
+        Ok(None)
+    }
+
+    fn feed(&mut self, data: &[u8], _matcher_idx: usize) -> HandlerOutput {
+        if let Some(encoder) = self.encoder.as_mut() {
+            encoder.write_all(data).map_err(error::Handler::new)?;
+        }
+        Ok(self.take_compressed())
+    }
+
+    fn end(&mut self, _path: &Path, _matcher_idx: usize, _token: Token) -> HandlerOutput {
+        if let Some(encoder) = self.encoder.take() {
+            let compressed = encoder.finish().map_err(error::Handler::new)?;
+            Ok(Some(compressed))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn is_converter(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Zstd;
+    use crate::{
+        handler::{Buffer, Group, Handler},
+        matcher::Simple,
+        path::Path,
+        strategy::{Strategy, Trigger},
+        streamer::{ParsedKind, Token},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    #[test]
+    fn round_trip() {
+        let mut handler = Zstd::from_str("19").unwrap();
+        let path = Path::new();
+        let value = br#"{"name": "first", "tags": ["a", "b", "a", "b", "a", "b"]}"#;
+
+        let mut compressed = vec![];
+        handler
+            .start(&path, 0, Token::Start(0, ParsedKind::Obj))
+            .unwrap();
+        for part in value.chunks(5) {
+            if let Some(data) = handler.feed(part, 0).unwrap() {
+                compressed.extend(data);
+            }
+        }
+        compressed.extend(
+            handler
+                .end(&path, 0, Token::End(value.len(), ParsedKind::Obj))
+                .unwrap()
+                .unwrap(),
+        );
+
+        assert_eq!(zstd::decode_all(&compressed[..]).unwrap(), value.to_vec());
+        assert!(Zstd::from_str("x").is_err());
+    }
+
+    #[test]
+    fn group() {
+        let buffer = Arc::new(Mutex::new(Buffer::new()));
+        let group = Group::new()
+            .add_handler(Arc::new(Mutex::new(Zstd::new())))
+            .add_handler(buffer.clone());
+
+        let mut trigger = Trigger::new();
+        trigger.add_matcher(
+            Box::new(Simple::new(r#"[]"#).unwrap()),
+            Arc::new(Mutex::new(group)),
+        );
+        trigger.process(br#"[{"a": 1}, "#).unwrap();
+        trigger.process(br#""second"]"#).unwrap();
+
+        let mut guard = buffer.lock().unwrap();
+        let (_, first) = guard.pop().unwrap();
+        assert_eq!(
+            zstd::decode_all(&first[..]).unwrap(),
+            br#"{"a": 1}"#.to_vec()
+        );
+        let (_, second) = guard.pop().unwrap();
+        assert_eq!(
+            zstd::decode_all(&second[..]).unwrap(),
+            br#""second""#.to_vec()
+        );
+        assert!(guard.pop().is_none());
+    }
+}
@@ -0,0 +1,335 @@
+//! Input adapters which read (and decode) data
+//! in chunks suitable to be fed into strategies
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{input::FramedInput, matcher, strategy::{self, Strategy}};
+//!
+//! let data = br#"{"users": [{"name": "first"}, {"name": "second"}]}"#;
+//! let mut extract = strategy::Extract::new();
+//! extract.add_matcher(Box::new(matcher::Simple::new(r#"{"users"}[]"#).unwrap()), None);
+//!
+//! for chunk in FramedInput::new(&data[..]).set_chunk_size(8) {
+//!     for output in extract.process(&chunk.unwrap()).unwrap() {
+//!         println!("{:?}", output);
+//!     }
+//! }
+//! ```
+
+use std::io::{self, Cursor, Read};
+
+#[cfg(feature = "with_gzip")]
+use flate2::read::MultiGzDecoder;
+
+/// Default size of the chunk which is read at once
+pub const DEFAULT_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// The first bytes of gzipped data
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+/// The first bytes of zstd compressed data
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Reader with the already read head of the data put back
+pub type Detected<R> = io::Chain<Cursor<Vec<u8>>, R>;
+
+/// Reader wrapped in a decoder
+enum Decoder<R: Read> {
+    /// Data are passed as they are
+    Identity(R),
+    /// Data are gzip decompressed
+    #[cfg(feature = "with_gzip")]
+    Gzip(MultiGzDecoder<R>),
+    /// Data are zstd decompressed
+    #[cfg(feature = "with_zstd")]
+    Zstd(zstd::Decoder<'static, io::BufReader<R>>),
+}
+
+impl<R: Read> Read for Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Identity(reader) => reader.read(buf),
+            #[cfg(feature = "with_gzip")]
+            Self::Gzip(reader) => reader.read(buf),
+            #[cfg(feature = "with_zstd")]
+            Self::Zstd(reader) => reader.read(buf),
+        }
+    }
+}
+
+/// Reads the data from the reader and yields decoded chunks
+///
+/// The iteration stops when the underlying reader is exhausted
+/// or right after the first error.
+pub struct FramedInput<R: Read> {
+    /// Decoded reader
+    decoder: Decoder<R>,
+    /// Max size of the yielded chunk
+    chunk_size: usize,
+    /// The input was read completely
+    finished: bool,
+}
+
+impl<R: Read> FramedInput<R> {
+    fn with_decoder(decoder: Decoder<R>) -> Self {
+        Self {
+            decoder,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            finished: false,
+        }
+    }
+
+    /// Creates a new input which yields the data as they are
+    ///
+    /// # Arguments
+    /// * `reader` - source of the data
+    pub fn new(reader: R) -> Self {
+        Self::with_decoder(Decoder::Identity(reader))
+    }
+
+    /// Creates a new input which decompresses gzipped data
+    ///
+    /// # Arguments
+    /// * `reader` - source of the gzipped data
+    #[cfg(feature = "with_gzip")]
+    pub fn gzip(reader: R) -> Self {
+        Self::with_decoder(Decoder::Gzip(MultiGzDecoder::new(reader)))
+    }
+
+    /// Creates a new input which decompresses zstd data
+    ///
+    /// # Arguments
+    /// * `reader` - source of the zstd compressed data
+    #[cfg(feature = "with_zstd")]
+    pub fn zstd(reader: R) -> io::Result<Self> {
+        Ok(Self::with_decoder(Decoder::Zstd(zstd::Decoder::new(
+            reader,
+        )?)))
+    }
+
+    /// Creates a new input which detects the compression from the data
+    ///
+    /// Gzipped and zstd compressed data are recognized by their magic bytes,
+    /// other data are yielded as they are.
+    ///
+    /// # Arguments
+    /// * `reader` - source of the (possibly compressed) data
+    ///
+    /// # Errors
+    /// The compressed data are detected, but the corresponding
+    /// feature (`with_gzip` or `with_zstd`) is not enabled.
+    pub fn auto(mut reader: R) -> io::Result<FramedInput<Detected<R>>> {
+        let mut head = vec![0; ZSTD_MAGIC.len()];
+        let mut size = 0;
+        while size < head.len() {
+            match reader.read(&mut head[size..]) {
+                Ok(0) => break,
+                Ok(read) => size += read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            }
+        }
+        head.truncate(size);
+        let gzip = head.starts_with(GZIP_MAGIC);
+        let zstd = head.starts_with(ZSTD_MAGIC);
+        let reader = Cursor::new(head).chain(reader);
+
+        if gzip {
+            #[cfg(feature = "with_gzip")]
+            return Ok(FramedInput::gzip(reader));
+            #[cfg(not(feature = "with_gzip"))]
+            return Err(unsupported("gzip", "with_gzip"));
+        }
+        if zstd {
+            #[cfg(feature = "with_zstd")]
+            return FramedInput::zstd(reader);
+            #[cfg(not(feature = "with_zstd"))]
+            return Err(unsupported("zstd", "with_zstd"));
+        }
+        Ok(FramedInput::new(reader))
+    }
+
+    /// Sets the max size of the yielded chunks
+    ///
+    /// # Arguments
+    /// * `chunk_size` - max size of the chunk (has to be greater than 0)
+    pub fn set_chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0);
+        self.chunk_size = chunk_size;
+        self
+    }
+}
+
+/// Error for compressed data which can't be decoded
+#[cfg(not(all(feature = "with_gzip", feature = "with_zstd")))]
+fn unsupported(compression: &str, feature: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+            "{} compressed input is not supported (enable `{}` feature)",
+            compression, feature
+        ),
+    )
+}
+
+/// Decoded data can be read directly as well
+impl<R: Read> Read for FramedInput<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.decoder.read(buf)
+    }
+}
+
+impl<R: Read> Iterator for FramedInput<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let mut buffer = vec![0; self.chunk_size];
+        loop {
+            match self.decoder.read(&mut buffer) {
+                Ok(0) => {
+                    self.finished = true;
+                    return None;
+                }
+                Ok(size) => {
+                    buffer.truncate(size);
+                    return Some(Ok(buffer));
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    self.finished = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FramedInput;
+    use crate::{
+        matcher::Simple,
+        strategy::{Extract, OutputConverter, Strategy},
+    };
+    use std::io::Read;
+
+    const INPUT: &[u8] = br#"{"users": [{"name": "first"}, {"name": "second"}]}"#;
+
+    fn extract<R: Read>(input: FramedInput<R>) -> Vec<Vec<u8>> {
+        let mut extract = Extract::new();
+        extract.add_matcher(Box::new(Simple::new(r#"{"users"}[]"#).unwrap()), None);
+        let mut converter = OutputConverter::new();
+        let mut res = vec![];
+        for chunk in input {
+            res.extend(
+                converter
+                    .convert(&extract.process(&chunk.unwrap()).unwrap())
+                    .into_iter()
+                    .map(|e| e.1),
+            );
+        }
+        res
+    }
+
+    fn expected() -> Vec<Vec<u8>> {
+        vec![
+            br#"{"name": "first"}"#.to_vec(),
+            br#"{"name": "second"}"#.to_vec(),
+        ]
+    }
+
+    #[test]
+    fn identity() {
+        let chunks: Vec<Vec<u8>> = FramedInput::new(INPUT)
+            .set_chunk_size(10)
+            .map(|e| e.unwrap())
+            .collect();
+        assert_eq!(chunks.len(), 5);
+        assert!(chunks.iter().all(|e| e.len() <= 10));
+        assert_eq!(chunks.concat(), INPUT.to_vec());
+
+        assert_eq!(
+            extract(FramedInput::new(INPUT).set_chunk_size(3)),
+            expected()
+        );
+    }
+
+    #[test]
+    fn empty() {
+        assert!(FramedInput::new(&b""[..]).next().is_none());
+        assert!(FramedInput::auto(&b""[..]).unwrap().next().is_none());
+    }
+
+    #[test]
+    fn auto_identity() {
+        assert_eq!(
+            extract(FramedInput::auto(INPUT).unwrap().set_chunk_size(3)),
+            expected()
+        );
+
+        // shorter than the magic bytes
+        let mut data = vec![];
+        FramedInput::auto(&b"[1"[..])
+            .unwrap()
+            .read_to_end(&mut data)
+            .unwrap();
+        assert_eq!(data, b"[1".to_vec());
+    }
+
+    #[cfg(feature = "with_gzip")]
+    #[test]
+    fn gzip() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(INPUT).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(
+            extract(FramedInput::gzip(&compressed[..]).set_chunk_size(5)),
+            expected()
+        );
+        assert!(FramedInput::gzip(INPUT).next().unwrap().is_err());
+        assert_eq!(
+            extract(
+                FramedInput::auto(&compressed[..])
+                    .unwrap()
+                    .set_chunk_size(5)
+            ),
+            expected()
+        );
+    }
+
+    #[cfg(feature = "with_zstd")]
+    #[test]
+    fn zstd() {
+        let compressed = zstd::encode_all(INPUT, 0).unwrap();
+
+        assert_eq!(
+            extract(
+                FramedInput::zstd(&compressed[..])
+                    .unwrap()
+                    .set_chunk_size(5)
+            ),
+            expected()
+        );
+        assert_eq!(
+            extract(
+                FramedInput::auto(&compressed[..])
+                    .unwrap()
+                    .set_chunk_size(5)
+            ),
+            expected()
+        );
+    }
+
+    #[cfg(not(feature = "with_zstd"))]
+    #[test]
+    fn auto_unsupported() {
+        assert!(FramedInput::auto(&[0x28, 0xb5, 0x2f, 0xfd, 0][..]).is_err());
+    }
+}
@@ -0,0 +1,218 @@
+//! Iterator over the matched data
+//!
+//! It works on stable rust and provides similar ergonomics
+//! as `streamson-generator` (which requires nightly generators).
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{iter::MatchIter, matcher::Simple};
+//!
+//! let input = br#"{"users": [{"name": "first"}, {"name": "second"}]}"#;
+//! let matcher = Box::new(Simple::new(r#"{"users"}[]{"name"}"#).unwrap());
+//!
+//! for item in MatchIter::new(&input[..], matcher) {
+//!     match item {
+//!         Ok((path, data)) => {
+//!             // Do something with the data
+//!             println!("{}: {}", path.unwrap(), String::from_utf8(data).unwrap());
+//!         }
+//!         Err(err) => {
+//!             // Deal with error situation
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::{
+    io::Read,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    error,
+    handler::Buffer,
+    input::FramedInput,
+    matcher::Matcher,
+    strategy::{Strategy, Trigger},
+};
+
+/// Iterates over the data matched in the reader
+///
+/// The reader is read in chunks only when no matched data are available.
+/// The iteration stops after the first error.
+pub struct MatchIter<R: Read> {
+    /// Source of the data
+    input: FramedInput<R>,
+    /// Strategy which processes the input
+    trigger: Trigger,
+    /// Collected matches
+    buffer: Arc<Mutex<Buffer>>,
+    /// The input was processed completely
+    finished: bool,
+    /// Processing failed
+    error_occured: bool,
+}
+
+impl<R: Read> MatchIter<R> {
+    /// Creates a new iterator over the matched data
+    ///
+    /// # Arguments
+    /// * `reader` - source of the data
+    /// * `matcher` - matcher which matches the path
+    pub fn new(reader: R, matcher: Box<dyn Matcher>) -> Self {
+        let mut trigger = Trigger::new();
+        let buffer = Arc::new(Mutex::new(Buffer::new().set_use_path(true)));
+        trigger.add_matcher(matcher, buffer.clone());
+        Self {
+            input: FramedInput::new(reader),
+            trigger,
+            buffer,
+            finished: false,
+            error_occured: false,
+        }
+    }
+
+    /// Sets the max size of the chunks which are read from the reader
+    ///
+    /// # Arguments
+    /// * `chunk_size` - max size of the chunk (has to be greater than 0)
+    pub fn set_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.input = self.input.set_chunk_size(chunk_size);
+        self
+    }
+}
+
+impl<R: Read> Iterator for MatchIter<R> {
+    type Item = Result<(Option<String>, Vec<u8>), error::General>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.error_occured {
+            // Don't continue on error
+            return None;
+        }
+        loop {
+            // Try to pop buffer first
+            if let Some(item) = self.buffer.lock().unwrap().pop() {
+                return Some(Ok(item));
+            }
+
+            if self.finished {
+                // Entire input processed
+                return None;
+            }
+
+            // Feed the buffer
+            let res = match self.input.next() {
+                Some(Ok(chunk)) => self.trigger.process(&chunk),
+                Some(Err(err)) => Err(err.into()),
+                None => {
+                    self.finished = true;
+                    self.trigger.terminate()
+                }
+            };
+            if let Err(err) = res {
+                self.error_occured = true;
+                return Some(Err(err));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MatchIter;
+    use crate::matcher::Simple;
+    use std::io::Read;
+
+    fn chunks(input: &'static [&'static [u8]]) -> impl Read {
+        input
+            .iter()
+            .fold(Box::new(&b""[..]) as Box<dyn Read>, |reader, chunk| {
+                Box::new(reader.chain(*chunk))
+            })
+    }
+
+    #[test]
+    fn test_empty() {
+        let matcher = Box::new(Simple::new(r#"{"users"}[]{"name"}"#).unwrap());
+        let mut iter = MatchIter::new(chunks(&[b"{", b"}"]), matcher);
+
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_basic() {
+        let input = chunks(&[
+            b"{",
+            br#""users": ["#,
+            br#"{"name": "user1"},"#,
+            br#"{"name": "user2"},"#,
+            br#"{"name": "user3"}"#,
+            b"]",
+            b"}",
+        ]);
+        let matcher = Box::new(Simple::new(r#"{"users"}[]{"name"}"#).unwrap());
+        let mut iter = MatchIter::new(input, matcher).set_chunk_size(4);
+
+        assert_eq!(
+            iter.next().unwrap().unwrap(),
+            (
+                Some(r#"{"users"}[0]{"name"}"#.to_string()),
+                br#""user1""#.to_vec()
+            )
+        );
+        assert_eq!(
+            iter.next().unwrap().unwrap(),
+            (
+                Some(r#"{"users"}[1]{"name"}"#.to_string()),
+                br#""user2""#.to_vec()
+            )
+        );
+        assert_eq!(
+            iter.next().unwrap().unwrap(),
+            (
+                Some(r#"{"users"}[2]{"name"}"#.to_string()),
+                br#""user3""#.to_vec()
+            )
+        );
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_multiple_input() {
+        let input = chunks(&[
+            br#"{"users": [{"name": "user1"},{"name": "user2"},{"name": "user3"}]}"#,
+            br#"{"users": [{"name": "user4"},{"name": "user5"}]}"#,
+        ]);
+        let matcher = Box::new(Simple::new(r#"{"users"}[]{"name"}"#).unwrap());
+        let items: Vec<(Option<String>, Vec<u8>)> = MatchIter::new(input, matcher)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(
+            items,
+            (0..5)
+                .map(|idx| (
+                    Some(format!(r#"{{"users"}}[{}]{{"name"}}"#, idx % 3)),
+                    format!(r#""user{}""#, idx + 1).into_bytes()
+                ))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_error() {
+        let matcher = Box::new(Simple::new(r#"[]"#).unwrap());
+        let mut iter = MatchIter::new(&b"[1, 2, }"[..], matcher);
+
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+
+        // unfinished input
+        let matcher = Box::new(Simple::new(r#"[]"#).unwrap());
+        let mut iter = MatchIter::new(&b"[1, 2"[..], matcher);
+        assert_eq!(iter.next().unwrap().unwrap().1, b"1".to_vec());
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+    }
+}
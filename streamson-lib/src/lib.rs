@@ -109,12 +109,18 @@
 //! ```
 
 pub mod error;
+pub mod get;
 pub mod handler;
+pub mod input;
+pub mod iter;
 pub mod matcher;
 pub mod path;
 pub mod strategy;
 pub mod streamer;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
+pub use get::get;
 pub use handler::Handler;
 pub use path::Path;
 pub use streamer::{Streamer, Token};
@@ -129,52 +135,3 @@ mod test_readme {
     }
     external_doc_test!(include_str!("../README.md"));
 }
-
-#[cfg(test)]
-pub mod test {
-    pub trait Splitter {
-        fn split(&self, input: Vec<u8>) -> Vec<Vec<Vec<u8>>>;
-    }
-
-    pub(crate) struct Single;
-
-    impl Single {
-        pub fn new() -> Self {
-            Self
-        }
-    }
-
-    impl Splitter for Single {
-        fn split(&self, input: Vec<u8>) -> Vec<Vec<Vec<u8>>> {
-            vec![input.iter().map(|e| vec![*e]).collect()]
-        }
-    }
-
-    pub(crate) struct Window {
-        size: usize,
-    }
-
-    impl Window {
-        pub fn new(size: usize) -> Self {
-            Self { size }
-        }
-    }
-
-    impl Splitter for Window {
-        fn split(&self, input: Vec<u8>) -> Vec<Vec<Vec<u8>>> {
-            if input.len() <= self.size {
-                return vec![vec![input]];
-            }
-            let out_count = input.len() - self.size;
-            let mut res = vec![];
-            for i in 0..=out_count {
-                res.push(vec![
-                    input[0..i].to_vec(),
-                    input[i..self.size + i].to_vec(),
-                    input[self.size + i..input.len()].to_vec(),
-                ]);
-            }
-            res
-        }
-    }
-}
@@ -4,16 +4,38 @@ use crate::streamer::ParsedKind;
 use std::fmt;
 
 pub mod combinator;
+pub mod deep_key;
 pub mod depth;
+pub mod document_index;
+pub(crate) mod index;
+pub mod json_path;
+pub mod number_compare;
+pub mod parent_key;
+pub mod pointer;
 #[cfg(feature = "with_regex")]
 pub mod regex;
 pub mod simple;
+pub mod value;
+pub mod value_kind;
+#[cfg(feature = "with_regex")]
+pub mod value_regex;
 
 pub use self::combinator::Combinator;
+pub use self::deep_key::DeepKey;
 pub use self::depth::Depth;
+pub use self::document_index::DocumentIndex;
+pub(crate) use self::index::MatcherIndex;
+pub use self::json_path::JsonPath;
+pub use self::number_compare::NumberCompare;
+pub use self::parent_key::ParentKey;
+pub use self::pointer::Pointer;
 #[cfg(feature = "with_regex")]
 pub use self::regex::Regex;
 pub use self::simple::Simple;
+pub use self::value::Value;
+pub use self::value_kind::ValueKind;
+#[cfg(feature = "with_regex")]
+pub use self::value_regex::ValueRegex;
 
 use crate::path::Path;
 
@@ -27,4 +49,46 @@ pub trait Matcher: fmt::Debug + Send {
     /// # Returns
     /// * `true` if path matches, `false` otherwise
     fn match_path(&self, path: &Path, kind: ParsedKind) -> bool;
+
+    /// Indicates that the matcher needs to inspect the value
+    ///
+    /// Strategies defer the decision for leaf elements (strings, numbers,
+    /// booleans and nulls) to the end of the element and call `match_value` instead
+    /// of `match_path` in that case.
+    fn needs_value(&self) -> bool {
+        false
+    }
+
+    /// Check whether the path and the value of a leaf element matches
+    /// # Arguments
+    /// * `path` - path to be matched (has to be a valid path)
+    /// * `kind` - what kind (string, integer, float, ...) are matched data
+    ///   (taken from `Token::End`, so the exact kind of a number is known)
+    /// * `value` - raw data of the element (e.g. `"str"` or `-1.5`)
+    ///
+    /// # Returns
+    /// * `true` if path and value matches, `false` otherwise
+    fn match_value(&self, path: &Path, kind: ParsedKind, _value: &[u8]) -> bool {
+        self.match_path(path, kind)
+    }
+
+    /// Called by strategies when a top level JSON is finished
+    ///
+    /// Matchers which keep track of processed documents can update their state here.
+    fn json_finished(&self) {}
+
+    /// Returns the matcher as `Simple` matcher
+    ///
+    /// Strategies use it to index the `Simple` matchers.
+    fn as_simple(&self) -> Option<&Simple> {
+        None
+    }
+
+    /// Writes a short description of the matcher (e.g. `depth(1-2)`)
+    ///
+    /// It is used when a `Combinator` is displayed.
+    /// The `Debug` output is used by default.
+    fn describe(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
 }
@@ -1,8 +1,8 @@
 //! Combinator path matcher
 
-use std::{ops, sync::Arc};
+use std::{fmt, ops, sync::Arc};
 
-use super::Matcher;
+use super::{Matcher, Simple};
 use crate::{path::Path, streamer::ParsedKind};
 
 #[derive(Debug, Clone)]
@@ -10,8 +10,12 @@ use crate::{path::Path, streamer::ParsedKind};
 ///
 /// It implements normal boolean algebra
 /// * `! comb`  will negate the combinator
+///   (when the value is needed, the decision made from the value is negated)
 /// * `comb1 & comb2` both should pass
 /// * `comb1 | comb2` at least one should pass
+///
+/// It can be displayed as a boolean expression
+/// (e.g. `(depth(1-2) | !simple({"users"}[]))`).
 pub enum Combinator {
     /// Represents the actual underlying matcher
     Matcher(Arc<dyn Matcher + Sync>),
@@ -36,6 +40,59 @@ impl Matcher for Combinator {
             }
         }
     }
+
+    fn needs_value(&self) -> bool {
+        match self {
+            Self::Matcher(matcher) => matcher.needs_value(),
+            Self::Not(combinator) => combinator.needs_value(),
+            Self::Or(first, second) | Self::And(first, second) => {
+                first.needs_value() || second.needs_value()
+            }
+        }
+    }
+
+    fn match_value(&self, path: &Path, kind: ParsedKind, value: &[u8]) -> bool {
+        match self {
+            Self::Matcher(matcher) => matcher.match_value(path, kind, value),
+            Self::Not(combinator) => !combinator.match_value(path, kind, value),
+            Self::Or(first, second) => {
+                first.match_value(path, kind, value) || second.match_value(path, kind, value)
+            }
+            Self::And(first, second) => {
+                first.match_value(path, kind, value) && second.match_value(path, kind, value)
+            }
+        }
+    }
+
+    fn json_finished(&self) {
+        match self {
+            Self::Matcher(matcher) => matcher.json_finished(),
+            Self::Not(combinator) => combinator.json_finished(),
+            Self::Or(first, second) | Self::And(first, second) => {
+                first.json_finished();
+                second.json_finished();
+            }
+        }
+    }
+
+    fn as_simple(&self) -> Option<&Simple> {
+        // only a single matcher can be indexed
+        match self {
+            Self::Matcher(matcher) => matcher.as_simple(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Combinator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Matcher(matcher) => matcher.describe(f),
+            Self::Not(combinator) => write!(f, "!{}", combinator),
+            Self::And(first, second) => write!(f, "({} & {})", first, second),
+            Self::Or(first, second) => write!(f, "({} | {})", first, second),
+        }
+    }
 }
 
 impl Combinator {
@@ -74,11 +131,17 @@ impl ops::BitOr for Combinator {
 mod tests {
     use super::{Combinator, Matcher};
     use crate::{
-        matcher::{Depth, Simple},
+        handler::Replace,
+        matcher::{Depth, NumberCompare, Pointer, Simple, ValueKind},
         path::Path,
+        strategy::{Convert, Extract, OutputConverter, Strategy},
         streamer::ParsedKind,
     };
-    use std::convert::TryFrom;
+    use std::{
+        convert::TryFrom,
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
 
     #[test]
     fn wrapper() {
@@ -99,6 +162,69 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn not_value() {
+        let comb = !Combinator::new(NumberCompare::from_str(">2").unwrap());
+        assert!(comb.needs_value());
+        let path = Path::try_from(r#"[0]"#).unwrap();
+        assert!(comb.match_value(&path, ParsedKind::Num, b"1"));
+        assert!(!comb.match_value(&path, ParsedKind::Num, b"3"));
+        assert!(comb.match_value(&path, ParsedKind::Str, br#""3""#));
+
+        // double negation
+        let comb = !comb;
+        assert!(comb.needs_value());
+        assert!(!comb.match_value(&path, ParsedKind::Num, b"1"));
+        assert!(comb.match_value(&path, ParsedKind::Num, b"3"));
+    }
+
+    #[test]
+    fn not_value_extract() {
+        let mut extract = Extract::new();
+        extract.add_matcher(
+            Box::new(
+                Combinator::new(Simple::new(r#"{"values"}[]"#).unwrap())
+                    & !Combinator::new(NumberCompare::from_str(">2").unwrap()),
+            ),
+            None,
+        );
+
+        let input = br#"{"values": [1, 5, 2.5, "x", 0, 3, [4]], "other": [1]}"#;
+        let mut output = vec![];
+        let mut converter = OutputConverter::new();
+        for part in input.chunks(3) {
+            for (_, data) in converter.convert(&extract.process(part).unwrap()) {
+                output.push(String::from_utf8(data).unwrap());
+            }
+        }
+        assert_eq!(output, vec!["1", r#""x""#, "0", "[4]"]);
+    }
+
+    #[test]
+    fn not_value_convert() {
+        let mut convert = Convert::new();
+        convert.add_matcher(
+            Box::new(
+                Combinator::new(Simple::new(r#"[]"#).unwrap())
+                    & !Combinator::new(ValueKind::new(ParsedKind::Int)),
+            ),
+            Arc::new(Mutex::new(Replace::new(b"null".to_vec()))),
+        );
+
+        let input = br#"[1, 1.5, 2, "3", 4e1]"#;
+        let mut output = vec![];
+        let mut converter = OutputConverter::new();
+        for part in input.chunks(2) {
+            for (_, data) in converter.convert(&convert.process(part).unwrap()) {
+                output.extend(data);
+            }
+        }
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"[1, null, 2, null, null]"#
+        );
+    }
+
     #[test]
     fn and() {
         let comb = Combinator::new(Depth::new(1, Some(1)))
@@ -149,4 +275,20 @@ mod tests {
             ParsedKind::Obj
         ));
     }
+
+    #[test]
+    fn display() {
+        let comb = Combinator::new(Depth::new(1, Some(2)))
+            | Combinator::new(Simple::new(r#"{"users"}[]"#).unwrap());
+        assert_eq!(comb.to_string(), r#"(depth(1-2) | simple({"users"}[]))"#);
+
+        let comb = !(comb & Combinator::new(ValueKind::new(ParsedKind::Int)))
+            | (Combinator::new(NumberCompare::from_str(">=1.5").unwrap())
+                & !Combinator::new(Pointer::new("/a~1b/0").unwrap()))
+            | Combinator::new(Depth::new(3, None));
+        assert_eq!(
+            comb.to_string(),
+            r#"((!((depth(1-2) | simple({"users"}[])) & kind(integer)) | (number(>=1.5) & !pointer(/a~1b/0))) | depth(3-))"#
+        );
+    }
 }
@@ -0,0 +1,128 @@
+//! Deep key matcher
+//!
+//! Matches elements stored under the given key which
+//! are nested deep enough (e.g. all `id` keys at depth >= 2).
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{matcher, strategy::{self, Strategy}};
+//!
+//! let matcher = matcher::DeepKey::new(2, "id");
+//!
+//! let mut extract = strategy::Extract::new();
+//! extract.add_matcher(Box::new(matcher), None);
+//!
+//! let output = extract.process(br#"{"id": 1, "users": [{"id": 2}]}"#).unwrap();
+//! ```
+
+use std::{fmt, str::FromStr};
+
+use super::Matcher;
+use crate::{
+    error,
+    path::{Element, Path},
+    streamer::ParsedKind,
+};
+
+/// Based on path depth and the last key
+///
+/// Path is matched when its depth is higher or equal `min_depth`
+/// and its last element is a key equal to `key`
+#[derive(Default, Debug, Clone)]
+pub struct DeepKey {
+    /// Minimal depth (lower won't be matched)
+    pub min_depth: usize,
+    /// Key of the matched element
+    pub key: String,
+}
+
+impl DeepKey {
+    /// Creates new deep key matcher
+    ///
+    /// # Arguments
+    /// * `min_depth` - minimal depth (lower won't be matched)
+    /// * `key` - key of the matched element
+    pub fn new<T>(min_depth: usize, key: T) -> Self
+    where
+        T: ToString,
+    {
+        Self {
+            min_depth,
+            key: key.to_string(),
+        }
+    }
+}
+
+impl Matcher for DeepKey {
+    fn match_path(&self, path: &Path, _kind: ParsedKind) -> bool {
+        if path.depth() < self.min_depth {
+            return false;
+        }
+        matches!(path.get_path().last(), Some(Element::Key(key)) if key == &self.key)
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "deep_key({},{})", self.min_depth, self.key)
+    }
+}
+
+impl FromStr for DeepKey {
+    type Err = error::Matcher;
+
+    /// Parses `min_depth,key` (e.g. `2,id`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let splitted: Vec<&str> = s.splitn(2, ',').collect();
+        if splitted.len() != 2 {
+            return Err(error::Matcher::Parse(s.into()));
+        }
+        let min_depth = splitted[0]
+            .parse()
+            .map_err(|_| error::Matcher::Parse(s.into()))?;
+        Ok(Self::new(min_depth, splitted[1]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeepKey, Matcher};
+    use crate::{path::Path, streamer::ParsedKind};
+    use std::{convert::TryFrom, str::FromStr};
+
+    #[test]
+    fn match_path() {
+        let matcher = DeepKey::new(2, "id");
+
+        assert!(!matcher.match_path(&Path::try_from(r#"{"id"}"#).unwrap(), ParsedKind::Num));
+        assert!(matcher.match_path(
+            &Path::try_from(r#"{"users"}{"id"}"#).unwrap(),
+            ParsedKind::Num
+        ));
+        assert!(matcher.match_path(
+            &Path::try_from(r#"{"users"}[0]{"id"}"#).unwrap(),
+            ParsedKind::Str
+        ));
+        assert!(!matcher.match_path(
+            &Path::try_from(r#"{"users"}[0]{"name"}"#).unwrap(),
+            ParsedKind::Str
+        ));
+        assert!(!matcher.match_path(&Path::try_from(r#"{"id"}[0]"#).unwrap(), ParsedKind::Num));
+        assert!(!matcher.match_path(&Path::try_from(r#""#).unwrap(), ParsedKind::Obj));
+
+        let matcher = DeepKey::new(0, "id");
+        assert!(matcher.match_path(&Path::try_from(r#"{"id"}"#).unwrap(), ParsedKind::Num));
+    }
+
+    #[test]
+    fn parse() {
+        let matcher = DeepKey::from_str("3,id").unwrap();
+        assert_eq!(matcher.min_depth, 3);
+        assert_eq!(matcher.key, "id");
+
+        let matcher = DeepKey::from_str("1,a,b").unwrap();
+        assert_eq!(matcher.key, "a,b");
+
+        assert!(DeepKey::from_str("").is_err());
+        assert!(DeepKey::from_str("1").is_err());
+        assert!(DeepKey::from_str("x,id").is_err());
+    }
+}
@@ -1,6 +1,6 @@
 //! Depth path matcher
 
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 use super::Matcher;
 use crate::{error, path::Path, streamer::ParsedKind};
@@ -34,6 +34,14 @@ impl Matcher for Depth {
             self.min <= depth
         }
     }
+
+    fn describe(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(max) = self.max {
+            write!(f, "depth({}-{})", self.min, max)
+        } else {
+            write!(f, "depth({}-)", self.min)
+        }
+    }
 }
 
 impl FromStr for Depth {
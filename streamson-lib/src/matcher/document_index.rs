@@ -0,0 +1,146 @@
+//! Matcher which matches only within the nth top level document
+//!
+//! It is useful for inputs with multiple JSONs (e.g. NDJSON) and
+//! it is usually combined with other matchers using `Combinator`.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let handler = Arc::new(Mutex::new(handler::Buffer::new()));
+//! // "level" of the second document only
+//! let matcher = matcher::Combinator::new(matcher::DocumentIndex::new(1))
+//!     & matcher::Combinator::new(matcher::Simple::new(r#"{"level"}"#).unwrap());
+//!
+//! let mut trigger = strategy::Trigger::new();
+//! trigger.add_matcher(Box::new(matcher), handler.clone());
+//!
+//! trigger.process(b"{\"level\": \"info\"}\n{\"level\": \"error\"}\n").unwrap();
+//! assert_eq!(handler.lock().unwrap().pop().unwrap().1, br#""error""#.to_vec());
+//! ```
+
+use std::{
+    fmt,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use super::Matcher;
+use crate::{error, path::Path, streamer::ParsedKind};
+
+/// Matches all paths within the nth top level document (counted from 0)
+///
+/// Documents are counted by the strategy which uses the matcher,
+/// so the matcher shouldn't be shared among several strategies.
+#[derive(Default, Debug)]
+pub struct DocumentIndex {
+    /// Index of the document which should be matched
+    idx: usize,
+    /// Index of the currently processed document
+    current: AtomicUsize,
+}
+
+impl Clone for DocumentIndex {
+    fn clone(&self) -> Self {
+        Self {
+            idx: self.idx,
+            current: AtomicUsize::new(self.current.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl DocumentIndex {
+    /// Creates new document index matcher
+    ///
+    /// # Arguments
+    /// * `idx` - index of the document to be matched (counted from 0)
+    pub fn new(idx: usize) -> Self {
+        Self {
+            idx,
+            current: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl Matcher for DocumentIndex {
+    fn match_path(&self, _path: &Path, _kind: ParsedKind) -> bool {
+        self.current.load(Ordering::Relaxed) == self.idx
+    }
+
+    fn json_finished(&self) {
+        self.current.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "document_index({})", self.idx)
+    }
+}
+
+impl FromStr for DocumentIndex {
+    type Err = error::Matcher;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse()
+            .map(Self::new)
+            .map_err(|_| error::Matcher::Parse(s.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DocumentIndex;
+    use crate::{
+        handler::Buffer,
+        matcher::{Combinator, Simple},
+        strategy::{Extract, Output, Strategy, Trigger},
+    };
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    const INPUT: &[u8] = b"{\"users\": [\"a\", \"b\"]}\n{\"users\": [\"c\"]}\n[\"d\"]\n";
+
+    #[test]
+    fn second_document() {
+        let mut extract = Extract::new();
+        extract.add_matcher(Box::new(DocumentIndex::new(1)), None);
+
+        let mut output = vec![];
+        for part in INPUT.chunks(4) {
+            for item in extract.process(part).unwrap() {
+                if let Output::Data(data) = item {
+                    output.extend(data);
+                }
+            }
+        }
+        assert_eq!(String::from_utf8(output).unwrap(), r#"{"users": ["c"]}"#);
+    }
+
+    #[test]
+    fn combined() {
+        let handler = Arc::new(Mutex::new(Buffer::new().set_use_path(true)));
+        let matcher = Combinator::new(DocumentIndex::from_str("1").unwrap())
+            & Combinator::new(Simple::new(r#"{"users"}[]"#).unwrap());
+        let mut trigger = Trigger::new();
+        trigger.add_matcher(Box::new(matcher), handler.clone());
+
+        for part in INPUT.chunks(3) {
+            trigger.process(part).unwrap();
+        }
+
+        let mut guard = handler.lock().unwrap();
+        assert_eq!(
+            guard.pop().unwrap(),
+            (Some(r#"{"users"}[0]"#.to_string()), br#""c""#.to_vec())
+        );
+        assert!(guard.pop().is_none());
+    }
+
+    #[test]
+    fn parse() {
+        assert!(DocumentIndex::from_str("3").is_ok());
+        assert!(DocumentIndex::from_str("-1").is_err());
+        assert!(DocumentIndex::from_str("").is_err());
+    }
+}
@@ -0,0 +1,279 @@
+//! Index of matchers used by strategies
+//!
+//! `Simple` matchers are stored in a prefix trie over path elements,
+//! so only the matchers which can match the path are checked.
+//! Other matchers (and `Simple` matchers with `*`) are always checked.
+
+use super::{simple::SimplePathElement, Matcher};
+use crate::{path::Element, streamer::ParsedKind};
+use std::collections::HashMap;
+
+/// Node of the trie
+#[derive(Debug, Default)]
+struct Node {
+    /// Matchers which end in this node
+    matchers: Vec<usize>,
+    /// Matchers which end with `~` right after this node
+    scalars: Vec<usize>,
+    /// Children reached by an exact key
+    keys: HashMap<String, Node>,
+    /// Other children (e.g. `{}`, `[1,3]` or `?`)
+    others: Vec<(SimplePathElement, Node)>,
+}
+
+impl Node {
+    /// Returns the child node (it is created if missing)
+    fn child(&mut self, element: &SimplePathElement) -> &mut Node {
+        if let SimplePathElement::Key(Some(key)) = element {
+            return self.keys.entry(key.clone()).or_default();
+        }
+        let position = match self.others.iter().position(|(other, _)| other == element) {
+            Some(position) => position,
+            None => {
+                self.others.push((element.clone(), Node::default()));
+                self.others.len() - 1
+            }
+        };
+        &mut self.others[position].1
+    }
+
+    /// Extends `nodes` with the children which match the element
+    fn matching_children<'a>(&'a self, element: &Element, nodes: &mut Vec<&'a Node>) {
+        if let Element::Key(key) = element {
+            if let Some(child) = self.keys.get(key) {
+                nodes.push(child);
+            }
+        }
+        for (matcher_element, child) in &self.others {
+            if matcher_element == element {
+                nodes.push(child);
+            }
+        }
+    }
+}
+
+/// Index of matchers
+#[derive(Debug, Default)]
+pub(crate) struct MatcherIndex {
+    /// Root of the trie with `Simple` matchers
+    root: Node,
+    /// Matchers which are not stored in the trie (sorted)
+    fallback: Vec<usize>,
+}
+
+impl MatcherIndex {
+    /// Creates a new empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a matcher to the index
+    ///
+    /// # Arguments
+    /// * `matcher_idx` - index of the matcher in the strategy
+    /// * `matcher` - matcher to be added
+    pub fn add(&mut self, matcher_idx: usize, matcher: &dyn Matcher) {
+        let elements = match matcher.as_simple() {
+            Some(simple) => simple.elements(),
+            None => {
+                self.add_fallback(matcher_idx);
+                return;
+            }
+        };
+        let (last, prefix) = match elements.split_last() {
+            Some((SimplePathElement::WildCardScalar, prefix)) => (true, prefix),
+            _ => (false, elements),
+        };
+        if prefix.iter().any(|element| {
+            matches!(
+                element,
+                SimplePathElement::WildCardAny | SimplePathElement::WildCardScalar
+            )
+        }) {
+            self.add_fallback(matcher_idx);
+            return;
+        }
+
+        let mut node = &mut self.root;
+        for element in prefix {
+            node = node.child(element);
+        }
+        if last {
+            node.scalars.push(matcher_idx);
+        } else {
+            node.matchers.push(matcher_idx);
+        }
+    }
+
+    /// Adds a matcher which is always checked (keeps the fallback sorted)
+    fn add_fallback(&mut self, matcher_idx: usize) {
+        if let Err(position) = self.fallback.binary_search(&matcher_idx) {
+            self.fallback.insert(position, matcher_idx);
+        }
+    }
+
+    /// Returns sorted indexes of matchers which may match the path
+    ///
+    /// Matchers from the trie are matching, the other ones
+    /// still need to be checked.
+    ///
+    /// # Arguments
+    /// * `path` - path elements
+    /// * `kind` - kind of the element
+    pub fn candidates(&self, path: &[Element], kind: ParsedKind) -> Vec<usize> {
+        let scalar = !matches!(kind, ParsedKind::Obj | ParsedKind::Arr);
+        let mut result = vec![];
+        let mut nodes = vec![&self.root];
+        for (idx, element) in path.iter().enumerate() {
+            if scalar && idx + 1 == path.len() {
+                // `~` matches the last element
+                for node in &nodes {
+                    result.extend(&node.scalars);
+                }
+            }
+            let mut children = vec![];
+            for node in nodes {
+                node.matching_children(element, &mut children);
+            }
+            if children.is_empty() {
+                break;
+            }
+            nodes = children;
+            if idx + 1 == path.len() {
+                for node in &nodes {
+                    result.extend(&node.matchers);
+                }
+            }
+        }
+        if path.is_empty() {
+            result.extend(&self.root.matchers);
+        }
+        if result.is_empty() {
+            return self.fallback.clone();
+        }
+        result.sort_unstable();
+        result.dedup();
+        merge(&result, &self.fallback)
+    }
+}
+
+/// Merges two sorted lists of indexes
+fn merge(first: &[usize], second: &[usize]) -> Vec<usize> {
+    let mut result = Vec::with_capacity(first.len() + second.len());
+    let (mut first_idx, mut second_idx) = (0, 0);
+    while first_idx < first.len() && second_idx < second.len() {
+        if first[first_idx] <= second[second_idx] {
+            result.push(first[first_idx]);
+            first_idx += 1;
+        } else {
+            result.push(second[second_idx]);
+            second_idx += 1;
+        }
+    }
+    result.extend(&first[first_idx..]);
+    result.extend(&second[second_idx..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MatcherIndex;
+    use crate::{
+        matcher::{Combinator, Depth, Matcher, Simple},
+        path::Path,
+        streamer::ParsedKind,
+    };
+    use std::convert::TryFrom;
+
+    fn index(matchers: &[Box<dyn Matcher>]) -> MatcherIndex {
+        let mut index = MatcherIndex::new();
+        for (idx, matcher) in matchers.iter().enumerate() {
+            index.add(idx, matcher.as_ref());
+        }
+        index
+    }
+
+    fn candidates(index: &MatcherIndex, path: &str, kind: ParsedKind) -> Vec<usize> {
+        index.candidates(Path::try_from(path).unwrap().get_path(), kind)
+    }
+
+    #[test]
+    fn simple() {
+        let index = index(&[
+            Box::new(Simple::new(r#"{"users"}[]{"name"}"#).unwrap()),
+            Box::new(Simple::new(r#"{"users"}[1]{"name"}"#).unwrap()),
+            Box::new(Simple::new(r#"{"users"}[]{}"#).unwrap()),
+            Box::new(Simple::new(r#"{"users"}~"#).unwrap()),
+            Box::new(Simple::new(r#"{"groups"}"#).unwrap()),
+            Box::new(Simple::new("").unwrap()),
+            Box::new(Simple::new(r#"{"users"}[]{!"name"}"#).unwrap()),
+        ]);
+        assert_eq!(
+            candidates(&index, r#"{"users"}[1]{"name"}"#, ParsedKind::Str),
+            vec![0, 1, 2]
+        );
+        assert_eq!(
+            candidates(&index, r#"{"users"}[0]{"name"}"#, ParsedKind::Str),
+            vec![0, 2]
+        );
+        assert_eq!(
+            candidates(&index, r#"{"users"}[0]{"age"}"#, ParsedKind::Str),
+            vec![2, 6]
+        );
+        assert_eq!(
+            candidates(&index, r#"{"users"}[0]"#, ParsedKind::Int),
+            vec![3]
+        );
+        assert!(candidates(&index, r#"{"users"}[0]"#, ParsedKind::Obj).is_empty());
+        assert_eq!(
+            candidates(&index, r#"{"groups"}"#, ParsedKind::Arr),
+            vec![4]
+        );
+        assert_eq!(candidates(&index, "", ParsedKind::Obj), vec![5]);
+        assert!(candidates(&index, r#"{"other"}[0]"#, ParsedKind::Obj).is_empty());
+    }
+
+    #[test]
+    fn fallback() {
+        let index = index(&[
+            Box::new(Simple::new(r#"{"users"}*"#).unwrap()),
+            Box::new(Simple::new(r#"{"users"}"#).unwrap()),
+            Box::new(Depth::new(1, None)),
+        ]);
+        assert_eq!(
+            candidates(&index, r#"{"groups"}"#, ParsedKind::Arr),
+            vec![0, 2]
+        );
+        assert_eq!(
+            candidates(&index, r#"{"users"}"#, ParsedKind::Arr),
+            vec![0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn combinator() {
+        let mut index = MatcherIndex::new();
+        index.add(
+            2,
+            &(Combinator::new(Simple::new(r#"{"users"}"#).unwrap())
+                | Combinator::new(Depth::new(2, None))),
+        );
+        index.add(1, &Combinator::new(Simple::new(r#"{"users"}"#).unwrap()));
+        index.add(0, &Combinator::new(Depth::new(1, None)));
+        index.add(3, &Combinator::new(Simple::new(r#"{"groups"}"#).unwrap()));
+
+        // single simple matchers are in the trie, the others are always checked
+        assert_eq!(
+            candidates(&index, r#"{"users"}"#, ParsedKind::Arr),
+            vec![0, 1, 2]
+        );
+        assert_eq!(
+            candidates(&index, r#"{"groups"}"#, ParsedKind::Arr),
+            vec![0, 2, 3]
+        );
+        assert_eq!(
+            candidates(&index, r#"{"other"}"#, ParsedKind::Arr),
+            vec![0, 2]
+        );
+    }
+}
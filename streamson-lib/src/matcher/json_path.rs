@@ -0,0 +1,320 @@
+//! JSONPath path matcher
+//!
+//! e.g. `$.users[*].name` matches `{"users"}[0]{"name"}`
+//!
+//! Supported syntax:
+//! * `$` - root element
+//! * `.key`, `['key']`, `["key"]`, `['a','b']` - object members
+//! * `.*`, `[*]` - any object member or array item
+//! * `[0]`, `[0,2]`, `[1:5]`, `[::2]` - array items (negative indexes are not supported)
+//! * `..` - recursive descent (e.g. `$..id`)
+//!
+//! Filter expressions (`[?(...)]`) are not supported, because
+//! they depend on the data which were not read yet.
+
+use std::{fmt, str::FromStr};
+
+use super::{
+    simple::{IndexMatch, SimplePathElement},
+    Matcher,
+};
+use crate::{
+    error,
+    path::{Element, Path},
+    streamer::ParsedKind,
+};
+
+/// Element of JSONPath
+#[derive(Debug, Clone, PartialEq)]
+enum JsonPathElement {
+    /// Object members (keys are escaped the same way as in `Path`)
+    Keys(Vec<String>),
+    /// Array items (always `SimplePathElement::Index`)
+    Indexes(SimplePathElement),
+    /// Any object member or array item
+    Wildcard,
+    /// Any number of elements (recursive descent)
+    Descendants,
+}
+
+impl JsonPathElement {
+    /// Checks whether a path element matches
+    fn matches(&self, element: &Element) -> bool {
+        match (self, element) {
+            (Self::Keys(keys), Element::Key(key)) => keys.contains(key),
+            (Self::Indexes(indexes), element) => indexes == element,
+            (Self::Wildcard, _) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Matches paths described by a JSONPath expression
+#[derive(Default, Debug, Clone)]
+pub struct JsonPath {
+    path: Vec<JsonPathElement>,
+    /// Original path expression
+    source: String,
+}
+
+impl JsonPath {
+    /// Creates new JSONPath matcher
+    ///
+    /// # Arguments
+    /// * `path_expr` - JSONPath expression (e.g. `$.users[*].name`)
+    pub fn new(path_expr: &str) -> Result<Self, error::Matcher> {
+        Self::from_str(path_expr)
+    }
+}
+
+/// Escapes the key so it can be compared with keys in `Path`
+fn escape_key(key: &str) -> String {
+    key.replace('\\', r#"\\"#).replace('"', r#"\""#)
+}
+
+/// Parses the content of brackets (e.g. `'a','b'` or `1:5`)
+fn parse_brackets(content: &str) -> Result<JsonPathElement, error::Matcher> {
+    let content = content.trim();
+    let error = || error::Matcher::Parse(content.to_string());
+    if content == "*" {
+        return Ok(JsonPathElement::Wildcard);
+    }
+    if content.starts_with('\'') || content.starts_with('"') {
+        let mut keys = vec![];
+        let mut chars = content.chars();
+        loop {
+            let quote = match chars.next() {
+                Some(quote) if quote == '\'' || quote == '"' => quote,
+                _ => return Err(error()),
+            };
+            let mut key = String::new();
+            loop {
+                match chars.next().ok_or_else(error)? {
+                    '\\' => key.push(chars.next().ok_or_else(error)?),
+                    chr if chr == quote => break,
+                    chr => key.push(chr),
+                }
+            }
+            keys.push(escape_key(&key));
+            match chars.by_ref().find(|chr| !chr.is_whitespace()) {
+                None => return Ok(JsonPathElement::Keys(keys)),
+                Some(',') => {
+                    // skip whitespaces before the next key
+                    let rest = chars.as_str().trim_start();
+                    chars = rest.chars();
+                }
+                Some(_) => return Err(error()),
+            }
+        }
+    }
+    if content.is_empty()
+        || !content
+            .chars()
+            .all(|chr| chr.is_ascii_digit() || chr == ':' || chr == ',')
+    {
+        // negative indexes and filters are not supported
+        return Err(error());
+    }
+    Ok(JsonPathElement::Indexes(SimplePathElement::Index(
+        content.parse::<IndexMatch>()?,
+    )))
+}
+
+impl FromStr for JsonPath {
+    type Err = error::Matcher;
+
+    fn from_str(path_expr: &str) -> Result<Self, Self::Err> {
+        let error = || error::Matcher::Parse(path_expr.to_string());
+        let rest = path_expr.trim().strip_prefix('$').ok_or_else(error)?;
+        let mut chars = rest.chars().peekable();
+        let mut path = vec![];
+
+        while let Some(chr) = chars.next() {
+            match chr {
+                '.' => {
+                    if chars.peek() == Some(&'.') {
+                        chars.next();
+                        path.push(JsonPathElement::Descendants);
+                        if chars.peek() == Some(&'[') {
+                            // e.g. `$..[0]`
+                            continue;
+                        }
+                    }
+                    let mut name = String::new();
+                    while let Some(chr) = chars.peek() {
+                        if *chr == '.' || *chr == '[' {
+                            break;
+                        }
+                        name.push(*chr);
+                        chars.next();
+                    }
+                    if name.is_empty() {
+                        return Err(error());
+                    }
+                    path.push(if name == "*" {
+                        JsonPathElement::Wildcard
+                    } else {
+                        JsonPathElement::Keys(vec![escape_key(&name)])
+                    });
+                }
+                '[' => {
+                    let mut content = String::new();
+                    let mut quote: Option<char> = None;
+                    loop {
+                        let chr = chars.next().ok_or_else(error)?;
+                        match (quote, chr) {
+                            (None, ']') => break,
+                            (None, '\'') | (None, '"') => quote = Some(chr),
+                            (Some(q), chr) if q == chr => quote = None,
+                            (Some(_), '\\') => {
+                                content.push(chr);
+                                content.push(chars.next().ok_or_else(error)?);
+                                continue;
+                            }
+                            _ => {}
+                        }
+                        content.push(chr);
+                    }
+                    path.push(parse_brackets(&content).map_err(|_| error())?);
+                }
+                _ => return Err(error()),
+            }
+        }
+
+        Ok(Self {
+            path,
+            source: path_expr.to_string(),
+        })
+    }
+}
+
+impl Matcher for JsonPath {
+    fn match_path(&self, path: &Path, _kind: ParsedKind) -> bool {
+        let path = path.get_path();
+
+        // first is element idx, second path index
+        let mut indexes = vec![(0, 0)];
+        while let Some((jpath_idx, path_idx)) = indexes.pop() {
+            if jpath_idx == self.path.len() {
+                if path_idx == path.len() {
+                    return true;
+                }
+                continue;
+            }
+            match &self.path[jpath_idx] {
+                JsonPathElement::Descendants => {
+                    indexes.push((jpath_idx + 1, path_idx));
+                    if path_idx < path.len() {
+                        indexes.push((jpath_idx, path_idx + 1));
+                    }
+                }
+                element => {
+                    if path_idx < path.len() && element.matches(&path[path_idx]) {
+                        indexes.push((jpath_idx + 1, path_idx + 1));
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "json_path({})", self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JsonPath, Matcher};
+    use crate::{
+        path::Path,
+        strategy::{Extract, OutputConverter, Strategy},
+        streamer::ParsedKind,
+    };
+    use std::convert::TryFrom;
+
+    fn matches(expr: &str, path: &str) -> bool {
+        JsonPath::new(expr)
+            .unwrap()
+            .match_path(&Path::try_from(path).unwrap(), ParsedKind::Null)
+    }
+
+    #[test]
+    fn keys() {
+        assert!(matches("$", ""));
+        assert!(!matches("$", r#"{"users"}"#));
+        assert!(matches("$.users", r#"{"users"}"#));
+        assert!(matches("$['users']", r#"{"users"}"#));
+        assert!(matches(r#"$["users"].name"#, r#"{"users"}{"name"}"#));
+        assert!(matches("$['a','b']", r#"{"b"}"#));
+        assert!(!matches("$['a','b']", r#"{"c"}"#));
+        assert!(matches(r#"$['a\'"b']"#, r#"{"a'\"b"}"#));
+        assert!(!matches("$.users", r#"{"users"}[0]"#));
+        assert!(!matches("$.users", "[0]"));
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(matches("$.users[*].name", r#"{"users"}[3]{"name"}"#));
+        assert!(matches("$.users.*.name", r#"{"users"}{"first"}{"name"}"#));
+        assert!(!matches("$.users[*].name", r#"{"users"}{"name"}"#));
+        assert!(matches("$.*", "[1]"));
+    }
+
+    #[test]
+    fn indexes() {
+        assert!(matches("$[0]", "[0]"));
+        assert!(!matches("$[0]", "[1]"));
+        assert!(matches("$[1,3]", "[3]"));
+        assert!(matches("$[1:5]", "[4]"));
+        assert!(!matches("$[1:5]", "[5]"));
+        assert!(matches("$[::2]", "[4]"));
+        assert!(!matches("$[::2]", "[3]"));
+        assert!(!matches("$[0]", r#"{"0"}"#));
+    }
+
+    #[test]
+    fn descendants() {
+        assert!(matches("$..id", r#"{"id"}"#));
+        assert!(matches("$..id", r#"{"users"}[0]{"id"}"#));
+        assert!(!matches("$..id", r#"{"users"}[0]{"id"}{"a"}"#));
+        assert!(matches("$..[0]", r#"{"users"}[0]"#));
+        assert!(matches("$.users..*", r#"{"users"}[0]{"id"}"#));
+        assert!(!matches("$.users..*", r#"{"users"}"#));
+    }
+
+    #[test]
+    fn parse_error() {
+        for expr in &[
+            "",
+            "users",
+            "$.",
+            "$..",
+            "$[",
+            "$[-1]",
+            "$[?(@.id)]",
+            "$['a'",
+            "$['a' 'b']",
+            "$[a]",
+            "$x",
+        ] {
+            assert!(JsonPath::new(expr).is_err(), "{}", expr);
+        }
+    }
+
+    #[test]
+    fn extract() {
+        let mut extract = Extract::new();
+        extract.add_matcher(Box::new(JsonPath::new("$.users[1:].name").unwrap()), None);
+
+        let input = br#"{"users": [{"name": "a"}, {"name": "b"}, {"name": "c"}]}"#;
+        let mut output = vec![];
+        let mut converter = OutputConverter::new();
+        for part in input.chunks(4) {
+            for (_, data) in converter.convert(&extract.process(part).unwrap()) {
+                output.push(String::from_utf8(data).unwrap());
+            }
+        }
+        assert_eq!(output, vec![r#""b""#, r#""c""#]);
+    }
+}
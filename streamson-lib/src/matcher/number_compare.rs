@@ -0,0 +1,218 @@
+//! Number comparison matcher
+//!
+//! It matches numbers based on their value.
+//! The decision is made at the end of the number, so
+//! strategies have to defer it (see `Matcher::needs_value`).
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{matcher, strategy::{self, Strategy}};
+//!
+//! // prices greater than 100
+//! let matcher = matcher::Combinator::new(matcher::Simple::new(r#"[]{"price"}"#).unwrap())
+//!     & matcher::Combinator::new(matcher::NumberCompare::new(matcher::number_compare::Op::Gt, 100.0));
+//!
+//! let mut extract = strategy::Extract::new();
+//! extract.add_matcher(Box::new(matcher), None);
+//!
+//! let output = extract.process(br#"[{"price": 99.5}, {"price": 150}]"#).unwrap();
+//! ```
+
+use std::{
+    fmt,
+    str::{self, FromStr},
+};
+
+use super::Matcher;
+use crate::{error, path::Path, streamer::ParsedKind};
+
+/// Comparison operator
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `==`
+    Eq,
+    /// `!=`
+    Ne,
+    /// `>=`
+    Ge,
+    /// `>`
+    Gt,
+}
+
+impl Op {
+    /// Compares two numbers
+    pub(super) fn compare(&self, left: f64, right: f64) -> bool {
+        match self {
+            Self::Lt => left < right,
+            Self::Le => left <= right,
+            Self::Eq => (left - right).abs() < f64::EPSILON,
+            Self::Ne => (left - right).abs() >= f64::EPSILON,
+            Self::Ge => left >= right,
+            Self::Gt => left > right,
+        }
+    }
+}
+
+impl AsRef<str> for Op {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+            Self::Ge => ">=",
+            Self::Gt => ">",
+        }
+    }
+}
+
+impl FromStr for Op {
+    type Err = error::Matcher;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "<" => Ok(Self::Lt),
+            "<=" => Ok(Self::Le),
+            "==" => Ok(Self::Eq),
+            "!=" => Ok(Self::Ne),
+            ">=" => Ok(Self::Ge),
+            ">" => Ok(Self::Gt),
+            _ => Err(error::Matcher::Parse(s.into())),
+        }
+    }
+}
+
+/// Matches numbers which satisfy the comparison
+///
+/// `value <op> self.value` has to be true
+#[derive(Debug, Clone)]
+pub struct NumberCompare {
+    /// Comparison operator
+    pub op: Op,
+    /// Value to compare with
+    pub value: f64,
+}
+
+impl NumberCompare {
+    /// Creates new number comparison matcher
+    ///
+    /// # Arguments
+    /// * `op` - comparison operator
+    /// * `value` - value to compare with
+    pub fn new(op: Op, value: f64) -> Self {
+        Self { op, value }
+    }
+}
+
+impl Matcher for NumberCompare {
+    fn match_path(&self, _path: &Path, _kind: ParsedKind) -> bool {
+        // can't be decided without the value
+        false
+    }
+
+    fn needs_value(&self) -> bool {
+        true
+    }
+
+    fn match_value(&self, _path: &Path, kind: ParsedKind, value: &[u8]) -> bool {
+        if !matches!(kind, ParsedKind::Num | ParsedKind::Int | ParsedKind::Float) {
+            return false;
+        }
+        str::from_utf8(value)
+            .ok()
+            .and_then(|e| e.parse::<f64>().ok())
+            .map(|number| self.op.compare(number, self.value))
+            .unwrap_or(false)
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "number({}{})", self.op.as_ref(), self.value)
+    }
+}
+
+impl FromStr for NumberCompare {
+    type Err = error::Matcher;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let op_len = s
+            .find(|c| !matches!(c, '<' | '>' | '=' | '!'))
+            .unwrap_or(s.len());
+        let op = Op::from_str(&s[..op_len]).map_err(|_| error::Matcher::Parse(s.into()))?;
+        let value = s[op_len..]
+            .trim()
+            .parse()
+            .map_err(|_| error::Matcher::Parse(s.into()))?;
+        Ok(Self::new(op, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Matcher, NumberCompare, Op};
+    use crate::{
+        matcher::{Combinator, Simple},
+        path::Path,
+        strategy::{Extract, OutputConverter, Strategy},
+        streamer::ParsedKind,
+    };
+    use std::{convert::TryFrom, str::FromStr};
+
+    #[test]
+    fn match_value() {
+        let path = Path::try_from(r#"[0]"#).unwrap();
+        let matcher = NumberCompare::new(Op::Gt, 100.0);
+        assert!(matcher.needs_value());
+        assert!(!matcher.match_path(&path, ParsedKind::Num));
+        assert!(matcher.match_value(&path, ParsedKind::Num, b"100.5"));
+        assert!(matcher.match_value(&path, ParsedKind::Num, b"1e3"));
+        assert!(!matcher.match_value(&path, ParsedKind::Num, b"100"));
+        assert!(!matcher.match_value(&path, ParsedKind::Num, b"-200"));
+        assert!(!matcher.match_value(&path, ParsedKind::Str, br#""200""#));
+
+        let matcher = NumberCompare::new(Op::Ne, 3.0);
+        assert!(matcher.match_value(&path, ParsedKind::Num, b"2"));
+        assert!(!matcher.match_value(&path, ParsedKind::Num, b"3.0"));
+    }
+
+    #[test]
+    fn parse() {
+        let matcher = NumberCompare::from_str(">=10.5").unwrap();
+        assert_eq!(matcher.op, Op::Ge);
+        assert_eq!(matcher.value, 10.5);
+
+        let matcher = NumberCompare::from_str("< -1").unwrap();
+        assert_eq!(matcher.op, Op::Lt);
+        assert_eq!(matcher.value, -1.0);
+
+        assert!(NumberCompare::from_str("").is_err());
+        assert!(NumberCompare::from_str("10").is_err());
+        assert!(NumberCompare::from_str("=<10").is_err());
+        assert!(NumberCompare::from_str(">x").is_err());
+    }
+
+    #[test]
+    fn extract_above_threshold() {
+        let matcher = Combinator::new(Simple::new(r#"[]{"price"}"#).unwrap())
+            & Combinator::new(NumberCompare::from_str(">100").unwrap());
+        let mut extract = Extract::new();
+        extract.add_matcher(Box::new(matcher), None);
+
+        let mut output = extract
+            .process(br#"[{"price": 99.5}, {"price": 15"#)
+            .unwrap();
+        output.extend(
+            extract
+                .process(br#"0}, {"price": "200"}, {"price": 1e3, "id": 300}]"#)
+                .unwrap(),
+        );
+
+        let output: Vec<Vec<u8>> = OutputConverter::new()
+            .convert(&output)
+            .into_iter()
+            .map(|e| e.1)
+            .collect();
+        assert_eq!(output, vec![b"150".to_vec(), b"1e3".to_vec()]);
+    }
+}
@@ -0,0 +1,104 @@
+//! Parent key matcher
+//!
+//! Matches elements which parent is stored under the given key
+//! e.g. items of `{"errors": [...]}` regardless of their index.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{matcher, strategy::{self, Strategy}};
+//!
+//! let matcher = matcher::ParentKey::new("errors");
+//!
+//! let mut extract = strategy::Extract::new();
+//! extract.add_matcher(Box::new(matcher), None);
+//!
+//! let output = extract.process(br#"{"errors": [1, 2], "warnings": [3]}"#).unwrap();
+//! ```
+
+use std::{fmt, str::FromStr};
+
+use super::Matcher;
+use crate::{
+    error,
+    path::{Element, Path},
+    streamer::ParsedKind,
+};
+
+/// Based on the key of the parent element
+///
+/// Path is matched when its second-to-last element is a key equal to `key`
+#[derive(Default, Debug, Clone)]
+pub struct ParentKey {
+    key: String,
+}
+
+impl ParentKey {
+    /// Creates new parent key matcher
+    ///
+    /// # Arguments
+    /// * `key` - key of the parent element
+    pub fn new<T>(key: T) -> Self
+    where
+        T: ToString,
+    {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+impl Matcher for ParentKey {
+    fn match_path(&self, path: &Path, _kind: ParsedKind) -> bool {
+        let elements = path.get_path();
+        if elements.len() < 2 {
+            return false;
+        }
+        matches!(&elements[elements.len() - 2], Element::Key(key) if key == &self.key)
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "parent_key({})", self.key)
+    }
+}
+
+impl FromStr for ParentKey {
+    type Err = error::Matcher;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Matcher, ParentKey};
+    use crate::{path::Path, streamer::ParsedKind};
+    use std::{convert::TryFrom, str::FromStr};
+
+    #[test]
+    fn match_path() {
+        let matcher = ParentKey::from_str("errors").unwrap();
+
+        assert!(matcher.match_path(
+            &Path::try_from(r#"{"errors"}[0]"#).unwrap(),
+            ParsedKind::Obj
+        ));
+        assert!(matcher.match_path(
+            &Path::try_from(r#"[1]{"errors"}[5]"#).unwrap(),
+            ParsedKind::Str
+        ));
+        assert!(matcher.match_path(
+            &Path::try_from(r#"{"errors"}{"first"}"#).unwrap(),
+            ParsedKind::Null
+        ));
+        assert!(!matcher.match_path(
+            &Path::try_from(r#"{"warnings"}[0]"#).unwrap(),
+            ParsedKind::Obj
+        ));
+        assert!(!matcher.match_path(&Path::try_from(r#"{"errors"}"#).unwrap(), ParsedKind::Arr));
+        assert!(!matcher.match_path(
+            &Path::try_from(r#"{"errors"}[0]{"msg"}"#).unwrap(),
+            ParsedKind::Str
+        ));
+        assert!(!matcher.match_path(&Path::try_from(r#"[0][0]"#).unwrap(), ParsedKind::Num));
+    }
+}
@@ -0,0 +1,124 @@
+//! JSON Pointer (RFC 6901) path matcher
+//!
+//! e.g. `/users/0/name` matches `{"users"}[0]{"name"}`
+//!
+//! Note that numeric reference tokens match both array indexes
+//! and object keys (`/0` matches `[0]` and `{"0"}`).
+
+use std::{fmt, str::FromStr};
+
+use super::Matcher;
+use crate::{error, path::Element, path::Path, streamer::ParsedKind};
+
+/// Matches the value addressed by a JSON Pointer
+#[derive(Default, Debug, Clone)]
+pub struct Pointer {
+    /// Unescaped reference tokens
+    tokens: Vec<String>,
+}
+
+impl Pointer {
+    /// Creates new JSON Pointer matcher
+    ///
+    /// # Arguments
+    /// * `pointer` - JSON Pointer (e.g. `/users/0/name`, `""` for the whole document)
+    pub fn new(pointer: &str) -> Result<Self, error::Matcher> {
+        Self::from_str(pointer)
+    }
+}
+
+/// Checks whether the reference token matches the path element
+fn token_matches(token: &str, element: &Element) -> bool {
+    match element {
+        Element::Key(key) => key == token,
+        Element::Index(idx) => {
+            // leading zeros are not allowed in array indexes
+            (token == "0" || !token.starts_with('0'))
+                && token.bytes().all(|byte| byte.is_ascii_digit())
+                && token.parse::<usize>().ok() == Some(*idx)
+        }
+    }
+}
+
+impl Matcher for Pointer {
+    fn match_path(&self, path: &Path, _kind: ParsedKind) -> bool {
+        let elements = path.get_path();
+        elements.len() == self.tokens.len()
+            && self
+                .tokens
+                .iter()
+                .zip(elements)
+                .all(|(token, element)| token_matches(token, element))
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "pointer(")?;
+        for token in &self.tokens {
+            write!(f, "/{}", token.replace('~', "~0").replace('/', "~1"))?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl FromStr for Pointer {
+    type Err = error::Matcher;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self::default());
+        }
+        if !s.starts_with('/') {
+            return Err(error::Matcher::Parse(s.into()));
+        }
+        let mut tokens = vec![];
+        for token in s[1..].split('/') {
+            let mut unescaped = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(chr) = chars.next() {
+                if chr == '~' {
+                    match chars.next() {
+                        Some('0') => unescaped.push('~'),
+                        Some('1') => unescaped.push('/'),
+                        _ => return Err(error::Matcher::Parse(s.into())),
+                    }
+                } else {
+                    unescaped.push(chr);
+                }
+            }
+            tokens.push(unescaped);
+        }
+        Ok(Self { tokens })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Matcher, Pointer};
+    use crate::{path::Path, streamer::ParsedKind};
+    use std::{convert::TryFrom, str::FromStr};
+
+    fn matches(pointer: &str, path: &str) -> bool {
+        Pointer::from_str(pointer)
+            .unwrap()
+            .match_path(&Path::try_from(path).unwrap(), ParsedKind::Null)
+    }
+
+    #[test]
+    fn match_path() {
+        assert!(matches("", ""));
+        assert!(!matches("", r#"{"users"}"#));
+        assert!(matches("/users/0/name", r#"{"users"}[0]{"name"}"#));
+        assert!(matches("/users/0/name", r#"{"users"}{"0"}{"name"}"#));
+        assert!(!matches("/users/0", r#"{"users"}[1]"#));
+        assert!(!matches("/users/00", r#"{"users"}[0]"#));
+        assert!(!matches("/users/0", r#"{"users"}[0]{"name"}"#));
+        assert!(matches("/a~1b/m~0n", r#"{"a/b"}{"m~n"}"#));
+        assert!(matches("/", r#"{""}"#));
+    }
+
+    #[test]
+    fn parse() {
+        assert!(Pointer::from_str("users").is_err());
+        assert!(Pointer::from_str("/a~2").is_err());
+        assert!(Pointer::from_str("/a~").is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use regex::{self, Error as RegexError};
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 use crate::{error, matcher::Matcher, path::Path, streamer::ParsedKind};
 
@@ -52,6 +52,10 @@ impl Matcher for Regex {
         let str_path: String = path.to_string();
         self.regex.is_match(&str_path)
     }
+
+    fn describe(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "regex({})", self.regex.as_str())
+    }
 }
 
 impl FromStr for Regex {
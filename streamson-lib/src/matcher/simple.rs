@@ -6,14 +6,27 @@ use crate::{
     path::{Element, Path},
     streamer::ParsedKind,
 };
-use std::str::FromStr;
+use std::{fmt, mem, str::FromStr};
 
 /// StringMatch to match array elements
 type StringMatch = Option<String>;
 
-/// IndexMatch to match array elements
+/// IndexMatch to match array elements (start, end, step)
 #[derive(Debug, Clone, PartialEq)]
-struct IndexMatch(Vec<(Option<usize>, Option<usize>)>);
+pub(super) struct IndexMatch(Vec<(Option<usize>, Option<usize>, usize)>);
+
+/// Parses optional index of a range
+fn parse_range_index(index: &str) -> Result<Option<usize>, error::Matcher> {
+    if index.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(
+            index
+                .parse()
+                .map_err(|_| error::Matcher::Parse(index.to_string()))?,
+        ))
+    }
+}
 
 impl FromStr for IndexMatch {
     type Err = error::Matcher;
@@ -23,13 +36,34 @@ impl FromStr for IndexMatch {
         let mut result = vec![];
 
         for item_str in splitted {
+            if item_str.contains(':') {
+                // slice `start:end:step`
+                let inner_splitted: Vec<_> = item_str.split(':').collect();
+                if inner_splitted.len() > 3 {
+                    return Err(error::Matcher::Parse(item_str.to_string()));
+                }
+                let start_opt = parse_range_index(inner_splitted[0])?;
+                let end_opt = parse_range_index(inner_splitted[1])?;
+                let step = parse_range_index(inner_splitted.get(2).unwrap_or(&""))?.unwrap_or(1);
+                if step == 0 {
+                    return Err(error::Matcher::Parse(item_str.to_string()));
+                }
+                if let (Some(start), Some(end)) = (start_opt, end_opt) {
+                    if start >= end {
+                        return Err(error::Matcher::Parse(item_str.to_string()));
+                    }
+                }
+                result.push((start_opt, end_opt, step));
+                continue;
+            }
+
             let inner_splitted: Vec<_> = item_str.split('-').collect();
             match inner_splitted.len() {
                 1 => {
                     let index: usize = inner_splitted[0]
                         .parse()
                         .map_err(|_| error::Matcher::Parse(inner_splitted[0].to_string()))?;
-                    result.push((Some(index), Some(index + 1)));
+                    result.push((Some(index), Some(index + 1), 1));
                 }
                 2 => {
                     let start_opt: Option<usize> =
@@ -57,7 +91,7 @@ impl FromStr for IndexMatch {
                         (None, None) => return Err(error::Matcher::Parse(item_str.to_string())),
                         _ => {}
                     }
-                    result.push((start_opt, end_opt));
+                    result.push((start_opt, end_opt, 1));
                 }
                 _ => return Err(error::Matcher::Parse(item_str.to_string())),
             }
@@ -69,10 +103,13 @@ impl FromStr for IndexMatch {
 
 /// SimplePath path matcher
 #[derive(Debug, Clone, PartialEq)]
-enum SimplePathElement {
+pub(super) enum SimplePathElement {
     Key(StringMatch),
+    /// Any key except the listed ones
+    KeyExcept(Vec<String>),
     Index(IndexMatch),
     WildCardSingle,
+    WildCardScalar,
     WildCardAny,
 }
 
@@ -87,12 +124,19 @@ impl PartialEq<Element> for SimplePathElement {
                     false
                 }
             }
+            SimplePathElement::KeyExcept(keys) => {
+                if let Element::Key(pkey) = other {
+                    !keys.contains(pkey)
+                } else {
+                    false
+                }
+            }
             SimplePathElement::Index(idx_matches) => {
                 if let Element::Index(idx) = other {
                     if idx_matches.0.is_empty() {
                         true
                     } else {
-                        idx_matches.0.iter().any(|(min_opt, max_opt)| {
+                        idx_matches.0.iter().any(|(min_opt, max_opt, step)| {
                             if let Some(max) = max_opt {
                                 if idx >= max {
                                     return false;
@@ -103,7 +147,7 @@ impl PartialEq<Element> for SimplePathElement {
                                     return false;
                                 }
                             }
-                            true
+                            (idx - min_opt.unwrap_or(0)) % step == 0
                         })
                     }
                 } else {
@@ -112,6 +156,7 @@ impl PartialEq<Element> for SimplePathElement {
             }
             SimplePathElement::WildCardAny => true,
             SimplePathElement::WildCardSingle => true,
+            SimplePathElement::WildCardScalar => true,
         }
     }
 }
@@ -121,9 +166,14 @@ impl PartialEq<Element> for SimplePathElement {
 /// It matches {"People"}[0]{"Height"} - height of the first person
 /// It matches {"People"}[]{"Height"} - matches the height of all people
 /// It matches {"People"}[0]{} - matches all attributes of the first person
+/// It matches {"People"}[0]{!"Age","Height"} - matches all attributes of the first person except age and height
+/// It matches {"People"}[0]~ - matches all scalar attributes of the first person
+/// It matches {"People"}[::2]{"Height"} - matches the height of every other person
 #[derive(Default, Debug, Clone)]
 pub struct Simple {
     path: Vec<SimplePathElement>,
+    /// Original path expression
+    source: String,
 }
 
 #[derive(Debug, PartialEq)]
@@ -133,61 +183,22 @@ enum SimpleMatcherStates {
     ObjectStart,
     Object(bool),
     ObjectEnd,
+    ExceptStart,
+    Except(bool),
+    ExceptEnd,
 }
 
 impl Matcher for Simple {
-    fn match_path(&self, path: &Path, _kind: ParsedKind) -> bool {
-        // If no AnyWildcard present and length differs
-        // return false right away
-        if !self
-            .path
-            .iter()
-            .any(|e| matches!(e, SimplePathElement::WildCardAny))
-            && path.depth() != self.path.len()
-        {
-            return false;
-        }
-
-        let path = path.get_path();
-
-        // first is element idx, second path index
-        // starting at the beginning
-        let mut indexes = vec![(0, 0)];
-
-        while !indexes.is_empty() {
-            let (spath_idx, path_idx) = indexes.pop().unwrap();
-
-            if spath_idx == self.path.len() && path_idx == path.len() {
-                // all matched
-                return true;
-            }
-
-            if spath_idx >= self.path.len() {
-                // matcher lenght reached => fallback
-                continue;
-            }
+    fn match_path(&self, path: &Path, kind: ParsedKind) -> bool {
+        self.matches_elements(path.get_path(), kind)
+    }
 
-            // match indexes
-            match self.path[spath_idx] {
-                SimplePathElement::WildCardAny => {
-                    indexes.push((spath_idx + 1, path_idx)); // wildcard over
-                    if path_idx < path.len() {
-                        indexes.push((spath_idx, path_idx + 1)); // wildcard matched
-                    }
-                }
-                _ => {
-                    if path_idx >= path.len() {
-                        continue;
-                    } else if self.path[spath_idx] == path[path_idx] {
-                        indexes.push((spath_idx + 1, path_idx + 1));
-                    } else {
-                        continue;
-                    }
-                }
-            }
-        }
+    fn as_simple(&self) -> Option<&Simple> {
+        Some(self)
+    }
 
-        false
+    fn describe(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "simple({})", self.source)
     }
 }
 
@@ -197,6 +208,7 @@ impl FromStr for Simple {
     fn from_str(path: &str) -> Result<Self, Self::Err> {
         let mut state = SimpleMatcherStates::ElementStart;
         let mut buffer = vec![];
+        let mut excluded = vec![];
         let mut result = vec![];
 
         for chr in path.chars() {
@@ -212,6 +224,12 @@ impl FromStr for Simple {
                         result.push(SimplePathElement::WildCardAny);
                         SimpleMatcherStates::ElementStart
                     }
+                    '~' => {
+                        result.push(SimplePathElement::WildCardScalar);
+                        SimpleMatcherStates::ElementStart
+                    }
+                    // insignificant whitespace between elements
+                    chr if chr.is_whitespace() => SimpleMatcherStates::ElementStart,
                     _ => {
                         return Err(error::Matcher::Parse(path.to_string()));
                     }
@@ -232,7 +250,7 @@ impl FromStr for Simple {
                         result.push(new_element);
                         SimpleMatcherStates::ElementStart
                     }
-                    '0'..='9' | '-' | ',' => {
+                    '0'..='9' | '-' | ',' | ':' => {
                         buffer.push(chr);
                         SimpleMatcherStates::Array
                     }
@@ -246,6 +264,7 @@ impl FromStr for Simple {
                         SimpleMatcherStates::ElementStart
                     }
                     '"' => SimpleMatcherStates::Object(false),
+                    '!' => SimpleMatcherStates::ExceptStart,
                     _ => {
                         return Err(error::Matcher::Parse(path.to_string()));
                     }
@@ -274,10 +293,47 @@ impl FromStr for Simple {
                         return Err(error::Matcher::Parse(path.to_string()));
                     }
                 },
+                SimpleMatcherStates::ExceptStart => match chr {
+                    '"' => SimpleMatcherStates::Except(false),
+                    _ => {
+                        return Err(error::Matcher::Parse(path.to_string()));
+                    }
+                },
+                SimpleMatcherStates::Except(false) => match chr {
+                    '"' => {
+                        excluded.push(buffer.drain(..).collect());
+                        SimpleMatcherStates::ExceptEnd
+                    }
+                    '\\' => {
+                        buffer.push(chr);
+                        SimpleMatcherStates::Except(true)
+                    }
+                    _ => {
+                        buffer.push(chr);
+                        SimpleMatcherStates::Except(false)
+                    }
+                },
+                SimpleMatcherStates::Except(true) => {
+                    buffer.push(chr);
+                    SimpleMatcherStates::Except(false)
+                }
+                SimpleMatcherStates::ExceptEnd => match chr {
+                    ',' => SimpleMatcherStates::ExceptStart,
+                    '}' => {
+                        result.push(SimplePathElement::KeyExcept(mem::take(&mut excluded)));
+                        SimpleMatcherStates::ElementStart
+                    }
+                    _ => {
+                        return Err(error::Matcher::Parse(path.to_string()));
+                    }
+                },
             }
         }
         if state == SimpleMatcherStates::ElementStart {
-            Ok(Self { path: result })
+            Ok(Self {
+                path: result,
+                source: path.to_string(),
+            })
         } else {
             Err(error::Matcher::Parse(path.to_string()))
         }
@@ -292,12 +348,98 @@ impl Simple {
     pub fn new(path_expr: &str) -> Result<Self, error::Matcher> {
         Self::from_str(path_expr)
     }
+
+    /// Returns parsed elements of the path expression
+    pub(super) fn elements(&self) -> &[SimplePathElement] {
+        &self.path
+    }
+
+    /// Checks whether path elements match
+    ///
+    /// It is the same as `match_path`, but it can be used
+    /// without constructing a `Path`.
+    ///
+    /// # Arguments
+    /// * `path` - path elements to be matched
+    /// * `kind` - what kind of data are matched (needed for `~`)
+    ///
+    /// # Example
+    /// ```
+    /// use streamson_lib::{matcher::Simple, path::Element, streamer::ParsedKind};
+    ///
+    /// let simple = Simple::new(r#"{"users"}[]"#).unwrap();
+    /// let elements = vec![Element::key("users"), Element::index(1)];
+    /// assert!(simple.matches_elements(&elements, ParsedKind::Obj));
+    /// ```
+    pub fn matches_elements(&self, path: &[Element], kind: ParsedKind) -> bool {
+        // If no AnyWildcard present and length differs
+        // return false right away
+        if !self
+            .path
+            .iter()
+            .any(|e| matches!(e, SimplePathElement::WildCardAny))
+            && path.len() != self.path.len()
+        {
+            return false;
+        }
+
+        // first is element idx, second path index
+        // starting at the beginning
+        let mut indexes = vec![(0, 0)];
+
+        while !indexes.is_empty() {
+            let (spath_idx, path_idx) = indexes.pop().unwrap();
+
+            if spath_idx == self.path.len() && path_idx == path.len() {
+                // all matched
+                return true;
+            }
+
+            if spath_idx >= self.path.len() {
+                // matcher lenght reached => fallback
+                continue;
+            }
+
+            // match indexes
+            match self.path[spath_idx] {
+                SimplePathElement::WildCardAny => {
+                    indexes.push((spath_idx + 1, path_idx)); // wildcard over
+                    if path_idx < path.len() {
+                        indexes.push((spath_idx, path_idx + 1)); // wildcard matched
+                    }
+                }
+                SimplePathElement::WildCardScalar => {
+                    // scalar can be only the last element of the path
+                    if path_idx + 1 == path.len()
+                        && !matches!(kind, ParsedKind::Obj | ParsedKind::Arr)
+                    {
+                        indexes.push((spath_idx + 1, path_idx + 1));
+                    }
+                }
+                _ => {
+                    if path_idx >= path.len() {
+                        continue;
+                    } else if self.path[spath_idx] == path[path_idx] {
+                        indexes.push((spath_idx + 1, path_idx + 1));
+                    } else {
+                        continue;
+                    }
+                }
+            }
+        }
+
+        false
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Matcher, Simple};
-    use crate::{path::Path, streamer::ParsedKind};
+    use super::{IndexMatch, Matcher, Simple, SimplePathElement};
+    use crate::{
+        path::Path,
+        strategy::{Extract, OutputConverter, Strategy},
+        streamer::ParsedKind,
+    };
     use std::{convert::TryFrom, str::FromStr};
 
     #[test]
@@ -396,6 +538,29 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn slice_array() {
+        let matched = |spec: &str| -> Vec<usize> {
+            let simple = Simple::from_str(spec).unwrap();
+            (0..15)
+                .filter(|idx| {
+                    simple.match_path(
+                        &Path::try_from(format!("[{}]", idx).as_str()).unwrap(),
+                        ParsedKind::Num,
+                    )
+                })
+                .collect()
+        };
+
+        assert_eq!(matched("[::2]"), vec![0, 2, 4, 6, 8, 10, 12, 14]);
+        assert_eq!(matched("[1:10:2]"), vec![1, 3, 5, 7, 9]);
+        assert_eq!(matched("[10::3]"), vec![10, 13]);
+        assert_eq!(matched("[:3]"), vec![0, 1, 2]);
+        assert_eq!(matched("[12:]"), vec![12, 13, 14]);
+        assert_eq!(matched("[:]").len(), 15);
+        assert_eq!(matched("[1:4:2,10,13-]"), vec![1, 3, 10, 13, 14]);
+    }
+
     #[test]
     fn wild_object() {
         let simple = Simple::from_str(r#"{"People"}[0]{}"#).unwrap();
@@ -461,6 +626,31 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn except_object() {
+        let simple = Simple::from_str(r#"{"users"}[]{!"password","se\"cret"}"#).unwrap();
+        assert!(simple.match_path(
+            &Path::try_from(r#"{"users"}[0]{"name"}"#).unwrap(),
+            ParsedKind::Str
+        ));
+        assert!(!simple.match_path(
+            &Path::try_from(r#"{"users"}[0]{"password"}"#).unwrap(),
+            ParsedKind::Str
+        ));
+        assert!(!simple.match_path(
+            &Path::try_from(r#"{"users"}[0]{"se\"cret"}"#).unwrap(),
+            ParsedKind::Str
+        ));
+        assert!(simple.match_path(
+            &Path::try_from(r#"{"users"}[0]{"secret"}"#).unwrap(),
+            ParsedKind::Str
+        ));
+        assert!(!simple.match_path(
+            &Path::try_from(r#"{"users"}[0][1]"#).unwrap(),
+            ParsedKind::Str
+        ));
+    }
+
     #[test]
     fn parse() {
         assert!(Simple::from_str(r#""#).is_ok());
@@ -477,6 +667,14 @@ mod tests {
         assert!(Simple::from_str(r#"*"#).is_ok());
         assert!(Simple::from_str(r#"****"#).is_ok());
         assert!(Simple::from_str(r#"*{}[1]**{"xx"}*"#).is_ok());
+        assert!(Simple::from_str(r#"~"#).is_ok());
+        assert!(Simple::from_str(r#"*{"xx"}[]~"#).is_ok());
+        assert!(Simple::from_str(r#"[::2]"#).is_ok());
+        assert!(Simple::from_str(r#"[1:10:2]"#).is_ok());
+        assert!(Simple::from_str(r#"[1:10]"#).is_ok());
+        assert!(Simple::from_str(r#"[:]"#).is_ok());
+        assert!(Simple::from_str(r#"{!"a"}"#).is_ok());
+        assert!(Simple::from_str(r#"{!"a","b",""}[]"#).is_ok());
     }
 
     #[test]
@@ -489,6 +687,70 @@ mod tests {
         assert!(Simple::from_str(r#"[3-3]"#).is_err());
         assert!(Simple::from_str(r#"[,2,8]"#).is_err());
         assert!(Simple::from_str(r#"[2,8,]"#).is_err());
+        assert!(Simple::from_str(r#"[~]"#).is_err());
+        assert!(Simple::from_str(r#"{~}"#).is_err());
+        assert!(Simple::from_str(r#"[::0]"#).is_err());
+        assert!(Simple::from_str(r#"[5:1]"#).is_err());
+        assert!(Simple::from_str(r#"[1:2:3:4]"#).is_err());
+        assert!(Simple::from_str(r#"[1-2:3]"#).is_err());
+        assert!(Simple::from_str(r#"[:x]"#).is_err());
+        assert!(Simple::from_str(r#"{!}"#).is_err());
+        assert!(Simple::from_str(r#"{!"a",}"#).is_err());
+        assert!(Simple::from_str(r#"{!"a""#).is_err());
+        assert!(Simple::from_str(r#"{!"a"!"b"}"#).is_err());
+    }
+
+    #[test]
+    fn parse_whitespace() {
+        let simple = Simple::from_str(" {\"a\"} [0]\n\t{} ? * ~ ").unwrap();
+        assert_eq!(
+            simple.path,
+            Simple::from_str(r#"{"a"}[0]{}?*~"#).unwrap().path
+        );
+
+        // whitespace within keys is preserved
+        let simple = Simple::from_str(r#"{" a b "} []"#).unwrap();
+        assert!(simple.match_path(
+            &Path::try_from(r#"{" a b "}[1]"#).unwrap(),
+            ParsedKind::Null
+        ));
+        assert!(!simple.match_path(&Path::try_from(r#"{"ab"}[1]"#).unwrap(), ParsedKind::Null));
+
+        // whitespace within elements is not allowed
+        assert!(Simple::from_str(r#"{ "a"}"#).is_err());
+        assert!(Simple::from_str(r#"{"a" }"#).is_err());
+        assert!(Simple::from_str(r#"[1, 2]"#).is_err());
+    }
+
+    #[test]
+    fn empty_key() {
+        let empty = Simple::from_str(r#"{""}[]"#).unwrap();
+        let any = Simple::from_str(r#"{}[]"#).unwrap();
+        assert_ne!(empty.path, any.path);
+        assert_eq!(
+            empty.path,
+            vec![
+                SimplePathElement::Key(Some(String::new())),
+                SimplePathElement::Index(IndexMatch(vec![]))
+            ]
+        );
+
+        let empty_path = Path::try_from(r#"{""}[0]"#).unwrap();
+        let key_path = Path::try_from(r#"{"a"}[0]"#).unwrap();
+        assert!(empty.match_path(&empty_path, ParsedKind::Num));
+        assert!(!empty.match_path(&key_path, ParsedKind::Num));
+        assert!(any.match_path(&empty_path, ParsedKind::Num));
+        assert!(any.match_path(&key_path, ParsedKind::Num));
+
+        // empty key within streamed data
+        let mut extract = Extract::new();
+        extract.add_matcher(Box::new(Simple::from_str(r#"{""}"#).unwrap()), None);
+        let output: Vec<Vec<u8>> = OutputConverter::new()
+            .convert(&extract.process(br#"{"a": 1, "": 2, " ": 3}"#).unwrap())
+            .into_iter()
+            .map(|e| e.1)
+            .collect();
+        assert_eq!(output, vec![b"2".to_vec()]);
     }
 
     #[test]
@@ -545,4 +807,83 @@ mod tests {
         assert!(!simple.match_path(&Path::try_from(r#"[1]{"range"}"#).unwrap(), ParsedKind::Obj));
         assert!(!simple.match_path(&Path::try_from(r#"[0]{"other"}"#).unwrap(), ParsedKind::Obj));
     }
+
+    #[test]
+    fn scalar_wild() {
+        let simple = Simple::from_str(r#"{"People"}[]~"#).unwrap();
+
+        assert!(simple.match_path(
+            &Path::try_from(r#"{"People"}[0]{"Height"}"#).unwrap(),
+            ParsedKind::Num
+        ));
+        assert!(simple.match_path(
+            &Path::try_from(r#"{"People"}[1][2]"#).unwrap(),
+            ParsedKind::Str
+        ));
+        assert!(simple.match_path(
+            &Path::try_from(r#"{"People"}[1]{"Dead"}"#).unwrap(),
+            ParsedKind::Bool
+        ));
+        assert!(simple.match_path(
+            &Path::try_from(r#"{"People"}[1]{"Spouse"}"#).unwrap(),
+            ParsedKind::Null
+        ));
+        assert!(!simple.match_path(
+            &Path::try_from(r#"{"People"}[0]{"Address"}"#).unwrap(),
+            ParsedKind::Obj
+        ));
+        assert!(!simple.match_path(
+            &Path::try_from(r#"{"People"}[0]{"Children"}"#).unwrap(),
+            ParsedKind::Arr
+        ));
+        assert!(!simple.match_path(
+            &Path::try_from(r#"{"People"}[0]"#).unwrap(),
+            ParsedKind::Num
+        ));
+
+        // only the last element can be a scalar
+        let simple = Simple::from_str(r#"~*"#).unwrap();
+        assert!(simple.match_path(&Path::try_from(r#"[0]"#).unwrap(), ParsedKind::Num));
+        assert!(!simple.match_path(&Path::try_from(r#"[0][1]"#).unwrap(), ParsedKind::Num));
+
+        let simple = Simple::from_str(r#"*~"#).unwrap();
+        assert!(simple.match_path(&Path::try_from(r#"[0]"#).unwrap(), ParsedKind::Null));
+        assert!(simple.match_path(&Path::try_from(r#"[0]{"a"}[2]"#).unwrap(), ParsedKind::Str));
+        assert!(!simple.match_path(&Path::try_from(r#"[0]{"a"}"#).unwrap(), ParsedKind::Arr));
+        assert!(!simple.match_path(&Path::try_from(r#""#).unwrap(), ParsedKind::Num));
+    }
+
+    #[test]
+    fn matches_elements() {
+        let patterns = [
+            r#"{"People"}[0]{"Height"}"#,
+            r#"{"People"}[]{}"#,
+            r#"{"People"}[1-2]"#,
+            r#"{"People"}*"#,
+            r#"?[0]"#,
+            r#"{"People"}[]~"#,
+            r#""#,
+        ];
+        let paths = [
+            (r#""#, ParsedKind::Obj),
+            (r#"{"People"}"#, ParsedKind::Arr),
+            (r#"{"People"}[0]"#, ParsedKind::Obj),
+            (r#"{"People"}[1]"#, ParsedKind::Num),
+            (r#"{"People"}[0]{"Height"}"#, ParsedKind::Num),
+            (r#"{"Others"}[0]{"Height"}"#, ParsedKind::Num),
+        ];
+        for pattern in &patterns {
+            let simple = Simple::from_str(pattern).unwrap();
+            for (path, kind) in &paths {
+                let path = Path::try_from(*path).unwrap();
+                assert_eq!(
+                    simple.matches_elements(path.get_path(), *kind),
+                    simple.match_path(&path, *kind),
+                    "{} {}",
+                    pattern,
+                    path
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,368 @@
+//! Value matcher
+//!
+//! It matches leaf elements (strings, numbers, booleans and nulls)
+//! based on their path and their content.
+//! The decision is made at the end of the element, so
+//! strategies have to defer it (see `Matcher::needs_value`).
+//!
+//! The matcher can be parsed from `<simple path><operator><value>` expression:
+//! * `{"status"}=="deleted"` - string equality (escapes are decoded)
+//! * `{"status"}!="active"` - string inequality
+//! * `{"deleted"}==true` - literals (`true`, `false` and `null`) and numbers can be compared too
+//! * `[]{"price"}>=100` - numeric comparison (`<`, `<=`, `>` and `>=`)
+//! * `{"email"}=~@example\.com$` - regex match of a string (requires `with_regex` feature)
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{matcher, strategy::{self, Strategy}};
+//! use std::str::FromStr;
+//!
+//! let matcher = matcher::Value::from_str(r#"{"users"}[]{"status"}=="deleted""#).unwrap();
+//!
+//! let mut extract = strategy::Extract::new();
+//! extract.add_matcher(Box::new(matcher), None);
+//!
+//! let output = extract.process(
+//!     br#"{"users": [{"status": "active"}, {"status": "deleted"}]}"#
+//! ).unwrap();
+//! ```
+
+use std::{
+    fmt,
+    str::{self, FromStr},
+};
+
+use super::{number_compare::Op, Matcher, Simple};
+use crate::{
+    error,
+    path::Path,
+    streamer::{unescape, ParsedKind},
+};
+
+/// Scalar value which is compared with the content of the element
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scalar {
+    /// Decoded string
+    Str(String),
+    /// Number
+    Number(f64),
+    /// `true` or `false`
+    Bool(bool),
+    /// `null`
+    Null,
+}
+
+impl Scalar {
+    /// Parses the raw JSON data of a leaf element
+    ///
+    /// # Arguments
+    /// * `kind` - kind of the element
+    /// * `value` - raw data (e.g. `"str"` or `-1.5`)
+    fn parse(kind: ParsedKind, value: &[u8]) -> Option<Self> {
+        match kind {
+            ParsedKind::Str if value.len() >= 2 => {
+                unescape(&value[1..value.len() - 1]).map(Self::Str)
+            }
+            ParsedKind::Num | ParsedKind::Int | ParsedKind::Float => str::from_utf8(value)
+                .ok()
+                .and_then(|e| e.parse().ok())
+                .map(Self::Number),
+            ParsedKind::Bool => Some(Self::Bool(value == b"true")),
+            ParsedKind::Null => Some(Self::Null),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Scalar {
+    type Err = error::Matcher;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s {
+            "true" => Ok(Self::Bool(true)),
+            "false" => Ok(Self::Bool(false)),
+            "null" => Ok(Self::Null),
+            _ if s.len() >= 2 && s.starts_with('"') && s.ends_with('"') => {
+                Self::parse(ParsedKind::Str, s.as_bytes()).ok_or_else(|| Self::Err::Parse(s.into()))
+            }
+            _ => s
+                .parse()
+                .map(Self::Number)
+                .map_err(|_| Self::Err::Parse(s.into())),
+        }
+    }
+}
+
+impl fmt::Display for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Str(string) => write!(f, "{:?}", string),
+            Self::Number(number) => write!(f, "{}", number),
+            Self::Bool(boolean) => write!(f, "{}", boolean),
+            Self::Null => write!(f, "null"),
+        }
+    }
+}
+
+/// Condition which the content of the element has to satisfy
+#[derive(Debug, Clone)]
+pub enum Condition {
+    /// Content is equal to the scalar
+    Eq(Scalar),
+    /// Content differs from the scalar
+    Ne(Scalar),
+    /// Content is a number and the comparison is true
+    /// (`content <op> number`)
+    Compare(Op, f64),
+    /// Content is a string and its decoded value matches the regex
+    #[cfg(feature = "with_regex")]
+    Regex(regex::Regex),
+}
+
+impl Condition {
+    /// Checks whether the raw data of a leaf element satisfy the condition
+    fn check(&self, kind: ParsedKind, value: &[u8]) -> bool {
+        let parsed = match Scalar::parse(kind, value) {
+            Some(parsed) => parsed,
+            None => return false,
+        };
+        match self {
+            Self::Eq(scalar) => scalar_eq(scalar, &parsed),
+            Self::Ne(scalar) => !scalar_eq(scalar, &parsed),
+            Self::Compare(op, number) => match parsed {
+                Scalar::Number(parsed) => op.compare(parsed, *number),
+                _ => false,
+            },
+            #[cfg(feature = "with_regex")]
+            Self::Regex(regex) => match parsed {
+                Scalar::Str(parsed) => regex.is_match(&parsed),
+                _ => false,
+            },
+        }
+    }
+}
+
+/// Compares scalars (numbers are compared by value)
+fn scalar_eq(left: &Scalar, right: &Scalar) -> bool {
+    match (left, right) {
+        (Scalar::Number(left), Scalar::Number(right)) => Op::Eq.compare(*left, *right),
+        (left, right) => left == right,
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Eq(scalar) => write!(f, "=={}", scalar),
+            Self::Ne(scalar) => write!(f, "!={}", scalar),
+            Self::Compare(op, number) => write!(f, "{}{}", op.as_ref(), number),
+            #[cfg(feature = "with_regex")]
+            Self::Regex(regex) => write!(f, "=~{}", regex.as_str()),
+        }
+    }
+}
+
+/// Matches leaf elements based on their path and content
+#[derive(Debug, Clone)]
+pub struct Value {
+    /// Path of the element
+    path: Simple,
+    /// Condition for the content
+    condition: Condition,
+}
+
+impl Value {
+    /// Creates new value matcher
+    ///
+    /// # Arguments
+    /// * `path` - path of the matched elements
+    /// * `condition` - condition which the content has to satisfy
+    pub fn new(path: Simple, condition: Condition) -> Self {
+        Self { path, condition }
+    }
+}
+
+impl Matcher for Value {
+    fn match_path(&self, _path: &Path, _kind: ParsedKind) -> bool {
+        // can't be decided without the value
+        false
+    }
+
+    fn needs_value(&self) -> bool {
+        true
+    }
+
+    fn match_value(&self, path: &Path, kind: ParsedKind, value: &[u8]) -> bool {
+        self.path.match_path(path, kind) && self.condition.check(kind, value)
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "value(")?;
+        self.path.describe(f)?;
+        write!(f, "{})", self.condition)
+    }
+}
+
+/// Returns the position where the operator starts
+///
+/// Operators can't be a part of simple path outside of keys
+/// (except for `!` of excluded keys e.g. `{!"a"}`).
+fn operator_position(s: &str) -> Option<usize> {
+    let mut in_key = false;
+    let mut escaped = false;
+    let mut previous = None;
+    for (idx, chr) in s.char_indices() {
+        if in_key {
+            if escaped {
+                escaped = false;
+            } else if chr == '\\' {
+                escaped = true;
+            } else if chr == '"' {
+                in_key = false;
+            }
+        } else if chr == '"' {
+            in_key = true;
+        } else if matches!(chr, '=' | '!' | '<' | '>') && !(chr == '!' && previous == Some('{')) {
+            return Some(idx);
+        }
+        previous = Some(chr);
+    }
+    None
+}
+
+impl FromStr for Value {
+    type Err = error::Matcher;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let position = operator_position(s).ok_or_else(|| Self::Err::Parse(s.into()))?;
+        let path = Simple::from_str(&s[..position])?;
+        let expression = &s[position..];
+        let condition = if let Some(scalar) = expression.strip_prefix("==") {
+            Condition::Eq(scalar.parse()?)
+        } else if let Some(scalar) = expression.strip_prefix("!=") {
+            Condition::Ne(scalar.parse()?)
+        } else if let Some(pattern) = expression.strip_prefix("=~") {
+            #[cfg(feature = "with_regex")]
+            {
+                Condition::Regex(
+                    regex::Regex::new(pattern).map_err(|e| Self::Err::Parse(e.to_string()))?,
+                )
+            }
+            #[cfg(not(feature = "with_regex"))]
+            {
+                return Err(Self::Err::Parse(pattern.into()));
+            }
+        } else {
+            let op_len = expression
+                .find(|c| !matches!(c, '<' | '>' | '='))
+                .unwrap_or(expression.len());
+            let op = Op::from_str(&expression[..op_len])?;
+            let number = expression[op_len..]
+                .trim()
+                .parse()
+                .map_err(|_| Self::Err::Parse(s.into()))?;
+            Condition::Compare(op, number)
+        };
+        Ok(Self::new(path, condition))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Condition, Scalar, Value};
+    use crate::{
+        matcher::{number_compare::Op, Matcher, Simple},
+        path::Path,
+        strategy::{Filter, OutputConverter, Strategy},
+        streamer::ParsedKind,
+    };
+    use std::{convert::TryFrom, str::FromStr};
+
+    #[test]
+    fn match_value() {
+        let path = Path::try_from(r#"{"status"}"#).unwrap();
+        let matcher = Value::from_str(r#"{"status"}=="del\"eted""#).unwrap();
+        assert!(matcher.needs_value());
+        assert!(!matcher.match_path(&path, ParsedKind::Str));
+        assert!(matcher.match_value(&path, ParsedKind::Str, br#""del\"eted""#));
+        assert!(matcher.match_value(&path, ParsedKind::Str, br#""del"eted""#));
+        assert!(!matcher.match_value(&path, ParsedKind::Str, br#""deleted""#));
+        assert!(!matcher.match_value(
+            &Path::try_from(r#"{"other"}"#).unwrap(),
+            ParsedKind::Str,
+            br#""del\"eted""#
+        ));
+
+        let matcher = Value::from_str(r#"{"status"}!="active""#).unwrap();
+        assert!(matcher.match_value(&path, ParsedKind::Str, br#""deleted""#));
+        assert!(matcher.match_value(&path, ParsedKind::Null, b"null"));
+        assert!(!matcher.match_value(&path, ParsedKind::Str, br#""active""#));
+
+        let matcher = Value::from_str(r#"{"status"}==1"#).unwrap();
+        assert!(matcher.match_value(&path, ParsedKind::Num, b"1.0"));
+        assert!(!matcher.match_value(&path, ParsedKind::Str, br#""1""#));
+
+        let matcher = Value::from_str(r#"{"status"}==null"#).unwrap();
+        assert!(matcher.match_value(&path, ParsedKind::Null, b"null"));
+        assert!(!matcher.match_value(&path, ParsedKind::Bool, b"false"));
+
+        let matcher = Value::from_str(r#"{"status"}>=100"#).unwrap();
+        assert!(matcher.match_value(&path, ParsedKind::Num, b"100"));
+        assert!(!matcher.match_value(&path, ParsedKind::Num, b"99.9"));
+        assert!(!matcher.match_value(&path, ParsedKind::Str, br#""200""#));
+    }
+
+    #[cfg(feature = "with_regex")]
+    #[test]
+    fn match_regex() {
+        let path = Path::try_from(r#"{"email"}"#).unwrap();
+        let matcher = Value::from_str(r#"{"email"}=~@example\.com$"#).unwrap();
+        assert!(matcher.match_value(&path, ParsedKind::Str, br#""a@example.com""#));
+        assert!(!matcher.match_value(&path, ParsedKind::Str, br#""a@example.org""#));
+    }
+
+    #[test]
+    fn parse() {
+        let matcher = Value::from_str(r#"{"a=b"}[]{"c"}<= -1.5"#).unwrap();
+        assert!(matches!(matcher.condition, Condition::Compare(Op::Le, n) if n == -1.5));
+        assert_eq!(
+            format!("{:?}", matcher.path),
+            format!("{:?}", Simple::new(r#"{"a=b"}[]{"c"}"#).unwrap())
+        );
+
+        let matcher = Value::from_str(r#"[]=="x""#).unwrap();
+        assert!(matches!(matcher.condition, Condition::Eq(Scalar::Str(ref s)) if s == "x"));
+        let matcher = Value::from_str(r#"{!"a"}!=true"#).unwrap();
+        assert!(matches!(
+            matcher.condition,
+            Condition::Ne(Scalar::Bool(true))
+        ));
+
+        assert!(Value::from_str(r#"{"a"}"#).is_err());
+        assert!(Value::from_str(r#"{"a"}==x"#).is_err());
+        assert!(Value::from_str(r#"{"a"}>"x""#).is_err());
+        assert!(Value::from_str(r#"{"a"}=<1"#).is_err());
+        assert!(Value::from_str(r#"{"a"==1"#).is_err());
+    }
+
+    #[test]
+    fn filter_deleted() {
+        let mut filter = Filter::new();
+        filter.add_matcher(
+            Box::new(Value::from_str(r#"[]{"status"}=="deleted""#).unwrap()),
+            None,
+        );
+        let output = OutputConverter::new()
+            .convert(
+                &filter
+                    .process(br#"[{"id": 1, "status": "deleted"}, {"id": 2, "status": "active"}]"#)
+                    .unwrap(),
+            )
+            .into_iter()
+            .flat_map(|e| e.1)
+            .collect::<Vec<u8>>();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"[{"id": 1}, {"id": 2, "status": "active"}]"#
+        );
+    }
+}
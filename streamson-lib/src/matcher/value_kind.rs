@@ -0,0 +1,168 @@
+//! Value kind matcher
+//!
+//! It matches paths based on the kind of their values
+//! (e.g. only keys which contain arrays).
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{matcher, strategy::{self, Strategy}, streamer::ParsedKind};
+//!
+//! // only arrays stored in the users
+//! let matcher = matcher::Combinator::new(matcher::Simple::new(r#"{"users"}[]{}"#).unwrap())
+//!     & matcher::Combinator::new(matcher::ValueKind::new(ParsedKind::Arr));
+//!
+//! let mut extract = strategy::Extract::new();
+//! extract.add_matcher(Box::new(matcher), None);
+//!
+//! let output = extract.process(
+//!     br#"{"users": [{"name": "first", "groups": ["admin", "user"]}]}"#
+//! ).unwrap();
+//! ```
+
+use super::Matcher;
+use crate::{path::Path, streamer::ParsedKind};
+use std::fmt;
+
+/// Matches values of the given kind
+///
+/// `ParsedKind::Num` matches all numbers. The exact kind of a number
+/// (`ParsedKind::Int` or `ParsedKind::Float`) is known only at its end,
+/// so the decision is deferred in that case (see `Matcher::needs_value`).
+#[derive(Debug, Clone)]
+pub struct ValueKind {
+    kind: ParsedKind,
+}
+
+impl ValueKind {
+    /// Creates new value kind matcher
+    ///
+    /// # Arguments
+    /// * `kind` - kind of the values which will be matched
+    pub fn new(kind: ParsedKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl Matcher for ValueKind {
+    fn match_path(&self, _path: &Path, kind: ParsedKind) -> bool {
+        self.kind == kind
+    }
+
+    fn needs_value(&self) -> bool {
+        matches!(self.kind, ParsedKind::Int | ParsedKind::Float)
+    }
+
+    fn match_value(&self, _path: &Path, kind: ParsedKind, _value: &[u8]) -> bool {
+        match self.kind {
+            ParsedKind::Num => {
+                matches!(kind, ParsedKind::Num | ParsedKind::Int | ParsedKind::Float)
+            }
+            expected => expected == kind,
+        }
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ParsedKind::Int => write!(f, "kind(integer)"),
+            ParsedKind::Float => write!(f, "kind(float)"),
+            kind => write!(f, "kind({})", kind.as_ref()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Matcher, ValueKind};
+    use crate::{
+        matcher::{Combinator, Simple},
+        path::Path,
+        strategy::{Extract, OutputConverter, Strategy},
+        streamer::ParsedKind,
+    };
+    use std::convert::TryFrom;
+
+    fn extract(matcher: Combinator, input: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let mut extract = Extract::new().set_export_path(true);
+        extract.add_matcher(Box::new(matcher), None);
+        let mut output = vec![];
+        for part in input.chunks(5) {
+            output.extend(extract.process(part).unwrap());
+        }
+        OutputConverter::new()
+            .convert(&output)
+            .into_iter()
+            .map(|(path, data)| (path.unwrap().to_string(), data))
+            .collect()
+    }
+
+    #[test]
+    fn match_path() {
+        let path = Path::try_from(r#"{"a"}"#).unwrap();
+        let matcher = ValueKind::new(ParsedKind::Arr);
+        assert!(!matcher.needs_value());
+        assert!(matcher.match_path(&path, ParsedKind::Arr));
+        assert!(!matcher.match_path(&path, ParsedKind::Obj));
+        assert!(!matcher.match_path(&path, ParsedKind::Str));
+    }
+
+    #[test]
+    fn only_arrays() {
+        let matcher = Combinator::new(Simple::new(r#"{}"#).unwrap())
+            & Combinator::new(ValueKind::new(ParsedKind::Arr));
+        let output = extract(
+            matcher,
+            br#"{"a": [1, [2]], "b": {"c": []}, "d": "[]", "e": [], "f": null}"#,
+        );
+        assert_eq!(
+            output,
+            vec![
+                (r#"{"a"}"#.to_string(), b"[1, [2]]".to_vec()),
+                (r#"{"e"}"#.to_string(), b"[]".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn match_value() {
+        let path = Path::try_from(r#"[0]"#).unwrap();
+        let matcher = ValueKind::new(ParsedKind::Int);
+        assert!(matcher.needs_value());
+        assert!(matcher.match_value(&path, ParsedKind::Int, b"1"));
+        assert!(!matcher.match_value(&path, ParsedKind::Float, b"1.0"));
+        assert!(!matcher.match_value(&path, ParsedKind::Str, br#""1""#));
+
+        let matcher = ValueKind::new(ParsedKind::Num);
+        assert!(!matcher.needs_value());
+        assert!(matcher.match_value(&path, ParsedKind::Float, b"1.0"));
+        assert!(!matcher.match_value(&path, ParsedKind::Null, b"null"));
+    }
+
+    #[test]
+    fn numbers() {
+        let input = br#"[1, 2.5, "3", -4e2, 5]"#;
+        let values = |kind| -> Vec<Vec<u8>> {
+            extract(
+                Combinator::new(Simple::new(r#"[]"#).unwrap())
+                    & Combinator::new(ValueKind::new(kind)),
+                input,
+            )
+            .into_iter()
+            .map(|e| e.1)
+            .collect()
+        };
+        assert_eq!(
+            values(ParsedKind::Num),
+            vec![
+                b"1".to_vec(),
+                b"2.5".to_vec(),
+                b"-4e2".to_vec(),
+                b"5".to_vec()
+            ]
+        );
+        assert_eq!(values(ParsedKind::Int), vec![b"1".to_vec(), b"5".to_vec()]);
+        assert_eq!(
+            values(ParsedKind::Float),
+            vec![b"2.5".to_vec(), b"-4e2".to_vec()]
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Value regex matcher
+//!
+//! It matches strings based on their decoded (unquoted) value.
+//! The decision is made at the end of the string, so
+//! strategies have to defer it (see `Matcher::needs_value`).
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{matcher, strategy::{self, Strategy}};
+//! use std::str::FromStr;
+//!
+//! // only error messages
+//! let matcher = matcher::Combinator::new(matcher::Simple::new(r#"{"logs"}[]{"message"}"#).unwrap())
+//!     & matcher::Combinator::new(matcher::ValueRegex::from_str("ERROR|FATAL").unwrap());
+//!
+//! let mut extract = strategy::Extract::new();
+//! extract.add_matcher(Box::new(matcher), None);
+//!
+//! let output = extract.process(
+//!     br#"{"logs": [{"message": "INFO started"}, {"message": "ERROR failed"}]}"#
+//! ).unwrap();
+//! ```
+
+use regex::{self, Error as RegexError};
+use std::{fmt, str::FromStr};
+
+use super::Matcher;
+use crate::{
+    error,
+    path::Path,
+    streamer::{unescape, ParsedKind},
+};
+
+/// Matches strings whose decoded value matches the regex
+#[derive(Debug, Clone)]
+pub struct ValueRegex {
+    regex: regex::Regex,
+}
+
+impl ValueRegex {
+    /// Creates new value regex matcher
+    ///
+    /// # Arguments
+    /// * `rgx` - regex structure
+    pub fn new(rgx: regex::Regex) -> Self {
+        Self { regex: rgx }
+    }
+}
+
+impl Matcher for ValueRegex {
+    fn match_path(&self, _path: &Path, _kind: ParsedKind) -> bool {
+        // can't be decided without the value
+        false
+    }
+
+    fn needs_value(&self) -> bool {
+        true
+    }
+
+    fn match_value(&self, _path: &Path, kind: ParsedKind, value: &[u8]) -> bool {
+        if kind != ParsedKind::Str || value.len() < 2 {
+            return false;
+        }
+        unescape(&value[1..value.len() - 1])
+            .map(|decoded| self.regex.is_match(&decoded))
+            .unwrap_or(false)
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "value_regex({})", self.regex.as_str())
+    }
+}
+
+impl FromStr for ValueRegex {
+    type Err = error::Matcher;
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        let regex = regex::Regex::from_str(pattern)
+            .map_err(|e: RegexError| Self::Err::Parse(e.to_string()))?;
+        Ok(Self::new(regex))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Matcher, ValueRegex};
+    use crate::{
+        matcher::{Combinator, Simple},
+        path::Path,
+        strategy::{Extract, OutputConverter, Strategy},
+        streamer::ParsedKind,
+    };
+    use std::{convert::TryFrom, str::FromStr};
+
+    #[test]
+    fn match_value() {
+        let path = Path::try_from(r#"[0]"#).unwrap();
+        let matcher = ValueRegex::from_str("^ERROR").unwrap();
+        assert!(matcher.needs_value());
+        assert!(!matcher.match_path(&path, ParsedKind::Str));
+        assert!(matcher.match_value(&path, ParsedKind::Str, br#""ERROR: failed""#));
+        assert!(!matcher.match_value(&path, ParsedKind::Str, br#"" ERROR""#));
+        assert!(!matcher.match_value(&path, ParsedKind::Num, b"1"));
+
+        // escapes are decoded
+        let matcher = ValueRegex::from_str(r#"^"a"\\\n€$"#).unwrap();
+        assert!(matcher.match_value(&path, ParsedKind::Str, r#""\"a\"\\\n\u20ac""#.as_bytes()));
+        let matcher = ValueRegex::from_str("^\u{1F600}$").unwrap();
+        assert!(matcher.match_value(&path, ParsedKind::Str, r#""\ud83d\ude00""#.as_bytes()));
+        assert!(!matcher.match_value(&path, ParsedKind::Str, br#""\ud83d""#));
+    }
+
+    #[test]
+    fn parse() {
+        assert!(ValueRegex::from_str("ERROR|FATAL").is_ok());
+        assert!(ValueRegex::from_str("(").is_err());
+    }
+
+    #[test]
+    fn extract_matching_messages() {
+        let matcher = Combinator::new(Simple::new(r#"{"logs"}[]{"message"}"#).unwrap())
+            & Combinator::new(ValueRegex::from_str("ERROR|FATAL").unwrap());
+        let mut extract = Extract::new();
+        extract.add_matcher(Box::new(matcher), None);
+
+        let mut output = extract
+            .process(br#"{"logs": [{"message": "INFO started"}, {"message": "ERR"#)
+            .unwrap();
+        output.extend(
+            extract
+                .process(
+                    br#"OR failed", "id": "FATAL"}, {"message": 1}, {"message": "FATAL \"x\""}]}"#,
+                )
+                .unwrap(),
+        );
+
+        let output: Vec<Vec<u8>> = OutputConverter::new()
+            .convert(&output)
+            .into_iter()
+            .map(|e| e.1)
+            .collect();
+        assert_eq!(
+            output,
+            vec![br#""ERROR failed""#.to_vec(), br#""FATAL \"x\"""#.to_vec()]
+        );
+    }
+}
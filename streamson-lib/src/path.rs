@@ -1,6 +1,6 @@
 //! Structs to handle abstraction over a path in JSON
 
-use crate::error;
+use crate::{error, streamer::unescape};
 use std::{cmp::Ordering, convert::TryFrom, fmt, hash::Hash};
 
 /// An element of the path
@@ -11,6 +11,28 @@ pub enum Element {
 }
 
 impl Element {
+    /// Creates a key element
+    ///
+    /// Quotes and backslashes in the `key` are escaped
+    /// so the element is the same as when the path is parsed.
+    ///
+    /// # Arguments
+    /// * `key` - unescaped object key
+    pub fn key<T>(key: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Element::Key(key.as_ref().replace('\\', r#"\\"#).replace('"', r#"\""#))
+    }
+
+    /// Creates an index element
+    ///
+    /// # Arguments
+    /// * `idx` - array index
+    pub fn index(idx: usize) -> Self {
+        Element::Index(idx)
+    }
+
     pub fn is_key(&self) -> bool {
         matches!(self, Element::Key(_))
     }
@@ -68,6 +90,35 @@ impl Path {
         self.path.push(element);
     }
 
+    /// Appends object key (builder pattern)
+    ///
+    /// # Arguments
+    /// * `key` - unescaped object key
+    ///
+    /// # Example
+    /// ```
+    /// use streamson_lib::path::Path;
+    ///
+    /// let path = Path::new().push_key("users").push_index(0).push_key("name");
+    /// assert_eq!(path.to_string(), r#"{"users"}[0]{"name"}"#);
+    /// ```
+    pub fn push_key<T>(mut self, key: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        self.push(Element::key(key));
+        self
+    }
+
+    /// Appends array index (builder pattern)
+    ///
+    /// # Arguments
+    /// * `idx` - array index
+    pub fn push_index(mut self, idx: usize) -> Self {
+        self.push(Element::index(idx));
+        self
+    }
+
     /// Returns the path depth
     pub fn depth(&self) -> usize {
         self.path.len()
@@ -77,6 +128,56 @@ impl Path {
     pub fn get_path(&self) -> &[Element] {
         &self.path
     }
+
+    /// Formats the path as a JSON Pointer (RFC 6901)
+    ///
+    /// # Example
+    /// ```
+    /// use streamson_lib::path::Path;
+    ///
+    /// let path = Path::new().push_key("users").push_index(0).push_key("a/b");
+    /// assert_eq!(path.to_pointer(), "/users/0/a~1b");
+    /// ```
+    pub fn to_pointer(&self) -> String {
+        let mut res = String::new();
+        for element in &self.path {
+            res.push('/');
+            match element {
+                Element::Key(key) => {
+                    // keys are stored escaped
+                    let key = unescape(key.as_bytes()).unwrap_or_else(|| key.clone());
+                    res.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                }
+                Element::Index(idx) => res.push_str(&idx.to_string()),
+            }
+        }
+        res
+    }
+
+    /// Formats only the last elements of the path
+    ///
+    /// Omitted elements are replaced with `…`.
+    ///
+    /// # Arguments
+    /// * `max_elements` - max number of the displayed elements
+    ///
+    /// # Example
+    /// ```
+    /// use streamson_lib::path::Path;
+    ///
+    /// let path = Path::new().push_key("root").push_key("sub").push_key("subsub");
+    /// assert_eq!(path.truncate_display(2), r#"…{"sub"}{"subsub"}"#);
+    /// ```
+    pub fn truncate_display(&self, max_elements: usize) -> String {
+        if self.path.len() <= max_elements {
+            return self.to_string();
+        }
+        let mut res = String::from("…");
+        for element in &self.path[self.path.len() - max_elements..] {
+            res.push_str(&element.to_string());
+        }
+        res
+    }
 }
 
 /// Path parsing state
@@ -214,4 +315,47 @@ mod tests {
         path.push(Element::Key(r#"my-ke\\y\" "#.into()));
         assert_eq!(Path::try_from(r#"{"my-ke\\y\" "}"#).unwrap(), path);
     }
+
+    #[test]
+    fn test_path_builder() {
+        let path = Path::new().push_key("users").push_index(0).push_key("name");
+        assert_eq!(path, Path::try_from(r#"{"users"}[0]{"name"}"#).unwrap());
+        assert_eq!(path.to_string(), r#"{"users"}[0]{"name"}"#);
+        assert_eq!(path.depth(), 3);
+
+        let path = Path::new().push_key(r#"my-ke\y" "#).push_index(10);
+        assert_eq!(path, Path::try_from(r#"{"my-ke\\y\" "}[10]"#).unwrap());
+        assert_eq!(path.to_string(), r#"{"my-ke\\y\" "}[10]"#);
+
+        assert_eq!(Element::key("a"), Element::Key("a".into()));
+        assert_eq!(Element::index(3), Element::Index(3));
+        assert_eq!(Path::new().to_string(), "");
+    }
+
+    #[test]
+    fn test_to_pointer() {
+        assert_eq!(Path::new().to_pointer(), "");
+        assert_eq!(
+            Path::try_from(r#"{"users"}[0]{"name"}"#)
+                .unwrap()
+                .to_pointer(),
+            "/users/0/name"
+        );
+        assert_eq!(
+            Path::try_from(r#"{"a/b"}{"m~n"}{"q\"\\"}{""}[10]"#)
+                .unwrap()
+                .to_pointer(),
+            r#"/a~1b/m~0n/q"\//10"#
+        );
+    }
+
+    #[test]
+    fn test_truncate_display() {
+        let path = Path::try_from(r#"{"root"}[1]{"sub"}{"subsub"}"#).unwrap();
+        assert_eq!(path.truncate_display(2), r#"…{"sub"}{"subsub"}"#);
+        assert_eq!(path.truncate_display(0), "…");
+        assert_eq!(path.truncate_display(4), r#"{"root"}[1]{"sub"}{"subsub"}"#);
+        assert_eq!(path.truncate_display(10), r#"{"root"}[1]{"sub"}{"subsub"}"#);
+        assert_eq!(Path::new().truncate_display(2), "");
+    }
 }
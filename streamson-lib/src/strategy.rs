@@ -4,27 +4,105 @@ pub mod all;
 pub mod convert;
 pub mod extract;
 pub mod filter;
+pub mod parallel;
 pub mod trigger;
 
 pub use all::All;
 pub use convert::Convert;
 pub use extract::Extract;
 pub use filter::Filter;
+pub use parallel::Parallel;
 pub use trigger::Trigger;
 
-use crate::{error, path::Path};
-use std::mem;
+use crate::{
+    error,
+    handler::{Buffer, Handler},
+    matcher::Matcher,
+    path::Path,
+    streamer::Token,
+};
+use std::{
+    io::{self, Write},
+    mem,
+    sync::{Arc, Mutex},
+};
+
+/// Callback which is called for each token read by a strategy
+///
+/// It is called with the token and the current path of the streamer
+pub type TraceCallback = Box<dyn FnMut(&Token, &Path) + Send>;
+
+/// Metrics collected during the processing
+///
+/// It is a view of the memory related part of `Stats`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Metrics {
+    /// The highest number of bytes which were buffered at once
+    pub peak_buffer_bytes: usize,
+}
+
+impl From<&Stats> for Metrics {
+    fn from(stats: &Stats) -> Self {
+        Self {
+            peak_buffer_bytes: stats.peak_buffer_bytes,
+        }
+    }
+}
+
+/// Statistics of the processing
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Stats {
+    /// Number of processed bytes
+    pub bytes: usize,
+    /// Number of matched items of each matcher (in the order the matchers were added)
+    pub matches: Vec<usize>,
+    /// Number of complete JSON documents
+    pub documents: usize,
+    /// Path where the last error occured
+    pub error_path: Option<Path>,
+    /// The highest number of bytes which were buffered at once
+    pub peak_buffer_bytes: usize,
+}
+
+impl Stats {
+    /// Updates the peak with currently buffered bytes
+    pub(crate) fn update_peak(&mut self, bytes: usize) {
+        if bytes > self.peak_buffer_bytes {
+            self.peak_buffer_bytes = bytes;
+        }
+    }
+
+    /// Increases the number of matches of a matcher
+    pub(crate) fn matched(&mut self, matcher_idx: usize) {
+        if self.matches.len() <= matcher_idx {
+            self.matches.resize(matcher_idx + 1, 0);
+        }
+        self.matches[matcher_idx] += 1;
+    }
+
+    /// Returns the statistics with the number of processed bytes and matchers
+    pub(crate) fn with_bytes(&self, bytes: usize, matchers: usize) -> Self {
+        let mut stats = self.clone();
+        stats.bytes = bytes;
+        stats.matches.resize(matchers, 0);
+        stats
+    }
+}
 
 #[derive(Debug, PartialEq)]
 pub enum Output {
     Start(Option<Path>),
     Data(Vec<u8>),
     End,
+    /// Same as `End`, but the input terminated before the data were finished
+    Incomplete,
 }
 
 #[derive(Default)]
 pub struct OutputConverter {
     buffer: Vec<u8>,
+    /// Buffers of the outer outputs (when outputs are nested)
+    parent_buffers: Vec<Vec<u8>>,
     paths: Vec<Option<Path>>,
 }
 
@@ -33,25 +111,52 @@ impl OutputConverter {
         Default::default()
     }
 
+    /// Groups the data by the matches
+    ///
+    /// Nested outputs (e.g. `All` with exported paths) are supported,
+    /// the data of the inner output are a part of the outer output as well.
     pub fn convert(&mut self, input: &[Output]) -> Vec<(Option<Path>, Vec<u8>)> {
         let mut res = vec![];
         for field in input {
             match field {
                 Output::Start(path_opt) => {
+                    if !self.paths.is_empty() {
+                        self.parent_buffers.push(mem::take(&mut self.buffer));
+                    }
                     self.paths.push(path_opt.clone());
                 }
                 Output::Data(data) => {
                     self.buffer.extend(data);
                 }
-                Output::End => {
-                    let mut output = vec![];
-                    mem::swap(&mut output, &mut self.buffer);
+                Output::End | Output::Incomplete => {
+                    let output = mem::take(&mut self.buffer);
+                    if let Some(parent) = self.parent_buffers.pop() {
+                        self.buffer = parent;
+                        self.buffer.extend(&output);
+                    }
                     res.push((self.paths.pop().unwrap_or(None), output));
                 }
             }
         }
         res
     }
+
+    /// Writes the data from the output directly to a writer
+    ///
+    /// Unlike `convert` the data are not grouped by the matches
+    /// and the data outside of the matches are written as well.
+    ///
+    /// # Arguments
+    /// * `outputs` - output of a strategy
+    /// * `writer` - where the data will be written
+    pub fn write_all<W: Write>(&mut self, outputs: &[Output], writer: &mut W) -> io::Result<()> {
+        for output in outputs {
+            if let Output::Data(data) = output {
+                writer.write_all(data)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 pub trait Strategy {
@@ -74,6 +179,27 @@ pub trait Strategy {
     /// This is caused because streamson does not validate JSON.
     fn process(&mut self, input: &[u8]) -> Result<Vec<Output>, error::General>;
 
+    /// Processes the last chunk of input data
+    ///
+    /// Unlike `process` it marks the end of the input so
+    /// trailing top level numbers, booleans and nulls are finished.
+    ///
+    /// # Arguments
+    /// * `input` - the last chunk of input data
+    ///
+    /// # Returns
+    /// * `Ok(_) processing passed
+    /// * `Err(_)` - error occured during processing
+    fn process_final(&mut self, input: &[u8]) -> Result<Vec<Output>, error::General> {
+        self.finish_input();
+        self.process(input)
+    }
+
+    /// Marks that no more input data will be processed
+    ///
+    /// It is called by `process_final` before the last chunk is processed.
+    fn finish_input(&mut self) {}
+
     /// Should be called when input data terminates
     ///
     /// # Returns
@@ -87,12 +213,118 @@ pub trait Strategy {
     /// * `Ok(_) processing passed
     /// * `Err(_)` - error occured during processing
     fn json_finished(&mut self) -> Result<Vec<Output>, error::General>;
+
+    /// Returns statistics of the processing
+    ///
+    /// It can be used to monitor long running processing.
+    fn stats(&self) -> Stats {
+        Stats::default()
+    }
+}
+
+/// Returns handlers without duplicates
+///
+/// The same handler can be registered for several matchers,
+/// but its lifecycle hooks (e.g. `json_finished`) should be called only once.
+pub(crate) fn unique_handlers<'a, I>(handlers: I) -> Vec<&'a Arc<Mutex<dyn Handler>>>
+where
+    I: IntoIterator<Item = &'a Arc<Mutex<dyn Handler>>>,
+{
+    let mut res: Vec<&Arc<Mutex<dyn Handler>>> = vec![];
+    for handler in handlers {
+        if !res.iter().any(|e| Arc::ptr_eq(e, handler)) {
+            res.push(handler);
+        }
+    }
+    res
+}
+
+/// Extracted data in (path, data) format
+type ExtractedItem = (Option<String>, Vec<u8>);
+
+/// Extracts all matched parts of an input which is entirely in memory
+///
+/// It is a shortcut for processing the input using `Trigger`
+/// strategy with `Buffer` handler and draining the matches afterwards.
+///
+/// # Arguments
+/// * `matcher` - matcher which selects the extracted parts
+/// * `input` - entire input data
+/// * `include_path` - whether to return paths with the extracted data
+///
+/// # Returns
+/// * `Ok(_)` - all matches in (path, data) format
+/// * `Err(_)` - error occured during processing
+///
+/// # Example
+/// ```
+/// use streamson_lib::{matcher, strategy};
+///
+/// let matcher = matcher::Simple::new(r#"{"users"}[]"#).unwrap();
+/// let users = strategy::extract_all(
+///     Box::new(matcher), br#"{"users": ["first", "second"]}"#, false
+/// ).unwrap();
+/// assert_eq!(users.len(), 2);
+/// ```
+pub fn extract_all(
+    matcher: Box<dyn Matcher>,
+    input: &[u8],
+    include_path: bool,
+) -> Result<Vec<ExtractedItem>, error::General> {
+    let buffer = Arc::new(Mutex::new(Buffer::new().set_use_path(include_path)));
+    let mut trigger = Trigger::new();
+    trigger.add_matcher(matcher, buffer.clone());
+
+    trigger.process(input)?;
+    trigger.terminate()?;
+
+    let mut guard = buffer.lock().unwrap();
+    let mut res = vec![];
+    while let Some(item) = guard.pop() {
+        res.push(item);
+    }
+    Ok(res)
 }
 
 #[cfg(test)]
 mod test {
-    use super::{Output, OutputConverter, Path};
-    use std::convert::TryFrom;
+    use super::{
+        extract_all, All, Convert, Extract, Filter, Output, OutputConverter, Parallel, Path,
+        Strategy, Trigger,
+    };
+    use crate::{
+        error,
+        handler::{Buffer, Handler},
+        matcher::Simple,
+    };
+    use std::{
+        any::Any,
+        convert::TryFrom,
+        sync::{Arc, Mutex},
+    };
+
+    /// Counts the lifecycle hook calls
+    #[derive(Default)]
+    struct Lifecycle {
+        jsons: usize,
+        inputs: usize,
+    }
+
+    impl Handler for Lifecycle {
+        fn json_finished(&mut self) -> Result<Option<Vec<u8>>, error::Handler> {
+            self.jsons += 1;
+            Ok(None)
+        }
+
+        fn input_finished(&mut self) -> Result<Option<Vec<u8>>, error::Handler> {
+            self.inputs += 1;
+            Ok(None)
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
 
     #[test]
     fn converter() {
@@ -116,4 +348,140 @@ mod test {
             vec![(Some(Path::try_from("").unwrap()), b"56789".to_vec())]
         );
     }
+
+    #[test]
+    fn converter_write_all() {
+        let mut converter = OutputConverter::new();
+        let mut output: Vec<u8> = vec![];
+        converter
+            .write_all(
+                &[
+                    Output::Start(None),
+                    Output::Data(b"12".to_vec()),
+                    Output::Data(b"34".to_vec()),
+                    Output::End,
+                    Output::Data(b"\n".to_vec()),
+                    Output::Start(Some(Path::try_from("").unwrap())),
+                ],
+                &mut output,
+            )
+            .unwrap();
+        converter
+            .write_all(&[Output::Data(b"56".to_vec()), Output::End], &mut output)
+            .unwrap();
+        assert_eq!(output, b"1234\n56".to_vec());
+    }
+
+    #[test]
+    fn extract_all_users() {
+        let input = br#"{"users": [{"name": "first"}, {"name": "second"}], "groups": [1]}"#;
+        let matcher = Simple::new(r#"{"users"}[]"#).unwrap();
+        assert_eq!(
+            extract_all(Box::new(matcher), input, true).unwrap(),
+            vec![
+                (
+                    Some(r#"{"users"}[0]"#.to_string()),
+                    br#"{"name": "first"}"#.to_vec()
+                ),
+                (
+                    Some(r#"{"users"}[1]"#.to_string()),
+                    br#"{"name": "second"}"#.to_vec()
+                ),
+            ]
+        );
+
+        let matcher = Simple::new(r#"{"users"}[]{"name"}"#).unwrap();
+        assert_eq!(
+            extract_all(Box::new(matcher), input, false).unwrap(),
+            vec![
+                (None, br#""first""#.to_vec()),
+                (None, br#""second""#.to_vec())
+            ]
+        );
+    }
+
+    #[test]
+    fn extract_all_unterminated() {
+        let matcher = Simple::new(r#"{"users"}[]"#).unwrap();
+        assert!(extract_all(Box::new(matcher), br#"{"users": [1, 2"#, false).is_err());
+    }
+
+    #[test]
+    fn stats() {
+        let mut trigger = Trigger::new();
+        let mut convert = Convert::new();
+        let mut extract = Extract::new();
+        let mut filter = Filter::new();
+        for matcher in &["[]", "{}", "[]{}"] {
+            let matcher = Simple::new(matcher).unwrap();
+            trigger.add_matcher(
+                Box::new(matcher.clone()),
+                Arc::new(Mutex::new(Buffer::new())),
+            );
+            convert.add_matcher(
+                Box::new(matcher.clone()),
+                Arc::new(Mutex::new(Buffer::new())),
+            );
+            extract.add_matcher(Box::new(matcher.clone()), None);
+            filter.add_matcher(Box::new(matcher), None);
+        }
+        let mut all = All::new();
+
+        let strategies: Vec<&mut dyn Strategy> = vec![
+            &mut trigger,
+            &mut convert,
+            &mut extract,
+            &mut filter,
+            &mut all,
+        ];
+        for strategy in strategies {
+            strategy.process(br#"[1, 2] {"a": 3} "#).unwrap();
+            strategy.process(br#"[{"#).unwrap();
+            let stats = strategy.stats();
+            assert_eq!(stats.bytes, 18);
+            assert_eq!(stats.documents, 2);
+            assert_eq!(stats.error_path, None);
+
+            assert!(strategy.process(br#""b" 4]"#).is_err());
+            let stats = strategy.stats();
+            assert_eq!(stats.error_path, Some(Path::try_from("[0]").unwrap()));
+            if !stats.matches.is_empty() {
+                assert_eq!(stats.matches, vec![3, 1, 0]);
+            }
+        }
+    }
+
+    #[test]
+    fn lifecycle_hooks() {
+        let handler = Arc::new(Mutex::new(Lifecycle::default()));
+        let mut trigger = Trigger::new();
+        let mut convert = Convert::new();
+        let mut extract = Extract::new();
+        let mut filter = Filter::new();
+        let mut parallel = Parallel::new(2);
+        for matcher in &["[]", "{}"] {
+            let matcher = Simple::new(matcher).unwrap();
+            trigger.add_matcher(Box::new(matcher.clone()), handler.clone());
+            parallel.add_matcher(Box::new(matcher.clone()), handler.clone());
+            convert.add_matcher(Box::new(matcher.clone()), handler.clone());
+            extract.add_matcher(Box::new(matcher.clone()), Some(handler.clone()));
+            filter.add_matcher(Box::new(matcher), Some(handler.clone()));
+        }
+
+        let strategies: Vec<&mut dyn Strategy> = vec![
+            &mut trigger,
+            &mut convert,
+            &mut extract,
+            &mut filter,
+            &mut parallel,
+        ];
+        for strategy in strategies {
+            *handler.lock().unwrap() = Lifecycle::default();
+            strategy.process(br#"[1] {"a": 2} []"#).unwrap();
+            strategy.terminate().unwrap();
+            let guard = handler.lock().unwrap();
+            assert_eq!(guard.jsons, 3);
+            assert_eq!(guard.inputs, 1);
+        }
+    }
 }
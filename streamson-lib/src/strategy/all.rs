@@ -3,11 +3,11 @@
 //! This strategy doesn't require any matchers
 //! Handlers will be triggered on every element
 
-use super::{Output, Strategy};
+use super::{Output, Stats, Strategy};
 use crate::{
     error,
     handler::{Group, Handler},
-    streamer::{Streamer, Token},
+    streamer::{InputFormat, Limits, Streamer, Token},
 };
 use std::sync::{Arc, Mutex};
 
@@ -16,6 +16,10 @@ use std::sync::{Arc, Mutex};
 pub struct All {
     /// Should handlers be used for converting
     convert: bool,
+    /// Export path of every element
+    export_path: bool,
+    /// Flush pending data when input terminates in the middle of a JSON
+    flush_incomplete: bool,
     /// Input idx against total idx
     input_start: usize,
     /// Responsible for data extraction
@@ -24,19 +28,78 @@ pub struct All {
     handlers: Arc<Mutex<Group>>,
     /// Current json level
     level: usize,
+    /// Statistics of the processing
+    stats: Stats,
+    /// Whether the rest of an invalid line is to be dropped
+    discarded: bool,
 }
 
 impl Strategy for All {
     fn process(&mut self, input: &[u8]) -> Result<Vec<Output>, error::General> {
+        let result = self.process_input(input);
+        if result.is_err() {
+            self.stats.error_path = Some(self.streamer.current_path().clone());
+        }
+        result
+    }
+
+    fn finish_input(&mut self) {
+        // input itself is fed in process
+        self.streamer.feed_final(&[]);
+    }
+
+    fn terminate(&mut self) -> Result<Vec<Output>, error::General> {
+        let mut result = vec![];
+        if self.level != 0 {
+            if !self.flush_incomplete {
+                return Err(error::InputTerminated::new(self.input_start).into());
+            }
+            result.extend(self.flush()?);
+        }
+        let output = self.handlers.lock().unwrap().input_finished()?;
+        if let Some(data) = output {
+            result.push(Output::Data(data));
+        }
+        Ok(result)
+    }
+
+    fn json_finished(&mut self) -> Result<Vec<Output>, error::General> {
+        let output = self.handlers.lock().unwrap().json_finished()?;
+        if let Some(data) = output {
+            Ok(vec![Output::Data(data)])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    fn stats(&self) -> Stats {
+        self.stats.with_bytes(self.input_start, 0)
+    }
+}
+
+impl All {
+    /// Creates a new `All`
+    ///
+    /// It triggers handlers on all found elements
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Processes input data (see `Strategy::process`)
+    fn process_input(&mut self, input: &[u8]) -> Result<Vec<Output>, error::General> {
         self.streamer.feed(input);
         let mut inner_idx = 0;
         let mut result = vec![];
         loop {
             match self.streamer.read()? {
                 Token::Start(idx, kind) => {
+                    if self.discarded {
+                        inner_idx = self.skip_line(input, inner_idx, idx - self.input_start);
+                        self.discarded = false;
+                    }
                     let path = self.streamer.current_path();
 
-                    if self.level == 0 {
+                    if self.level == 0 && !self.export_path {
                         result.push(Output::Start(None));
                     }
 
@@ -47,6 +110,9 @@ impl Strategy for All {
                             result.push(Output::Data(data));
                         }
                     }
+                    if self.export_path {
+                        result.push(Output::Start(Some(path.clone())));
+                    }
                     if let Some(data) = guard.start(path, 0, Token::Start(idx, kind))? {
                         if self.convert {
                             result.push(Output::Data(data));
@@ -73,7 +139,11 @@ impl Strategy for All {
                     inner_idx = to;
                     self.level -= 1;
                     std::mem::drop(guard); // clear the guard so self can be reborrowed
+                    if self.export_path && self.level != 0 {
+                        result.push(Output::End);
+                    }
                     if self.level == 0 {
+                        self.stats.documents += 1;
                         let json_finished_data = self.json_finished()?;
                         if !json_finished_data.is_empty() {
                             result.extend(json_finished_data);
@@ -83,6 +153,9 @@ impl Strategy for All {
                 }
                 Token::Pending => {
                     self.input_start += input.len();
+                    if self.discarded {
+                        inner_idx = self.skip_line(input, inner_idx, input.len());
+                    }
                     let mut guard = self.handlers.lock().unwrap();
                     if let Some(data) = guard.feed(&input[inner_idx..], 0)? {
                         if self.convert {
@@ -91,40 +164,103 @@ impl Strategy for All {
                     }
                     return Ok(if self.convert { result } else { vec![] });
                 }
-                Token::Separator(_) => {}
+                Token::Discarded(idx) => {
+                    let to = idx - self.input_start;
+                    if self.discarded {
+                        // previous line was invalid as well
+                        inner_idx = self.skip_line(input, inner_idx, to);
+                    }
+                    let mut guard = self.handlers.lock().unwrap();
+                    if let Some(data) = guard.feed(&input[inner_idx..to], 0)? {
+                        if self.convert {
+                            result.push(Output::Data(data));
+                        }
+                    }
+                    std::mem::drop(guard);
+                    if self.level > 0 {
+                        result.extend(self.flush()?);
+                    }
+                    inner_idx = to;
+                    self.discarded = true;
+                }
+                Token::Separator(_, _) | Token::Finished(_) => {}
             }
         }
     }
 
-    fn terminate(&mut self) -> Result<Vec<Output>, error::General> {
-        if self.level == 0 {
-            let output = self.handlers.lock().unwrap().input_finished()?;
-            if let Some(data) = output {
-                Ok(vec![Output::Data(data)])
-            } else {
-                Ok(vec![])
-            }
+    /// Skips the rest of an invalid line (see `set_skip_invalid`)
+    ///
+    /// # Returns
+    /// * input idx of the data behind the line
+    fn skip_line(&mut self, input: &[u8], from: usize, to: usize) -> usize {
+        if let Some(pos) = input[from..to].iter().position(|e| *e == b'\n') {
+            self.discarded = false;
+            from + pos + 1
         } else {
-            Err(error::InputTerminated::new(self.input_start).into())
+            to
         }
     }
 
-    fn json_finished(&mut self) -> Result<Vec<Output>, error::General> {
-        let output = self.handlers.lock().unwrap().json_finished()?;
+    /// Flushes the data of the unfinished JSON (see `set_flush_incomplete`)
+    fn flush(&mut self) -> Result<Vec<Output>, error::General> {
+        let output = self.handlers.lock().unwrap().flush()?;
+        // close all the unfinished outputs
+        let unfinished = if self.export_path { self.level } else { 1 };
+        self.level = 0;
+        if !self.convert {
+            return Ok(vec![]);
+        }
+
+        let mut result = vec![];
         if let Some(data) = output {
-            Ok(vec![Output::Data(data)])
-        } else {
-            Ok(vec![])
+            result.push(Output::Data(data));
         }
+        result.extend((0..unfinished).map(|_| Output::Incomplete));
+        Ok(result)
     }
-}
 
-impl All {
-    /// Creates a new `All`
+    /// Sets strict mode
     ///
-    /// It triggers handlers on all found elements
-    pub fn new() -> Self {
-        Default::default()
+    /// Invalid JSON input (e.g. `[01]` or `[1,]`) is rejected in strict mode.
+    ///
+    /// # Arguments
+    /// * `strict` - whether the input should be validated
+    pub fn set_strict(&mut self, strict: bool) {
+        self.streamer.set_strict(strict);
+    }
+
+    /// Sets the format of the input data
+    ///
+    /// With `InputFormat::JsonLines` each line is processed as a separate JSON
+    /// (see `Streamer::set_input_format`).
+    ///
+    /// # Arguments
+    /// * `format` - format of the input data
+    pub fn set_input_format(&mut self, format: InputFormat) {
+        self.streamer.set_input_format(format);
+    }
+
+    /// Sets whether the lines with an incorrect input are skipped
+    ///
+    /// Handlers are fed with an invalid line only until the incorrect character.
+    /// Then the data of the unfinished JSON are flushed as in `set_flush_incomplete`
+    /// and the rest of the line is dropped (see `Streamer::set_skip_invalid`).
+    ///
+    /// # Arguments
+    /// * `skip` - whether invalid lines should be skipped
+    pub fn set_skip_invalid(&mut self, skip: bool) {
+        self.streamer.set_skip_invalid(skip);
+    }
+
+    /// Sets limits of the input data
+    ///
+    /// Input which exceeds the limits (e.g. which is nested too deeply)
+    /// is rejected (see `Streamer::set_limits`).
+    ///
+    /// # Arguments
+    /// * `limits` - limits of the input data
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.streamer.set_limits(limits);
     }
 
     /// Sets whether handlers should be actually used to converting data
@@ -132,6 +268,30 @@ impl All {
         self.convert = convert;
     }
 
+    /// Sets whether the path of every element should be exported
+    ///
+    /// When set, each element (not only the top level one) is wrapped
+    /// in `Output::Start(Some(path))` and `Output::End`, so the converted
+    /// data can be paired with the paths of the elements
+    /// (see `OutputConverter`).
+    pub fn set_export_path(&mut self, export: bool) {
+        self.export_path = export;
+    }
+
+    /// Sets whether the pending data should be flushed on termination
+    ///
+    /// When set and the input terminates in the middle of a JSON,
+    /// `terminate` doesn't fail. It returns the data buffered within
+    /// the handlers instead (see `Handler::flush`) and the unfinished
+    /// outputs are closed by `Output::Incomplete`. This allows
+    /// best-effort conversion of truncated input (e.g. logs).
+    ///
+    /// # Arguments
+    /// * `flush` - whether to flush the pending data
+    pub fn set_flush_incomplete(&mut self, flush: bool) {
+        self.flush_incomplete = flush;
+    }
+
     /// Adds a handler to `All`
     ///
     /// # Arguments
@@ -159,8 +319,9 @@ mod tests {
     use super::{All, Strategy};
     use crate::{
         handler::{Analyser, Replace},
-        strategy::OutputConverter,
-        test::{Single, Splitter, Window},
+        strategy::{Output, OutputConverter},
+        streamer::InputFormat,
+        testing::{Single, Splitter, Window},
     };
     use rstest::*;
     use std::sync::{Arc, Mutex};
@@ -221,4 +382,103 @@ mod tests {
             assert_eq!(result, br#"..........."#);
         }
     }
+
+    #[rstest(
+        splitter,
+        case::single(Box::new(Single::new())),
+        case::window1(Box::new(Window::new(1))),
+        case::window5(Box::new(Window::new(5))),
+        case::window100(Box::new(Window::new(100)))
+    )]
+    fn export_path(splitter: Box<dyn Splitter>) {
+        let input = br#"{"a": [1, {"b": null}]} [2]"#.to_vec();
+        for part in splitter.split(input) {
+            let mut all = All::new();
+            all.set_convert(true);
+            all.set_export_path(true);
+            all.add_handler(Arc::new(Mutex::new(Replace::new(br#"."#.to_vec()))));
+            let mut result = vec![];
+            let mut converter = OutputConverter::new();
+            for input in part {
+                result.extend(converter.convert(&all.process(&input).unwrap()));
+            }
+
+            let result: Vec<(String, String)> = result
+                .into_iter()
+                .map(|(path, data)| (path.unwrap().to_string(), String::from_utf8(data).unwrap()))
+                .collect();
+            assert_eq!(
+                result,
+                vec![
+                    (r#"{"a"}[0]"#.into(), ".".into()),
+                    (r#"{"a"}[1]{"b"}"#.into(), ".".into()),
+                    (r#"{"a"}[1]"#.into(), "..".into()),
+                    (r#"{"a"}"#.into(), "....".into()),
+                    ("".into(), ".....".into()),
+                    ("[0]".into(), ".".into()),
+                    ("".into(), "..".into()),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn export_path_no_convert() {
+        let mut all = All::new();
+        all.set_export_path(true);
+        let handler = Arc::new(Mutex::new(Analyser::new()));
+        all.add_handler(handler.clone());
+        assert!(all.process(br#"{"a": 1}"#).unwrap().is_empty());
+        assert_eq!(
+            handler.lock().unwrap().results(),
+            vec![("".into(), 1), (r#"{"a"}"#.into(), 1)]
+        );
+    }
+
+    #[test]
+    fn flush_incomplete() {
+        let input = br#"[1] {"a": [2, 3"#;
+
+        let mut all = All::new();
+        all.set_convert(true);
+        all.add_handler(Arc::new(Mutex::new(Replace::new(br#"."#.to_vec()))));
+        all.process(input).unwrap();
+        assert!(all.terminate().is_err());
+
+        let mut all = All::new();
+        all.set_convert(true);
+        all.set_export_path(true);
+        all.set_flush_incomplete(true);
+        all.add_handler(Arc::new(Mutex::new(Replace::new(br#"."#.to_vec()))));
+        all.process(input).unwrap();
+        assert_eq!(
+            all.terminate().unwrap(),
+            vec![Output::Incomplete, Output::Incomplete, Output::Incomplete]
+        );
+        // terminated state is cleared
+        assert!(all.terminate().unwrap().is_empty());
+    }
+
+    #[test]
+    fn skip_invalid() {
+        let input = b"{\"a\": 1}\n{\"a\": x}\nx\n[2, x]\n{\"a\": 3}\n";
+        for size in 1..input.len() {
+            let mut all = All::new();
+            all.set_input_format(InputFormat::JsonLines);
+            all.set_skip_invalid(true);
+            let analyser = Arc::new(Mutex::new(Analyser::new()));
+            all.add_handler(analyser.clone());
+
+            for part in input.chunks(size) {
+                all.process(part).unwrap();
+            }
+            all.terminate().unwrap();
+            assert_eq!(
+                analyser.lock().unwrap().results(),
+                vec![("".into(), 4), ("[]".into(), 1), (r#"{"a"}"#.into(), 2)],
+                "chunk size {}",
+                size
+            );
+        }
+    }
 }
@@ -5,19 +5,30 @@
 //! Nested matches are not considered. Data are converted only by the
 //! first match.
 
-use super::{Output, Strategy};
+use super::{unique_handlers, Output, Stats, Strategy, TraceCallback};
 use crate::{
     error,
-    handler::Handler,
-    matcher::Matcher,
+    handler::{Group, Handler},
+    matcher::{Matcher, MatcherIndex},
     path::Path,
-    streamer::{Streamer, Token},
+    streamer::{InputFormat, Limits, ParsedKind, Streamer, Token},
 };
 use std::sync::{Arc, Mutex};
 
 /// Item in matcher list
 type MatcherItem = (Box<dyn Matcher>, Arc<Mutex<dyn Handler>>);
 
+/// Leaf element waiting for the matchers which need its value
+#[derive(Debug)]
+struct PendingValue {
+    /// Total index of the element start
+    idx: usize,
+    /// Kind of the element
+    kind: ParsedKind,
+    /// Collected data of the element
+    data: Vec<u8>,
+}
+
 /// Processes data from input and triggers handler
 pub struct Convert {
     /// Input idx against total idx
@@ -26,10 +37,22 @@ pub struct Convert {
     matched: Option<(Path, usize)>,
     /// Path matchers and a handler
     matchers: Vec<MatcherItem>,
+    /// Index of the matchers
+    index: MatcherIndex,
+    /// Leaf element which matching depends on its value
+    pending_value: Option<PendingValue>,
     /// Responsible for data extraction
     streamer: Streamer,
     /// Current json level
     level: usize,
+    /// Statistics of the processing
+    stats: Stats,
+    /// Callback triggered for each token read
+    trace: Option<TraceCallback>,
+    /// Flush pending data when input terminates in the middle of a JSON
+    flush_incomplete: bool,
+    /// Whether the rest of an invalid line is to be dropped
+    discarded: bool,
 }
 
 impl Default for Convert {
@@ -38,33 +61,119 @@ impl Default for Convert {
             input_start: 0,
             matched: None,
             matchers: vec![],
+            index: MatcherIndex::new(),
+            pending_value: None,
             streamer: Streamer::new(),
             level: 0,
+            stats: Stats::default(),
+            trace: None,
+            flush_incomplete: false,
+            discarded: false,
         }
     }
 }
 
 impl Strategy for Convert {
     fn process(&mut self, input: &[u8]) -> Result<Vec<Output>, error::General> {
+        let result = self.process_input(input);
+        if result.is_err() {
+            self.stats.error_path = Some(self.streamer.current_path().clone());
+        }
+        result
+    }
+
+    fn finish_input(&mut self) {
+        // input itself is fed in process
+        self.streamer.feed_final(&[]);
+    }
+
+    fn terminate(&mut self) -> Result<Vec<Output>, error::General> {
+        let mut res = vec![];
+        if self.level != 0 {
+            if !self.flush_incomplete {
+                return Err(error::InputTerminated::new(self.input_start).into());
+            }
+            res.extend(self.flush()?);
+        }
+        for handler in unique_handlers(self.matchers.iter().map(|e| &e.1)) {
+            let output = handler.lock().unwrap().input_finished()?;
+            if let Some(data) = output {
+                res.push(Output::Data(data));
+            }
+        }
+        Ok(res)
+    }
+
+    fn json_finished(&mut self) -> Result<Vec<Output>, error::General> {
+        let mut res = vec![];
+        for (matcher, _) in &self.matchers {
+            matcher.json_finished();
+        }
+        for handler in unique_handlers(self.matchers.iter().map(|e| &e.1)) {
+            let output = handler.lock().unwrap().json_finished()?;
+            if let Some(data) = output {
+                res.push(Output::Data(data));
+            }
+        }
+        Ok(res)
+    }
+
+    fn stats(&self) -> Stats {
+        self.stats.with_bytes(self.input_start, self.matchers.len())
+    }
+}
+
+impl Convert {
+    /// Processes input data (see `Strategy::process`)
+    fn process_input(&mut self, input: &[u8]) -> Result<Vec<Output>, error::General> {
         self.streamer.feed(input);
         let mut inner_idx = 0;
 
         let mut result = vec![];
         loop {
-            match self.streamer.read()? {
+            let token = self.streamer.read()?;
+            if let Some(trace) = self.trace.as_mut() {
+                trace(&token, self.streamer.current_path());
+            }
+            match token {
                 Token::Start(idx, kind) => {
+                    if self.discarded {
+                        inner_idx = self.skip_line(input, inner_idx, idx - self.input_start);
+                        self.discarded = false;
+                    }
                     if self.level == 0 {
                         result.push(Output::Start(None));
                     }
                     self.level += 1;
 
-                    if self.matched.is_none() {
+                    let leaf = !matches!(kind, ParsedKind::Obj | ParsedKind::Arr);
+                    if self.matched.is_none()
+                        && leaf
+                        && self
+                            .matchers
+                            .iter()
+                            .any(|(matcher, _)| matcher.needs_value())
+                    {
+                        // Flush remaining data to output
+                        let to = idx - self.input_start;
+                        result.push(Output::Data(input[inner_idx..to].to_vec()));
+                        inner_idx = to;
+
+                        // decided when the entire value is read
+                        self.pending_value = Some(PendingValue {
+                            idx,
+                            kind,
+                            data: vec![],
+                        });
+                    } else if self.matched.is_none() {
                         // try to check whether it matches
-                        for (matcher_idx, (matcher, _)) in self.matchers.iter().enumerate() {
-                            if matcher.match_path(self.streamer.current_path(), kind) {
+                        let path = self.streamer.current_path();
+                        for matcher_idx in self.index.candidates(path.get_path(), kind) {
+                            if self.matchers[matcher_idx].0.match_path(path, kind) {
                                 // start collecting
                                 self.matched =
                                     Some((self.streamer.current_path().clone(), matcher_idx));
+                                self.stats.matched(matcher_idx);
 
                                 // Flush remaining data to output
                                 let to = idx - self.input_start;
@@ -88,7 +197,12 @@ impl Strategy for Convert {
                 Token::End(idx, kind) => {
                     let mut clear = false;
                     self.level -= 1;
-                    if let Some((matched_path, matcher_idx)) = self.matched.take() {
+                    if let Some(mut pending) = self.pending_value.take() {
+                        let to = idx - self.input_start;
+                        pending.data.extend(&input[inner_idx..to]);
+                        inner_idx = to;
+                        result.extend(self.end_pending(pending, Token::End(idx, kind))?);
+                    } else if let Some((matched_path, matcher_idx)) = self.matched.take() {
                         if self.streamer.current_path() == &matched_path {
                             clear = true;
 
@@ -108,7 +222,7 @@ impl Strategy for Convert {
                             if let Some(data) = handler.end(
                                 self.streamer.current_path(),
                                 matcher_idx,
-                                Token::Start(idx, kind),
+                                Token::End(idx, kind),
                             )? {
                                 result.push(Output::Data(data));
                             }
@@ -125,6 +239,7 @@ impl Strategy for Convert {
                     }
 
                     if self.level == 0 {
+                        self.stats.documents += 1;
                         let json_finished_data = self.json_finished()?;
                         if !json_finished_data.is_empty() {
                             result.extend(json_finished_data);
@@ -134,7 +249,12 @@ impl Strategy for Convert {
                 }
                 Token::Pending => {
                     self.input_start += input.len();
-                    if let Some((_, matcher_idx)) = self.matched {
+                    if self.discarded {
+                        inner_idx = self.skip_line(input, inner_idx, input.len());
+                    }
+                    if let Some(pending) = self.pending_value.as_mut() {
+                        pending.data.extend(&input[inner_idx..]);
+                    } else if let Some((_, matcher_idx)) = self.matched {
                         let mut handler = self.matchers[matcher_idx].1.lock().unwrap();
                         if let Some(to_output) = handler.feed(&input[inner_idx..], matcher_idx)? {
                             result.push(Output::Data(to_output));
@@ -147,41 +267,107 @@ impl Strategy for Convert {
                     }
                     return Ok(result);
                 }
-                Token::Separator(_) => {}
+                Token::Discarded(idx) => {
+                    let to = idx - self.input_start;
+                    if self.discarded {
+                        // previous line was invalid as well
+                        inner_idx = self.skip_line(input, inner_idx, to);
+                    }
+                    if self.level > 0 {
+                        let data = &input[inner_idx..to];
+                        if let Some(pending) = self.pending_value.as_mut() {
+                            pending.data.extend(data);
+                        } else if let Some((_, matcher_idx)) = self.matched {
+                            let mut handler = self.matchers[matcher_idx].1.lock().unwrap();
+                            if let Some(to_output) = handler.feed(data, matcher_idx)? {
+                                result.push(Output::Data(to_output));
+                            }
+                        } else {
+                            result.push(Output::Data(data.to_vec()));
+                        }
+                        result.extend(self.flush()?);
+                    } else if inner_idx < to {
+                        result.push(Output::Data(input[inner_idx..to].to_vec()));
+                    }
+                    inner_idx = to;
+                    self.discarded = true;
+                }
+                Token::Separator(_, _) | Token::Finished(_) => {}
             }
         }
     }
 
-    fn terminate(&mut self) -> Result<Vec<Output>, error::General> {
-        if self.level == 0 {
-            dbg!("terminated HERER");
-            let mut res = vec![];
-            for (_, handler) in &self.matchers {
-                let output = handler.lock().unwrap().input_finished()?;
-                if let Some(data) = output {
-                    res.push(Output::Data(data));
-                }
+    /// Skips the rest of an invalid line (see `set_skip_invalid`)
+    ///
+    /// # Returns
+    /// * input idx of the data behind the line
+    fn skip_line(&mut self, input: &[u8], from: usize, to: usize) -> usize {
+        if let Some(pos) = input[from..to].iter().position(|e| *e == b'\n') {
+            self.discarded = false;
+            from + pos + 1
+        } else {
+            to
+        }
+    }
+
+    /// Finishes matching of a leaf element which value was needed
+    fn end_pending(
+        &mut self,
+        pending: PendingValue,
+        end_token: Token,
+    ) -> Result<Vec<Output>, error::General> {
+        let end_kind = if let Token::End(_, kind) = end_token {
+            kind
+        } else {
+            unreachable!();
+        };
+        let path = self.streamer.current_path().clone();
+        let matched = self.matchers.iter().position(|(matcher, _)| {
+            if matcher.needs_value() {
+                matcher.match_value(&path, end_kind, &pending.data)
+            } else {
+                matcher.match_path(&path, pending.kind)
+            }
+        });
+
+        let mut result = vec![];
+        if let Some(matcher_idx) = matched {
+            self.stats.matched(matcher_idx);
+            let mut handler = self.matchers[matcher_idx].1.lock().unwrap();
+            let start_token = Token::Start(pending.idx, pending.kind);
+            if let Some(data) = handler.start(&path, matcher_idx, start_token)? {
+                result.push(Output::Data(data));
+            }
+            if let Some(data) = handler.feed(&pending.data, matcher_idx)? {
+                result.push(Output::Data(data));
+            }
+            if let Some(data) = handler.end(&path, matcher_idx, end_token)? {
+                result.push(Output::Data(data));
             }
-            Ok(res)
         } else {
-            Err(error::InputTerminated::new(self.input_start).into())
+            // not matched -> keep original data
+            result.push(Output::Data(pending.data));
         }
+        Ok(result)
     }
 
-    fn json_finished(&mut self) -> Result<Vec<Output>, error::General> {
-        let mut res = vec![];
-        for (_, handler) in &self.matchers {
-            dbg!("Finished HERER");
-            let output = handler.lock().unwrap().json_finished()?;
-            if let Some(data) = output {
-                res.push(Output::Data(data));
+    /// Flushes the data of the unfinished JSON (see `set_flush_incomplete`)
+    fn flush(&mut self) -> Result<Vec<Output>, error::General> {
+        let mut result = vec![];
+        if let Some(pending) = self.pending_value.take() {
+            // not decided yet -> keep original data
+            result.push(Output::Data(pending.data));
+        } else if let Some((_, matcher_idx)) = self.matched.take() {
+            let mut handler = self.matchers[matcher_idx].1.lock().unwrap();
+            if let Some(data) = handler.flush()? {
+                result.push(Output::Data(data));
             }
         }
-        Ok(res)
+        result.push(Output::Incomplete);
+        self.level = 0;
+        Ok(result)
     }
-}
 
-impl Convert {
     /// Creates a new `Convert`
     ///
     /// It should replace a parts of the JSON using custom bytes
@@ -190,6 +376,156 @@ impl Convert {
         Self::default()
     }
 
+    /// Sets whether the pending data should be flushed on termination
+    ///
+    /// When set and the input terminates in the middle of a JSON,
+    /// `terminate` doesn't fail. It returns the data buffered within
+    /// the matched handler instead (see `Handler::flush`) followed
+    /// by `Output::Incomplete`.
+    ///
+    /// # Arguments
+    /// * `flush` - whether to flush the pending data
+    pub fn set_flush_incomplete(&mut self, flush: bool) {
+        self.flush_incomplete = flush;
+    }
+
+    /// Sets whether the lines with an incorrect input are skipped
+    ///
+    /// The conversion of an invalid line stops at the incorrect character.
+    /// Data buffered in the matched handler are flushed as in
+    /// `set_flush_incomplete` and the rest of the line is dropped
+    /// (see `Streamer::set_skip_invalid`).
+    ///
+    /// # Arguments
+    /// * `skip` - whether invalid lines should be skipped
+    pub fn set_skip_invalid(&mut self, skip: bool) {
+        self.streamer.set_skip_invalid(skip);
+    }
+
+    /// Sets strict mode
+    ///
+    /// Invalid JSON input (e.g. `[01]` or `[1,]`) is rejected in strict mode.
+    ///
+    /// # Arguments
+    /// * `strict` - whether the input should be validated
+    pub fn set_strict(&mut self, strict: bool) {
+        self.streamer.set_strict(strict);
+    }
+
+    /// Sets the format of the input data
+    ///
+    /// With `InputFormat::JsonLines` each line is processed as a separate JSON
+    /// (see `Streamer::set_input_format`).
+    ///
+    /// # Arguments
+    /// * `format` - format of the input data
+    pub fn set_input_format(&mut self, format: InputFormat) {
+        self.streamer.set_input_format(format);
+    }
+
+    /// Sets limits of the input data
+    ///
+    /// Input which exceeds the limits (e.g. which is nested too deeply)
+    /// is rejected (see `Streamer::set_limits`).
+    ///
+    /// # Arguments
+    /// * `limits` - limits of the input data
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.streamer.set_limits(limits);
+    }
+
+    /// Sets a callback which is triggered for each token read
+    ///
+    /// It can be used for debugging e.g. to find out why
+    /// a matcher doesn't match.
+    ///
+    /// # Arguments
+    /// * `trace` - function called with a token and the current path
+    pub fn set_trace(&mut self, trace: TraceCallback) {
+        self.trace = Some(trace);
+    }
+
+    /// Lists the parts of the input which would be converted
+    ///
+    /// Entire `input` is processed using a separate streamer and
+    /// handlers are not called, so the state of `Convert` is not affected.
+    /// It can be used e.g. to check what would be changed before
+    /// the actual conversion.
+    ///
+    /// # Arguments
+    /// * `input` - entire input data
+    ///
+    /// # Returns
+    /// * matched paths and their original data
+    pub fn plan(&self, input: &[u8]) -> Result<Vec<(Path, Vec<u8>)>, error::General> {
+        let mut streamer = Streamer::new();
+        streamer.set_strict(self.streamer.is_strict());
+        streamer.set_input_format(self.streamer.input_format());
+        streamer.set_limits(self.streamer.limits());
+        streamer.set_skip_invalid(self.streamer.is_skip_invalid());
+        streamer.feed_final(input);
+
+        let mut result = vec![];
+        let mut level = 0;
+        // level, start index and kind of the currently matched element
+        let mut matched: Option<(usize, usize, ParsedKind)> = None;
+        loop {
+            match streamer.read()? {
+                Token::Start(idx, kind) => {
+                    level += 1;
+                    if matched.is_none() {
+                        let leaf = !matches!(kind, ParsedKind::Obj | ParsedKind::Arr);
+                        let path = streamer.current_path();
+                        // leaf elements are decided when the entire value is read
+                        if leaf
+                            || self
+                                .matchers
+                                .iter()
+                                .any(|(matcher, _)| matcher.match_path(path, kind))
+                        {
+                            matched = Some((level, idx, kind));
+                        }
+                    }
+                }
+                Token::End(idx, end_kind) => {
+                    if let Some((matched_level, start, kind)) = matched {
+                        if matched_level == level {
+                            matched = None;
+                            let path = streamer.current_path();
+                            let data = &input[start..idx];
+                            let leaf = !matches!(kind, ParsedKind::Obj | ParsedKind::Arr);
+                            if !leaf
+                                || self.matchers.iter().any(|(matcher, _)| {
+                                    if matcher.needs_value() {
+                                        matcher.match_value(path, end_kind, data)
+                                    } else {
+                                        matcher.match_path(path, kind)
+                                    }
+                                })
+                            {
+                                result.push((path.clone(), data.to_vec()));
+                            }
+                        }
+                    }
+                    level -= 1;
+                }
+                Token::Pending => {
+                    if level == 0 {
+                        return Ok(result);
+                    } else {
+                        return Err(error::InputTerminated::new(input.len()).into());
+                    }
+                }
+                Token::Discarded(_) => {
+                    // the invalid line is not converted
+                    matched = None;
+                    level = 0;
+                }
+                Token::Separator(_, _) | Token::Finished(_) => {}
+            }
+        }
+    }
+
     /// Adds a mathcher and a handler to `Convert`
     ///
     /// # Arguments
@@ -211,8 +547,46 @@ impl Convert {
     /// );
     /// ```
     pub fn add_matcher(&mut self, matcher: Box<dyn Matcher>, handler: Arc<Mutex<dyn Handler>>) {
+        self.index.add(self.matchers.len(), matcher.as_ref());
         self.matchers.push((matcher, handler));
     }
+
+    /// Adds a matcher with a chain of handlers
+    ///
+    /// Unlike `add_matcher_handlers` of the other strategies, the handlers
+    /// are chained - the output of a handler is passed to the following one
+    /// (handlers are wrapped in a `handler::Group` which is not fan-out).
+    ///
+    /// # Arguments
+    /// * `matcher` - matcher which matches the path
+    /// * `handlers` - handlers which will convert the data
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use streamson_lib::{strategy, matcher, handler};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let mut convert = strategy::Convert::new();
+    /// let matcher = matcher::Simple::new(r#"{"list"}[]"#).unwrap();
+    /// convert.add_matcher_chain(
+    ///     Box::new(matcher),
+    ///     &[
+    ///         Arc::new(Mutex::new(handler::Trim::new())),
+    ///         Arc::new(Mutex::new(handler::Shorten::new(3, "..".into()))),
+    ///     ],
+    /// );
+    /// ```
+    pub fn add_matcher_chain(
+        &mut self,
+        matcher: Box<dyn Matcher>,
+        handlers: &[Arc<Mutex<dyn Handler>>],
+    ) {
+        let group = handlers.iter().fold(Group::new(), |group, handler| {
+            group.add_handler(handler.clone())
+        });
+        self.add_matcher(matcher, Arc::new(Mutex::new(group)));
+    }
 }
 
 #[cfg(test)]
@@ -220,12 +594,18 @@ mod tests {
     use super::{Convert, Output, Strategy};
     use crate::{
         handler::{Group, Replace, Shorten},
-        matcher::Simple,
+        matcher::{Combinator, NumberCompare, Simple},
+        path::Path,
         strategy::OutputConverter,
-        test::{Single, Splitter, Window},
+        streamer::InputFormat,
+        testing::{Single, Splitter, Window},
     };
     use rstest::*;
-    use std::sync::{Arc, Mutex};
+    use std::{
+        convert::TryFrom,
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
 
     fn make_replace_handler() -> Arc<Mutex<Replace>> {
         return Arc::new(Mutex::new(Replace::new(vec![b'"', b'*', b'*', b'*', b'"'])));
@@ -316,4 +696,192 @@ mod tests {
             r#"[{"id": 1, "password": "****..."}, {"id": 2, "password": "****..."}]"#
         );
     }
+
+    #[test]
+    fn multiple_handlers() {
+        let mut convert = Convert::new();
+        let matcher = Simple::new(r#"[]{"password"}"#).unwrap();
+        let replace = Arc::new(Mutex::new(Replace::new(br#""*****************""#.to_vec())));
+        let shorten = Arc::new(Mutex::new(Shorten::new(4, "...\"".into())));
+        convert.add_matcher_chain(Box::new(matcher), &[replace, shorten]);
+
+        let output: Vec<u8> = OutputConverter::new()
+            .convert(&convert.process(br#"[{"password": "secret1"}]"#).unwrap())
+            .into_iter()
+            .flat_map(|e| e.1)
+            .collect();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"[{"password": "****..."}]"#
+        );
+    }
+
+    #[rstest(
+        splitter,
+        case::single(Box::new(Single::new())),
+        case::window1(Box::new(Window::new(1))),
+        case::window5(Box::new(Window::new(5))),
+        case::window100(Box::new(Window::new(100)))
+    )]
+    fn value_matcher(splitter: Box<dyn Splitter>) {
+        let input = br#"[{"id": 1, "price": 150}, {"id": 200, "price": 50.5}]"#.to_vec();
+        for parts in splitter.split(input) {
+            let mut convert = Convert::new();
+            let matcher = Combinator::new(Simple::new(r#"[]{"price"}"#).unwrap())
+                & Combinator::new(NumberCompare::from_str(">=100").unwrap());
+            convert.add_matcher(Box::new(matcher), make_replace_handler());
+
+            let mut result = vec![];
+            let mut converter = OutputConverter::new();
+            for part in parts {
+                let converted = convert.process(&part).unwrap();
+                let output = converter.convert(&converted);
+                result.extend(output.into_iter().map(|e| e.1));
+            }
+
+            assert_eq!(
+                String::from_utf8(result.into_iter().flatten().collect()).unwrap(),
+                r#"[{"id": 1, "price": "***"}, {"id": 200, "price": 50.5}]"#
+            );
+        }
+    }
+
+    #[test]
+    fn plan() {
+        let input = br#"{"users": [{"name": "first", "password": "1234", "card": {"no": 1}}, {"name": "second", "password": "0000", "age": 150}]}"#;
+
+        let mut convert = Convert::new();
+        let handler = make_replace_handler();
+        convert.add_matcher(
+            Box::new(Simple::new(r#"{"users"}[]{"password"}"#).unwrap()),
+            handler.clone(),
+        );
+        convert.add_matcher(
+            Box::new(Simple::new(r#"{"users"}[]{"card"}"#).unwrap()),
+            handler,
+        );
+        convert.add_matcher(
+            Box::new(
+                Combinator::new(Simple::new(r#"{"users"}[]{}"#).unwrap())
+                    & Combinator::new(NumberCompare::from_str(">100").unwrap()),
+            ),
+            make_replace_handler(),
+        );
+
+        let planned = convert.plan(input).unwrap();
+        assert_eq!(
+            planned,
+            vec![
+                (
+                    Path::try_from(r#"{"users"}[0]{"password"}"#).unwrap(),
+                    br#""1234""#.to_vec()
+                ),
+                (
+                    Path::try_from(r#"{"users"}[0]{"card"}"#).unwrap(),
+                    br#"{"no": 1}"#.to_vec()
+                ),
+                (
+                    Path::try_from(r#"{"users"}[1]{"password"}"#).unwrap(),
+                    br#""0000""#.to_vec()
+                ),
+                (
+                    Path::try_from(r#"{"users"}[1]{"age"}"#).unwrap(),
+                    br#"150"#.to_vec()
+                ),
+            ]
+        );
+
+        // state is not affected
+        let mut converter = OutputConverter::new();
+        let output: Vec<u8> = converter
+            .convert(&convert.process(input).unwrap())
+            .into_iter()
+            .flat_map(|e| e.1)
+            .collect();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"{"users": [{"name": "first", "password": "***", "card": "***"}, {"name": "second", "password": "***", "age": "***"}]}"#
+        );
+        assert_eq!(convert.plan(input).unwrap(), planned);
+
+        assert!(convert.plan(br#"{"users": ["#).is_err());
+    }
+
+    #[test]
+    fn flush_incomplete() {
+        let input = br#"{"users": [{"name": "first", "password": "1234"}, {"password": "00"#;
+
+        let mut convert = Convert::new();
+        convert.add_matcher(
+            Box::new(Simple::new(r#"{"users"}[]{"password"}"#).unwrap()),
+            make_replace_handler(),
+        );
+        convert.process(input).unwrap();
+        assert!(convert.terminate().is_err());
+
+        let mut convert = Convert::new();
+        convert.set_flush_incomplete(true);
+        convert.add_matcher(
+            Box::new(Simple::new(r#"{"users"}[]{"password"}"#).unwrap()),
+            make_replace_handler(),
+        );
+        let mut output = convert.process(input).unwrap();
+        let terminated = convert.terminate().unwrap();
+        assert_eq!(terminated.last(), Some(&Output::Incomplete));
+        output.extend(terminated);
+
+        let mut converter = OutputConverter::new();
+        let output = converter.convert(&output);
+        assert_eq!(output.len(), 1);
+        assert_eq!(
+            String::from_utf8(output[0].1.clone()).unwrap(),
+            r#"{"users": [{"name": "first", "password": "***"}, {"password": "#
+        );
+    }
+
+    #[test]
+    fn skip_invalid() {
+        let input = b"{\"a\": \"x\"}\n{\"a\": x}\nx\n{\"b\": [1, x]}\n{\"a\": \"y\"}\n";
+        for size in 1..input.len() {
+            let mut convert = Convert::new();
+            convert.set_input_format(InputFormat::JsonLines);
+            convert.set_skip_invalid(true);
+            convert.add_matcher(
+                Box::new(Simple::new(r#"{"a"}"#).unwrap()),
+                make_replace_handler(),
+            );
+
+            let mut converter = OutputConverter::new();
+            let mut res = vec![];
+            for part in input.chunks(size) {
+                let output = convert.process(part).unwrap();
+                res.extend(converter.convert(&output).into_iter().map(|e| e.1));
+            }
+            convert.terminate().unwrap();
+            assert_eq!(
+                res,
+                vec![
+                    br#"{"a": "***"}"#.to_vec(),
+                    b"\n{\"a\": ".to_vec(),
+                    b"{\"b\": [1, ".to_vec(),
+                    b"{\"a\": \"***\"}".to_vec(),
+                ],
+                "chunk size {}",
+                size
+            );
+        }
+        let plan = {
+            let mut convert = Convert::new();
+            convert.set_input_format(InputFormat::JsonLines);
+            convert.set_skip_invalid(true);
+            convert.add_matcher(
+                Box::new(Simple::new(r#"{"a"}"#).unwrap()),
+                make_replace_handler(),
+            );
+            convert.plan(input).unwrap()
+        };
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[1].1, br#""y""#.to_vec());
+    }
 }
@@ -2,20 +2,74 @@
 //!
 //! It uses matchers to extract a parts of JSON.
 //! Nested matches have no meaning here
+//!
+//! The output can be sent to a bounded channel instead of being returned
+//! (see `Extract::set_channel`). When the channel is full, processing blocks
+//! until the consumer catches up, so the memory usage remains bounded.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{matcher, strategy::{self, Strategy}};
+//! use std::{sync::mpsc, thread};
+//!
+//! let (sender, receiver) = mpsc::sync_channel(16);
+//! let consumer = thread::spawn(move || {
+//!     let mut converter = strategy::OutputConverter::new();
+//!     for output in receiver {
+//!         for (_, data) in converter.convert(&[output]) {
+//!             // Do something with the data
+//!             println!("{}", String::from_utf8(data).unwrap());
+//!         }
+//!     }
+//! });
+//!
+//! let mut extract = strategy::Extract::new().set_channel(sender);
+//! extract.add_matcher(Box::new(matcher::Simple::new(r#"{"users"}[]"#).unwrap()), None);
+//!
+//! for input in vec![
+//!     br#"{"users": [{"name": "first"}, "#.to_vec(),
+//!     br#"{"name": "second"}]}"#.to_vec(),
+//! ] {
+//!     // all the output is sent to the channel
+//!     assert!(extract.process(&input).unwrap().is_empty());
+//! }
+//!
+//! // Sender is dropped with the extract which terminates the consumer
+//! drop(extract);
+//! consumer.join().unwrap();
+//! ```
 
 use crate::{
     error,
-    handler::Handler,
-    matcher::Matcher,
+    handler::{Group, Handler},
+    matcher::{Matcher, MatcherIndex},
     path::Path,
-    streamer::{Streamer, Token},
+    streamer::{InputFormat, Limits, ParsedKind, Streamer, Token},
+};
+use std::{
+    io, mem,
+    sync::{mpsc::SyncSender, Arc, Mutex},
 };
-use std::sync::{Arc, Mutex};
 
-use super::{Output, Strategy};
+use super::{unique_handlers, Metrics, Output, Stats, Strategy, TraceCallback};
 
 type MatcherItem = (Box<dyn Matcher>, Option<Arc<Mutex<dyn Handler>>>);
 
+/// Leaf element waiting for the matchers which need its value
+#[derive(Debug)]
+struct PendingValue {
+    /// Total index of the element start
+    idx: usize,
+    /// Kind of the element
+    kind: ParsedKind,
+    /// Indexes to matchers which matched by path
+    matched_indexes: Vec<usize>,
+    /// Indexes to matchers which need the value
+    deferred_indexes: Vec<usize>,
+    /// Collected data of the element
+    data: Vec<u8>,
+}
+
 pub struct Extract {
     /// Export path as well
     export_path: bool,
@@ -25,10 +79,24 @@ pub struct Extract {
     matches: Option<(Path, Vec<usize>)>,
     /// Path matchers
     matchers: Vec<MatcherItem>,
+    /// Index of the matchers
+    index: MatcherIndex,
+    /// Remaining number of matches for each matcher (`None` means unlimited)
+    remaining: Vec<Option<usize>>,
+    /// Leaf element which matching depends on its value
+    pending_value: Option<PendingValue>,
     /// Creates to token stream
     streamer: Streamer,
     /// Current json level
     level: usize,
+    /// Statistics of the processing
+    stats: Stats,
+    /// Callback triggered for each token read
+    trace: Option<TraceCallback>,
+    /// Channel where the output is sent instead of being returned
+    sender: Option<SyncSender<Output>>,
+    /// Number of extracted matches (when counting is enabled)
+    match_count: Option<usize>,
 }
 
 impl Default for Extract {
@@ -38,21 +106,84 @@ impl Default for Extract {
             input_start: 0,
             matches: None,
             matchers: vec![],
+            index: MatcherIndex::new(),
+            remaining: vec![],
+            pending_value: None,
             streamer: Streamer::new(),
             level: 0,
+            stats: Stats::default(),
+            trace: None,
+            sender: None,
+            match_count: None,
         }
     }
 }
 
 impl Strategy for Extract {
     fn process(&mut self, input: &[u8]) -> Result<Vec<Output>, error::General> {
+        let result = self.process_input(input);
+        if result.is_err() {
+            self.stats.error_path = Some(self.streamer.current_path().clone());
+        }
+        result
+    }
+
+    fn finish_input(&mut self) {
+        // input itself is fed in process
+        self.streamer.feed_final(&[]);
+    }
+
+    fn terminate(&mut self) -> Result<Vec<Output>, error::General> {
+        if self.level == 0 {
+            let mut res = vec![];
+            for handler in unique_handlers(self.matchers.iter().filter_map(|e| e.1.as_ref())) {
+                let output = handler.lock().unwrap().input_finished()?;
+                if let Some(data) = output {
+                    res.push(Output::Data(data));
+                }
+            }
+            self.send(&mut res)?;
+            Ok(res)
+        } else {
+            Err(error::InputTerminated::new(self.input_start).into())
+        }
+    }
+
+    fn json_finished(&mut self) -> Result<Vec<Output>, error::General> {
+        let mut res = vec![];
+        for (matcher, _) in &self.matchers {
+            matcher.json_finished();
+        }
+        for handler in unique_handlers(self.matchers.iter().filter_map(|e| e.1.as_ref())) {
+            let output = handler.lock().unwrap().json_finished()?;
+            if let Some(data) = output {
+                res.push(Output::Data(data));
+            }
+        }
+        Ok(res)
+    }
+
+    fn stats(&self) -> Stats {
+        self.stats.with_bytes(self.input_start, self.matchers.len())
+    }
+}
+
+impl Extract {
+    /// Processes input data (see `Strategy::process`)
+    fn process_input(&mut self, input: &[u8]) -> Result<Vec<Output>, error::General> {
         self.streamer.feed(input);
+        self.update_peak();
 
         let mut input_idx = 0;
 
         let mut result = vec![];
         loop {
-            match self.streamer.read()? {
+            self.send(&mut result)?;
+            let token = self.streamer.read()?;
+            if let Some(trace) = self.trace.as_mut() {
+                trace(&token, self.streamer.current_path());
+            }
+            match token {
                 Token::Start(idx, kind) => {
                     self.level += 1;
                     if self.matches.is_none() {
@@ -60,15 +191,38 @@ impl Strategy for Extract {
 
                         // try to check whether it matches
                         let mut matched_indexes = vec![];
-                        for (matcher_idx, (matcher, _handler)) in self.matchers.iter().enumerate() {
-                            if matcher.match_path(path, kind) {
+                        let mut deferred_indexes = vec![];
+                        let leaf = !matches!(kind, ParsedKind::Obj | ParsedKind::Arr);
+                        for matcher_idx in self.index.candidates(path.get_path(), kind) {
+                            let matcher = &self.matchers[matcher_idx].0;
+                            if self.remaining[matcher_idx] == Some(0) {
+                                // limit reached
+                                continue;
+                            }
+                            if leaf && matcher.needs_value() {
+                                // decided when the entire value is read
+                                deferred_indexes.push(matcher_idx);
+                            } else if matcher.match_path(path, kind) {
                                 matched_indexes.push(matcher_idx);
                             }
                         }
-                        if !matched_indexes.is_empty() {
+                        if !deferred_indexes.is_empty() {
+                            input_idx = idx - self.input_start;
+                            self.pending_value = Some(PendingValue {
+                                idx,
+                                kind,
+                                matched_indexes,
+                                deferred_indexes,
+                                data: vec![],
+                            });
+                        } else if !matched_indexes.is_empty() {
                             // New match appears here
                             input_idx = idx - self.input_start;
                             for matcher_idx in &matched_indexes {
+                                if let Some(remaining) = self.remaining[*matcher_idx].as_mut() {
+                                    *remaining -= 1;
+                                }
+                                self.stats.matched(*matcher_idx);
                                 if let Some(handler) = self.matchers[*matcher_idx].1.as_ref() {
                                     let mut guard = handler.lock().unwrap();
                                     // triger handlers start
@@ -87,6 +241,10 @@ impl Strategy for Extract {
                     }
                 }
                 Token::Pending => {
+                    if let Some(pending) = self.pending_value.as_mut() {
+                        pending.data.extend(&input[input_idx..]);
+                    }
+                    self.update_peak();
                     if let Some((_, matched_indexes)) = self.matches.as_ref() {
                         for matcher_idx in matched_indexes {
                             if let Some(handler) = self.matchers[*matcher_idx].1.as_ref() {
@@ -98,10 +256,17 @@ impl Strategy for Extract {
                         result.push(Output::Data(input[input_idx..].to_vec()));
                     }
                     self.input_start += input.len();
+                    self.send(&mut result)?;
                     return Ok(result);
                 }
                 Token::End(idx, kind) => {
                     self.level -= 1;
+                    if let Some(mut pending) = self.pending_value.take() {
+                        let old_idx = input_idx;
+                        input_idx = idx - self.input_start;
+                        pending.data.extend(&input[old_idx..input_idx]);
+                        self.end_pending(pending, Token::End(idx, kind), &mut result)?;
+                    }
                     if let Some((path, matched_indexes)) = self.matches.as_ref() {
                         // Put the data to results
                         if path == self.streamer.current_path() {
@@ -109,6 +274,9 @@ impl Strategy for Extract {
                             input_idx = idx - self.input_start;
                             result.push(Output::Data(input[old_idx..input_idx].to_vec()));
                             result.push(Output::End);
+                            if let Some(count) = self.match_count.as_mut() {
+                                *count += 1;
+                            }
                             // Feed and end handlers
                             for matcher_idx in matched_indexes {
                                 if let Some(handler) = self.matchers[*matcher_idx].1.as_ref() {
@@ -123,49 +291,101 @@ impl Strategy for Extract {
                     }
 
                     if self.level == 0 {
+                        self.stats.documents += 1;
                         let json_finished_data = self.json_finished()?;
                         if !json_finished_data.is_empty() {
                             result.extend(json_finished_data);
                         }
                     }
                 }
+                Token::Discarded(idx) => {
+                    // the rest of the line is skipped
+                    if self.matches.take().is_some() {
+                        let to = idx - self.input_start;
+                        result.push(Output::Data(input[input_idx..to].to_vec()));
+                        result.push(Output::Incomplete);
+                    }
+                    self.pending_value = None;
+                    self.level = 0;
+                }
                 _ => {}
             }
         }
     }
 
-    fn terminate(&mut self) -> Result<Vec<Output>, error::General> {
-        if self.level == 0 {
-            let mut res = vec![];
-            for (_, handler) in &self.matchers {
-                if let Some(handler) = handler {
-                    let output = handler.lock().unwrap().input_finished()?;
-                    if let Some(data) = output {
-                        res.push(Output::Data(data));
-                    }
-                }
+    /// Sends the output to the channel (if set)
+    ///
+    /// It blocks until there is a free space in the channel.
+    fn send(&self, output: &mut Vec<Output>) -> Result<(), error::General> {
+        if let Some(sender) = self.sender.as_ref() {
+            for item in mem::take(output) {
+                sender.send(item).map_err(|_| {
+                    io::Error::new(io::ErrorKind::BrokenPipe, "Output channel is disconnected")
+                })?;
             }
-            Ok(res)
-        } else {
-            Err(error::InputTerminated::new(self.input_start).into())
         }
+        Ok(())
     }
 
-    fn json_finished(&mut self) -> Result<Vec<Output>, error::General> {
-        let mut res = vec![];
-        for (_, handler) in &self.matchers {
-            if let Some(handler) = handler {
-                let output = handler.lock().unwrap().json_finished()?;
-                if let Some(data) = output {
-                    res.push(Output::Data(data));
-                }
+    /// Updates the peak of buffered data
+    fn update_peak(&mut self) {
+        let pending_value_len = self.pending_value.as_ref().map_or(0, |e| e.data.len());
+        self.stats
+            .update_peak(self.streamer.pending_len() + pending_value_len);
+    }
+
+    /// Finishes matching of a leaf element which value was needed
+    fn end_pending(
+        &mut self,
+        pending: PendingValue,
+        end_token: Token,
+        result: &mut Vec<Output>,
+    ) -> Result<(), error::General> {
+        let end_kind = if let Token::End(_, kind) = end_token {
+            kind
+        } else {
+            unreachable!();
+        };
+        let path = self.streamer.current_path().clone();
+        let matched_indexes: Vec<usize> = (0..self.matchers.len())
+            .filter(|idx| {
+                pending.matched_indexes.contains(idx)
+                    || (pending.deferred_indexes.contains(idx)
+                        && self.matchers[*idx]
+                            .0
+                            .match_value(&path, end_kind, &pending.data))
+            })
+            .collect();
+        if matched_indexes.is_empty() {
+            return Ok(());
+        }
+
+        result.push(Output::Start(if self.export_path {
+            Some(path.clone())
+        } else {
+            None
+        }));
+        result.push(Output::Data(pending.data.clone()));
+        result.push(Output::End);
+        if let Some(count) = self.match_count.as_mut() {
+            *count += 1;
+        }
+
+        for matcher_idx in matched_indexes {
+            if let Some(remaining) = self.remaining[matcher_idx].as_mut() {
+                *remaining -= 1;
+            }
+            self.stats.matched(matcher_idx);
+            if let Some(handler) = self.matchers[matcher_idx].1.as_ref() {
+                let mut guard = handler.lock().unwrap();
+                guard.start(&path, matcher_idx, Token::Start(pending.idx, pending.kind))?;
+                guard.feed(&pending.data, matcher_idx)?;
+                guard.end(&path, matcher_idx, end_token.clone())?;
             }
         }
-        Ok(res)
+        Ok(())
     }
-}
 
-impl Extract {
     /// Creates a new `Extract`
     ///
     /// It exracts matched data parts (not nested)
@@ -173,6 +393,69 @@ impl Extract {
         Self::default()
     }
 
+    /// Sets strict mode
+    ///
+    /// Invalid JSON input (e.g. `[01]` or `[1,]`) is rejected in strict mode.
+    ///
+    /// # Arguments
+    /// * `strict` - whether the input should be validated
+    pub fn set_strict(&mut self, strict: bool) {
+        self.streamer.set_strict(strict);
+    }
+
+    /// Sets the format of the input data
+    ///
+    /// With `InputFormat::JsonLines` each line is processed as a separate JSON
+    /// (see `Streamer::set_input_format`).
+    ///
+    /// # Arguments
+    /// * `format` - format of the input data
+    pub fn set_input_format(&mut self, format: InputFormat) {
+        self.streamer.set_input_format(format);
+    }
+
+    /// Sets whether the lines with an incorrect input are skipped
+    ///
+    /// The extraction of an invalid line stops at the incorrect character
+    /// and an unfinished match is closed by `Output::Incomplete`
+    /// (see `Streamer::set_skip_invalid`). Its handlers are not notified.
+    ///
+    /// # Arguments
+    /// * `skip` - whether invalid lines should be skipped
+    pub fn set_skip_invalid(&mut self, skip: bool) {
+        self.streamer.set_skip_invalid(skip);
+    }
+
+    /// Sets limits of the input data
+    ///
+    /// Input which exceeds the limits (e.g. which is nested too deeply)
+    /// is rejected (see `Streamer::set_limits`).
+    ///
+    /// # Arguments
+    /// * `limits` - limits of the input data
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.streamer.set_limits(limits);
+    }
+
+    /// Sets a callback which is triggered for each token read
+    ///
+    /// It can be used for debugging e.g. to find out why
+    /// a matcher doesn't match.
+    ///
+    /// # Arguments
+    /// * `trace` - function called with a token and the current path
+    pub fn set_trace(&mut self, trace: TraceCallback) {
+        self.trace = Some(trace);
+    }
+
+    /// Returns metrics collected during the processing (see `Stats`)
+    ///
+    /// Buffered bytes include the data which were not processed yet
+    /// and leaf values which are kept until the matchers which need them decide.
+    pub fn metrics(&self) -> Metrics {
+        Metrics::from(&self.stats)
+    }
+
     /// Sets whether matched path should be exported with data
     /// Output data will be enriched with the path from were the data
     /// were extracted
@@ -183,6 +466,47 @@ impl Extract {
         self
     }
 
+    /// Sets a bounded channel where the output will be sent
+    ///
+    /// The output is sent as soon as it is available and `process`
+    /// returns no output in that case. When the channel is full,
+    /// processing is blocked until the consumer receives some output.
+    ///
+    /// # Arguments
+    /// * `sender` - sending part of the channel (see `std::sync::mpsc::sync_channel`)
+    pub fn set_channel(mut self, sender: SyncSender<Output>) -> Self {
+        self.sender = Some(sender);
+        self
+    }
+
+    /// Enables counting of the extracted matches
+    ///
+    /// The count is available via `Extract::match_count`
+    /// so no separate handler is required to count the matches.
+    ///
+    /// # Example
+    /// ```
+    /// use streamson_lib::{matcher, strategy::{self, Strategy}};
+    ///
+    /// let mut extract = strategy::Extract::new().with_counter();
+    /// extract.add_matcher(Box::new(matcher::Simple::new(r#"[]"#).unwrap()), None);
+    /// extract.process(b"[1, 2, 3]").unwrap();
+    /// assert_eq!(extract.match_count(), Some(3));
+    /// ```
+    pub fn with_counter(mut self) -> Self {
+        self.match_count = Some(0);
+        self
+    }
+
+    /// Returns the number of extracted matches
+    ///
+    /// # Returns
+    /// * `None` - counting is not enabled (see `Extract::with_counter`)
+    /// * `Some(count)` - number of matches extracted so far
+    pub fn match_count(&self) -> Option<usize> {
+        self.match_count
+    }
+
     /// Adds new matcher for data extraction
     ///
     /// # Arguments
@@ -208,7 +532,81 @@ impl Extract {
         matcher: Box<dyn Matcher>,
         handler: Option<Arc<Mutex<dyn Handler>>>,
     ) {
+        self.index.add(self.matchers.len(), matcher.as_ref());
         self.matchers.push((matcher, handler));
+        self.remaining.push(None);
+    }
+
+    /// Adds a matcher which stops matching after a number of matches
+    ///
+    /// # Arguments
+    /// * `matcher` - matcher which matches the path
+    /// * `handler` - optinal handler to be used to process data
+    /// * `limit` - max number of matches
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use streamson_lib::{strategy, matcher};
+    ///
+    /// let mut extract = strategy::Extract::new();
+    /// let matcher = matcher::Simple::new(r#"{"list"}[]"#).unwrap();
+    /// // only the first 10 items are extracted
+    /// extract.add_matcher_limited(Box::new(matcher), None, 10);
+    /// ```
+    pub fn add_matcher_limited(
+        &mut self,
+        matcher: Box<dyn Matcher>,
+        handler: Option<Arc<Mutex<dyn Handler>>>,
+        limit: usize,
+    ) {
+        self.index.add(self.matchers.len(), matcher.as_ref());
+        self.matchers.push((matcher, handler));
+        self.remaining.push(Some(limit));
+    }
+
+    /// Adds a matcher with multiple handlers
+    ///
+    /// Each handler receives the same matched data,
+    /// no handler is used when `handlers` is empty
+    /// (handlers are wrapped in a fan-out `handler::Group`).
+    ///
+    /// # Arguments
+    /// * `matcher` - matcher which matches the path
+    /// * `handlers` - handlers which will be triggered on a match
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use streamson_lib::{strategy, matcher, handler};
+    /// use std::{io, sync::{Arc, Mutex}};
+    ///
+    /// let mut extract = strategy::Extract::new();
+    /// let matcher = matcher::Simple::new(r#"{"list"}[]"#).unwrap();
+    /// extract.add_matcher_handlers(
+    ///     Box::new(matcher),
+    ///     &[
+    ///         Arc::new(Mutex::new(handler::Output::new(io::stdout()))),
+    ///         Arc::new(Mutex::new(handler::Buffer::new())),
+    ///     ],
+    /// );
+    /// ```
+    pub fn add_matcher_handlers(
+        &mut self,
+        matcher: Box<dyn Matcher>,
+        handlers: &[Arc<Mutex<dyn Handler>>],
+    ) {
+        let handler: Option<Arc<Mutex<dyn Handler>>> = if handlers.is_empty() {
+            None
+        } else {
+            let group = handlers
+                .iter()
+                .fold(Group::new().fan_out(), |group, handler| {
+                    group.add_handler(handler.clone())
+                });
+            Some(Arc::new(Mutex::new(group)))
+        };
+        self.add_matcher(matcher, handler);
     }
 }
 
@@ -217,14 +615,21 @@ mod tests {
     use super::{Extract, Output, Strategy};
     use crate::{
         handler::Buffer,
-        matcher::Simple,
+        matcher::{number_compare::Op, Depth, NumberCompare, Simple},
         path::Path,
-        test::{Single, Splitter, Window},
+        strategy::OutputConverter,
+        streamer::InputFormat,
+        testing::{Single, Splitter, Window},
     };
     use rstest::*;
     use std::{
         convert::TryFrom,
-        sync::{Arc, Mutex},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            mpsc, Arc, Mutex,
+        },
+        thread,
+        time::Duration,
     };
 
     fn get_input() -> Vec<u8> {
@@ -371,4 +776,171 @@ mod tests {
             assert_eq!(String::from_utf8(res).unwrap(), r#""fred""bob""admins""#)
         }
     }
+
+    #[test]
+    fn skip_invalid() {
+        let input = b"{\"a\": [1, 2]}\n{\"a\": [3, x]}\n{\"a\": 4}\n";
+        for size in 1..input.len() {
+            let mut extract = Extract::new();
+            extract.set_input_format(InputFormat::JsonLines);
+            extract.set_skip_invalid(true);
+            extract.add_matcher(Box::new(Simple::new(r#"{"a"}"#).unwrap()), None);
+
+            let mut converter = OutputConverter::new();
+            let mut res = vec![];
+            for part in input.chunks(size) {
+                let output = extract.process(part).unwrap();
+                res.extend(converter.convert(&output).into_iter().map(|e| e.1));
+            }
+            extract.terminate().unwrap();
+            assert_eq!(
+                res,
+                vec![b"[1, 2]".to_vec(), b"[3, ".to_vec(), b"4".to_vec()],
+                "chunk size {}",
+                size
+            );
+        }
+    }
+
+    #[test]
+    fn process_final() {
+        let mut extract = Extract::new();
+        extract.add_matcher(Box::new(Depth::new(0, Some(0))), None);
+
+        let mut converter = OutputConverter::new();
+        let output = converter.convert(&extract.process(br#"{"a": 1} 4"#).unwrap());
+        assert_eq!(output, vec![(None, br#"{"a": 1}"#.to_vec())]);
+
+        let output = converter.convert(&extract.process_final(b"2").unwrap());
+        assert_eq!(output, vec![(None, b"42".to_vec())]);
+        assert!(extract.terminate().is_ok());
+    }
+
+    #[test]
+    fn unicode_escaped_key() {
+        let mut extract = Extract::new().set_export_path(true);
+        extract.add_matcher(Box::new(Simple::new(r#"[]{"A"}"#).unwrap()), None);
+
+        let mut converter = OutputConverter::new();
+        let output = converter.convert(
+            &extract
+                .process(br#"[{"\u0041": 1}, {"A": 2}, {"a": 3}]"#)
+                .unwrap(),
+        );
+        assert_eq!(
+            output,
+            vec![
+                (Some(Path::try_from(r#"[0]{"A"}"#).unwrap()), b"1".to_vec()),
+                (Some(Path::try_from(r#"[1]{"A"}"#).unwrap()), b"2".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn channel() {
+        let (sender, receiver) = mpsc::sync_channel(2);
+        let done = Arc::new(AtomicBool::new(false));
+        let done_cloned = done.clone();
+
+        let producer = thread::spawn(move || {
+            let mut extract = Extract::new().set_channel(sender);
+            extract.add_matcher(Box::new(Simple::new(r#"[]"#).unwrap()), None);
+            let records: Vec<String> = (0..100).map(|e| e.to_string()).collect();
+            let input = format!("[{}]", records.join(", "));
+            for part in input.as_bytes().chunks(10) {
+                assert!(extract.process(part).unwrap().is_empty());
+            }
+            assert!(extract.terminate().unwrap().is_empty());
+            done_cloned.store(true, Ordering::SeqCst);
+        });
+
+        // producer is blocked until the output is consumed
+        thread::sleep(Duration::from_millis(100));
+        assert!(!done.load(Ordering::SeqCst));
+
+        // slow consumer
+        let mut converter = OutputConverter::new();
+        let mut res = vec![];
+        for output in receiver {
+            thread::sleep(Duration::from_millis(1));
+            res.extend(converter.convert(&[output]).into_iter().map(|e| e.1));
+        }
+        producer.join().unwrap();
+        assert!(done.load(Ordering::SeqCst));
+        assert_eq!(
+            res,
+            (0..100)
+                .map(|e| e.to_string().into_bytes())
+                .collect::<Vec<Vec<u8>>>()
+        );
+    }
+
+    #[test]
+    fn channel_disconnected() {
+        let (sender, receiver) = mpsc::sync_channel(2);
+        drop(receiver);
+        let mut extract = Extract::new().set_channel(sender);
+        extract.add_matcher(Box::new(Simple::new(r#"[]"#).unwrap()), None);
+        assert!(extract.process(b"[1, 2]").is_err());
+    }
+
+    #[test]
+    fn metrics() {
+        let large = format!(
+            r#"{{"small": [1, 2], "large": "{}", "last": 2}}"#,
+            "x".repeat(10000)
+        );
+        let small = format!("[{}]", vec!["1"; 5000].join(", "));
+        let peak = |input: &str| {
+            let mut extract = Extract::new();
+            extract.add_matcher(Box::new(Simple::new(r#"{"large"}"#).unwrap()), None);
+            for part in input.as_bytes().chunks(100) {
+                extract.process(part).unwrap();
+            }
+            extract.metrics().peak_buffer_bytes
+        };
+        // the large string is kept until it ends
+        assert!(peak(&large) >= 10002);
+        assert!(peak(&small) <= 200);
+    }
+
+    #[test]
+    fn counter() {
+        let mut extract = Extract::new();
+        extract.add_matcher(Box::new(Simple::new(r#"[]"#).unwrap()), None);
+        extract.process(b"[1]").unwrap();
+        assert_eq!(extract.match_count(), None);
+
+        let mut extract = Extract::new().with_counter();
+        extract.add_matcher(Box::new(Simple::new(r#"{"a"}[]"#).unwrap()), None);
+        extract.add_matcher(Box::new(NumberCompare::new(Op::Gt, 1.0)), None);
+        let input = br#"{"a": [{"b": 5}, [3], "x"], "c": 7} [0, 2]"#;
+        let mut matches = 0;
+        for part in input.chunks(3) {
+            matches += extract
+                .process(part)
+                .unwrap()
+                .into_iter()
+                .filter(|output| *output == Output::End)
+                .count();
+        }
+        assert_eq!(matches, 5);
+        assert_eq!(extract.match_count(), Some(matches));
+    }
+
+    #[test]
+    fn limited() {
+        let mut extract = Extract::new();
+        extract.add_matcher_limited(Box::new(Simple::new(r#"{"a"}[]"#).unwrap()), None, 2);
+        extract.add_matcher_limited(Box::new(NumberCompare::new(Op::Gt, 10.0)), None, 1);
+        let input = br#"{"a": [1, 2, 3, 4, 5], "b": [11, 12]}"#;
+        let mut output = vec![];
+        let mut converter = OutputConverter::new();
+        for part in input.chunks(3) {
+            for (_, data) in converter.convert(&extract.process(part).unwrap()) {
+                output.push(String::from_utf8(data).unwrap());
+            }
+        }
+        assert_eq!(output, vec!["1", "2", "11"]);
+    }
 }
@@ -11,13 +11,13 @@ use std::{
 
 use crate::{
     error,
-    handler::Handler,
-    matcher::Matcher,
+    handler::{Group, Handler},
+    matcher::{Matcher, MatcherIndex},
     path::Path,
-    streamer::{Streamer, Token},
+    streamer::{InputFormat, Limits, ParsedKind, Streamer, Token},
 };
 
-use super::{Output, Strategy};
+use super::{unique_handlers, Metrics, Output, Stats, Strategy, TraceCallback};
 
 type MatcherItem = (Box<dyn Matcher>, Option<Arc<Mutex<dyn Handler>>>);
 
@@ -33,12 +33,26 @@ pub struct Filter {
     streamer: Streamer,
     /// Matchers which will cause filtering
     matchers: Vec<MatcherItem>,
-    /// What is currently matched - path and indexes to matchers
-    matches: Option<(Path, Vec<usize>)>,
+    /// Index of the matchers
+    index: MatcherIndex,
+    /// Leaf element (idx, kind) which matching depends on its value
+    pending_value: Option<(usize, ParsedKind)>,
+    /// Stack of current matches - path and indexes to matchers
+    ///
+    /// The first item is the removed element, the others are nested within it
+    matches: Vec<(Path, Vec<usize>)>,
     /// Path which data were written to stream for the last time
     last_streaming_path: Option<Path>,
     /// Current json level
     level: usize,
+    /// Total idx of the rest of an invalid line which is to be removed from the buffer
+    discarded: Option<usize>,
+    /// Statistics of the processing
+    stats: Stats,
+    /// Handler which receives the data which were not filtered out
+    observer: Option<Arc<Mutex<dyn Handler>>>,
+    /// Callback triggered for each token read
+    trace: Option<TraceCallback>,
 }
 
 impl Default for Filter {
@@ -48,99 +62,188 @@ impl Default for Filter {
             buffer_idx: 0,
             buffer: VecDeque::new(),
             matchers: vec![],
+            index: MatcherIndex::new(),
+            pending_value: None,
             streamer: Streamer::new(),
-            matches: None,
+            matches: vec![],
             last_streaming_path: None,
             level: 0,
+            discarded: None,
+            stats: Stats::default(),
+            observer: None,
+            trace: None,
         }
     }
 }
 
 impl Strategy for Filter {
     fn process(&mut self, input: &[u8]) -> Result<Vec<Output>, error::General> {
+        let result = self.process_input(input);
+        if result.is_err() {
+            self.stats.error_path = Some(self.streamer.current_path().clone());
+        }
+        result
+    }
+
+    fn finish_input(&mut self) {
+        // input itself is fed in process
+        self.streamer.feed_final(&[]);
+    }
+
+    fn terminate(&mut self) -> Result<Vec<Output>, error::General> {
+        if self.level == 0 {
+            let mut res = vec![];
+            for handler in unique_handlers(self.matchers.iter().filter_map(|e| e.1.as_ref())) {
+                let output = handler.lock().unwrap().input_finished()?;
+                if let Some(data) = output {
+                    res.push(Output::Data(data));
+                }
+            }
+            Ok(res)
+        } else {
+            Err(error::InputTerminated::new(self.input_start).into())
+        }
+    }
+
+    fn json_finished(&mut self) -> Result<Vec<Output>, error::General> {
+        let mut res = vec![];
+        for (matcher, _) in &self.matchers {
+            matcher.json_finished();
+        }
+        for handler in unique_handlers(self.matchers.iter().filter_map(|e| e.1.as_ref())) {
+            let output = handler.lock().unwrap().json_finished()?;
+            if let Some(data) = output {
+                res.push(Output::Data(data));
+            }
+        }
+        Ok(res)
+    }
+
+    fn stats(&self) -> Stats {
+        self.stats.with_bytes(self.input_start, self.matchers.len())
+    }
+}
+
+impl Filter {
+    /// Create new filter
+    ///
+    /// It removes matched parts of the input
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Processes input data (see `Strategy::process`)
+    fn process_input(&mut self, input: &[u8]) -> Result<Vec<Output>, error::General> {
         // Feed the streamer
         self.streamer.feed(input);
 
         // Feed the input buffer
         self.buffer.extend(input);
+        self.stats
+            .update_peak(self.buffer.len() + self.streamer.pending_len());
 
         // initialize result
         let mut result = Vec::new();
 
-        // Finish skip
+        // top level tokens for the observer
+        let mut top_level_tokens = Vec::new();
 
         loop {
-            match self.streamer.read()? {
+            let token = self.streamer.read()?;
+            if let Some(trace) = self.trace.as_mut() {
+                trace(&token, self.streamer.current_path());
+            }
+            match token {
                 Token::Start(idx, kind) => {
+                    self.skip_line(idx);
                     if self.level == 0 {
                         result.push(Output::Start(None));
+                        top_level_tokens.push(Token::Start(idx, kind));
                     }
                     self.level += 1;
-                    if let Some((path, matched_indexes)) = self.matches.take() {
-                        let data = self.move_forward(idx);
-                        self.feed_handlers(&matched_indexes, data)?;
-                        self.matches = Some((path, matched_indexes));
-                    } else {
-                        // The path is not matched yet
-                        let current_path = self.streamer.current_path().clone();
+                    let current_path = self.streamer.current_path().clone();
+                    let leaf = !matches!(kind, ParsedKind::Obj | ParsedKind::Arr);
 
-                        // Try to match current path
-                        let matcher_indexes: Vec<usize> = self
+                    if leaf
+                        && self
                             .matchers
                             .iter()
-                            .enumerate()
-                            .map(|(idx, matcher)| (idx, matcher.0.match_path(&current_path, kind)))
-                            .filter(|(_, matched)| *matched)
-                            .map(|(idx, _)| idx)
-                            .collect();
-
-                        if !matcher_indexes.is_empty() {
-                            // Trigger handlers start
-                            self.start_handlers(
-                                &current_path,
-                                &matcher_indexes,
-                                Token::Start(idx, kind),
-                            )?;
-                            self.matches = Some((current_path, matcher_indexes));
+                            .any(|(matcher, _)| matcher.needs_value())
+                    {
+                        // data are kept in the buffer until the entire value is read
+                        self.pending_value = Some((idx, kind));
+                        continue;
+                    }
+
+                    // Try to match current path
+                    let matcher_indexes: Vec<usize> = self
+                        .index
+                        .candidates(current_path.get_path(), kind)
+                        .into_iter()
+                        .filter(|idx| self.matchers[*idx].0.match_path(&current_path, kind))
+                        .collect();
+
+                    if !self.matches.is_empty() {
+                        // within removed data
+                        let data = self.move_forward(idx);
+                        self.feed_matches(&data)?;
+                    }
+
+                    if !matcher_indexes.is_empty() {
+                        // Trigger handlers start
+                        self.start_handlers(
+                            &current_path,
+                            &matcher_indexes,
+                            Token::Start(idx, kind),
+                        )?;
+                        if self.matches.is_empty() {
                             self.move_forward(idx); // discard e.g. '"key": '
-                        } else {
-                            // no match here -> extend output
-                            self.last_streaming_path = Some(current_path);
-                            result
-                                .push(Output::Data(self.move_forward(idx + 1).drain(..).collect()));
                         }
+                        self.matches.push((current_path, matcher_indexes));
+                    } else if self.matches.is_empty() {
+                        // no match here -> extend output
+                        self.last_streaming_path = Some(current_path);
+                        result.push(Output::Data(self.move_forward(idx + 1).drain(..).collect()));
                     }
                 }
                 Token::End(idx, kind) => {
                     self.level -= 1;
-                    if let Some((path, matched_indexes)) = self.matches.take() {
+                    if let Some((start_idx, start_kind)) = self.pending_value.take() {
+                        if let Some(data) =
+                            self.end_pending(start_idx, start_kind, Token::End(idx, kind))?
+                        {
+                            result.push(Output::Data(data));
+                        }
+                    } else if !self.matches.is_empty() {
                         // Trigger handler feed
                         let data = self.move_forward(idx);
-                        self.feed_handlers(&matched_indexes, data)?;
+                        self.feed_matches(&data)?;
 
-                        if &path == self.streamer.current_path() {
+                        if &self.matches.last().unwrap().0 == self.streamer.current_path() {
                             // Trigger handlers end
+                            let (path, matched_indexes) = self.matches.pop().unwrap();
                             self.end_handlers(&path, &matched_indexes, Token::End(idx, kind))?;
-                        } else {
-                            self.matches = Some((path, matched_indexes));
                         }
                     } else {
                         self.last_streaming_path = Some(self.streamer.current_path().clone());
                         result.push(Output::Data(self.move_forward(idx).drain(..).collect()));
                     }
                     if self.level == 0 {
+                        self.stats.documents += 1;
                         let json_finished_data = self.json_finished()?;
                         if !json_finished_data.is_empty() {
                             result.extend(json_finished_data);
                         }
                         result.push(Output::End);
+                        top_level_tokens.push(Token::End(idx, kind));
                     }
                 }
                 Token::Pending => {
                     self.input_start += input.len();
+                    self.observe(&result, top_level_tokens)?;
                     return Ok(result);
                 }
-                Token::Separator(idx) => {
+                Token::Separator(idx, _) => {
                     if let Some(path) = self.last_streaming_path.as_ref() {
                         if self.streamer.current_path() == path {
                             // removing ',' if the first record from array / object was deleted
@@ -148,47 +251,89 @@ impl Strategy for Filter {
                         }
                     }
                 }
-            }
-        }
-    }
-
-    fn terminate(&mut self) -> Result<Vec<Output>, error::General> {
-        if self.level == 0 {
-            let mut res = vec![];
-            for (_, handler) in &self.matchers {
-                if let Some(handler) = handler {
-                    let output = handler.lock().unwrap().input_finished()?;
-                    if let Some(data) = output {
-                        res.push(Output::Data(data));
+                Token::Discarded(idx) => {
+                    // previous line might be invalid as well
+                    self.skip_line(idx);
+                    if self.level > 0 {
+                        let data = self.move_forward(idx);
+                        if self.matches.is_empty() {
+                            result.push(Output::Data(data.into_iter().collect()));
+                        }
+                        result.push(Output::Incomplete);
                     }
+                    self.discarded = Some(idx);
+                    self.level = 0;
+                    self.matches.clear();
+                    self.pending_value = None;
+                    self.last_streaming_path = None;
                 }
+                Token::Finished(_) => {}
             }
-            Ok(res)
-        } else {
-            Err(error::InputTerminated::new(self.input_start).into())
         }
     }
 
-    fn json_finished(&mut self) -> Result<Vec<Output>, error::General> {
-        let mut res = vec![];
-        for (_, handler) in &self.matchers {
-            if let Some(handler) = handler {
-                let output = handler.lock().unwrap().json_finished()?;
-                if let Some(data) = output {
-                    res.push(Output::Data(data));
-                }
-            }
-        }
-        Ok(res)
+    /// Sets strict mode
+    ///
+    /// Invalid JSON input (e.g. `[01]` or `[1,]`) is rejected in strict mode.
+    ///
+    /// # Arguments
+    /// * `strict` - whether the input should be validated
+    pub fn set_strict(&mut self, strict: bool) {
+        self.streamer.set_strict(strict);
     }
-}
 
-impl Filter {
-    /// Create new filter
+    /// Sets the format of the input data
     ///
-    /// It removes matched parts of the input
-    pub fn new() -> Self {
-        Self::default()
+    /// With `InputFormat::JsonLines` each line is processed as a separate JSON
+    /// (see `Streamer::set_input_format`).
+    ///
+    /// # Arguments
+    /// * `format` - format of the input data
+    pub fn set_input_format(&mut self, format: InputFormat) {
+        self.streamer.set_input_format(format);
+    }
+
+    /// Sets whether the lines with an incorrect input are skipped
+    ///
+    /// The data of an invalid line are written to the output only until
+    /// the incorrect character, then the output is closed by `Output::Incomplete`
+    /// and the rest of the line is removed (see `Streamer::set_skip_invalid`).
+    /// Handlers of the unfinished matches are not notified.
+    ///
+    /// # Arguments
+    /// * `skip` - whether invalid lines should be skipped
+    pub fn set_skip_invalid(&mut self, skip: bool) {
+        self.streamer.set_skip_invalid(skip);
+    }
+
+    /// Sets limits of the input data
+    ///
+    /// Input which exceeds the limits (e.g. which is nested too deeply)
+    /// is rejected (see `Streamer::set_limits`).
+    ///
+    /// # Arguments
+    /// * `limits` - limits of the input data
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.streamer.set_limits(limits);
+    }
+
+    /// Sets a callback which is triggered for each token read
+    ///
+    /// It can be used for debugging e.g. to find out why
+    /// a matcher doesn't match.
+    ///
+    /// # Arguments
+    /// * `trace` - function called with a token and the current path
+    pub fn set_trace(&mut self, trace: TraceCallback) {
+        self.trace = Some(trace);
+    }
+
+    /// Returns metrics collected during the processing (see `Stats`)
+    ///
+    /// Buffered bytes include the data which were not processed yet
+    /// and the data which are kept until it is known whether they are filtered.
+    pub fn metrics(&self) -> Metrics {
+        Metrics::from(&self.stats)
     }
 
     /// Split working buffer and return the removed part
@@ -206,6 +351,24 @@ impl Filter {
         splitted
     }
 
+    /// Removes the rest of an invalid line from the buffer (see `set_skip_invalid`)
+    ///
+    /// The data before the invalid line are kept.
+    ///
+    /// # Arguments
+    /// * `idx` - total idx of the data which follow the line
+    fn skip_line(&mut self, idx: usize) {
+        if let Some(from) = self.discarded.take() {
+            let kept = self.move_forward(from);
+            let end = self.buffer.iter().position(|e| *e == b'\n');
+            self.move_forward(end.map_or(idx, |e| from + e + 1));
+            self.buffer_idx -= kept.len();
+            for byte in kept.into_iter().rev() {
+                self.buffer.push_front(byte);
+            }
+        }
+    }
+
     /// Adds new matcher into filtering
     ///
     /// # Arguments
@@ -230,15 +393,179 @@ impl Filter {
         matcher: Box<dyn Matcher>,
         handler: Option<Arc<Mutex<dyn Handler>>>,
     ) {
+        self.index.add(self.matchers.len(), matcher.as_ref());
         self.matchers.push((matcher, handler));
     }
 
-    fn start_handlers(
+    /// Adds a matcher with multiple handlers
+    ///
+    /// Each handler receives the same matched data,
+    /// no handler is used when `handlers` is empty
+    /// (handlers are wrapped in a fan-out `handler::Group`).
+    ///
+    /// # Arguments
+    /// * `matcher` - matcher which matches the path
+    /// * `handlers` - handlers which will be triggered on a match
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use streamson_lib::{strategy, matcher, handler};
+    /// use std::{io, sync::{Arc, Mutex}};
+    ///
+    /// let mut filter = strategy::Filter::new();
+    /// let matcher = matcher::Simple::new(r#"{"list"}[]"#).unwrap();
+    /// filter.add_matcher_handlers(
+    ///     Box::new(matcher),
+    ///     &[
+    ///         Arc::new(Mutex::new(handler::Output::new(io::stdout()))),
+    ///         Arc::new(Mutex::new(handler::Buffer::new())),
+    ///     ],
+    /// );
+    /// ```
+    pub fn add_matcher_handlers(
+        &mut self,
+        matcher: Box<dyn Matcher>,
+        handlers: &[Arc<Mutex<dyn Handler>>],
+    ) {
+        let handler: Option<Arc<Mutex<dyn Handler>>> = if handlers.is_empty() {
+            None
+        } else {
+            let group = handlers
+                .iter()
+                .fold(Group::new().fan_out(), |group, handler| {
+                    group.add_handler(handler.clone())
+                });
+            Some(Arc::new(Mutex::new(group)))
+        };
+        self.add_matcher(matcher, handler);
+    }
+
+    /// Sets a handler which observes the data which were not filtered out
+    ///
+    /// Observer is fed with exactly the same data as the filter outputs.
+    /// Its output is ignored.
+    ///
+    /// # Arguments
+    /// * `observer` - handler which receives kept data (`None` to unset)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use streamson_lib::{strategy, handler};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let mut filter = strategy::Filter::new();
+    /// let observer = Arc::new(Mutex::new(handler::Buffer::new()));
+    /// filter.set_observer(Some(observer));
+    /// ```
+    pub fn set_observer(&mut self, observer: Option<Arc<Mutex<dyn Handler>>>) {
+        self.observer = observer;
+    }
+
+    /// Passes the output to the observer
+    ///
+    /// # Arguments
+    /// * `output` - output of the filter
+    /// * `top_level_tokens` - start and end tokens of top level elements in the output
+    fn observe(
         &self,
+        output: &[Output],
+        top_level_tokens: Vec<Token>,
+    ) -> Result<(), error::General> {
+        let observer = if let Some(observer) = self.observer.as_ref() {
+            observer
+        } else {
+            return Ok(());
+        };
+        let mut guard = observer.lock().unwrap();
+        let path = Path::new();
+        let mut tokens = top_level_tokens.into_iter();
+        for item in output {
+            match item {
+                Output::Start(_) => {
+                    guard.start(&path, 0, tokens.next().unwrap())?;
+                }
+                Output::Data(data) => {
+                    guard.feed(data, 0)?;
+                }
+                Output::End => {
+                    guard.end(&path, 0, tokens.next().unwrap())?;
+                }
+                // filter doesn't flush incomplete data
+                Output::Incomplete => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Finishes matching of a leaf element which value was needed
+    ///
+    /// # Returns
+    /// * `None` - element was filtered out
+    /// * `Some(data)` - data to be written to output
+    fn end_pending(
+        &mut self,
+        start_idx: usize,
+        kind: ParsedKind,
+        end_token: Token,
+    ) -> Result<Option<Vec<u8>>, error::General> {
+        let (end_idx, end_kind) = if let Token::End(idx, kind) = end_token {
+            (idx, kind)
+        } else {
+            unreachable!();
+        };
+        let path = self.streamer.current_path().clone();
+        let value: Vec<u8> = self
+            .buffer
+            .range(start_idx - self.buffer_idx..end_idx - self.buffer_idx)
+            .copied()
+            .collect();
+
+        let matched_indexes: Vec<usize> = self
+            .matchers
+            .iter()
+            .enumerate()
+            .filter(|(_, (matcher, _))| {
+                if matcher.needs_value() {
+                    matcher.match_value(&path, end_kind, &value)
+                } else {
+                    matcher.match_path(&path, kind)
+                }
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+
+        if matched_indexes.is_empty() {
+            if self.matches.is_empty() {
+                self.last_streaming_path = Some(path);
+                Ok(Some(self.move_forward(end_idx).drain(..).collect()))
+            } else {
+                // data are fed to the current matches later
+                Ok(None)
+            }
+        } else {
+            let data = self.move_forward(start_idx); // discard e.g. '"key": '
+            self.feed_matches(&data)?;
+            self.start_handlers(&path, &matched_indexes, Token::Start(start_idx, kind))?;
+            self.matches.push((path, matched_indexes));
+            let data = self.move_forward(end_idx);
+            self.feed_matches(&data)?;
+            let (path, matched_indexes) = self.matches.pop().unwrap();
+            self.end_handlers(&path, &matched_indexes, end_token)?;
+            Ok(None)
+        }
+    }
+
+    fn start_handlers(
+        &mut self,
         path: &Path,
         matched_indexes: &[usize],
         token: Token,
     ) -> Result<(), error::General> {
+        for matcher_idx in matched_indexes {
+            self.stats.matched(*matcher_idx);
+        }
         for (matcher_idx, handler) in matched_indexes
             .iter()
             .filter(|idx| self.matchers[**idx].1.is_some())
@@ -250,10 +577,18 @@ impl Filter {
         Ok(())
     }
 
+    /// Feeds handlers of all current matches
+    fn feed_matches(&self, data: &VecDeque<u8>) -> Result<(), error::General> {
+        for (_, matched_indexes) in &self.matches {
+            self.feed_handlers(matched_indexes, data)?;
+        }
+        Ok(())
+    }
+
     fn feed_handlers(
         &self,
         matched_indexes: &[usize],
-        data: VecDeque<u8>,
+        data: &VecDeque<u8>,
     ) -> Result<(), error::General> {
         let (first, second) = data.as_slices();
         for (matcher_idx, handler) in matched_indexes
@@ -291,11 +626,17 @@ impl Filter {
 mod tests {
     use super::{Filter, Strategy};
     use crate::{
-        matcher::{Combinator, Simple},
+        handler::Buffer,
+        matcher::{Combinator, NumberCompare, Simple},
         strategy::OutputConverter,
-        test::{Single, Splitter, Window},
+        streamer::InputFormat,
+        testing::{Single, Splitter, Window},
     };
     use rstest::*;
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
 
     fn get_input() -> Vec<u8> {
         br#"{"users": [{"uid": 1}, {"uid": 2}, {"uid": 3}], "groups": [{"gid": 1}, {"gid": 2}], "void": {}}"#
@@ -497,6 +838,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn skip_invalid() {
+        let input = b"{\"a\": 1, \"b\": 2}\nx\n{\"a\": 3, \"b\": [x]}\n x\n{\"a\": 5, \"b\": 6}\n";
+        for size in 1..input.len() {
+            let mut filter = Filter::new();
+            filter.set_input_format(InputFormat::JsonLines);
+            filter.set_skip_invalid(true);
+            filter.add_matcher(Box::new(Simple::new(r#"{"a"}"#).unwrap()), None);
+
+            let mut converter = OutputConverter::new();
+            let mut res = vec![];
+            for part in input.chunks(size) {
+                let output = filter.process(part).unwrap();
+                res.extend(converter.convert(&output).into_iter().map(|e| e.1));
+            }
+            filter.terminate().unwrap();
+            assert_eq!(
+                res,
+                vec![
+                    br#"{ "b": 2}"#.to_vec(),
+                    b"\n{ \"b\": [".to_vec(),
+                    b" { \"b\": 6}".to_vec()
+                ],
+                "chunk size {}",
+                size
+            );
+        }
+    }
+
     #[rstest(
         splitter,
         case::single(Box::new(Single::new())),
@@ -530,4 +900,203 @@ mod tests {
             )
         }
     }
+
+    #[rstest(
+        splitter,
+        case::single(Box::new(Single::new())),
+        case::window1(Box::new(Window::new(1))),
+        case::window5(Box::new(Window::new(5))),
+        case::window100(Box::new(Window::new(100)))
+    )]
+    fn value_matcher(splitter: Box<dyn Splitter>) {
+        let input =
+            br#"{"users": [{"uid": 1, "age": 30}, {"uid": 2, "age": 10}], "count": 2}"#.to_vec();
+        for parts in splitter.split(input) {
+            let matcher = Combinator::new(NumberCompare::from_str("<3").unwrap());
+            let mut filter = Filter::new();
+            filter.add_matcher(Box::new(matcher), None);
+            let mut result: Vec<u8> = Vec::new();
+
+            let mut converter = OutputConverter::new();
+            for part in parts {
+                result.extend(
+                    converter
+                        .convert(&filter.process(&part).unwrap())
+                        .into_iter()
+                        .flat_map(|e| e.1)
+                        .collect::<Vec<u8>>(),
+                );
+            }
+            assert_eq!(
+                String::from_utf8(result).unwrap(),
+                r#"{"users": [{ "age": 30}, { "age": 10}]}"#
+            )
+        }
+    }
+
+    #[rstest(
+        splitter,
+        case::single(Box::new(Single::new())),
+        case::window1(Box::new(Window::new(1))),
+        case::window5(Box::new(Window::new(5))),
+        case::window100(Box::new(Window::new(100)))
+    )]
+    fn observer(splitter: Box<dyn Splitter>) {
+        let mut input = get_input();
+        input.extend(br#" [1, 2]"#);
+        for parts in splitter.split(input) {
+            let matcher = Simple::new(r#"{"users"}[1]"#).unwrap();
+            let observer = Arc::new(Mutex::new(Buffer::new().set_use_path(false)));
+            let mut filter = Filter::new();
+            filter.add_matcher(Box::new(matcher), None);
+            filter.set_observer(Some(observer.clone()));
+
+            let mut result: Vec<u8> = Vec::new();
+            let mut converter = OutputConverter::new();
+            for part in parts {
+                result.extend(
+                    converter
+                        .convert(&filter.process(&part).unwrap())
+                        .into_iter()
+                        .flat_map(|e| e.1)
+                        .collect::<Vec<u8>>(),
+                );
+            }
+            assert_eq!(
+                String::from_utf8(result).unwrap(),
+                r#"{"users": [{"uid": 1}, {"uid": 3}], "groups": [{"gid": 1}, {"gid": 2}], "void": {}} [1, 2]"#
+            );
+
+            // observer sees the same data per document
+            let mut guard = observer.lock().unwrap();
+            assert_eq!(
+                guard.pop().unwrap(),
+                (
+                    None,
+                    br#"{"users": [{"uid": 1}, {"uid": 3}], "groups": [{"gid": 1}, {"gid": 2}], "void": {}}"#.to_vec()
+                )
+            );
+            assert_eq!(guard.pop().unwrap(), (None, br#" [1, 2]"#.to_vec()));
+            assert!(guard.pop().is_none());
+        }
+    }
+
+    #[rstest(
+        splitter,
+        case::single(Box::new(Single::new())),
+        case::window1(Box::new(Window::new(1))),
+        case::window5(Box::new(Window::new(5))),
+        case::window100(Box::new(Window::new(100)))
+    )]
+    fn nested(splitter: Box<dyn Splitter>) {
+        let input = br#"{"users": [{"uid": 1}, 2, [3]], "groups": [{"gid": 1}]} {"users": [4, 5]}"#
+            .to_vec();
+        for parts in splitter.split(input) {
+            let array = Arc::new(Mutex::new(Buffer::new().set_use_path(true)));
+            let elements = Arc::new(Mutex::new(Buffer::new().set_use_path(true)));
+            let mut filter = Filter::new();
+            filter.add_matcher(
+                Box::new(Simple::new(r#"{"users"}"#).unwrap()),
+                Some(array.clone()),
+            );
+            filter.add_matcher(
+                Box::new(
+                    Combinator::new(Simple::new(r#"{}[]"#).unwrap())
+                        & !Combinator::new(NumberCompare::from_str("==5").unwrap()),
+                ),
+                Some(elements.clone()),
+            );
+            let mut result: Vec<u8> = Vec::new();
+
+            let mut converter = OutputConverter::new();
+            for part in parts {
+                result.extend(
+                    converter
+                        .convert(&filter.process(&part).unwrap())
+                        .into_iter()
+                        .flat_map(|e| e.1)
+                        .collect::<Vec<u8>>(),
+                );
+            }
+            assert_eq!(String::from_utf8(result).unwrap(), r#"{ "groups": []} {}"#);
+
+            // the removed arrays
+            let mut guard = array.lock().unwrap();
+            assert_eq!(
+                guard.pop().unwrap(),
+                (
+                    Some(r#"{"users"}"#.to_string()),
+                    br#"[{"uid": 1}, 2, [3]]"#.to_vec()
+                )
+            );
+            assert_eq!(
+                guard.pop().unwrap(),
+                (Some(r#"{"users"}"#.to_string()), br#"[4, 5]"#.to_vec())
+            );
+            assert!(guard.pop().is_none());
+
+            // elements nested within the removed arrays
+            let mut guard = elements.lock().unwrap();
+            let expected: Vec<(Option<String>, Vec<u8>)> = vec![
+                (
+                    Some(r#"{"users"}[0]"#.to_string()),
+                    br#"{"uid": 1}"#.to_vec(),
+                ),
+                (Some(r#"{"users"}[1]"#.to_string()), br#"2"#.to_vec()),
+                (Some(r#"{"users"}[2]"#.to_string()), br#"[3]"#.to_vec()),
+                (
+                    Some(r#"{"groups"}[0]"#.to_string()),
+                    br#"{"gid": 1}"#.to_vec(),
+                ),
+                (Some(r#"{"users"}[0]"#.to_string()), br#"4"#.to_vec()),
+            ];
+            assert_eq!(guard.drain_iter().collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn multiple_handlers() {
+        let first = Arc::new(Mutex::new(Buffer::new()));
+        let second = Arc::new(Mutex::new(Buffer::new()));
+        let mut filter = Filter::new();
+        filter.add_matcher_handlers(
+            Box::new(Simple::new(r#"{"users"}[]"#).unwrap()),
+            &[first.clone(), second.clone()],
+        );
+        let output: Vec<u8> = OutputConverter::new()
+            .convert(&filter.process(br#"{"users": [1, {"a": 2}]}"#).unwrap())
+            .into_iter()
+            .flat_map(|e| e.1)
+            .collect();
+
+        assert_eq!(String::from_utf8(output).unwrap(), r#"{"users": []}"#);
+        for handler in &[first, second] {
+            let data: Vec<Vec<u8>> = handler.lock().unwrap().drain_iter().map(|e| e.1).collect();
+            assert_eq!(data, vec![b"1".to_vec(), br#"{"a": 2}"#.to_vec()]);
+        }
+    }
+
+    #[test]
+    fn metrics() {
+        let large = format!(
+            r#"{{"small": [1, 2], "large": "{}", "last": 2}}"#,
+            "x".repeat(10000)
+        );
+        let small = format!("[{}]", vec!["1"; 5000].join(", "));
+        let peak = |input: &str| {
+            let mut filter = Filter::new();
+            filter.add_matcher(Box::new(Simple::new(r#"{"small"}"#).unwrap()), None);
+            for part in input.as_bytes().chunks(100) {
+                filter.process(part).unwrap();
+            }
+            assert_eq!(
+                filter.metrics().peak_buffer_bytes,
+                filter.stats().peak_buffer_bytes
+            );
+            filter.metrics().peak_buffer_bytes
+        };
+        // the large string is kept until it ends
+        assert!(peak(&large) >= 10002);
+        assert!(peak(&small) <= 400);
+    }
 }
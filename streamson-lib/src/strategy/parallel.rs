@@ -0,0 +1,511 @@
+//! Trigger strategy which runs the handlers in a pool of threads
+//!
+//! The input is parsed and matched in the caller's thread (the same
+//! way as in `Trigger`) and the matched data are sent to worker threads
+//! where the handlers are called.
+//!
+//! All the data of a single handler are processed by the same worker,
+//! so the handler receives the data in the same order as in `Trigger`.
+//! Errors of the handlers are returned from the next `process` call
+//! (or from `terminate`).
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{handler, matcher, strategy::{self, Strategy}};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let users = Arc::new(Mutex::new(handler::Buffer::new()));
+//! let groups = Arc::new(Mutex::new(handler::Buffer::new()));
+//!
+//! let mut parallel = strategy::Parallel::new(2);
+//! parallel.add_matcher(
+//!     Box::new(matcher::Simple::new(r#"{"users"}[]"#).unwrap()),
+//!     users.clone(),
+//! );
+//! parallel.add_matcher(
+//!     Box::new(matcher::Simple::new(r#"{"groups"}[]"#).unwrap()),
+//!     groups.clone(),
+//! );
+//!
+//! parallel.process(br#"{"users": ["first", "second"], "groups": ["admins"]}"#).unwrap();
+//! // waits for the workers
+//! parallel.terminate().unwrap();
+//!
+//! while let Some((_, data)) = users.lock().unwrap().pop() {
+//!     // Do something with the data
+//!     println!("{}", String::from_utf8(data).unwrap());
+//! }
+//! ```
+
+use super::{Output, Stats, Strategy, Trigger};
+use crate::{
+    error,
+    handler::Handler,
+    matcher::Matcher,
+    path::Path,
+    streamer::{InputFormat, Limits, Token},
+};
+use std::{
+    any::Any,
+    collections::HashMap,
+    sync::{
+        mpsc::{sync_channel, SyncSender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+};
+
+/// Max number of events waiting for a worker
+const QUEUE_SIZE: usize = 1024;
+
+/// Handler call which is performed in a worker
+enum Event {
+    Start(Path, usize, Token),
+    Feed(Vec<u8>, usize),
+    End(Path, usize, Token),
+    JsonFinished,
+    /// Output of the handler is sent back
+    InputFinished(SyncSender<Result<Option<Vec<u8>>, error::Handler>>),
+}
+
+/// Event for a handler
+type Message = (Arc<Mutex<dyn Handler>>, Event);
+
+/// The first error which occured in the workers
+type SharedError = Arc<Mutex<Option<error::Handler>>>;
+
+/// Thread which calls the handlers
+struct Worker {
+    sender: Option<SyncSender<Message>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Worker {
+    fn new(error: SharedError) -> Self {
+        let (sender, receiver) = sync_channel::<Message>(QUEUE_SIZE);
+        let thread = thread::spawn(move || {
+            for (handler, event) in receiver {
+                let mut guard = handler.lock().unwrap();
+                let result = match event {
+                    Event::Start(path, matcher_idx, token) => {
+                        guard.start(&path, matcher_idx, token)
+                    }
+                    Event::Feed(data, matcher_idx) => guard.feed(&data, matcher_idx),
+                    Event::End(path, matcher_idx, token) => guard.end(&path, matcher_idx, token),
+                    Event::JsonFinished => guard.json_finished(),
+                    Event::InputFinished(reply) => {
+                        // the receiver is waiting for the reply
+                        let _ = reply.send(guard.input_finished());
+                        continue;
+                    }
+                };
+                if let Err(err) = result {
+                    let mut shared = error.lock().unwrap();
+                    if shared.is_none() {
+                        *shared = Some(err);
+                    }
+                }
+            }
+        });
+        Self {
+            sender: Some(sender),
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        // closing the channel stops the thread
+        self.sender.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Handler which passes the calls to a worker
+struct Dispatch {
+    handler: Arc<Mutex<dyn Handler>>,
+    sender: SyncSender<Message>,
+    error: SharedError,
+}
+
+impl Dispatch {
+    /// Sends the event to the worker
+    fn send(&self, event: Event) -> Result<Option<Vec<u8>>, error::Handler> {
+        if let Some(err) = self.error.lock().unwrap().as_ref() {
+            return Err(err.clone());
+        }
+        self.sender
+            .send((self.handler.clone(), event))
+            .map_err(|_| error::Handler::new("Worker thread is not running"))?;
+        Ok(None)
+    }
+}
+
+impl Handler for Dispatch {
+    fn start(
+        &mut self,
+        path: &Path,
+        matcher_idx: usize,
+        token: Token,
+    ) -> Result<Option<Vec<u8>>, error::Handler> {
+        self.send(Event::Start(path.clone(), matcher_idx, token))
+    }
+
+    fn feed(&mut self, data: &[u8], matcher_idx: usize) -> Result<Option<Vec<u8>>, error::Handler> {
+        self.send(Event::Feed(data.to_vec(), matcher_idx))
+    }
+
+    fn end(
+        &mut self,
+        path: &Path,
+        matcher_idx: usize,
+        token: Token,
+    ) -> Result<Option<Vec<u8>>, error::Handler> {
+        self.send(Event::End(path.clone(), matcher_idx, token))
+    }
+
+    fn json_finished(&mut self) -> Result<Option<Vec<u8>>, error::Handler> {
+        self.send(Event::JsonFinished)
+    }
+
+    fn input_finished(&mut self) -> Result<Option<Vec<u8>>, error::Handler> {
+        let (reply, receiver) = sync_channel(1);
+        self.send(Event::InputFinished(reply))?;
+        let output = receiver
+            .recv()
+            .map_err(|_| error::Handler::new("Worker thread is not running"))?;
+        // the previous events might have failed
+        if let Some(err) = self.error.lock().unwrap().as_ref() {
+            return Err(err.clone());
+        }
+        output
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Processes data from input and triggers handlers in worker threads
+pub struct Parallel {
+    /// Strategy which matches the data
+    ///
+    /// Note that it (as well as the dispatchers) has to be dropped
+    /// before the workers so that the channels to the workers are closed
+    trigger: Trigger,
+    /// Dispatcher of each handler (handlers are identified by pointers)
+    ///
+    /// The same handler registered for several matchers shares the dispatcher,
+    /// so that its lifecycle hooks are called only once
+    dispatchers: HashMap<usize, Arc<Mutex<dyn Handler>>>,
+    /// Worker threads
+    workers: Vec<Worker>,
+    /// The first error which occured in the workers
+    error: SharedError,
+}
+
+impl Strategy for Parallel {
+    fn process(&mut self, input: &[u8]) -> Result<Vec<Output>, error::General> {
+        self.check_error()?;
+        self.trigger.process(input)
+    }
+
+    fn finish_input(&mut self) {
+        self.trigger.finish_input();
+    }
+
+    fn terminate(&mut self) -> Result<Vec<Output>, error::General> {
+        // waits until the handlers process all the data
+        let output = self.trigger.terminate()?;
+        self.check_error()?;
+        Ok(output)
+    }
+
+    fn json_finished(&mut self) -> Result<Vec<Output>, error::General> {
+        self.trigger.json_finished()
+    }
+
+    fn stats(&self) -> Stats {
+        self.trigger.stats()
+    }
+}
+
+impl Parallel {
+    /// Creates a new `Parallel`
+    ///
+    /// # Arguments
+    /// * `threads` - number of worker threads (at least one is used)
+    pub fn new(threads: usize) -> Self {
+        let error: SharedError = Arc::new(Mutex::new(None));
+        Self {
+            trigger: Trigger::new(),
+            dispatchers: HashMap::new(),
+            workers: (0..threads.max(1))
+                .map(|_| Worker::new(error.clone()))
+                .collect(),
+            error,
+        }
+    }
+
+    /// Sets strict mode (see `Trigger::set_strict`)
+    pub fn set_strict(&mut self, strict: bool) {
+        self.trigger.set_strict(strict);
+    }
+
+    /// Sets the format of the input data (see `Trigger::set_input_format`)
+    pub fn set_input_format(&mut self, format: InputFormat) {
+        self.trigger.set_input_format(format);
+    }
+
+    /// Sets limits of the input data (see `Trigger::set_limits`)
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.trigger.set_limits(limits);
+    }
+
+    /// Sets whether the lines with an incorrect input are skipped
+    /// (see `Trigger::set_skip_invalid`)
+    pub fn set_skip_invalid(&mut self, skip: bool) {
+        self.trigger.set_skip_invalid(skip);
+    }
+
+    /// Adds a matcher and a handler to `Parallel`
+    ///
+    /// Handlers are assigned to the workers in the round robin fashion.
+    /// The same handler is always called from the same worker.
+    ///
+    /// # Arguments
+    /// * `matcher` - matcher which matches the path
+    /// * `handler` - handler to be triggered in a worker when path matches
+    pub fn add_matcher(&mut self, matcher: Box<dyn Matcher>, handler: Arc<Mutex<dyn Handler>>) {
+        let key = Arc::as_ptr(&handler) as *const () as usize;
+        let worker_idx = self.dispatchers.len() % self.workers.len();
+        let sender = self.workers[worker_idx].sender.clone().unwrap();
+        let error = self.error.clone();
+        let dispatch = self.dispatchers.entry(key).or_insert_with(|| {
+            Arc::new(Mutex::new(Dispatch {
+                handler,
+                sender,
+                error,
+            }))
+        });
+        self.trigger.add_matcher(matcher, dispatch.clone());
+    }
+
+    /// Returns the first error which occured in the workers
+    fn check_error(&self) -> Result<(), error::General> {
+        if let Some(err) = self.error.lock().unwrap().as_ref() {
+            Err(err.clone().into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Parallel;
+    use crate::{
+        error,
+        handler::{Buffer, Handler},
+        matcher::Simple,
+        path::Path,
+        strategy::{Output, Strategy},
+        streamer::{InputFormat, Limits, Token},
+    };
+    use std::{
+        any::Any,
+        sync::{Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+
+    #[derive(Default)]
+    struct SlowHandler {
+        data: Vec<u8>,
+        threads: Vec<thread::ThreadId>,
+    }
+
+    impl Handler for SlowHandler {
+        fn feed(
+            &mut self,
+            data: &[u8],
+            _matcher_idx: usize,
+        ) -> Result<Option<Vec<u8>>, error::Handler> {
+            thread::sleep(Duration::from_millis(1));
+            self.threads.push(thread::current().id());
+            self.data.extend(data);
+            Ok(None)
+        }
+
+        fn end(
+            &mut self,
+            _path: &Path,
+            _matcher_idx: usize,
+            _token: Token,
+        ) -> Result<Option<Vec<u8>>, error::Handler> {
+            if self.data.ends_with(b"x\"") {
+                return Err(error::Handler::new("x found"));
+            }
+            self.data.push(b';');
+            Ok(None)
+        }
+
+        fn input_finished(&mut self) -> Result<Option<Vec<u8>>, error::Handler> {
+            Ok(Some(self.data.clone()))
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn ordering() {
+        let mut parallel = Parallel::new(3);
+        let handlers: Vec<Arc<Mutex<SlowHandler>>> = (0..4)
+            .map(|_| Arc::new(Mutex::new(SlowHandler::default())))
+            .collect();
+        for (idx, handler) in handlers.iter().enumerate() {
+            let matcher = Simple::new(&format!("[{}][]", idx)).unwrap();
+            parallel.add_matcher(Box::new(matcher), handler.clone());
+        }
+        // shared handler
+        parallel.add_matcher(Box::new(Simple::new("[4][]").unwrap()), handlers[0].clone());
+
+        let input = br#"[[1, 2, 3], [4, 5], [6], [], [7, 8]]"#;
+        for part in input.chunks(3) {
+            parallel.process(part).unwrap();
+        }
+        let output = parallel.terminate().unwrap();
+        assert_eq!(
+            output,
+            vec![
+                Output::Data(b"1;2;3;7;8;".to_vec()),
+                Output::Data(b"4;5;".to_vec()),
+                Output::Data(b"6;".to_vec()),
+                Output::Data(b"".to_vec()),
+            ]
+        );
+
+        // handlers were called from the worker threads
+        let first = handlers[0].lock().unwrap().threads[0];
+        assert_ne!(first, thread::current().id());
+        assert!(handlers[0]
+            .lock()
+            .unwrap()
+            .threads
+            .iter()
+            .all(|id| *id == first));
+        assert_ne!(handlers[1].lock().unwrap().threads[0], first);
+    }
+
+    #[derive(Default)]
+    struct Lifecycle {
+        jsons: usize,
+        inputs: usize,
+    }
+
+    impl Handler for Lifecycle {
+        fn json_finished(&mut self) -> Result<Option<Vec<u8>>, error::Handler> {
+            self.jsons += 1;
+            Ok(None)
+        }
+
+        fn input_finished(&mut self) -> Result<Option<Vec<u8>>, error::Handler> {
+            self.inputs += 1;
+            Ok(None)
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn shared_handler_hooks() {
+        let mut parallel = Parallel::new(2);
+        let handler = Arc::new(Mutex::new(Lifecycle::default()));
+        parallel.add_matcher(Box::new(Simple::new("[]").unwrap()), handler.clone());
+        parallel.add_matcher(Box::new(Simple::new("{}").unwrap()), handler.clone());
+
+        parallel.process(br#"[1] {"a": 2}"#).unwrap();
+        parallel.terminate().unwrap();
+
+        let guard = handler.lock().unwrap();
+        assert_eq!(guard.jsons, 2);
+        assert_eq!(guard.inputs, 1);
+    }
+
+    #[test]
+    fn json_lines() {
+        let mut parallel = Parallel::new(2);
+        parallel.set_input_format(InputFormat::JsonLines);
+        let buffer = Arc::new(Mutex::new(Buffer::new()));
+        parallel.add_matcher(Box::new(Simple::new("[]").unwrap()), buffer.clone());
+
+        parallel.process(b"[1, 2]\n[3]\n").unwrap();
+        parallel.terminate().unwrap();
+        let mut guard = buffer.lock().unwrap();
+        assert_eq!(guard.pop().unwrap().1, b"1".to_vec());
+        assert_eq!(guard.pop().unwrap().1, b"2".to_vec());
+        assert_eq!(guard.pop().unwrap().1, b"3".to_vec());
+        drop(guard);
+
+        // two values on a single line
+        assert!(parallel.process(b"[4] [5]\n").is_err());
+    }
+
+    #[test]
+    fn limits() {
+        let mut parallel = Parallel::new(2);
+        parallel.set_limits(Limits::new().set_max_depth(Some(2)));
+        let buffer = Arc::new(Mutex::new(Buffer::new()));
+        parallel.add_matcher(Box::new(Simple::new("[]").unwrap()), buffer.clone());
+
+        parallel.process(b"[[1], 2]").unwrap();
+        parallel.terminate().unwrap();
+        let mut guard = buffer.lock().unwrap();
+        assert_eq!(guard.pop().unwrap().1, b"[1]".to_vec());
+        assert_eq!(guard.pop().unwrap().1, b"2".to_vec());
+        drop(guard);
+
+        match parallel.process(b" [[[3]]]") {
+            Err(error::General::LimitExceeded(err)) => assert_eq!(err.idx(), 11),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn skip_invalid() {
+        let mut parallel = Parallel::new(2);
+        parallel.set_input_format(InputFormat::JsonLines);
+        parallel.set_skip_invalid(true);
+        let buffer = Arc::new(Mutex::new(Buffer::new()));
+        parallel.add_matcher(Box::new(Simple::new("[]").unwrap()), buffer.clone());
+
+        parallel.process(b"[1]\n[2, x]\nx\n[3]\n").unwrap();
+        parallel.terminate().unwrap();
+        let mut guard = buffer.lock().unwrap();
+        assert_eq!(guard.pop().unwrap().1, b"1".to_vec());
+        assert_eq!(guard.pop().unwrap().1, b"2".to_vec());
+        assert_eq!(guard.pop().unwrap().1, b"3".to_vec());
+        assert!(guard.pop().is_none());
+    }
+
+    #[test]
+    fn error() {
+        let mut parallel = Parallel::new(2);
+        let failing = Arc::new(Mutex::new(SlowHandler::default()));
+        let buffer = Arc::new(Mutex::new(Buffer::new()));
+        parallel.add_matcher(Box::new(Simple::new(r#"{"a"}[]"#).unwrap()), failing);
+        parallel.add_matcher(Box::new(Simple::new(r#"{"b"}[]"#).unwrap()), buffer.clone());
+
+        parallel.process(br#"{"a": ["x"], "b": [1]}"#).unwrap();
+        assert!(parallel.terminate().is_err());
+        assert!(parallel.process(br#"{"b": [2]}"#).is_err());
+        assert_eq!(buffer.lock().unwrap().pop().unwrap().1, b"1".to_vec());
+    }
+}
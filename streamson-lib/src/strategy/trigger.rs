@@ -8,16 +8,16 @@
 
 use crate::{
     error,
-    handler::Handler,
-    matcher::Matcher,
-    streamer::{Streamer, Token},
+    handler::{ErrorCollector, Group, Handler},
+    matcher::{Matcher, MatcherIndex},
+    streamer::{InputFormat, Limits, ParsedKind, Streamer, Token},
 };
 use std::{
     collections::HashSet,
     sync::{Arc, Mutex},
 };
 
-use super::{Output, Strategy};
+use super::{unique_handlers, Output, Stats, Strategy, TraceCallback};
 
 #[derive(Debug)]
 struct StackItem {
@@ -27,6 +27,19 @@ struct StackItem {
     match_idx: usize,
 }
 
+/// Leaf element waiting for the matchers which need its value
+#[derive(Debug)]
+struct PendingValue {
+    /// Total index of the element start
+    idx: usize,
+    /// Kind of the element
+    kind: ParsedKind,
+    /// Idx to vec of matchers which need the value
+    match_indexes: Vec<usize>,
+    /// Collected data of the element
+    data: Vec<u8>,
+}
+
 /// Item in matcher list
 type MatcherItem = (Box<dyn Matcher>, Arc<Mutex<dyn Handler>>);
 
@@ -36,12 +49,26 @@ pub struct Trigger {
     input_start: usize,
     /// Path matchers and handlers
     matchers: Vec<MatcherItem>,
+    /// Index of the matchers
+    index: MatcherIndex,
+    /// Remaining number of matches for each matcher (`None` means unlimited)
+    remaining: Vec<Option<usize>>,
     /// Responsible for data extraction
     streamer: Streamer,
     /// Matched stack
     matched_stack: Vec<Vec<StackItem>>,
+    /// Leaf element which matching depends on its value
+    pending_value: Option<PendingValue>,
     /// Current json level
     level: usize,
+    /// Statistics of the processing
+    stats: Stats,
+    /// Callback triggered for each token read
+    trace: Option<TraceCallback>,
+    /// Collects the errors which were recovered from
+    error_collector: Option<Arc<Mutex<ErrorCollector>>>,
+    /// Skip the lines with an incorrect input
+    skip_invalid: bool,
 }
 
 impl Default for Trigger {
@@ -49,19 +76,92 @@ impl Default for Trigger {
         Self {
             input_start: 0,
             matchers: vec![],
+            index: MatcherIndex::new(),
+            remaining: vec![],
             streamer: Streamer::new(),
             matched_stack: vec![],
+            pending_value: None,
             level: 0,
+            stats: Stats::default(),
+            trace: None,
+            error_collector: None,
+            skip_invalid: false,
         }
     }
 }
 
 impl Strategy for Trigger {
     fn process(&mut self, input: &[u8]) -> Result<Vec<Output>, error::General> {
+        let result = self.process_input(input);
+        if result.is_err() {
+            self.stats.error_path = Some(self.streamer.current_path().clone());
+        }
+        result
+    }
+
+    fn finish_input(&mut self) {
+        // input itself is fed in process
+        self.streamer.feed_final(&[]);
+    }
+
+    fn terminate(&mut self) -> Result<Vec<Output>, error::General> {
+        if self.level == 0 {
+            let mut res = vec![];
+            for handler in unique_handlers(self.matchers.iter().map(|e| &e.1)) {
+                let output = handler.lock().unwrap().input_finished()?;
+                if let Some(data) = output {
+                    res.push(Output::Data(data));
+                }
+            }
+            if let Some(collector) = self.error_collector.as_ref() {
+                if let Some(data) = collector.lock().unwrap().input_finished()? {
+                    res.push(Output::Data(data));
+                }
+            }
+            Ok(res)
+        } else {
+            Err(error::InputTerminated::new(self.input_start).into())
+        }
+    }
+
+    fn json_finished(&mut self) -> Result<Vec<Output>, error::General> {
+        let mut res = vec![];
+        for (matcher, _) in &self.matchers {
+            matcher.json_finished();
+        }
+        for handler in unique_handlers(self.matchers.iter().map(|e| &e.1)) {
+            let output = handler.lock().unwrap().json_finished()?;
+            if let Some(data) = output {
+                res.push(Output::Data(data));
+            }
+        }
+        Ok(res)
+    }
+
+    fn stats(&self) -> Stats {
+        self.stats.with_bytes(self.input_start, self.matchers.len())
+    }
+}
+
+impl Trigger {
+    /// Creates a new `Trigger`
+    ///
+    /// It collects matched data and triggers handlers when entire
+    /// data are read.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Processes input data (see `Strategy::process`)
+    fn process_input(&mut self, input: &[u8]) -> Result<Vec<Output>, error::General> {
         self.streamer.feed(input);
         let mut inner_idx = 0;
         loop {
-            match self.streamer.read()? {
+            let token = self.streamer.read()?;
+            if let Some(trace) = self.trace.as_mut() {
+                trace(&token, self.streamer.current_path());
+            }
+            match token {
                 Token::Start(idx, kind) => {
                     self.level += 1;
                     // trigger handler for matched
@@ -70,19 +170,41 @@ impl Strategy for Trigger {
                     inner_idx = to;
 
                     let mut matched = vec![];
+                    let mut deferred = vec![];
                     let path = self.streamer.current_path();
+                    let leaf = !matches!(kind, ParsedKind::Obj | ParsedKind::Arr);
 
                     // try to check whether it matches
-                    for (match_idx, (matcher, _)) in self.matchers.iter().enumerate() {
-                        if matcher.match_path(path, kind) {
+                    for match_idx in self.index.candidates(path.get_path(), kind) {
+                        let matcher = &self.matchers[match_idx].0;
+                        if self.remaining[match_idx] == Some(0) {
+                            // limit reached
+                            continue;
+                        }
+                        if leaf && matcher.needs_value() {
+                            // decided when the entire value is read
+                            deferred.push(match_idx);
+                        } else if matcher.match_path(path, kind) {
                             // handler starts
                             let mut guard = self.matchers[match_idx].1.lock().unwrap();
                             guard.start(path, match_idx, Token::Start(idx, kind))?;
                             matched.push(StackItem { idx, match_idx });
+                            if let Some(remaining) = self.remaining[match_idx].as_mut() {
+                                *remaining -= 1;
+                            }
+                            self.stats.matched(match_idx);
                         }
                     }
 
                     self.matched_stack.push(matched);
+                    if !deferred.is_empty() {
+                        self.pending_value = Some(PendingValue {
+                            idx,
+                            kind,
+                            match_indexes: deferred,
+                            data: vec![],
+                        });
+                    }
                 }
                 Token::End(idx, kind) => {
                     self.level -= 1;
@@ -91,6 +213,25 @@ impl Strategy for Trigger {
                     inner_idx = to;
 
                     let current_path = self.streamer.current_path();
+                    if let Some(pending) = self.pending_value.take() {
+                        for match_idx in pending.match_indexes {
+                            let (matcher, handler) = &self.matchers[match_idx];
+                            if matcher.match_value(current_path, kind, &pending.data) {
+                                if let Some(remaining) = self.remaining[match_idx].as_mut() {
+                                    *remaining -= 1;
+                                }
+                                self.stats.matched(match_idx);
+                                let mut guard = handler.lock().unwrap();
+                                guard.start(
+                                    current_path,
+                                    match_idx,
+                                    Token::Start(pending.idx, pending.kind),
+                                )?;
+                                guard.feed(&pending.data, match_idx)?;
+                                guard.end(current_path, match_idx, Token::End(idx, kind))?;
+                            }
+                        }
+                    }
                     let items = self.matched_stack.pop().unwrap();
                     for item in items {
                         // run handlers for the matches
@@ -98,6 +239,7 @@ impl Strategy for Trigger {
                         guard.end(current_path, item.match_idx, Token::End(idx, kind))?;
                     }
                     if self.level == 0 {
+                        self.stats.documents += 1;
                         self.json_finished()?;
                     }
                 }
@@ -106,45 +248,127 @@ impl Strategy for Trigger {
                     self.feed(&input[inner_idx..])?;
                     return Ok(vec![]);
                 }
-                Token::Separator(_) => {}
+                Token::Discarded(idx) => {
+                    if let Some(collector) = self.error_collector.as_ref() {
+                        let byte = input
+                            .get(idx.saturating_sub(self.input_start))
+                            .copied()
+                            .unwrap_or_default();
+                        collector.lock().unwrap().record(
+                            &error::IncorrectInput::new(byte, idx),
+                            self.streamer.current_path(),
+                            input,
+                            self.input_start,
+                        );
+                    }
+                    // the rest of the line is skipped
+                    self.reset();
+                }
+                Token::Separator(_, _) | Token::Finished(_) => {}
             }
         }
     }
 
-    fn terminate(&mut self) -> Result<Vec<Output>, error::General> {
-        if self.level == 0 {
-            let mut res = vec![];
-            for (_, handler) in &self.matchers {
-                let output = handler.lock().unwrap().input_finished()?;
-                if let Some(data) = output {
-                    res.push(Output::Data(data));
-                }
-            }
-            Ok(res)
-        } else {
-            Err(error::InputTerminated::new(self.input_start).into())
-        }
+    /// Sets strict mode
+    ///
+    /// Invalid JSON input (e.g. `[01]` or `[1,]`) is rejected in strict mode.
+    ///
+    /// # Arguments
+    /// * `strict` - whether the input should be validated
+    pub fn set_strict(&mut self, strict: bool) {
+        self.streamer.set_strict(strict);
     }
 
-    fn json_finished(&mut self) -> Result<Vec<Output>, error::General> {
-        let mut res = vec![];
-        for (_, handler) in &self.matchers {
-            let output = handler.lock().unwrap().json_finished()?;
-            if let Some(data) = output {
-                res.push(Output::Data(data));
-            }
-        }
-        Ok(res)
+    /// Sets the format of the input data
+    ///
+    /// With `InputFormat::JsonLines` each line is processed as a separate JSON
+    /// (see `Streamer::set_input_format`).
+    ///
+    /// # Arguments
+    /// * `format` - format of the input data
+    pub fn set_input_format(&mut self, format: InputFormat) {
+        self.streamer.set_input_format(format);
     }
-}
 
-impl Trigger {
-    /// Creates a new `Trigger`
+    /// Sets limits of the input data
     ///
-    /// It collects matched data and triggers handlers when entire
-    /// data are read.
-    pub fn new() -> Self {
-        Self::default()
+    /// Input which exceeds the limits (e.g. which is nested too deeply)
+    /// is rejected (see `Streamer::set_limits`).
+    ///
+    /// # Arguments
+    /// * `limits` - limits of the input data
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.streamer.set_limits(limits);
+    }
+
+    /// Sets a callback which is triggered for each token read
+    ///
+    /// It can be used for debugging e.g. to find out why
+    /// a matcher doesn't match.
+    ///
+    /// # Arguments
+    /// * `trace` - function called with a token and the current path
+    pub fn set_trace(&mut self, trace: TraceCallback) {
+        self.trace = Some(trace);
+    }
+
+    /// Recovers from an error which occured during processing
+    ///
+    /// The rest of the line where the error occured is skipped
+    /// and the processing continues on the next line (see `Streamer::resync`).
+    ///
+    /// Note that handlers of the unfinished matches are not notified,
+    /// so they might need to be reset as well.
+    pub fn resync(&mut self) -> Result<Vec<Output>, error::General> {
+        let input = self.skip_line();
+        self.process(&input)
+    }
+
+    /// Resets the processing and skips the rest of the current line
+    ///
+    /// # Returns
+    /// * data behind the line which need to be processed again
+    fn skip_line(&mut self) -> Vec<u8> {
+        self.reset();
+        let (input_start, input) = self.streamer.resync();
+        self.input_start = input_start;
+        input
+    }
+
+    /// Drops the state of the unfinished JSON
+    fn reset(&mut self) {
+        self.level = 0;
+        self.matched_stack.clear();
+        self.pending_value = None;
+    }
+
+    /// Sets a collector of the input errors
+    ///
+    /// When set, the processing doesn't fail on an incorrect input.
+    /// The error is recorded in the collector and the processing
+    /// continues on the next line (see `Trigger::set_skip_invalid`).
+    ///
+    /// # Arguments
+    /// * `collector` - handler which keeps the error contexts
+    pub fn set_error_collector(&mut self, collector: Arc<Mutex<ErrorCollector>>) {
+        self.error_collector = Some(collector);
+        self.streamer.set_skip_invalid(true);
+    }
+
+    /// Sets whether the lines with an incorrect input are skipped
+    ///
+    /// When set, the processing doesn't fail on an incorrect input
+    /// and it continues on the next line (see `Streamer::set_skip_invalid`).
+    /// It is meant to be used together with `InputFormat::JsonLines`.
+    ///
+    /// Note that handlers of the unfinished matches are not notified.
+    ///
+    /// # Arguments
+    /// * `skip` - whether invalid lines should be skipped
+    pub fn set_skip_invalid(&mut self, skip: bool) {
+        self.skip_invalid = skip;
+        self.streamer
+            .set_skip_invalid(skip || self.error_collector.is_some());
     }
 
     /// Adds a mathcher and a handler to `Trigger`
@@ -168,10 +392,88 @@ impl Trigger {
     /// );
     /// ```
     pub fn add_matcher(&mut self, matcher: Box<dyn Matcher>, handler: Arc<Mutex<dyn Handler>>) {
+        self.index.add(self.matchers.len(), matcher.as_ref());
+        self.matchers.push((matcher, handler));
+        self.remaining.push(None);
+    }
+
+    /// Adds a matcher which stops matching after a number of matches
+    ///
+    /// # Arguments
+    /// * `matcher` - matcher which matches the path
+    /// * `handler` - handler to be triggers when path matches
+    /// * `limit` - max number of matches
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use streamson_lib::{strategy, matcher, handler};
+    /// use std::{io, sync::{Arc, Mutex}};
+    ///
+    /// let mut trigger = strategy::Trigger::new();
+    /// let handler = handler::Output::new(io::stdout());
+    /// let matcher = matcher::Simple::new(r#"{"list"}[]"#).unwrap();
+    /// // only the first 10 items are triggered
+    /// trigger.add_matcher_limited(
+    ///     Box::new(matcher),
+    ///     Arc::new(Mutex::new(handler)),
+    ///     10,
+    /// );
+    /// ```
+    pub fn add_matcher_limited(
+        &mut self,
+        matcher: Box<dyn Matcher>,
+        handler: Arc<Mutex<dyn Handler>>,
+        limit: usize,
+    ) {
+        self.index.add(self.matchers.len(), matcher.as_ref());
         self.matchers.push((matcher, handler));
+        self.remaining.push(Some(limit));
+    }
+
+    /// Adds a matcher with multiple handlers
+    ///
+    /// Each handler receives the same matched data
+    /// (handlers are wrapped in a fan-out `handler::Group`).
+    ///
+    /// # Arguments
+    /// * `matcher` - matcher which matches the path
+    /// * `handlers` - handlers which will be triggered on a match
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use streamson_lib::{strategy, matcher, handler};
+    /// use std::{io, sync::{Arc, Mutex}};
+    ///
+    /// let mut trigger = strategy::Trigger::new();
+    /// let matcher = matcher::Simple::new(r#"{"list"}[]"#).unwrap();
+    /// trigger.add_matcher_handlers(
+    ///     Box::new(matcher),
+    ///     &[
+    ///         Arc::new(Mutex::new(handler::Output::new(io::stdout()))),
+    ///         Arc::new(Mutex::new(handler::Buffer::new())),
+    ///     ],
+    /// );
+    /// ```
+    pub fn add_matcher_handlers(
+        &mut self,
+        matcher: Box<dyn Matcher>,
+        handlers: &[Arc<Mutex<dyn Handler>>],
+    ) {
+        let group = handlers
+            .iter()
+            .fold(Group::new().fan_out(), |group, handler| {
+                group.add_handler(handler.clone())
+            });
+        self.add_matcher(matcher, Arc::new(Mutex::new(group)));
     }
 
     fn feed(&mut self, data: &[u8]) -> Result<(), error::Handler> {
+        if let Some(pending) = self.pending_value.as_mut() {
+            pending.data.extend(data);
+        }
+
         // feed only once in case that there is some nested matcher
         let mut seen_match_idx = HashSet::<usize>::new();
         for matched_items in &self.matched_stack {
@@ -191,15 +493,16 @@ mod tests {
     use super::{Strategy, Trigger};
     use crate::{
         error,
-        handler::Handler,
-        matcher::Simple,
+        handler::{Buffer, Handler},
+        matcher::{Combinator, Depth, NumberCompare, Simple},
         path::Path,
-        streamer::Token,
-        test::{Single, Splitter, Window},
+        streamer::{InputFormat, Limits, ParsedKind, Token},
+        testing::{Single, Splitter, Window},
     };
     use rstest::*;
     use std::{
         any::Any,
+        str::FromStr,
         sync::{Arc, Mutex},
     };
 
@@ -266,6 +569,25 @@ mod tests {
         assert_eq!(guard.data[3], br#"4"#.to_vec());
     }
 
+    #[test]
+    fn multiple_handlers() {
+        let mut trigger = Trigger::new();
+        let first = Arc::new(Mutex::new(TestHandler::default()));
+        let second = Arc::new(Mutex::new(TestHandler::default()));
+        let matcher = Simple::new(r#"{"elements"}[]"#).unwrap();
+        trigger.add_matcher_handlers(Box::new(matcher), &[first.clone(), second.clone()]);
+        trigger.process(br#"{"elements": [1, "two"]}"#).unwrap();
+
+        for handler in &[first, second] {
+            let guard = handler.lock().unwrap();
+            assert_eq!(
+                guard.paths,
+                vec![r#"{"elements"}[0]"#, r#"{"elements"}[1]"#]
+            );
+            assert_eq!(guard.data, vec![b"1".to_vec(), br#""two""#.to_vec()]);
+        }
+    }
+
     #[rstest(
         splitter,
         case::single(Box::new(Single::new())),
@@ -300,4 +622,210 @@ mod tests {
             assert_eq!(guard.data[3], br#"4"#.to_vec());
         }
     }
+
+    #[rstest(
+        splitter,
+        case::single(Box::new(Single::new())),
+        case::window1(Box::new(Window::new(1))),
+        case::window5(Box::new(Window::new(5))),
+        case::window100(Box::new(Window::new(100)))
+    )]
+    fn value_matcher(splitter: Box<dyn Splitter>) {
+        let input = br#"{"elements": [1, 22, 3, {"x": 40}, 4]}"#.to_vec();
+        for parts in splitter.split(input) {
+            let mut trigger = Trigger::new();
+            let handler = Arc::new(Mutex::new(TestHandler::default()));
+            let matcher = Combinator::new(Simple::new(r#"{"elements"}[]"#).unwrap())
+                & Combinator::new(NumberCompare::from_str(">2").unwrap());
+            trigger.add_matcher(Box::new(matcher), handler.clone());
+
+            for part in parts {
+                trigger.process(&part).unwrap();
+            }
+
+            let guard = handler.lock().unwrap();
+            assert_eq!(
+                guard.paths,
+                vec![
+                    r#"{"elements"}[1]"#,
+                    r#"{"elements"}[2]"#,
+                    r#"{"elements"}[4]"#
+                ]
+            );
+            assert_eq!(
+                guard.data,
+                vec![br#"22"#.to_vec(), br#"3"#.to_vec(), br#"4"#.to_vec()]
+            );
+        }
+    }
+
+    #[test]
+    fn trace() {
+        let trace = Arc::new(Mutex::new(vec![]));
+        let trace_cloned = trace.clone();
+
+        let mut trigger = Trigger::new();
+        trigger.set_trace(Box::new(move |token, path| {
+            trace_cloned
+                .lock()
+                .unwrap()
+                .push((token.clone(), path.to_string()));
+        }));
+        trigger.process(br#"{"a": [1]"#).unwrap();
+        trigger.process(br#"}"#).unwrap();
+
+        assert_eq!(
+            *trace.lock().unwrap(),
+            vec![
+                (Token::Start(0, ParsedKind::Obj), "".to_string()),
+                (Token::Start(6, ParsedKind::Arr), r#"{"a"}"#.to_string()),
+                (Token::Start(7, ParsedKind::Num), r#"{"a"}[0]"#.to_string()),
+                (Token::End(8, ParsedKind::Int), r#"{"a"}[0]"#.to_string()),
+                (Token::End(9, ParsedKind::Arr), r#"{"a"}"#.to_string()),
+                (Token::Pending, "".to_string()),
+                (Token::End(10, ParsedKind::Obj), "".to_string()),
+                (Token::Finished(10), "".to_string()),
+                (Token::Pending, "".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn resync() {
+        let buffer = Arc::new(Mutex::new(Buffer::new()));
+        let mut trigger = Trigger::new();
+        trigger.add_matcher(Box::new(Simple::new(r#"{"a"}"#).unwrap()), buffer.clone());
+
+        assert!(trigger
+            .process(b"{\"a\": 1}\n{\"a\": [2}\n{\"a\": 3}\n{\"a\": 4")
+            .is_err());
+        buffer.lock().unwrap().clear_unfinished();
+        trigger.resync().unwrap();
+        trigger.process(b"}\n").unwrap();
+
+        let mut guard = buffer.lock().unwrap();
+        assert_eq!(guard.pop().unwrap().1, b"1".to_vec());
+        assert_eq!(guard.pop().unwrap().1, b"3".to_vec());
+        assert_eq!(guard.pop().unwrap().1, b"4".to_vec());
+        assert!(guard.pop().is_none());
+    }
+
+    #[test]
+    fn limited() {
+        let limited = Arc::new(Mutex::new(TestHandler::default()));
+        let unlimited = Arc::new(Mutex::new(TestHandler::default()));
+        let value = Arc::new(Mutex::new(TestHandler::default()));
+        let mut trigger = Trigger::new();
+        trigger.add_matcher_limited(Box::new(Simple::new(r#"[]"#).unwrap()), limited.clone(), 2);
+        trigger.add_matcher(Box::new(Simple::new(r#"[]"#).unwrap()), unlimited.clone());
+        trigger.add_matcher_limited(
+            Box::new(NumberCompare::from_str(">0").unwrap()),
+            value.clone(),
+            2,
+        );
+        for part in br#"[1, 2, 3, 4, 5]"#.chunks(2) {
+            trigger.process(part).unwrap();
+        }
+
+        assert_eq!(
+            limited.lock().unwrap().data,
+            vec![b"1".to_vec(), b"2".to_vec()]
+        );
+        assert_eq!(unlimited.lock().unwrap().data.len(), 5);
+        assert_eq!(
+            value.lock().unwrap().data,
+            vec![b"1".to_vec(), b"2".to_vec()]
+        );
+    }
+
+    #[test]
+    fn json_lines() {
+        let input = b"{\"a\": 1}\n{\"a\": \n{\"b\" 5, \"a\": 2}\n{\"a\": 3} x\n\n{\"a\": 4}\n";
+        for size in 1..input.len() {
+            let mut trigger = Trigger::new();
+            trigger.set_input_format(InputFormat::JsonLines);
+            trigger.set_skip_invalid(true);
+            let handler = Arc::new(Mutex::new(TestHandler::default()));
+            trigger.add_matcher(Box::new(Simple::new(r#"{"a"}"#).unwrap()), handler.clone());
+
+            for part in input.chunks(size) {
+                trigger.process(part).unwrap();
+            }
+            trigger.terminate().unwrap();
+
+            let guard = handler.lock().unwrap();
+            assert_eq!(
+                guard.data,
+                vec![b"1".to_vec(), b"3".to_vec(), b"4".to_vec()],
+                "chunk size {}",
+                size
+            );
+        }
+
+        // fails without skipping
+        let mut trigger = Trigger::new();
+        trigger.set_input_format(InputFormat::JsonLines);
+        assert!(trigger.process(b"[1]\n[\n2]\n").is_err());
+    }
+
+    #[test]
+    fn json_lines_many_invalid() {
+        let mut input = "x\n".repeat(5000);
+        input.push_str("{\"a\": 1}\n");
+
+        let mut trigger = Trigger::new();
+        trigger.set_input_format(InputFormat::JsonLines);
+        trigger.set_skip_invalid(true);
+        let handler = Arc::new(Mutex::new(TestHandler::default()));
+        trigger.add_matcher(Box::new(Simple::new(r#"{"a"}"#).unwrap()), handler.clone());
+        trigger.process(input.as_bytes()).unwrap();
+        trigger.terminate().unwrap();
+
+        assert_eq!(handler.lock().unwrap().data, vec![b"1".to_vec()]);
+    }
+
+    #[test]
+    fn limits() {
+        let mut trigger = Trigger::new();
+        trigger.set_limits(Limits::new().set_max_depth(Some(3)));
+        let handler = Arc::new(Mutex::new(TestHandler::default()));
+        trigger.add_matcher(Box::new(Simple::new(r#"[]"#).unwrap()), handler.clone());
+
+        trigger.process(br#"[[[1]], 2]"#).unwrap();
+        match trigger.process(br#" [[[[]]]]"#) {
+            Err(error::General::LimitExceeded(err)) => assert_eq!(err.idx(), 14),
+            _ => unreachable!(),
+        }
+        assert_eq!(
+            handler.lock().unwrap().data,
+            vec![b"[[1]]".to_vec(), b"2".to_vec()]
+        );
+    }
+
+    #[test]
+    fn many_matchers() {
+        let mut trigger = Trigger::new();
+        let handler = Arc::new(Mutex::new(TestHandler::default()));
+        for idx in 0..100 {
+            let matcher = Simple::new(&format!(r#"{{"items"}}[{}]{{"id"}}"#, idx)).unwrap();
+            trigger.add_matcher(Box::new(matcher), handler.clone());
+        }
+        trigger.add_matcher(Box::new(Depth::new(4, None)), handler.clone());
+        trigger.add_matcher(Box::new(Simple::new(r#"*{"x"}"#).unwrap()), handler.clone());
+
+        trigger
+            .process(br#"{"items": [{"id": 1}, {"id": {"x": 2}}, {"name": 3}]}"#)
+            .unwrap();
+
+        let guard = handler.lock().unwrap();
+        assert_eq!(
+            guard.paths,
+            vec![
+                r#"{"items"}[0]{"id"}"#,
+                r#"{"items"}[1]{"id"}"#,
+                r#"{"items"}[1]{"id"}{"x"}"#,
+                r#"{"items"}[1]{"id"}{"x"}"#,
+            ]
+        );
+    }
 }
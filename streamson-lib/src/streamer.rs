@@ -6,11 +6,12 @@ use crate::{
 };
 use std::{
     collections::{vec_deque::Drain, VecDeque},
-    str::from_utf8,
+    fmt,
+    str::{from_utf8, FromStr},
 };
 
 /// Kind of output
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum ParsedKind {
     /// Object e.g. {}
     Obj,
@@ -18,8 +19,12 @@ pub enum ParsedKind {
     Arr,
     /// String e.g. ""
     Str,
-    /// Number e.g. 0
+    /// Number e.g. 0 (its exact kind is not known yet)
     Num,
+    /// Integer number e.g. 3 (determined when number ends)
+    Int,
+    /// Float number e.g. 3.0 or 3e2 (determined when number ends)
+    Float,
     /// Null e.g. null
     Null,
     /// Bolean e.g. false
@@ -34,9 +39,20 @@ pub enum Token {
     /// Path ends here
     End(usize, ParsedKind),
     /// Element separator idx (idx of `,` between array/object elements)
-    Separator(usize),
+    /// and the kind of the container (array or object) it separates
+    Separator(usize, ParsedKind),
     /// Needs more data
     Pending,
+    /// Top level value (document) which ends at idx was entirely read
+    ///
+    /// It is emitted right after the `End` token of the top level value.
+    Finished(usize),
+    /// Top level value turned out not to be a valid JSON (idx of the incorrect character)
+    ///
+    /// It is emitted only in scan mode or when invalid lines are skipped.
+    /// The value won't be finished, so the tokens which were emitted
+    /// since its `Start` token should be dropped.
+    Discarded(usize),
 }
 
 impl Token {
@@ -51,17 +67,221 @@ impl AsRef<str> for ParsedKind {
             ParsedKind::Obj => "object",
             ParsedKind::Arr => "array",
             ParsedKind::Str => "string",
-            ParsedKind::Num => "number",
+            ParsedKind::Num | ParsedKind::Int | ParsedKind::Float => "number",
             ParsedKind::Bool => "boolean",
             ParsedKind::Null => "null",
         }
     }
 }
 
+impl FromStr for ParsedKind {
+    type Err = error::Matcher;
+    /// Parses the names produced by `AsRef<str>`
+    ///
+    /// Note that `"number"` is parsed as `ParsedKind::Num`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "object" => Ok(ParsedKind::Obj),
+            "array" => Ok(ParsedKind::Arr),
+            "string" => Ok(ParsedKind::Str),
+            "number" => Ok(ParsedKind::Num),
+            "boolean" => Ok(ParsedKind::Bool),
+            "null" => Ok(ParsedKind::Null),
+            _ => Err(error::Matcher::Parse(input.into())),
+        }
+    }
+}
+
+/// Reads 4 hex digits of `\uXXXX` escape which starts at `idx`
+pub(crate) fn read_unicode_escape(input: &[u8], idx: usize) -> Option<u32> {
+    if input.len() < idx + 6 || &input[idx..idx + 2] != b"\\u" {
+        return None;
+    }
+    let digits = from_utf8(&input[idx + 2..idx + 6]).ok()?;
+    u32::from_str_radix(digits, 16).ok()
+}
+
+/// Decodes the content of a JSON string (without quotes)
+///
+/// Returns `None` when the string is not valid
+pub(crate) fn unescape(input: &[u8]) -> Option<String> {
+    let mut res = String::with_capacity(input.len());
+    let mut idx = 0;
+    while idx < input.len() {
+        let end = input[idx..]
+            .iter()
+            .position(|e| *e == b'\\')
+            .map_or(input.len(), |pos| idx + pos);
+        res.push_str(from_utf8(&input[idx..end]).ok()?);
+        idx = end;
+        if idx >= input.len() {
+            break;
+        }
+        let (chr, size) = match input.get(idx + 1)? {
+            b'"' => ('"', 2),
+            b'\\' => ('\\', 2),
+            b'/' => ('/', 2),
+            b'b' => ('\u{08}', 2),
+            b'f' => ('\u{0c}', 2),
+            b'n' => ('\n', 2),
+            b'r' => ('\r', 2),
+            b't' => ('\t', 2),
+            b'u' => match read_unicode_escape(input, idx)? {
+                high @ 0xD800..=0xDBFF => {
+                    let low = read_unicode_escape(input, idx + 6)?;
+                    if !(0xDC00..=0xDFFF).contains(&low) {
+                        return None;
+                    }
+                    (
+                        std::char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))?,
+                        12,
+                    )
+                }
+                code => (std::char::from_u32(code)?, 6),
+            },
+            _ => return None,
+        };
+        res.push(chr);
+        idx += size;
+    }
+    Some(res)
+}
+
+/// Escapes string to be used within a JSON string (see `unescape`)
+pub(crate) fn escape(input: &str) -> String {
+    let mut res = String::with_capacity(input.len());
+    for chr in input.chars() {
+        match chr {
+            '"' => res.push_str(r#"\""#),
+            '\\' => res.push_str(r#"\\"#),
+            '\n' => res.push_str(r#"\n"#),
+            '\r' => res.push_str(r#"\r"#),
+            '\t' => res.push_str(r#"\t"#),
+            chr if (chr as u32) < 0x20 => res.push_str(&format!("\\u{:04x}", chr as u32)),
+            chr => res.push(chr),
+        }
+    }
+    res
+}
+
+/// Returns the position of the first byte which makes the number invalid
+///
+/// Position behind the data is returned when the number is incomplete.
+fn invalid_number_position(data: &[u8]) -> Option<usize> {
+    let digits = |idx: usize| {
+        data[idx.min(data.len())..]
+            .iter()
+            .take_while(|e| e.is_ascii_digit())
+            .count()
+    };
+    let mut idx = 0;
+    if data.first() == Some(&b'-') {
+        idx += 1;
+    }
+    // integer part without leading zeros
+    match data.get(idx) {
+        Some(b'0') => idx += 1,
+        Some(b'1'..=b'9') => idx += digits(idx),
+        _ => return Some(idx),
+    }
+    if data.get(idx) == Some(&b'.') {
+        idx += 1;
+        let count = digits(idx);
+        if count == 0 {
+            return Some(idx);
+        }
+        idx += count;
+    }
+    if matches!(data.get(idx), Some(b'e') | Some(b'E')) {
+        idx += 1;
+        if matches!(data.get(idx), Some(b'+') | Some(b'-')) {
+            idx += 1;
+        }
+        let count = digits(idx);
+        if count == 0 {
+            return Some(idx);
+        }
+        idx += count;
+    }
+    if idx < data.len() {
+        Some(idx)
+    } else {
+        None
+    }
+}
+
+/// Returns the position of the first byte which makes the literal invalid
+///
+/// Position behind the data is returned when the literal is incomplete.
+fn invalid_literal_position(data: &[u8]) -> Option<usize> {
+    let expected: &[u8] = match data.first() {
+        Some(b't') => b"true",
+        Some(b'f') => b"false",
+        _ => b"null",
+    };
+    if data == expected {
+        return None;
+    }
+    Some(
+        data.iter()
+            .zip(expected)
+            .take_while(|(left, right)| left == right)
+            .count(),
+    )
+}
+
+/// Decodes `\uXXXX` escapes in a key
+///
+/// Other escapes are kept untouched and decoded characters which
+/// would need to be escaped (quotes, backslashes and control characters)
+/// are kept in the escaped form so the key remains a valid JSON string.
+fn decode_unicode_escapes(key: &str) -> String {
+    if !key.contains("\\u") {
+        return key.to_string();
+    }
+    let input = key.as_bytes();
+    let mut res = String::with_capacity(key.len());
+    let mut idx = 0;
+    let mut last = 0;
+    while idx < input.len() {
+        if input[idx] != b'\\' {
+            idx += 1;
+            continue;
+        }
+        let (chr, size) = match read_unicode_escape(input, idx) {
+            Some(high @ 0xD800..=0xDBFF) => match read_unicode_escape(input, idx + 6) {
+                Some(low @ 0xDC00..=0xDFFF) => (
+                    std::char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)),
+                    12,
+                ),
+                _ => (None, 6),
+            },
+            Some(code) => (std::char::from_u32(code), 6),
+            // other escape (keep the escaped character as well)
+            None => (
+                None,
+                1 + key[idx + 1..].chars().next().map_or(0, char::len_utf8),
+            ),
+        };
+        res.push_str(&key[last..idx]);
+        match chr {
+            Some('"') => res.push_str("\\\""),
+            Some('\\') => res.push_str("\\\\"),
+            Some(chr) if !chr.is_control() => res.push(chr),
+            _ => res.push_str(&key[idx..(idx + size).min(input.len())]),
+        }
+        idx += size;
+        last = idx.min(input.len());
+    }
+    res.push_str(&key[last..]);
+    res
+}
+
 /// Key parsing states
 #[derive(Debug)]
 enum ObjectKeyState {
-    Init,
+    /// Whether the key follows a separator
+    Init(bool),
     Parse(StringState),
 }
 
@@ -70,6 +290,18 @@ enum ObjectKeyState {
 enum StringState {
     Normal,
     Escaped,
+    /// Remaining hex digits and the value of `\uXXXX` escape (in strict mode)
+    ///
+    /// `low` is set when the escape has to be a low surrogate.
+    Unicode {
+        remaining: u8,
+        value: u32,
+        low: bool,
+    },
+    /// High surrogate was read, `\` of a low surrogate is expected (in strict mode)
+    Surrogate,
+    /// High surrogate was read, `u` of a low surrogate is expected (in strict mode)
+    SurrogateEscaped,
 }
 
 /// JSON processing states
@@ -77,7 +309,7 @@ enum StringState {
 enum States {
     Value(Option<Element>),
     Str(StringState),
-    Number,
+    Number(bool),
     Bool,
     Null,
     Array(usize),
@@ -85,6 +317,91 @@ enum States {
     ObjectKey(ObjectKeyState),
     Colon,
     RemoveWhitespaces,
+    SkipLine,
+    LineEnd,
+}
+
+/// Format of the input data
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// JSON values separated by whitespaces
+    #[default]
+    Json,
+    /// Each line contains a single JSON value (NDJSON / JSON Lines)
+    JsonLines,
+}
+
+impl FromStr for InputFormat {
+    type Err = error::Matcher;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "json" => Ok(Self::Json),
+            "ndjson" | "jsonl" | "jsonlines" => Ok(Self::JsonLines),
+            _ => Err(error::Matcher::Parse(input.to_string())),
+        }
+    }
+}
+
+/// Limits of the input data
+///
+/// They can be used to reject untrusted input (e.g. deeply nested payloads)
+/// before the internal state grows too much.
+/// When a limit is exceeded `error::General::LimitExceeded` is returned.
+///
+/// # Example
+/// ```
+/// use streamson_lib::streamer::{Limits, Streamer};
+///
+/// let mut streamer = Streamer::new();
+/// streamer.set_limits(Limits::new().set_max_depth(Some(2)));
+/// streamer.feed(b"[[[1]]]");
+/// streamer.read().unwrap();
+/// streamer.read().unwrap();
+/// assert!(streamer.read().is_err());
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Max nesting level of arrays and objects
+    max_depth: Option<usize>,
+    /// Max length of an object key
+    max_key_length: Option<usize>,
+    /// Max length of a string or a number
+    max_value_length: Option<usize>,
+}
+
+impl Limits {
+    /// Creates new limits (nothing is limited by default)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets max nesting level of arrays and objects
+    ///
+    /// # Arguments
+    /// * `max_depth` - max depth (e.g. `[[1]]` has depth 2)
+    pub fn set_max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets max length of object keys
+    ///
+    /// # Arguments
+    /// * `max_key_length` - max length of a key in bytes (without quotes)
+    pub fn set_max_key_length(mut self, max_key_length: Option<usize>) -> Self {
+        self.max_key_length = max_key_length;
+        self
+    }
+
+    /// Sets max length of strings and numbers
+    ///
+    /// # Arguments
+    /// * `max_value_length` - max length of a value in bytes (including quotes)
+    pub fn set_max_value_length(mut self, max_value_length: Option<usize>) -> Self {
+        self.max_value_length = max_value_length;
+        self
+    }
 }
 
 /// Reads parts of UTF-8 json input and emits paths
@@ -103,20 +420,20 @@ enum States {
 /// Start( 1, ParsedKind::Arr) // Streamer.path == "{\"People\"}"
 /// Start( 3, ParsedKind::Obj) // Streamer.path == "{\"People\"}[0]"
 /// Start( 4, ParsedKind::Num) // Streamer.path == "{\"People\"}[0]{\"Height\"}"
-/// End(   5, ParsedKind::Num)
+/// End(   5, ParsedKind::Int)
 /// Start( 6, ParsedKind::Num) // Streamer.path == "{\"People\"}[0]{\"Age\"}"
-/// End(   7, ParsedKind::Num)
+/// End(   7, ParsedKind::Int)
 /// End(   8, ParsedKind::Obj)
 /// Start( 9, ParsedKind::Obj) // Streamer.path == "{\"People\"}[1]"
 /// Start(10, ParsedKind::Num) // Streamer.path == "{\"People\"}[1]{\"Height\"}"
-/// End(  11, ParsedKind::Num)
+/// End(  11, ParsedKind::Int)
 /// Start(12, ParsedKind::Num) // Streamer.path == "{\"People\"}[1]{\"Age\"}"
-/// End(  13, ParsedKind::Num)
+/// End(  13, ParsedKind::Int)
 /// End(  14, ParsedKind::Obj)
 /// End(  15, ParsedKind::Arr)
 /// End(  16, ParsedKind::Obj)
+/// Finished(16)
 /// ```
-#[derive(Debug)]
 pub struct Streamer {
     /// Path stack
     path: Path,
@@ -130,6 +447,58 @@ pub struct Streamer {
     total_idx: usize,
     /// Indicator whether to pop path in the next read
     pop_path: bool,
+    /// Skip non-JSON data between top level objects and arrays
+    scan_mode: bool,
+    /// Skip the rest of the line when the input is incorrect
+    skip_invalid: bool,
+    /// Path of the discarded value is kept until the next read
+    discarded: bool,
+    /// Keep `\uXXXX` escapes in object keys as they are
+    raw_keys: bool,
+    /// No more data will be fed
+    finished: bool,
+    /// Callback triggered when a top level value is read (start idx, end idx)
+    document_callback: Option<Box<dyn FnMut(usize, usize) + Send>>,
+    /// Total index where the current top level value starts
+    document_start: usize,
+    /// Total index where the last top level value ended (not reported yet)
+    document_end: Option<usize>,
+    /// Check internal invariants after each read (debug builds only)
+    invariant_checks: bool,
+    /// Reject input which is not a valid JSON
+    strict: bool,
+    /// Number of path elements which belong to the path prefix
+    prefix_depth: usize,
+    /// Format of the input data
+    input_format: InputFormat,
+    /// Limits of the input data
+    limits: Limits,
+}
+
+impl fmt::Debug for Streamer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Streamer")
+            .field("path", &self.path)
+            .field("states", &self.states)
+            .field("pending", &self.pending)
+            .field("pending_idx", &self.pending_idx)
+            .field("total_idx", &self.total_idx)
+            .field("pop_path", &self.pop_path)
+            .field("scan_mode", &self.scan_mode)
+            .field("skip_invalid", &self.skip_invalid)
+            .field("discarded", &self.discarded)
+            .field("raw_keys", &self.raw_keys)
+            .field("finished", &self.finished)
+            .field("document_callback", &self.document_callback.is_some())
+            .field("document_start", &self.document_start)
+            .field("document_end", &self.document_end)
+            .field("invariant_checks", &self.invariant_checks)
+            .field("strict", &self.strict)
+            .field("prefix_depth", &self.prefix_depth)
+            .field("input_format", &self.input_format)
+            .field("limits", &self.limits)
+            .finish()
+    }
 }
 
 impl Default for Streamer {
@@ -141,6 +510,19 @@ impl Default for Streamer {
             pending_idx: 0,
             total_idx: 0,
             pop_path: false,
+            scan_mode: false,
+            skip_invalid: false,
+            discarded: false,
+            raw_keys: false,
+            finished: false,
+            document_callback: None,
+            document_start: 0,
+            document_end: None,
+            invariant_checks: false,
+            strict: false,
+            prefix_depth: 0,
+            input_format: InputFormat::default(),
+            limits: Limits::default(),
         }
     }
 }
@@ -151,6 +533,11 @@ impl Streamer {
         Self::default()
     }
 
+    /// Returns the size of data which were fed and not processed yet
+    pub(crate) fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
     /// Returns current path
     pub fn current_path(&mut self) -> &mut Path {
         &mut self.path
@@ -193,19 +580,392 @@ impl Streamer {
         self.pending.extend(input);
     }
 
-    /// Moves cursor forward while characters are whitespace
-    fn process_remove_whitespace(&mut self) -> Option<Token> {
+    /// Feed streamer with the last chunk of data
+    ///
+    /// It marks the end of the input so that trailing
+    /// numbers, booleans and nulls can be terminated
+    /// (e.g. `123` at the end of the input)
+    pub fn feed_final(&mut self, input: &[u8]) {
+        self.feed(input);
+        self.finished = true;
+    }
+
+    /// Sets scan mode
+    ///
+    /// In scan mode all data on the top level are skipped
+    /// until `{` or `[` is found. It can be used to process
+    /// JSONs which are embedded in other text (e.g. logs).
+    ///
+    /// When the data after `{` or `[` are not a valid JSON
+    /// (e.g. `[INFO]`), `Token::Discarded` is emitted and the
+    /// scanning continues from the invalid character.
+    ///
+    /// # Arguments
+    /// * `scan` - whether scan mode should be used
+    pub fn set_scan_mode(&mut self, scan: bool) {
+        self.scan_mode = scan;
+    }
+
+    /// Sets whether the lines with an incorrect input are skipped
+    ///
+    /// When set, an incorrect input doesn't cause an error.
+    /// `Token::Discarded` is emitted instead and the rest of the line
+    /// is skipped. It is meant to be used together with `InputFormat::JsonLines`.
+    ///
+    /// The path where the error occured remains available
+    /// (see `Streamer::current_path`) until the next read.
+    ///
+    /// # Arguments
+    /// * `skip` - whether invalid lines should be skipped
+    pub fn set_skip_invalid(&mut self, skip: bool) {
+        self.skip_invalid = skip;
+    }
+
+    /// Returns whether invalid lines are skipped
+    pub(crate) fn is_skip_invalid(&self) -> bool {
+        self.skip_invalid
+    }
+
+    /// Sets raw keys mode
+    ///
+    /// By default `\uXXXX` escapes in object keys are decoded
+    /// so e.g. `{"\u0041": 1}` is stored as `{"A"}` in the path.
+    /// In raw keys mode the escapes are kept as they are.
+    ///
+    /// # Arguments
+    /// * `raw` - whether the keys should be kept in the raw form
+    pub fn set_raw_keys(&mut self, raw: bool) {
+        self.raw_keys = raw;
+    }
+
+    /// Sets a callback which is triggered when a top level value is read
+    ///
+    /// It is called with total indexes where the value starts and ends.
+    /// It can be used e.g. to split the input into separate documents.
+    ///
+    /// # Arguments
+    /// * `callback` - function called with (start, end) indexes
+    pub fn set_document_callback(&mut self, callback: Box<dyn FnMut(usize, usize) + Send>) {
+        self.document_callback = Some(callback);
+    }
+
+    /// Sets a prefix which is prepended to all reported paths
+    ///
+    /// It is useful when the input is a fragment which is logically nested
+    /// inside a larger document. The prefix can be changed at any time,
+    /// the parsing position is kept and only the path is re-rooted.
+    ///
+    /// # Arguments
+    /// * `prefix` - path which will be prepended
+    ///
+    /// # Example
+    /// ```
+    /// use streamson_lib::{path::Path, streamer::{Streamer, Token}};
+    ///
+    /// let mut streamer = Streamer::new();
+    /// streamer.set_path_prefix(Path::new().push_key("users"));
+    /// streamer.feed(br#"[{"name": "carl"}]"#);
+    /// streamer.read().unwrap(); // array start
+    /// streamer.read().unwrap(); // object start
+    /// assert_eq!(streamer.current_path().to_string(), r#"{"users"}[0]"#);
+    /// ```
+    pub fn set_path_prefix(&mut self, prefix: Path) {
+        let mut path = prefix;
+        for element in &self.path.get_path()[self.prefix_depth..] {
+            path.push(element.clone());
+        }
+        self.prefix_depth = path.depth() - (self.path.depth() - self.prefix_depth);
+        self.path = path;
+    }
+
+    /// Returns the path prefix
+    fn path_prefix(&self) -> Path {
+        let mut prefix = Path::new();
+        for element in &self.path.get_path()[..self.prefix_depth] {
+            prefix.push(element.clone());
+        }
+        prefix
+    }
+
+    /// Sets strict mode
+    ///
+    /// By default the input is parsed leniently and some invalid JSONs
+    /// are accepted (e.g. `[01]`, `[1,]` or `[nul]`).
+    /// In strict mode numbers, literals, trailing commas and escape sequences
+    /// in strings (e.g. `\uXXXX`) are validated and raw control characters
+    /// (U+0000 - U+001F) in strings are rejected.
+    ///
+    /// # Arguments
+    /// * `strict` - whether the input should be validated
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Sets the format of the input data
+    ///
+    /// With `InputFormat::JsonLines` each line is treated as a separate
+    /// JSON document. A line which ends before the document is complete or which
+    /// contains other data after the document is reported as an incorrect input
+    /// (see `Streamer::resync` to skip such lines). Empty lines are ignored.
+    ///
+    /// # Arguments
+    /// * `format` - format of the input data
+    ///
+    /// # Example
+    /// ```
+    /// use streamson_lib::streamer::{InputFormat, Streamer, Token};
+    ///
+    /// let mut streamer = Streamer::new();
+    /// streamer.set_input_format(InputFormat::JsonLines);
+    /// streamer.feed(b"1 2\n");
+    /// assert!(matches!(streamer.read().unwrap(), Token::Start(0, _)));
+    /// assert!(matches!(streamer.read().unwrap(), Token::End(1, _)));
+    /// assert!(matches!(streamer.read().unwrap(), Token::Finished(1)));
+    /// assert!(streamer.read().is_err());
+    /// ```
+    pub fn set_input_format(&mut self, format: InputFormat) {
+        self.input_format = format;
+    }
+
+    /// Returns the format of the input data
+    pub(crate) fn input_format(&self) -> InputFormat {
+        self.input_format
+    }
+
+    /// Sets limits of the input data
+    ///
+    /// # Arguments
+    /// * `limits` - limits which are checked while parsing (see `Limits`)
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// Returns limits of the input data
+    pub(crate) fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    /// Checks whether the length doesn't exceed the limit
+    fn check_limit(
+        &self,
+        limit: &str,
+        max: Option<usize>,
+        length: usize,
+    ) -> Result<(), error::General> {
+        match max {
+            Some(max) if length > max => {
+                Err(error::LimitExceeded::new(limit, max, self.total_idx + self.pending_idx).into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns whether strict mode is used
+    pub(crate) fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Checks whether the scalar value which ends at cursor is valid (in strict mode)
+    fn check_scalar(&self, kind: ParsedKind) -> Result<(), error::General> {
+        if !self.strict {
+            return Ok(());
+        }
+        let data: Vec<u8> = self.pending.range(..self.pending_idx).copied().collect();
+        let invalid = match kind {
+            ParsedKind::Num => invalid_number_position(&data),
+            _ => invalid_literal_position(&data),
+        };
+        if let Some(pos) = invalid {
+            // the data is never empty
+            let pos = pos.min(data.len() - 1);
+            Err(error::IncorrectInput::new(data[pos], self.total_idx + pos).into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Sets whether internal invariants are checked
+    ///
+    /// When enabled, the internal state of the streamer is checked
+    /// after each successful `read()` and it panics when the state is corrupted.
+    /// It is meant to be used for fuzzing and testing and
+    /// the checks are performed only in debug builds.
+    ///
+    /// # Arguments
+    /// * `checks` - whether the invariants should be checked
+    pub fn set_invariant_checks(&mut self, checks: bool) {
+        self.invariant_checks = checks;
+    }
+
+    /// Asserts that the internal state is consistent
+    fn check_invariants(&self) {
+        debug_assert!(
+            self.pending_idx <= self.pending.len(),
+            "pending index {} is out of pending data ({})",
+            self.pending_idx,
+            self.pending.len()
+        );
+
+        // open arrays and objects (whether it is an array)
+        let mut containers: Vec<bool> = vec![];
+        for (idx, state) in self.states.iter().enumerate() {
+            match state {
+                States::Array(_) => containers.push(true),
+                States::Object => containers.push(false),
+                States::Value(None) | States::SkipLine | States::LineEnd => debug_assert!(
+                    containers.is_empty(),
+                    "top level state {:?} is nested",
+                    state
+                ),
+                States::Value(Some(Element::Index(_))) => debug_assert_eq!(
+                    containers.last(),
+                    Some(&true),
+                    "array element is not within an array"
+                ),
+                States::Value(Some(Element::Key(_))) | States::ObjectKey(_) | States::Colon => {
+                    debug_assert_eq!(
+                        containers.last(),
+                        Some(&false),
+                        "object element is not within an object"
+                    )
+                }
+                States::Str(_) | States::Number(_) | States::Bool | States::Null => {
+                    debug_assert_eq!(
+                        idx + 1,
+                        self.states.len(),
+                        "{:?} is not the last state",
+                        state
+                    )
+                }
+                States::RemoveWhitespaces => {}
+            }
+        }
+
+        // path contains the elements of the containers (except for the top level one)
+        // and the element of the currently parsed scalar value
+        let in_scalar = matches!(
+            self.states.last(),
+            Some(States::Str(_))
+                | Some(States::Number(_))
+                | Some(States::Bool)
+                | Some(States::Null)
+        );
+        let expected_depth = if in_scalar {
+            containers.len()
+        } else {
+            containers.len().saturating_sub(1)
+        };
+        let depth = (self.path.depth() - self.prefix_depth).saturating_sub(self.pop_path as usize);
+        debug_assert_eq!(
+            depth, expected_depth,
+            "path depth doesn't match the states {:?}",
+            self.states
+        );
+    }
+
+    /// Skips the rest of the current line and resets the parsing
+    ///
+    /// It can be used to recover from an error in the input
+    /// where each JSON is on a separate line (e.g. logs).
+    ///
+    /// Data after the end of the line are removed from the streamer
+    /// and have to be fed again. If the end of the line wasn't fed yet,
+    /// the data will be skipped until it is found.
+    ///
+    /// # Returns
+    /// * `(idx, data)` - total index of the removed data and the data itself
+    pub fn resync(&mut self) -> (usize, Vec<u8>) {
+        self.path = self.path_prefix();
+        self.pop_path = false;
+        self.document_end = None;
+        self.states = vec![States::Value(None), States::RemoveWhitespaces];
+        let newline = self
+            .pending
+            .iter()
+            .skip(self.pending_idx)
+            .position(|byte| *byte == b'\n');
+        if let Some(pos) = newline {
+            self.pending_idx += pos + 1;
+        } else {
+            self.pending_idx = self.pending.len();
+            self.states.push(States::SkipLine);
+        }
+        self.advance();
+        (self.total_idx, self.pending.drain(..).collect())
+    }
+
+    /// Moves cursor forward behind the end of the line
+    fn process_skip_line(&mut self) -> Option<Token> {
         while let Some(byte) = self.peek() {
+            self.forward();
+            if byte == b'\n' {
+                self.advance();
+                return None;
+            }
+        }
+        self.advance();
+        self.states.push(States::SkipLine);
+        Some(Token::Pending)
+    }
+
+    /// Moves cursor forward behind the end of the line
+    /// where only whitespaces are expected (JSON Lines)
+    fn process_line_end(&mut self) -> Result<Option<Token>, error::General> {
+        while let Some(byte) = self.peek() {
+            if byte == b'\n' {
+                self.forward();
+                self.advance();
+                return Ok(None);
+            }
             if !byte.is_ascii_whitespace() {
+                self.advance();
+                return Err(error::IncorrectInput::new(byte, self.total_idx).into());
+            }
+            self.forward();
+        }
+        self.advance();
+        self.states.push(States::LineEnd);
+        Ok(Some(Token::Pending))
+    }
+
+    /// Moves cursor forward until an object or an array starts
+    fn process_scan(&mut self) -> Option<Token> {
+        while let Some(byte) = self.peek() {
+            if byte == b'{' || byte == b'[' {
                 self.advance();
                 return None;
             }
             self.forward();
         }
-        self.states.push(States::RemoveWhitespaces);
+        self.advance();
+        self.states.push(States::Value(None));
         Some(Token::Pending)
     }
 
+    /// Moves cursor forward while characters are whitespace
+    fn process_remove_whitespace(&mut self) -> Result<Option<Token>, error::General> {
+        while let Some(byte) = self.peek() {
+            if !byte.is_ascii_whitespace() {
+                self.advance();
+                return Ok(None);
+            }
+            if byte == b'\n'
+                && self.input_format == InputFormat::JsonLines
+                && self
+                    .states
+                    .iter()
+                    .any(|state| matches!(state, States::Array(_) | States::Object))
+            {
+                // the line ended before the document is complete
+                self.advance();
+                return Err(error::IncorrectInput::new(byte, self.total_idx).into());
+            }
+            self.forward();
+        }
+        self.states.push(States::RemoveWhitespaces);
+        Ok(Some(Token::Pending))
+    }
+
     /// Processes value which type will be determined later
     fn process_value(&mut self, element: Option<Element>) -> Result<Option<Token>, error::General> {
         if let Some(byte) = self.peek() {
@@ -219,8 +979,8 @@ impl Streamer {
                     }
                     Ok(Some(Token::Start(self.total_idx, ParsedKind::Str)))
                 }
-                b'0'..=b'9' => {
-                    self.states.push(States::Number);
+                b'0'..=b'9' | b'-' => {
+                    self.states.push(States::Number(false));
                     self.advance();
                     if let Some(element) = element {
                         self.path.push(element);
@@ -244,6 +1004,9 @@ impl Streamer {
                     Ok(Some(Token::Start(self.total_idx, ParsedKind::Null)))
                 }
                 b'[' => {
+                    let depth =
+                        self.path.depth() - self.prefix_depth + 1 + element.is_some() as usize;
+                    self.check_limit("depth", self.limits.max_depth, depth)?;
                     self.states.push(States::Array(0));
                     self.states.push(States::RemoveWhitespaces);
                     self.states.push(States::Value(Some(Element::Index(0))));
@@ -256,9 +1019,13 @@ impl Streamer {
                     Ok(Some(Token::Start(self.total_idx, ParsedKind::Arr)))
                 }
                 b'{' => {
+                    let depth =
+                        self.path.depth() - self.prefix_depth + 1 + element.is_some() as usize;
+                    self.check_limit("depth", self.limits.max_depth, depth)?;
                     self.states.push(States::Object);
                     self.states.push(States::RemoveWhitespaces);
-                    self.states.push(States::ObjectKey(ObjectKeyState::Init));
+                    self.states
+                        .push(States::ObjectKey(ObjectKeyState::Init(false)));
                     self.states.push(States::RemoveWhitespaces);
                     self.advance();
                     self.forward();
@@ -268,8 +1035,21 @@ impl Streamer {
                     Ok(Some(Token::Start(self.total_idx, ParsedKind::Obj)))
                 }
                 b']' | b'}' => {
-                    // End of an array or object -> no value matched
-                    Ok(None)
+                    let trailing = match element {
+                        Some(Element::Index(idx)) => idx > 0,
+                        Some(Element::Key(_)) => true,
+                        None => false,
+                    };
+                    if element.is_none() || (self.strict && trailing) {
+                        // there is no array or object to be closed on the top level
+                        Err(
+                            error::IncorrectInput::new(byte, self.total_idx + self.pending_idx)
+                                .into(),
+                        )
+                    } else {
+                        // End of an array or object -> no value matched
+                        Ok(None)
+                    }
                 }
                 byte => {
                     Err(error::IncorrectInput::new(byte, self.total_idx + self.pending_idx).into())
@@ -282,89 +1062,200 @@ impl Streamer {
     }
 
     /// Processes string on the input
-    fn process_str(&mut self, state: StringState) -> Option<Token> {
+    fn process_str(&mut self, state: StringState) -> Result<Option<Token>, error::General> {
         if let Some(byte) = self.peek() {
+            // cursor is not moved since the start of the string
+            self.check_limit(
+                "value length",
+                self.limits.max_value_length,
+                self.pending_idx + 1,
+            )?;
+            if state != StringState::Normal {
+                let new_state = self.escape_state(state, byte)?;
+                self.forward();
+                self.states.push(States::Str(new_state));
+                return Ok(None);
+            }
             match byte {
                 b'"' => {
-                    if state == StringState::Normal {
-                        self.forward();
-                        self.advance();
-                        Some(Token::End(self.total_idx, ParsedKind::Str))
-                    } else {
-                        self.forward();
-                        self.states.push(States::Str(StringState::Normal));
-                        None
-                    }
+                    self.forward();
+                    self.advance();
+                    Ok(Some(Token::End(self.total_idx, ParsedKind::Str)))
                 }
                 b'\\' => {
                     self.forward();
-                    let new_state = match state {
-                        StringState::Escaped => StringState::Normal,
-                        StringState::Normal => StringState::Escaped,
-                    };
-                    self.states.push(States::Str(new_state));
-                    None
+                    self.states.push(States::Str(StringState::Escaped));
+                    Ok(None)
+                }
+                0x00..=0x1f if self.strict => {
+                    // control characters have to be escaped
+                    Err(error::IncorrectInput::new(byte, self.total_idx + self.pending_idx).into())
                 }
                 _ => {
                     self.forward();
                     self.states.push(States::Str(StringState::Normal));
-                    None
+                    Ok(None)
                 }
             }
         } else {
             self.states.push(States::Str(state));
-            Some(Token::Pending)
+            Ok(Some(Token::Pending))
+        }
+    }
+
+    /// Returns the string state after a byte of an escape sequence
+    ///
+    /// Escape sequences are validated only in strict mode
+    fn escape_state(&self, state: StringState, byte: u8) -> Result<StringState, error::General> {
+        let valid = match state {
+            StringState::Normal => return Ok(StringState::Normal),
+            StringState::Escaped if !self.strict => return Ok(StringState::Normal),
+            StringState::Escaped if byte == b'u' => {
+                return Ok(StringState::Unicode {
+                    remaining: 4,
+                    value: 0,
+                    low: false,
+                })
+            }
+            StringState::Escaped => {
+                matches!(byte, b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't')
+            }
+            StringState::Unicode {
+                remaining,
+                value,
+                low,
+            } => match (byte as char).to_digit(16) {
+                Some(digit) if remaining > 1 => {
+                    return Ok(StringState::Unicode {
+                        remaining: remaining - 1,
+                        value: value * 16 + digit,
+                        low,
+                    })
+                }
+                Some(digit) => match (low, value * 16 + digit) {
+                    // surrogates have to be paired
+                    (false, 0xD800..=0xDBFF) => return Ok(StringState::Surrogate),
+                    (false, 0xDC00..=0xDFFF) => false,
+                    (true, code) => (0xDC00..=0xDFFF).contains(&code),
+                    (false, _) => true,
+                },
+                None => false,
+            },
+            StringState::Surrogate if byte == b'\\' => return Ok(StringState::SurrogateEscaped),
+            StringState::SurrogateEscaped if byte == b'u' => {
+                return Ok(StringState::Unicode {
+                    remaining: 4,
+                    value: 0,
+                    low: true,
+                })
+            }
+            StringState::Surrogate | StringState::SurrogateEscaped => false,
+        };
+        if valid {
+            Ok(StringState::Normal)
+        } else {
+            Err(error::IncorrectInput::new(byte, self.total_idx + self.pending_idx).into())
         }
     }
 
     /// Processes the number
-    fn process_number(&mut self) -> Option<Token> {
+    ///
+    /// # Arguments
+    /// * `float` - whether a fraction or an exponent was already seen
+    fn process_number(&mut self, float: bool) -> Result<Option<Token>, error::General> {
         if let Some(byte) = self.peek() {
-            if byte.is_ascii_digit() || byte == b'.' {
-                self.forward();
-                self.states.push(States::Number);
-                None
-            } else {
-                self.advance();
-                Some(Token::End(self.total_idx, ParsedKind::Num))
+            match byte {
+                b'0'..=b'9' | b'-' | b'+' => {
+                    self.check_limit(
+                        "value length",
+                        self.limits.max_value_length,
+                        self.pending_idx + 1,
+                    )?;
+                    self.forward();
+                    self.states.push(States::Number(float));
+                    Ok(None)
+                }
+                b'.' | b'e' | b'E' => {
+                    self.check_limit(
+                        "value length",
+                        self.limits.max_value_length,
+                        self.pending_idx + 1,
+                    )?;
+                    self.forward();
+                    self.states.push(States::Number(true));
+                    Ok(None)
+                }
+                _ => {
+                    self.check_scalar(ParsedKind::Num)?;
+                    self.advance();
+                    Ok(Some(Token::End(
+                        self.total_idx,
+                        if float {
+                            ParsedKind::Float
+                        } else {
+                            ParsedKind::Int
+                        },
+                    )))
+                }
             }
+        } else if self.finished {
+            self.check_scalar(ParsedKind::Num)?;
+            self.advance();
+            Ok(Some(Token::End(
+                self.total_idx,
+                if float {
+                    ParsedKind::Float
+                } else {
+                    ParsedKind::Int
+                },
+            )))
         } else {
-            self.states.push(States::Number);
-            Some(Token::Pending)
+            self.states.push(States::Number(float));
+            Ok(Some(Token::Pending))
         }
     }
 
     /// Processes bool
-    fn process_bool(&mut self) -> Option<Token> {
+    fn process_bool(&mut self) -> Result<Option<Token>, error::General> {
         if let Some(byte) = self.peek() {
             if byte.is_ascii_alphabetic() {
                 self.forward();
                 self.states.push(States::Bool);
-                None
+                Ok(None)
             } else {
+                self.check_scalar(ParsedKind::Bool)?;
                 self.advance();
-                Some(Token::End(self.total_idx, ParsedKind::Bool))
+                Ok(Some(Token::End(self.total_idx, ParsedKind::Bool)))
             }
+        } else if self.finished {
+            self.check_scalar(ParsedKind::Bool)?;
+            self.advance();
+            Ok(Some(Token::End(self.total_idx, ParsedKind::Bool)))
         } else {
             self.states.push(States::Bool);
-            Some(Token::Pending)
+            Ok(Some(Token::Pending))
         }
     }
 
     /// Processes null
-    fn process_null(&mut self) -> Option<Token> {
+    fn process_null(&mut self) -> Result<Option<Token>, error::General> {
         if let Some(byte) = self.peek() {
             if byte.is_ascii_alphabetic() {
                 self.forward();
                 self.states.push(States::Null);
-                None
+                Ok(None)
             } else {
+                self.check_scalar(ParsedKind::Null)?;
                 self.advance();
-                Some(Token::End(self.total_idx, ParsedKind::Null))
+                Ok(Some(Token::End(self.total_idx, ParsedKind::Null)))
             }
+        } else if self.finished {
+            self.check_scalar(ParsedKind::Null)?;
+            self.advance();
+            Ok(Some(Token::End(self.total_idx, ParsedKind::Null)))
         } else {
             self.states.push(States::Null);
-            Some(Token::Pending)
+            Ok(Some(Token::Pending))
         }
     }
 
@@ -384,7 +1275,7 @@ impl Streamer {
                     self.states
                         .push(States::Value(Some(Element::Index(idx + 1))));
                     self.states.push(States::RemoveWhitespaces);
-                    Ok(Some(Token::Separator(self.total_idx)))
+                    Ok(Some(Token::Separator(self.total_idx, ParsedKind::Arr)))
                 }
                 byte => {
                     Err(error::IncorrectInput::new(byte, self.total_idx + self.pending_idx).into())
@@ -409,9 +1300,10 @@ impl Streamer {
                     self.forward();
                     self.states.push(States::Object);
                     self.states.push(States::RemoveWhitespaces);
-                    self.states.push(States::ObjectKey(ObjectKeyState::Init));
+                    self.states
+                        .push(States::ObjectKey(ObjectKeyState::Init(true)));
                     self.states.push(States::RemoveWhitespaces);
-                    Ok(Some(Token::Separator(self.total_idx)))
+                    Ok(Some(Token::Separator(self.total_idx, ParsedKind::Obj)))
                 }
                 byte => {
                     Err(error::IncorrectInput::new(byte, self.total_idx + self.pending_idx).into())
@@ -429,7 +1321,7 @@ impl Streamer {
         state: ObjectKeyState,
     ) -> Result<Option<Token>, error::General> {
         match state {
-            ObjectKeyState::Init => {
+            ObjectKeyState::Init(next) => {
                 if let Some(byte) = self.peek() {
                     match byte {
                         b'"' => {
@@ -440,6 +1332,11 @@ impl Streamer {
                             )));
                             Ok(None)
                         }
+                        b'}' if next && self.strict => Err(error::IncorrectInput::new(
+                            byte,
+                            self.total_idx + self.pending_idx,
+                        )
+                        .into()), // trailing comma
                         b'}' => Ok(None), // end has been reached to Object
 
                         byte => Err(error::IncorrectInput::new(
@@ -455,13 +1352,41 @@ impl Streamer {
             }
             ObjectKeyState::Parse(string_state) => {
                 if let Some(byte) = self.peek() {
+                    if string_state != StringState::Normal || byte != b'"' {
+                        // cursor is at the opening quote
+                        self.check_limit(
+                            "key length",
+                            self.limits.max_key_length,
+                            self.pending_idx,
+                        )?;
+                    }
+                    if self.strict && byte < 0x20 {
+                        // control characters have to be escaped
+                        return Err(error::IncorrectInput::new(
+                            byte,
+                            self.total_idx + self.pending_idx,
+                        )
+                        .into());
+                    }
+                    if string_state != StringState::Normal {
+                        let new_state = self.escape_state(string_state, byte)?;
+                        self.forward();
+                        self.states
+                            .push(States::ObjectKey(ObjectKeyState::Parse(new_state)));
+                        return Ok(None);
+                    }
                     self.forward();
                     match string_state {
                         StringState::Normal => match byte {
                             b'\"' => {
                                 let idx = self.pending_idx;
                                 let slice = &self.advance().collect::<Vec<u8>>()[1..idx - 1];
-                                let key = from_utf8(slice)?.to_string();
+                                let key = from_utf8(slice)?;
+                                let key = if self.raw_keys {
+                                    key.to_string()
+                                } else {
+                                    decode_unicode_escapes(key)
+                                };
                                 self.states.push(States::Value(Some(Element::Key(key))));
                                 self.states.push(States::RemoveWhitespaces);
                                 self.states.push(States::Colon);
@@ -481,12 +1406,7 @@ impl Streamer {
                                 Ok(None)
                             }
                         },
-                        StringState::Escaped => {
-                            self.states.push(States::ObjectKey(ObjectKeyState::Parse(
-                                StringState::Normal,
-                            )));
-                            Ok(None)
-                        }
+                        _ => unreachable!(),
                     }
                 } else {
                     self.states
@@ -515,25 +1435,93 @@ impl Streamer {
 
     /// Reads data from streamer and emits [Token](enum.Token.html) struct
     ///
+    /// `Token::Finished` is emitted after each top level value so the end
+    /// of a document can be distinguished from `Token::Pending`.
+    ///
     /// # Errors
     ///
     /// If invalid JSON is passed and error may be emitted.
     /// Note that validity of input JSON is not checked.
     pub fn read(&mut self) -> Result<Token, error::General> {
+        if self.discarded {
+            self.path = self.path_prefix();
+            self.discarded = false;
+        }
+        if let Some(idx) = self.document_end.take() {
+            return Ok(Token::Finished(idx));
+        }
+        let token = match self.read_token() {
+            Err(error::General::IncorrectInput(err)) if self.scan_mode || self.skip_invalid => {
+                return Ok(self.discard(err.idx()));
+            }
+            res => res?,
+        };
+        if cfg!(debug_assertions) && self.invariant_checks {
+            self.check_invariants();
+        }
+        if let Token::End(idx, _) = token {
+            // only top level values have empty path on start and end
+            if self.path.depth() == self.prefix_depth {
+                self.document_end = Some(idx);
+            }
+        }
+        if let Token::Start(idx, _) = token {
+            // only top level values have empty path on start and end
+            if self.path.depth() == self.prefix_depth {
+                self.document_start = idx;
+            }
+        }
+        if let Some(callback) = self.document_callback.as_mut() {
+            if let Token::End(idx, _) = token {
+                if self.path.depth() == self.prefix_depth {
+                    callback(self.document_start, idx)
+                }
+            }
+        }
+        Ok(token)
+    }
+
+    /// Drops the partially read top level value
+    ///
+    /// In scan mode the scanning continues from the character which
+    /// caused the error as it may start another value.
+    /// Otherwise the rest of the line is skipped.
+    fn discard(&mut self, idx: usize) -> Token {
+        self.pop_path = false;
+        self.document_end = None;
+        self.discarded = true;
+        self.states = vec![States::Value(None), States::RemoveWhitespaces];
+        if !self.scan_mode {
+            self.states.push(States::SkipLine);
+        }
+        self.advance();
+        Token::Discarded(idx)
+    }
+
+    /// Reads the next token
+    fn read_token(&mut self) -> Result<Token, error::General> {
         loop {
             while let Some(state) = self.states.pop() {
                 if self.pop_path {
-                    self.path.pop();
+                    // top level values don't have an element in the path
+                    if self.path.depth() > self.prefix_depth {
+                        self.path.pop();
+                    }
                     self.pop_path = false;
                 }
 
                 match state {
                     States::RemoveWhitespaces => {
-                        if let Some(output) = self.process_remove_whitespace() {
+                        if let Some(output) = self.process_remove_whitespace()? {
                             return Ok(output);
                         }
                     }
                     States::Value(element) => {
+                        if element.is_none() && self.scan_mode {
+                            if let Some(output) = self.process_scan() {
+                                return Ok(output);
+                            }
+                        }
                         if let Some(output) = self.process_value(element)? {
                             return Ok(output);
                         }
@@ -542,25 +1530,25 @@ impl Streamer {
                         }
                     }
                     States::Str(state) => {
-                        if let Some(output) = self.process_str(state) {
+                        if let Some(output) = self.process_str(state)? {
                             self.pop_path = output.is_end();
                             return Ok(output);
                         }
                     }
-                    States::Number => {
-                        if let Some(output) = self.process_number() {
+                    States::Number(float) => {
+                        if let Some(output) = self.process_number(float)? {
                             self.pop_path = output.is_end();
                             return Ok(output);
                         }
                     }
                     States::Bool => {
-                        if let Some(output) = self.process_bool() {
+                        if let Some(output) = self.process_bool()? {
                             self.pop_path = output.is_end();
                             return Ok(output);
                         }
                     }
                     States::Null => {
-                        if let Some(output) = self.process_null() {
+                        if let Some(output) = self.process_null()? {
                             self.pop_path = output.is_end();
                             return Ok(output);
                         }
@@ -587,19 +1575,124 @@ impl Streamer {
                             return Ok(output);
                         }
                     }
+                    States::SkipLine => {
+                        if let Some(output) = self.process_skip_line() {
+                            return Ok(output);
+                        }
+                    }
+                    States::LineEnd => {
+                        if let Some(output) = self.process_line_end()? {
+                            return Ok(output);
+                        }
+                    }
                 }
             }
             self.states.push(States::Value(None));
             self.states.push(States::RemoveWhitespaces);
+            if self.input_format == InputFormat::JsonLines {
+                self.states.push(States::LineEnd);
+            }
         }
     }
 }
 
+/// Builder which creates a configured [Streamer](struct.Streamer.html)
+///
+/// All options are the same as the options of `Streamer::new()` by default.
+///
+/// # Example
+/// ```
+/// use streamson_lib::streamer::{StreamerBuilder, Token};
+///
+/// let mut streamer = StreamerBuilder::new()
+///     .set_strict(true)
+///     .set_scan_mode(true)
+///     .build();
+/// streamer.feed(b"log: [1]");
+/// assert!(matches!(streamer.read().unwrap(), Token::Start(5, _)));
+/// ```
+#[derive(Debug, Default)]
+pub struct StreamerBuilder {
+    /// Streamer which is being configured
+    streamer: Streamer,
+}
+
+impl StreamerBuilder {
+    /// Creates a new builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets scan mode (see `Streamer::set_scan_mode`)
+    pub fn set_scan_mode(mut self, scan: bool) -> Self {
+        self.streamer.set_scan_mode(scan);
+        self
+    }
+
+    /// Sets whether invalid lines are skipped (see `Streamer::set_skip_invalid`)
+    pub fn set_skip_invalid(mut self, skip: bool) -> Self {
+        self.streamer.set_skip_invalid(skip);
+        self
+    }
+
+    /// Sets raw keys mode (see `Streamer::set_raw_keys`)
+    pub fn set_raw_keys(mut self, raw: bool) -> Self {
+        self.streamer.set_raw_keys(raw);
+        self
+    }
+
+    /// Sets strict mode (see `Streamer::set_strict`)
+    pub fn set_strict(mut self, strict: bool) -> Self {
+        self.streamer.set_strict(strict);
+        self
+    }
+
+    /// Sets the input format (see `Streamer::set_input_format`)
+    pub fn set_input_format(mut self, format: InputFormat) -> Self {
+        self.streamer.set_input_format(format);
+        self
+    }
+
+    /// Sets limits of the input data (see `Streamer::set_limits`)
+    pub fn set_limits(mut self, limits: Limits) -> Self {
+        self.streamer.set_limits(limits);
+        self
+    }
+
+    /// Sets invariant checks (see `Streamer::set_invariant_checks`)
+    pub fn set_invariant_checks(mut self, checks: bool) -> Self {
+        self.streamer.set_invariant_checks(checks);
+        self
+    }
+
+    /// Sets the path prefix (see `Streamer::set_path_prefix`)
+    pub fn set_path_prefix(mut self, prefix: Path) -> Self {
+        self.streamer.set_path_prefix(prefix);
+        self
+    }
+
+    /// Sets the document callback (see `Streamer::set_document_callback`)
+    pub fn set_document_callback(mut self, callback: Box<dyn FnMut(usize, usize) + Send>) -> Self {
+        self.streamer.set_document_callback(callback);
+        self
+    }
+
+    /// Creates the configured streamer
+    pub fn build(self) -> Streamer {
+        self.streamer
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::{ParsedKind, Streamer, Token};
+    use super::{Element, InputFormat, Limits, ParsedKind, Streamer, StreamerBuilder, Token};
+    use crate::error;
     use crate::path::Path;
-    use std::convert::TryFrom;
+    use std::{
+        convert::TryFrom,
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
 
     fn make_path(path: &str) -> Path {
         Path::try_from(path).unwrap()
@@ -620,6 +1713,7 @@ mod test {
         assert_eq!(streamer.current_path(), &make_path(""));
         assert_eq!(streamer.read().unwrap(), Token::End(36, ParsedKind::Str));
         assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(36));
         assert_eq!(streamer.read().unwrap(), Token::Pending);
 
         let mut streamer = Streamer::new();
@@ -628,6 +1722,7 @@ mod test {
         assert_eq!(streamer.current_path(), &make_path(""));
         assert_eq!(streamer.read().unwrap(), Token::End(15, ParsedKind::Str));
         assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(15));
         assert_eq!(streamer.read().unwrap(), Token::Pending);
     }
 
@@ -637,9 +1732,58 @@ mod test {
         streamer.feed(br#" 3.24 "#);
         assert_eq!(streamer.read().unwrap(), Token::Start(1, ParsedKind::Num));
         assert_eq!(streamer.current_path(), &make_path(""));
-        assert_eq!(streamer.read().unwrap(), Token::End(5, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(5, ParsedKind::Float));
         assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(5));
+        assert_eq!(streamer.read().unwrap(), Token::Pending);
+    }
+
+    #[test]
+    fn test_number_kinds() {
+        let mut streamer = Streamer::new();
+        streamer.feed(br#"[3, 3.0, 3e2, -3, -3.5E-2]"#);
+        assert_eq!(streamer.read().unwrap(), Token::Start(0, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Start(1, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(2, ParsedKind::Int));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(2, ParsedKind::Arr)
+        );
+        assert_eq!(streamer.read().unwrap(), Token::Start(4, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(7, ParsedKind::Float));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(7, ParsedKind::Arr)
+        );
+        assert_eq!(streamer.read().unwrap(), Token::Start(9, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(12, ParsedKind::Float));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(12, ParsedKind::Arr)
+        );
+        assert_eq!(streamer.read().unwrap(), Token::Start(14, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(16, ParsedKind::Int));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(16, ParsedKind::Arr)
+        );
+        assert_eq!(streamer.read().unwrap(), Token::Start(18, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(25, ParsedKind::Float));
+        assert_eq!(streamer.read().unwrap(), Token::End(26, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(26));
+        assert_eq!(streamer.read().unwrap(), Token::Pending);
+
+        // number kind is kept when the number is split
+        let mut streamer = Streamer::new();
+        streamer.feed(br#"[12"#);
+        assert_eq!(streamer.read().unwrap(), Token::Start(0, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Start(1, ParsedKind::Num));
         assert_eq!(streamer.read().unwrap(), Token::Pending);
+        streamer.feed(br#"e3]"#);
+        assert_eq!(streamer.read().unwrap(), Token::End(5, ParsedKind::Float));
+
+        assert_eq!(ParsedKind::Int.as_ref(), ParsedKind::Num.as_ref());
+        assert_eq!(ParsedKind::Float.as_ref(), ParsedKind::Num.as_ref());
     }
 
     #[test]
@@ -650,6 +1794,7 @@ mod test {
         assert_eq!(streamer.current_path(), &make_path(""));
         assert_eq!(streamer.read().unwrap(), Token::End(6, ParsedKind::Bool));
         assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(6));
         assert_eq!(streamer.read().unwrap(), Token::Pending);
     }
 
@@ -668,6 +1813,7 @@ mod test {
         assert_eq!(streamer.current_path(), &make_path(""));
         assert_eq!(streamer.read().unwrap(), Token::End(4, ParsedKind::Null));
         assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(4));
         assert_eq!(streamer.read().unwrap(), Token::Pending);
     }
 
@@ -681,18 +1827,25 @@ mod test {
         assert_eq!(streamer.current_path(), &make_path("[0]"));
         assert_eq!(streamer.read().unwrap(), Token::End(6, ParsedKind::Null));
         assert_eq!(streamer.current_path(), &make_path("[0]"));
-        assert_eq!(streamer.read().unwrap(), Token::Separator(6));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(6, ParsedKind::Arr)
+        );
         assert_eq!(streamer.read().unwrap(), Token::Start(8, ParsedKind::Num));
         assert_eq!(streamer.current_path(), &make_path("[1]"));
-        assert_eq!(streamer.read().unwrap(), Token::End(10, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(10, ParsedKind::Int));
         assert_eq!(streamer.current_path(), &make_path("[1]"));
-        assert_eq!(streamer.read().unwrap(), Token::Separator(10));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(10, ParsedKind::Arr)
+        );
         assert_eq!(streamer.read().unwrap(), Token::Start(12, ParsedKind::Str));
         assert_eq!(streamer.current_path(), &make_path("[2]"));
         assert_eq!(streamer.read().unwrap(), Token::End(20, ParsedKind::Str));
         assert_eq!(streamer.current_path(), &make_path("[2]"));
         assert_eq!(streamer.read().unwrap(), Token::End(22, ParsedKind::Arr));
         assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(22));
         assert_eq!(streamer.read().unwrap(), Token::Pending);
     }
 
@@ -706,15 +1859,21 @@ mod test {
         assert_eq!(streamer.current_path(), &make_path("[0]"));
         assert_eq!(streamer.read().unwrap(), Token::End(6, ParsedKind::Null));
         assert_eq!(streamer.current_path(), &make_path("[0]"));
-        assert_eq!(streamer.read().unwrap(), Token::Separator(6));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(6, ParsedKind::Arr)
+        );
         assert_eq!(streamer.read().unwrap(), Token::Start(8, ParsedKind::Num));
         assert_eq!(streamer.current_path(), &make_path("[1]"));
         assert_eq!(streamer.read().unwrap(), Token::Pending);
         assert_eq!(streamer.current_path(), &make_path("[1]"));
         streamer.feed(br#"3,"#);
-        assert_eq!(streamer.read().unwrap(), Token::End(10, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(10, ParsedKind::Int));
         assert_eq!(streamer.current_path(), &make_path("[1]"));
-        assert_eq!(streamer.read().unwrap(), Token::Separator(10));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(10, ParsedKind::Arr)
+        );
         assert_eq!(streamer.read().unwrap(), Token::Pending);
         assert_eq!(streamer.current_path(), &make_path(""));
         streamer.feed(br#" "string" ]"#);
@@ -724,6 +1883,7 @@ mod test {
         assert_eq!(streamer.current_path(), &make_path("[2]"));
         assert_eq!(streamer.read().unwrap(), Token::End(22, ParsedKind::Arr));
         assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(22));
         assert_eq!(streamer.read().unwrap(), Token::Pending);
     }
 
@@ -735,6 +1895,7 @@ mod test {
         assert_eq!(streamer.current_path(), &make_path(""));
         assert_eq!(streamer.read().unwrap(), Token::End(2, ParsedKind::Arr));
         assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(2));
         assert_eq!(streamer.read().unwrap(), Token::Pending);
     }
 
@@ -748,32 +1909,45 @@ mod test {
         assert_eq!(streamer.current_path(), &make_path("[0]"));
         assert_eq!(streamer.read().unwrap(), Token::End(4, ParsedKind::Arr));
         assert_eq!(streamer.current_path(), &make_path("[0]"));
-        assert_eq!(streamer.read().unwrap(), Token::Separator(4));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(4, ParsedKind::Arr)
+        );
         assert_eq!(streamer.read().unwrap(), Token::Start(6, ParsedKind::Num));
         assert_eq!(streamer.current_path(), &make_path("[1]"));
-        assert_eq!(streamer.read().unwrap(), Token::End(8, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(8, ParsedKind::Int));
         assert_eq!(streamer.current_path(), &make_path("[1]"));
-        assert_eq!(streamer.read().unwrap(), Token::Separator(8));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(8, ParsedKind::Arr)
+        );
         assert_eq!(streamer.read().unwrap(), Token::Start(10, ParsedKind::Arr));
         assert_eq!(streamer.current_path(), &make_path("[2]"));
         assert_eq!(streamer.read().unwrap(), Token::Start(11, ParsedKind::Str));
         assert_eq!(streamer.current_path(), &make_path("[2][0]"));
         assert_eq!(streamer.read().unwrap(), Token::End(19, ParsedKind::Str));
         assert_eq!(streamer.current_path(), &make_path("[2][0]"));
-        assert_eq!(streamer.read().unwrap(), Token::Separator(20));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(20, ParsedKind::Arr)
+        );
         assert_eq!(streamer.read().unwrap(), Token::Start(22, ParsedKind::Num));
         assert_eq!(streamer.current_path(), &make_path("[2][1]"));
-        assert_eq!(streamer.read().unwrap(), Token::End(24, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(24, ParsedKind::Int));
         assert_eq!(streamer.current_path(), &make_path("[2][1]"));
         assert_eq!(streamer.read().unwrap(), Token::End(25, ParsedKind::Arr));
         assert_eq!(streamer.current_path(), &make_path("[2]"));
-        assert_eq!(streamer.read().unwrap(), Token::Separator(25));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(25, ParsedKind::Arr)
+        );
         assert_eq!(streamer.read().unwrap(), Token::Start(27, ParsedKind::Arr));
         assert_eq!(streamer.current_path(), &make_path("[3]"));
         assert_eq!(streamer.read().unwrap(), Token::End(31, ParsedKind::Arr));
         assert_eq!(streamer.current_path(), &make_path("[3]"));
         assert_eq!(streamer.read().unwrap(), Token::End(32, ParsedKind::Arr));
         assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(32));
         assert_eq!(streamer.read().unwrap(), Token::Pending);
     }
 
@@ -787,23 +1961,33 @@ mod test {
         assert_eq!(streamer.current_path(), &make_path("{\"a\"}"));
         assert_eq!(streamer.read().unwrap(), Token::End(8, ParsedKind::Str));
         assert_eq!(streamer.current_path(), &make_path("{\"a\"}"));
-        assert_eq!(streamer.read().unwrap(), Token::Separator(8));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(8, ParsedKind::Obj)
+        );
         assert_eq!(streamer.read().unwrap(), Token::Start(17, ParsedKind::Bool));
         assert_eq!(streamer.current_path(), &make_path("{\"b\"}"));
         assert_eq!(streamer.read().unwrap(), Token::End(21, ParsedKind::Bool));
         assert_eq!(streamer.current_path(), &make_path("{\"b\"}"));
-        assert_eq!(streamer.read().unwrap(), Token::Separator(22));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(22, ParsedKind::Obj)
+        );
         assert_eq!(streamer.read().unwrap(), Token::Start(29, ParsedKind::Null));
         assert_eq!(streamer.current_path(), &make_path("{\"c\"}"));
         assert_eq!(streamer.read().unwrap(), Token::End(33, ParsedKind::Null));
         assert_eq!(streamer.current_path(), &make_path("{\"c\"}"));
-        assert_eq!(streamer.read().unwrap(), Token::Separator(33));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(33, ParsedKind::Obj)
+        );
         assert_eq!(streamer.read().unwrap(), Token::Start(50, ParsedKind::Num));
         assert_eq!(streamer.current_path(), &make_path(r#"{" \" \\\" \\"}"#));
-        assert_eq!(streamer.read().unwrap(), Token::End(52, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(52, ParsedKind::Int));
         assert_eq!(streamer.current_path(), &make_path(r#"{" \" \\\" \\"}"#));
         assert_eq!(streamer.read().unwrap(), Token::End(53, ParsedKind::Obj));
         assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(53));
         assert_eq!(streamer.read().unwrap(), Token::Pending);
     }
 
@@ -815,6 +1999,7 @@ mod test {
         assert_eq!(streamer.current_path(), &make_path(""));
         assert_eq!(streamer.read().unwrap(), Token::End(2, ParsedKind::Obj));
         assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(2));
         assert_eq!(streamer.read().unwrap(), Token::Pending);
     }
 
@@ -828,22 +2013,29 @@ mod test {
         assert_eq!(streamer.current_path(), &make_path("{\"u\"}"));
         assert_eq!(streamer.read().unwrap(), Token::End(9, ParsedKind::Obj));
         assert_eq!(streamer.current_path(), &make_path("{\"u\"}"));
-        assert_eq!(streamer.read().unwrap(), Token::Separator(9));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(9, ParsedKind::Obj)
+        );
         assert_eq!(streamer.read().unwrap(), Token::Start(16, ParsedKind::Obj));
         assert_eq!(streamer.current_path(), &make_path("{\"j\"}"));
         assert_eq!(streamer.read().unwrap(), Token::Start(22, ParsedKind::Obj));
         assert_eq!(streamer.current_path(), &make_path("{\"j\"}{\"x\"}"));
         assert_eq!(streamer.read().unwrap(), Token::End(26, ParsedKind::Obj));
         assert_eq!(streamer.current_path(), &make_path("{\"j\"}{\"x\"}"));
-        assert_eq!(streamer.read().unwrap(), Token::Separator(26));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(26, ParsedKind::Obj)
+        );
         assert_eq!(streamer.read().unwrap(), Token::Start(33, ParsedKind::Num));
         assert_eq!(streamer.current_path(), &make_path("{\"j\"}{\"y\"}"));
-        assert_eq!(streamer.read().unwrap(), Token::End(35, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(35, ParsedKind::Int));
         assert_eq!(streamer.current_path(), &make_path("{\"j\"}{\"y\"}"));
         assert_eq!(streamer.read().unwrap(), Token::End(36, ParsedKind::Obj));
         assert_eq!(streamer.current_path(), &make_path("{\"j\"}"));
         assert_eq!(streamer.read().unwrap(), Token::End(37, ParsedKind::Obj));
         assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(37));
         assert_eq!(streamer.read().unwrap(), Token::Pending);
     }
 
@@ -893,7 +2085,7 @@ mod test {
                 get_item(Some("[0]{\"aha y\"}")),
                 Token::End(14, ParsedKind::Obj)
             );
-            assert_eq!(get_item(None), Token::Separator(14));
+            assert_eq!(get_item(None), Token::Separator(14, ParsedKind::Obj));
             assert_eq!(
                 get_item(Some("[0]{\"j\"}")),
                 Token::Start(21, ParsedKind::Obj)
@@ -910,7 +2102,7 @@ mod test {
                 get_item(Some("[0]{\"j\"}{\"x\"}[0]")),
                 Token::End(32, ParsedKind::Obj)
             );
-            assert_eq!(get_item(None), Token::Separator(32));
+            assert_eq!(get_item(None), Token::Separator(32, ParsedKind::Arr));
             assert_eq!(
                 get_item(Some("[0]{\"j\"}{\"x\"}[1]")),
                 Token::Start(34, ParsedKind::Arr)
@@ -923,7 +2115,7 @@ mod test {
                 get_item(Some("[0]{\"j\"}{\"x\"}[1][0]")),
                 Token::End(38, ParsedKind::Obj)
             );
-            assert_eq!(get_item(None), Token::Separator(38));
+            assert_eq!(get_item(None), Token::Separator(38, ParsedKind::Arr));
             assert_eq!(
                 get_item(Some("[0]{\"j\"}{\"x\"}[1][1]")),
                 Token::Start(40, ParsedKind::Null)
@@ -940,27 +2132,27 @@ mod test {
                 get_item(Some("[0]{\"j\"}{\"x\"}")),
                 Token::End(47, ParsedKind::Arr)
             );
-            assert_eq!(get_item(None), Token::Separator(47));
+            assert_eq!(get_item(None), Token::Separator(47, ParsedKind::Obj));
             assert_eq!(
                 get_item(Some("[0]{\"j\"}{\"y\"}")),
                 Token::Start(55, ParsedKind::Num)
             );
             assert_eq!(
                 get_item(Some("[0]{\"j\"}{\"y\"}")),
-                Token::End(57, ParsedKind::Num)
+                Token::End(57, ParsedKind::Int)
             );
             assert_eq!(
                 get_item(Some("[0]{\"j\"}")),
                 Token::End(58, ParsedKind::Obj)
             );
             assert_eq!(get_item(Some("[0]")), Token::End(59, ParsedKind::Obj));
-            assert_eq!(get_item(None), Token::Separator(59));
+            assert_eq!(get_item(None), Token::Separator(59, ParsedKind::Arr));
             assert_eq!(get_item(Some("[1]")), Token::Start(61, ParsedKind::Null));
             assert_eq!(get_item(Some("[1]")), Token::End(65, ParsedKind::Null));
-            assert_eq!(get_item(None), Token::Separator(65));
+            assert_eq!(get_item(None), Token::Separator(65, ParsedKind::Arr));
             assert_eq!(get_item(Some("[2]")), Token::Start(67, ParsedKind::Num));
-            assert_eq!(get_item(Some("[2]")), Token::End(69, ParsedKind::Num));
-            assert_eq!(get_item(None), Token::Separator(69));
+            assert_eq!(get_item(Some("[2]")), Token::End(69, ParsedKind::Int));
+            assert_eq!(get_item(None), Token::Separator(69, ParsedKind::Arr));
             assert_eq!(get_item(Some("[3]")), Token::Start(71, ParsedKind::Arr));
             assert_eq!(get_item(Some("[3][0]")), Token::Start(73, ParsedKind::Obj));
             assert_eq!(
@@ -974,6 +2166,7 @@ mod test {
             assert_eq!(get_item(Some("[3][0]")), Token::End(85, ParsedKind::Obj));
             assert_eq!(get_item(Some("[3]")), Token::End(87, ParsedKind::Arr));
             assert_eq!(get_item(Some("")), Token::End(89, ParsedKind::Arr));
+            assert_eq!(get_item(Some("")), Token::Finished(89));
             assert_eq!(get_item(None), Token::Pending);
         }
     }
@@ -1024,14 +2217,50 @@ mod test {
                 Token::End(26, ParsedKind::Str)
             );
             assert_eq!(get_item(Some("[0]")), Token::End(27, ParsedKind::Obj));
-            assert_eq!(get_item(None), Token::Separator(27));
+            assert_eq!(get_item(None), Token::Separator(27, ParsedKind::Arr));
             assert_eq!(get_item(Some("[1]")), Token::Start(29, ParsedKind::Str));
             assert_eq!(get_item(Some("[1]")), Token::End(40, ParsedKind::Str));
             assert_eq!(get_item(Some("")), Token::End(41, ParsedKind::Arr));
+            assert_eq!(get_item(Some("")), Token::Finished(41));
             assert_eq!(get_item(None), Token::Pending);
         }
     }
 
+    #[test]
+    fn test_unicode_escaped_keys() {
+        let input = br#"{"\u0041": 1, "a\u00e9\ud83d\ude00": 2, "\u0022\n\\u0041\u001f\ud800": 3}"#;
+        let read_paths = |streamer: &mut Streamer| {
+            streamer.feed(input);
+            let mut paths = vec![];
+            loop {
+                match streamer.read().unwrap() {
+                    Token::Start(_, ParsedKind::Num) => {
+                        paths.push(streamer.current_path().to_string())
+                    }
+                    Token::Pending => break paths,
+                    _ => {}
+                }
+            }
+        };
+
+        let mut streamer = Streamer::new();
+        assert_eq!(
+            read_paths(&mut streamer),
+            vec![r#"{"A"}"#, r#"{"aé😀"}"#, r#"{"\"\n\\u0041\u001f\ud800"}"#,]
+        );
+
+        let mut streamer = Streamer::new();
+        streamer.set_raw_keys(true);
+        assert_eq!(
+            read_paths(&mut streamer),
+            vec![
+                r#"{"\u0041"}"#,
+                r#"{"a\u00e9\ud83d\ude00"}"#,
+                r#"{"\u0022\n\\u0041\u001f\ud800"}"#,
+            ]
+        );
+    }
+
     #[test]
     fn test_multiple_input_flat() {
         let mut streamer = Streamer::new();
@@ -1040,15 +2269,132 @@ mod test {
         assert_eq!(streamer.current_path(), &make_path(""));
         assert_eq!(streamer.read().unwrap(), Token::End(7, ParsedKind::Str));
         assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(7));
         assert_eq!(streamer.read().unwrap(), Token::Start(8, ParsedKind::Str));
         assert_eq!(streamer.current_path(), &make_path(""));
         assert_eq!(streamer.read().unwrap(), Token::End(16, ParsedKind::Str));
         assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(16));
         assert_eq!(streamer.read().unwrap(), Token::Start(16, ParsedKind::Str));
         assert_eq!(streamer.current_path(), &make_path(""));
         assert_eq!(streamer.read().unwrap(), Token::End(23, ParsedKind::Str));
         assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(23));
+        assert_eq!(streamer.read().unwrap(), Token::Pending);
+    }
+
+    #[test]
+    fn test_scan_mode() {
+        let mut streamer = Streamer::new();
+        streamer.set_scan_mode(true);
+        streamer.feed(br#"INFO got {"a": 1} and then"#);
+        assert_eq!(streamer.read().unwrap(), Token::Start(9, ParsedKind::Obj));
+        assert_eq!(streamer.read().unwrap(), Token::Start(15, ParsedKind::Num));
+        assert_eq!(streamer.current_path(), &make_path(r#"{"a"}"#));
+        assert_eq!(streamer.read().unwrap(), Token::End(16, ParsedKind::Int));
+        assert_eq!(streamer.read().unwrap(), Token::End(17, ParsedKind::Obj));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(17));
+        assert_eq!(streamer.read().unwrap(), Token::Pending);
+        streamer.feed(br#" "other" text [true] done"#);
+        assert_eq!(streamer.read().unwrap(), Token::Start(40, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Start(41, ParsedKind::Bool));
+        assert_eq!(streamer.current_path(), &make_path("[0]"));
+        assert_eq!(streamer.read().unwrap(), Token::End(45, ParsedKind::Bool));
+        assert_eq!(streamer.read().unwrap(), Token::End(46, ParsedKind::Arr));
+        assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(46));
+        assert_eq!(streamer.read().unwrap(), Token::Pending);
+    }
+
+    #[test]
+    fn test_scan_mode_discard() {
+        let mut streamer = Streamer::new();
+        streamer.set_scan_mode(true);
+        streamer.feed(br#"INFO [see docs] got {"a": 1}"#);
+        assert_eq!(streamer.read().unwrap(), Token::Start(5, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Discarded(6));
+        assert_eq!(streamer.read().unwrap(), Token::Start(20, ParsedKind::Obj));
+        assert_eq!(streamer.read().unwrap(), Token::Start(26, ParsedKind::Num));
+        assert_eq!(streamer.current_path(), &make_path(r#"{"a"}"#));
+        assert_eq!(streamer.read().unwrap(), Token::End(27, ParsedKind::Int));
+        assert_eq!(streamer.read().unwrap(), Token::End(28, ParsedKind::Obj));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(28));
+        assert_eq!(streamer.read().unwrap(), Token::Pending);
+
+        // invalid character starts another value
+        streamer.feed(br#" {"b" [1]}"#);
+        assert_eq!(streamer.read().unwrap(), Token::Start(29, ParsedKind::Obj));
+        assert_eq!(streamer.read().unwrap(), Token::Discarded(34));
+        assert_eq!(streamer.read().unwrap(), Token::Start(34, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Start(35, ParsedKind::Num));
+        assert_eq!(streamer.current_path(), &make_path("[0]"));
+        assert_eq!(streamer.read().unwrap(), Token::End(36, ParsedKind::Int));
+        assert_eq!(streamer.read().unwrap(), Token::End(37, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(37));
+        assert_eq!(streamer.read().unwrap(), Token::Pending);
+    }
+
+    #[test]
+    fn test_skip_invalid() {
+        let mut streamer = Streamer::new();
+        streamer.set_input_format(InputFormat::JsonLines);
+        streamer.set_skip_invalid(true);
+        streamer.feed(b"{\"a\": [1 x]}\n[2]");
+        assert_eq!(streamer.read().unwrap(), Token::Start(0, ParsedKind::Obj));
+        assert_eq!(streamer.read().unwrap(), Token::Start(6, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Start(7, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(8, ParsedKind::Int));
+        assert_eq!(streamer.read().unwrap(), Token::Discarded(9));
+        // path of the error is kept till the next read
+        assert_eq!(streamer.current_path(), &make_path(r#"{"a"}"#));
+        assert_eq!(streamer.read().unwrap(), Token::Start(13, ParsedKind::Arr));
+        assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Start(14, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(15, ParsedKind::Int));
+        assert_eq!(streamer.read().unwrap(), Token::End(16, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(16));
+        assert_eq!(streamer.read().unwrap(), Token::Pending);
+
+        // the end of the line is not fed yet
+        streamer.feed(b"\n[x");
+        assert_eq!(streamer.read().unwrap(), Token::Start(17, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Discarded(18));
+        assert_eq!(streamer.read().unwrap(), Token::Pending);
+        streamer.feed(b"]]\n3\n");
+        assert_eq!(streamer.read().unwrap(), Token::Start(22, ParsedKind::Num));
+    }
+
+    #[test]
+    fn test_resync() {
+        let mut streamer = Streamer::new();
+        streamer.feed(b"[1]\n{\"a\" 1}");
+        assert_eq!(streamer.read().unwrap(), Token::Start(0, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Start(1, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(2, ParsedKind::Int));
+        assert_eq!(streamer.read().unwrap(), Token::End(3, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(3));
+        assert_eq!(streamer.read().unwrap(), Token::Start(4, ParsedKind::Obj));
+        assert!(streamer.read().is_err());
+
+        // the end of the line was not fed yet
+        assert_eq!(streamer.resync(), (11, vec![]));
+        assert_eq!(streamer.current_path(), &make_path(""));
+        streamer.feed(br#"{"b": [} "#);
+        assert_eq!(streamer.read().unwrap(), Token::Pending);
+        streamer.feed(b"\n{\"c\": true}\n");
+        assert_eq!(streamer.read().unwrap(), Token::Start(21, ParsedKind::Obj));
+
+        // the end of the line is already present
+        let mut streamer = Streamer::new();
+        streamer.feed(b"{\"a\": [}\n\"b\"");
+        assert_eq!(streamer.read().unwrap(), Token::Start(0, ParsedKind::Obj));
+        assert_eq!(streamer.read().unwrap(), Token::Start(6, ParsedKind::Arr));
+        assert!(streamer.read().is_err());
+        assert_eq!(streamer.resync(), (9, br#""b""#.to_vec()));
         assert_eq!(streamer.read().unwrap(), Token::Pending);
+        streamer.feed(br#""b""#);
+        assert_eq!(streamer.read().unwrap(), Token::Start(9, ParsedKind::Str));
+        assert_eq!(streamer.read().unwrap(), Token::End(12, ParsedKind::Str));
     }
 
     #[test]
@@ -1069,22 +2415,465 @@ mod test {
         assert_eq!(streamer.current_path(), &make_path("{\"u\"}"));
         assert_eq!(streamer.read().unwrap(), Token::End(26, ParsedKind::Obj));
         assert_eq!(streamer.current_path(), &make_path("{\"u\"}"));
-        assert_eq!(streamer.read().unwrap(), Token::Separator(26));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(26, ParsedKind::Obj)
+        );
         assert_eq!(streamer.read().unwrap(), Token::Start(49, ParsedKind::Obj));
         assert_eq!(streamer.current_path(), &make_path("{\"j\"}"));
         assert_eq!(streamer.read().unwrap(), Token::Start(76, ParsedKind::Obj));
         assert_eq!(streamer.current_path(), &make_path("{\"j\"}{\"x\"}"));
         assert_eq!(streamer.read().unwrap(), Token::End(80, ParsedKind::Obj));
         assert_eq!(streamer.current_path(), &make_path("{\"j\"}{\"x\"}"));
-        assert_eq!(streamer.read().unwrap(), Token::Separator(81));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(81, ParsedKind::Obj)
+        );
         assert_eq!(streamer.read().unwrap(), Token::Start(107, ParsedKind::Num));
         assert_eq!(streamer.current_path(), &make_path("{\"j\"}{\"y\"}"));
-        assert_eq!(streamer.read().unwrap(), Token::End(109, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(109, ParsedKind::Int));
         assert_eq!(streamer.current_path(), &make_path("{\"j\"}{\"y\"}"));
         assert_eq!(streamer.read().unwrap(), Token::End(127, ParsedKind::Obj));
         assert_eq!(streamer.current_path(), &make_path("{\"j\"}"));
         assert_eq!(streamer.read().unwrap(), Token::End(141, ParsedKind::Obj));
         assert_eq!(streamer.current_path(), &make_path(""));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(141));
+        assert_eq!(streamer.read().unwrap(), Token::Pending);
+    }
+
+    #[test]
+    fn test_stray_closing_bracket() {
+        let mut streamer = Streamer::new();
+        streamer.feed(br#"  }{"a": 1}"#);
+        let err = streamer.read().unwrap_err();
+        assert_eq!(err.to_string(), "Incorrect input (byte '125' on idx 2)");
+
+        let mut streamer = Streamer::new();
+        streamer.feed(br#"[1] ]"#);
+        assert_eq!(streamer.read().unwrap(), Token::Start(0, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Start(1, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(2, ParsedKind::Int));
+        assert_eq!(streamer.read().unwrap(), Token::End(3, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(3));
+        let err = streamer.read().unwrap_err();
+        assert_eq!(err.to_string(), "Incorrect input (byte '93' on idx 4)");
+    }
+
+    #[test]
+    fn test_feed_final() {
+        let mut streamer = Streamer::new();
+        streamer.feed(br#"{"a": 1} 12"#);
+        assert_eq!(streamer.read().unwrap(), Token::Start(0, ParsedKind::Obj));
+        assert_eq!(streamer.read().unwrap(), Token::Start(6, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(7, ParsedKind::Int));
+        assert_eq!(streamer.read().unwrap(), Token::End(8, ParsedKind::Obj));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(8));
+        assert_eq!(streamer.read().unwrap(), Token::Start(9, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::Pending);
+
+        streamer.feed_final(b"3.5");
+        assert_eq!(streamer.read().unwrap(), Token::End(14, ParsedKind::Float));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(14));
+        assert_eq!(streamer.read().unwrap(), Token::Pending);
+
+        for (input, start_kind, end_kind) in &[
+            (&b"true"[..], ParsedKind::Bool, ParsedKind::Bool),
+            (&b"null"[..], ParsedKind::Null, ParsedKind::Null),
+            (&b"-1"[..], ParsedKind::Num, ParsedKind::Int),
+        ] {
+            let mut streamer = Streamer::new();
+            streamer.feed_final(input);
+            assert_eq!(streamer.read().unwrap(), Token::Start(0, *start_kind));
+            assert_eq!(streamer.read().unwrap(), Token::End(input.len(), *end_kind));
+            assert_eq!(streamer.read().unwrap(), Token::Finished(input.len()));
+            assert_eq!(streamer.read().unwrap(), Token::Pending);
+        }
+    }
+
+    #[test]
+    fn test_document_callback() {
+        let documents = Arc::new(Mutex::new(vec![]));
+        let documents_cloned = documents.clone();
+
+        let mut streamer = Streamer::new();
+        streamer.set_document_callback(Box::new(move |start, end| {
+            documents_cloned.lock().unwrap().push((start, end));
+        }));
+
+        let input = br#"{"a": [1, {}]} ["b"] "c""#;
+        for part in input.chunks(4) {
+            streamer.feed(part);
+            while streamer.read().unwrap() != Token::Pending {}
+        }
+
+        let documents = documents.lock().unwrap();
+        assert_eq!(*documents, vec![(0, 14), (15, 20), (21, 24)]);
+        assert_eq!(&input[documents[0].0..documents[0].1], br#"{"a": [1, {}]}"#);
+        assert_eq!(&input[documents[1].0..documents[1].1], br#"["b"]"#);
+        assert_eq!(&input[documents[2].0..documents[2].1], br#""c""#);
+    }
+
+    #[test]
+    fn test_invariant_checks() {
+        const COMPLEX_DATA: &[u8] = br#" [{"aha y": {}, "j": {"x": [{  }, [ {}, null ]], "y" : 10}}, null, 43, [ {"a": false, "\"b\"": "c\\"} ] ] 1.5 "x""#;
+
+        for i in 0..COMPLEX_DATA.len() {
+            let mut streamer = Streamer::new();
+            streamer.set_invariant_checks(true);
+            let mut ends = 0;
+            for part in &[&COMPLEX_DATA[0..i], &COMPLEX_DATA[i..]] {
+                streamer.feed(part);
+                loop {
+                    match streamer.read().unwrap() {
+                        Token::Pending => break,
+                        Token::End(_, _) => ends += 1,
+                        _ => {}
+                    }
+                }
+            }
+            streamer.feed_final(b"");
+            while streamer.read().unwrap() != Token::Pending {}
+            assert_eq!(ends, 18);
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "path depth")]
+    fn test_invariant_checks_corrupted() {
+        let mut streamer = Streamer::new();
+        streamer.set_invariant_checks(true);
+        streamer.feed(br#"{"a": [1, "#);
+        assert_eq!(streamer.read().unwrap(), Token::Start(0, ParsedKind::Obj));
+        streamer.path.push(Element::Index(0));
+        streamer.read().unwrap();
+    }
+
+    #[test]
+    fn test_strict() {
+        fn read_all(input: &[u8], strict: bool, split: usize) -> Result<usize, error::General> {
+            let mut streamer = Streamer::new();
+            streamer.set_strict(strict);
+            let mut ends = 0;
+            for (idx, part) in [&input[..split], &input[split..]].iter().enumerate() {
+                if idx == 0 {
+                    streamer.feed(part);
+                } else {
+                    streamer.feed_final(part);
+                }
+                loop {
+                    match streamer.read()? {
+                        Token::Pending => break,
+                        Token::End(_, _) => ends += 1,
+                        _ => {}
+                    }
+                }
+            }
+            Ok(ends)
+        }
+
+        let valid: &[&[u8]] = &[
+            br#"[0, -0, 10, -1.5, 1e5, 1E-5, 2.5e+3, true, false, null, [], {}]"#,
+            br#"{"a": [1, {"b": null}], "c": ""}"#,
+            b"42",
+            b"-0.0",
+            br#"["\" \\ \/ \b \f \n \r \t \u00e9 \uABCD", {"\u0041\"": 1}]"#,
+            br#"["\ud83d\ude00", {"\uD83D\uDE00": 1}]"#,
+        ];
+        for input in valid {
+            for split in 0..=input.len() {
+                assert!(read_all(input, true, split).is_ok());
+            }
+        }
+
+        let invalid: &[(&[u8], u8, usize)] = &[
+            (b"[01]", b'1', 2),
+            (b"[-]", b'-', 1),
+            (b"[1.]", b'.', 2),
+            (b"[1e]", b'e', 2),
+            (b"[1-2]", b'-', 2),
+            (b"[nul]", b'l', 3),
+            (b"[trux]", b'x', 4),
+            (b"[falsey]", b'y', 6),
+            (b"[1, ]", b']', 4),
+            (br#"{"a": 1, }"#, b'}', 9),
+            (br#"{"a": }"#, b'}', 6),
+            (b"1.", b'.', 1),
+            // raw control characters in strings
+            (b"[\"a\nb\"]", b'\n', 3),
+            (b"[\"\0\"]", 0, 2),
+            (b"[\"\\\n\"]", b'\n', 3),
+            (b"{\"a\tb\": 1}", b'\t', 3),
+            // escape sequences
+            (br#"["\x"]"#, b'x', 3),
+            (br#"["\u12g4"]"#, b'g', 6),
+            (br#"["\u12"]"#, b'"', 6),
+            (br#"{"\q": 1}"#, b'q', 3),
+            (br#"{"\u00": 1}"#, b'"', 6),
+            // unpaired surrogates
+            (br#"["\ud83d"]"#, b'"', 8),
+            (br#"["\ude00"]"#, b'0', 7),
+            (br#"["\ud83d\u0041"]"#, b'1', 13),
+            (br#"{"\ud83dx": 1}"#, b'x', 8),
+            (b"[12.3.4]", b'.', 5),
+            (b"[truefalse]", b'f', 5),
+        ];
+        for (input, byte, idx) in invalid {
+            // lenient by default
+            assert!(
+                read_all(input, false, 0).is_ok(),
+                "{:?}",
+                String::from_utf8_lossy(input)
+            );
+            for split in 0..=input.len() {
+                match read_all(input, true, split) {
+                    Err(error::General::IncorrectInput(err)) => {
+                        assert_eq!(err, error::IncorrectInput::new(*byte, *idx))
+                    }
+                    other => panic!("{:?} {:?}", String::from_utf8_lossy(input), other),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_path_prefix() {
+        let mut streamer = Streamer::new();
+        streamer.set_invariant_checks(true);
+        streamer.set_path_prefix(make_path(r#"{"data"}[1]"#));
+        streamer.feed(br#"{"a": [1]} 2"#);
+        assert_eq!(streamer.read().unwrap(), Token::Start(0, ParsedKind::Obj));
+        assert_eq!(streamer.current_path(), &make_path(r#"{"data"}[1]"#));
+        assert_eq!(streamer.read().unwrap(), Token::Start(6, ParsedKind::Arr));
+        assert_eq!(streamer.current_path(), &make_path(r#"{"data"}[1]{"a"}"#));
+        assert_eq!(streamer.read().unwrap(), Token::Start(7, ParsedKind::Num));
+        assert_eq!(
+            streamer.current_path(),
+            &make_path(r#"{"data"}[1]{"a"}[0]"#)
+        );
+
+        // change the prefix while parsing
+        streamer.set_path_prefix(make_path(r#"{"other"}"#));
+        assert_eq!(streamer.current_path(), &make_path(r#"{"other"}{"a"}[0]"#));
+        assert_eq!(streamer.read().unwrap(), Token::End(8, ParsedKind::Int));
+        assert_eq!(streamer.read().unwrap(), Token::End(9, ParsedKind::Arr));
+        assert_eq!(streamer.current_path(), &make_path(r#"{"other"}{"a"}"#));
+        assert_eq!(streamer.read().unwrap(), Token::End(10, ParsedKind::Obj));
+        assert_eq!(streamer.current_path(), &make_path(r#"{"other"}"#));
+
+        // top level values keep the prefix
+        assert_eq!(streamer.read().unwrap(), Token::Finished(10));
+        assert_eq!(streamer.read().unwrap(), Token::Start(11, ParsedKind::Num));
+        assert_eq!(streamer.current_path(), &make_path(r#"{"other"}"#));
+        assert_eq!(streamer.read().unwrap(), Token::Pending);
+        streamer.feed_final(b"");
+        assert_eq!(streamer.read().unwrap(), Token::End(12, ParsedKind::Int));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(12));
+        assert_eq!(streamer.read().unwrap(), Token::Pending);
+        assert_eq!(streamer.current_path(), &make_path(r#"{"other"}"#));
+
+        // remove the prefix
+        streamer.set_path_prefix(Path::new());
+        assert_eq!(streamer.current_path(), &make_path(""));
+    }
+
+    #[test]
+    fn test_builder() {
+        let documents = Arc::new(Mutex::new(vec![]));
+        let cloned = documents.clone();
+        let mut streamer = StreamerBuilder::new()
+            .set_scan_mode(true)
+            .set_raw_keys(true)
+            .set_strict(true)
+            .set_invariant_checks(true)
+            .set_path_prefix(make_path(r#"{"root"}"#))
+            .set_document_callback(Box::new(move |start, end| {
+                cloned.lock().unwrap().push((start, end))
+            }))
+            .build();
+
+        // scan mode skips the prefix
+        streamer.feed(br#"x {"\u0041": 1}"#);
+        assert_eq!(streamer.read().unwrap(), Token::Start(2, ParsedKind::Obj));
+        assert_eq!(streamer.current_path(), &make_path(r#"{"root"}"#));
+        assert_eq!(streamer.read().unwrap(), Token::Start(13, ParsedKind::Num));
+        // raw key with prefix
+        assert_eq!(streamer.current_path().to_string(), r#"{"root"}{"\u0041"}"#);
+        assert_eq!(streamer.read().unwrap(), Token::End(14, ParsedKind::Int));
+        assert_eq!(streamer.read().unwrap(), Token::End(15, ParsedKind::Obj));
+        assert_eq!(*documents.lock().unwrap(), vec![(2, 15)]);
+
+        // strict mode
+        let mut streamer = StreamerBuilder::new().set_strict(true).build();
+        streamer.feed(b"[01]");
+        assert!(matches!(
+            streamer.read(),
+            Ok(Token::Start(0, ParsedKind::Arr))
+        ));
+        assert!(matches!(
+            streamer.read(),
+            Ok(Token::Start(1, ParsedKind::Num))
+        ));
+        assert!(streamer.read().is_err());
+    }
+
+    #[test]
+    fn test_kind_from_str() {
+        for kind in &[
+            ParsedKind::Obj,
+            ParsedKind::Arr,
+            ParsedKind::Str,
+            ParsedKind::Num,
+            ParsedKind::Bool,
+            ParsedKind::Null,
+        ] {
+            assert_eq!(ParsedKind::from_str(kind.as_ref()).unwrap(), *kind);
+        }
+        for kind in &[ParsedKind::Int, ParsedKind::Float] {
+            assert_eq!(
+                ParsedKind::from_str(kind.as_ref()).unwrap(),
+                ParsedKind::Num
+            );
+        }
+        assert!(ParsedKind::from_str("Object").is_err());
+        assert!(ParsedKind::from_str("").is_err());
+    }
+
+    #[test]
+    fn test_finished() {
+        let input = br#"{"a": [1]} [] "b" 4 "#;
+        for size in 1..input.len() {
+            let mut streamer = Streamer::new();
+            let mut finished = vec![];
+            for part in input.chunks(size) {
+                streamer.feed(part);
+                loop {
+                    match streamer.read().unwrap() {
+                        Token::Finished(idx) => {
+                            assert_eq!(streamer.current_path(), &make_path(""));
+                            finished.push(idx);
+                        }
+                        Token::Pending => break,
+                        _ => {}
+                    }
+                }
+            }
+            assert_eq!(finished, vec![10, 13, 17, 19]);
+        }
+
+        // documents at the prefix level
+        let mut streamer = Streamer::new();
+        streamer.set_path_prefix(make_path(r#"{"logs"}"#));
+        streamer.feed(br#"{"a": 1}"#);
+        assert_eq!(streamer.read().unwrap(), Token::Start(0, ParsedKind::Obj));
+        assert_eq!(streamer.read().unwrap(), Token::Start(6, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(7, ParsedKind::Int));
+        assert_eq!(streamer.read().unwrap(), Token::End(8, ParsedKind::Obj));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(8));
+        assert_eq!(streamer.current_path(), &make_path(r#"{"logs"}"#));
         assert_eq!(streamer.read().unwrap(), Token::Pending);
     }
+
+    #[test]
+    fn test_json_lines() {
+        let input = b"{\"a\": 1}\n\n  [2] \r\n\"3\"\n4";
+        for size in 1..input.len() {
+            let mut streamer = Streamer::new();
+            streamer.set_input_format(InputFormat::JsonLines);
+            let mut finished = vec![];
+            for part in input.chunks(size) {
+                streamer.feed(part);
+                loop {
+                    match streamer.read().unwrap() {
+                        Token::Finished(idx) => finished.push(idx),
+                        Token::Pending => break,
+                        _ => {}
+                    }
+                }
+            }
+            streamer.feed_final(b"");
+            while let Ok(token) = streamer.read() {
+                match token {
+                    Token::Finished(idx) => finished.push(idx),
+                    Token::Pending => break,
+                    _ => {}
+                }
+            }
+            assert_eq!(finished, vec![8, 15, 21, 23]);
+        }
+
+        // unfinished line
+        let mut streamer = Streamer::new();
+        streamer.set_input_format(InputFormat::JsonLines);
+        streamer.feed(b"[1,\n2]\n[3]\n");
+        assert_eq!(streamer.read().unwrap(), Token::Start(0, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Start(1, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(2, ParsedKind::Int));
+        assert_eq!(
+            streamer.read().unwrap(),
+            Token::Separator(2, ParsedKind::Arr)
+        );
+        match streamer.read() {
+            Err(error::General::IncorrectInput(err)) => assert_eq!(err.idx(), 3),
+            _ => unreachable!(),
+        }
+        assert_eq!(streamer.resync(), (4, b"2]\n[3]\n".to_vec()));
+
+        // multiple values on a single line
+        let mut streamer = Streamer::new();
+        streamer.set_input_format(InputFormat::JsonLines);
+        streamer.feed(b"[1] [2]\n");
+        assert_eq!(streamer.read().unwrap(), Token::Start(0, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Start(1, ParsedKind::Num));
+        assert_eq!(streamer.read().unwrap(), Token::End(2, ParsedKind::Int));
+        assert_eq!(streamer.read().unwrap(), Token::End(3, ParsedKind::Arr));
+        assert_eq!(streamer.read().unwrap(), Token::Finished(3));
+        match streamer.read() {
+            Err(error::General::IncorrectInput(err)) => assert_eq!(err.idx(), 4),
+            _ => unreachable!(),
+        }
+
+        assert_eq!(
+            InputFormat::from_str("ndjson").unwrap(),
+            InputFormat::JsonLines
+        );
+        assert_eq!(InputFormat::from_str("json").unwrap(), InputFormat::Json);
+        assert!(InputFormat::from_str("xml").is_err());
+    }
+
+    fn read_all(limits: Limits, input: &[u8]) -> Result<(), error::General> {
+        for size in 1..=input.len() {
+            let mut streamer = StreamerBuilder::new().set_limits(limits).build();
+            for part in input.chunks(size) {
+                streamer.feed(part);
+                while streamer.read()? != Token::Pending {}
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_limits() {
+        let depth = Limits::new().set_max_depth(Some(2));
+        assert!(read_all(depth, br#"[[1], {"a": 2}, 3]"#).is_ok());
+        match read_all(depth, br#"[1, {"a": [{}]}]"#) {
+            Err(error::General::LimitExceeded(err)) => assert_eq!(err.idx(), 10),
+            _ => unreachable!(),
+        }
+
+        let key = Limits::new().set_max_key_length(Some(3));
+        assert!(read_all(key, br#"{"abc": "long value", "\"a": 1}"#).is_ok());
+        match read_all(key, br#"{"abc": {"ab\"": 1}}"#) {
+            Err(error::General::LimitExceeded(err)) => assert_eq!(err.idx(), 13),
+            _ => unreachable!(),
+        }
+
+        let value = Limits::new().set_max_value_length(Some(4));
+        assert!(read_all(value, br#"{"long key": ["ab", 1234, -1.5, true]}"#).is_ok());
+        assert!(read_all(value, br#"["abc"]"#).is_err());
+        assert!(read_all(value, br#"[12345]"#).is_err());
+
+        // the limits are not set
+        assert!(read_all(Limits::new(), br#"[[[[["long string"]]]]]"#).is_ok());
+    }
 }
@@ -0,0 +1,103 @@
+//! Utilities for testing components with differently split input
+//!
+//! Streamson processes the input in chunks and the chunk boundaries
+//! can be anywhere (even in the middle of a key or a string).
+//! These splitters help to verify that a component behaves identically
+//! regardless of how the input is split.
+//!
+//! It requires `testing` feature to be enabled.
+//!
+//! # Example
+//! ```
+//! use streamson_lib::{
+//!     matcher,
+//!     strategy::{self, Strategy},
+//!     testing::{Single, Splitter, Window},
+//! };
+//!
+//! let input = br#"{"users": [{"name": "first"}, {"name": "second"}]}"#.to_vec();
+//!
+//! let splitters: Vec<Box<dyn Splitter>> = vec![Box::new(Single::new()), Box::new(Window::new(5))];
+//! for splitter in splitters {
+//!     for parts in splitter.split(input.clone()) {
+//!         let mut extract = strategy::Extract::new();
+//!         extract.add_matcher(Box::new(matcher::Simple::new(r#"{"users"}[]"#).unwrap()), None);
+//!
+//!         let mut output = vec![];
+//!         for part in parts {
+//!             output.extend(extract.process(&part).unwrap());
+//!         }
+//!         let data: Vec<u8> = strategy::OutputConverter::new()
+//!             .convert(&output)
+//!             .into_iter()
+//!             .flat_map(|(_, data)| data)
+//!             .collect();
+//!         assert_eq!(data, br#"{"name": "first"}{"name": "second"}"#.to_vec());
+//!     }
+//! }
+//! ```
+
+/// Splits the input into several variants of chunks
+pub trait Splitter {
+    /// Returns variants of the split input
+    ///
+    /// Each variant contains all the input data split into parts
+    ///
+    /// # Arguments
+    /// * `input` - data which will be split
+    fn split(&self, input: Vec<u8>) -> Vec<Vec<Vec<u8>>>;
+}
+
+/// Splits the input into one byte long parts
+#[derive(Debug, Default, Clone)]
+pub struct Single;
+
+impl Single {
+    /// Creates a new single byte splitter
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Splitter for Single {
+    fn split(&self, input: Vec<u8>) -> Vec<Vec<Vec<u8>>> {
+        vec![input.iter().map(|e| vec![*e]).collect()]
+    }
+}
+
+/// Moves a window of a fixed size over the input
+///
+/// Each variant consists of the data before the window,
+/// the window itself and the data after the window.
+#[derive(Debug, Clone)]
+pub struct Window {
+    size: usize,
+}
+
+impl Window {
+    /// Creates a new window splitter
+    ///
+    /// # Arguments
+    /// * `size` - size of the window
+    pub fn new(size: usize) -> Self {
+        Self { size }
+    }
+}
+
+impl Splitter for Window {
+    fn split(&self, input: Vec<u8>) -> Vec<Vec<Vec<u8>>> {
+        if input.len() <= self.size {
+            return vec![vec![input]];
+        }
+        let out_count = input.len() - self.size;
+        let mut res = vec![];
+        for i in 0..=out_count {
+            res.push(vec![
+                input[0..i].to_vec(),
+                input[i..self.size + i].to_vec(),
+                input[self.size + i..input.len()].to_vec(),
+            ]);
+        }
+        res
+    }
+}
@@ -1,9 +1,15 @@
 //! Decoders which implement `tokio_util::codec::Decoder`
 //! and are able to extract (path, bytes) items for `AsyncRead`
+//! or to process the data using any of the strategies
 //!
 
 use bytes::{Bytes, BytesMut};
+#[cfg(feature = "with_serde")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "with_serde")]
+use std::marker::PhantomData;
 use std::{
+    collections::VecDeque,
     io,
     sync::{Arc, Mutex},
 };
@@ -13,6 +19,16 @@ use streamson_lib::{
 };
 use tokio_util::codec::Decoder;
 
+/// What to do when invalid data are found
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OnError {
+    /// Error is returned and the processing ends
+    Fail,
+    /// The rest of the line with invalid data is skipped
+    /// and the processing continues on the next line
+    Skip,
+}
+
 /// This struct uses `streamson_lib::matcher` to decode data.
 ///
 /// # Examples
@@ -39,6 +55,7 @@ use tokio_util::codec::Decoder;
 pub struct Extractor {
     trigger: strategy::Trigger,
     handler: Arc<Mutex<handler::Buffer>>,
+    on_error: OnError,
 }
 
 impl Extractor {
@@ -54,7 +71,33 @@ impl Extractor {
         ));
         let mut trigger = strategy::Trigger::new();
         trigger.add_matcher(Box::new(matcher), handler.clone());
-        Self { trigger, handler }
+        Self {
+            trigger,
+            handler,
+            on_error: OnError::Fail,
+        }
+    }
+
+    /// Sets what to do when invalid data are found
+    ///
+    /// # Arguments
+    /// * `on_error` - fail (default) or skip the line with invalid data
+    pub fn set_on_error(mut self, on_error: OnError) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    /// Processes the data and recovers from errors if configured
+    fn process(&mut self, data: &[u8]) -> Result<(), error::General> {
+        let mut result = self.trigger.process(data);
+        while let Err(err) = result {
+            if self.on_error == OnError::Fail {
+                return Err(err);
+            }
+            self.handler.lock().unwrap().clear_unfinished();
+            result = self.trigger.resync();
+        }
+        Ok(())
     }
 }
 
@@ -77,12 +120,17 @@ impl Decoder for Extractor {
                 return Ok(None);
             }
             let data = buf.split_to(buf.len());
-            self.trigger.process(&data[..])?;
+            self.process(&data[..])?;
         }
     }
 
     fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        self.trigger.terminate()?;
+        if let Err(err) = self.trigger.terminate() {
+            // unfinished data at the end can't be recovered
+            if self.on_error == OnError::Fail {
+                return Err(err);
+            }
+        }
         match self.decode(buf)? {
             Some(frame) => Ok(Some(frame)),
             None => {
@@ -96,12 +144,193 @@ impl Decoder for Extractor {
     }
 }
 
+/// Decoder which deserializes extracted data into `T`
+///
+/// It works as [Extractor](struct.Extractor.html) but each matched value
+/// is deserialized using `serde_json`.
+///
+/// # Examples
+/// ```
+/// use serde::Deserialize;
+/// use streamson_lib::{error, matcher};
+/// use streamson_tokio::decoder::TypedExtractor;
+/// use tokio::{fs, stream::StreamExt};
+/// use tokio_util::codec::FramedRead;
+///
+/// #[derive(Deserialize)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// async fn process() -> Result<(), error::General> {
+///     let mut file = fs::File::open("/tmp/large.json").await?;
+///     let matcher = matcher::Simple::new(r#"{"users"}[]"#).unwrap();
+///     let extractor = TypedExtractor::<User>::new(matcher, false);
+///     let mut output = FramedRead::new(file, extractor);
+///     while let Some(item) = output.next().await {
+///         let (_, user) = item?;
+///         // Do something with deserialized user
+///     }
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "with_serde")]
+pub struct TypedExtractor<T: DeserializeOwned> {
+    extractor: Extractor,
+    phantom: PhantomData<T>,
+}
+
+#[cfg(feature = "with_serde")]
+impl<T: DeserializeOwned> TypedExtractor<T> {
+    /// Creates a new `TypedExtractor`
+    ///
+    /// # Arguments
+    /// * `matcher` - matcher to be used for extractions (see `streamson_lib::matcher`)
+    /// * `include_path` - will path be included in output
+    pub fn new(matcher: impl matcher::Matcher + 'static, include_path: bool) -> Self {
+        Self {
+            extractor: Extractor::new(matcher, include_path),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Sets what to do when invalid data are found
+    ///
+    /// Note that data which can't be deserialized into `T` always fail.
+    ///
+    /// # Arguments
+    /// * `on_error` - fail (default) or skip the line with invalid data
+    pub fn set_on_error(mut self, on_error: OnError) -> Self {
+        self.extractor = self.extractor.set_on_error(on_error);
+        self
+    }
+
+    /// Deserializes extracted item
+    fn deserialize(
+        item: Option<(Option<String>, Bytes)>,
+    ) -> Result<Option<(Option<String>, T)>, error::General> {
+        if let Some((path, bytes)) = item {
+            let value = serde_json::from_slice(&bytes).map_err(io::Error::from)?;
+            Ok(Some((path, value)))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(feature = "with_serde")]
+impl<T: DeserializeOwned> Decoder for TypedExtractor<T> {
+    type Item = (Option<String>, T);
+    type Error = error::General;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        Self::deserialize(self.extractor.decode(buf)?)
+    }
+
+    fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        Self::deserialize(self.extractor.decode_eof(buf)?)
+    }
+}
+
+/// Decoder which processes the data using a strategy
+///
+/// It can wrap any strategy (e.g. `Convert`, `Filter`, `Extract` or `All`)
+/// and it yields the output of the strategy as soon as it is available,
+/// so the data can be rewritten without collecting them into memory.
+///
+/// # Examples
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use streamson_lib::{error, handler, matcher, strategy};
+/// use streamson_tokio::decoder::Processor;
+/// use tokio::{fs, io::AsyncWriteExt, stream::StreamExt};
+/// use tokio_util::codec::FramedRead;
+///
+/// async fn process() -> Result<(), error::General> {
+///     let file = fs::File::open("/tmp/large.json").await?;
+///     let mut convert = strategy::Convert::new();
+///     convert.add_matcher(
+///         Box::new(matcher::Simple::new(r#"{"users"}[]{"password"}"#).unwrap()),
+///         Arc::new(Mutex::new(handler::Replace::new(br#""***""#.to_vec()))),
+///     );
+///     let mut output = FramedRead::new(file, Processor::new(convert));
+///     let mut out_file = fs::File::create("/tmp/converted.json").await?;
+///     while let Some(item) = output.next().await {
+///         if let strategy::Output::Data(data) = item? {
+///             out_file.write_all(&data).await?;
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct Processor<S: Strategy> {
+    strategy: S,
+    output: VecDeque<strategy::Output>,
+    terminated: bool,
+}
+
+impl<S: Strategy> Processor<S> {
+    /// Creates a new `Processor`
+    ///
+    /// # Arguments
+    /// * `strategy` - configured strategy which processes the data
+    pub fn new(strategy: S) -> Self {
+        Self {
+            strategy,
+            output: VecDeque::new(),
+            terminated: false,
+        }
+    }
+
+    /// Returns the wrapped strategy
+    pub fn strategy_mut(&mut self) -> &mut S {
+        &mut self.strategy
+    }
+}
+
+impl<S: Strategy> Decoder for Processor<S> {
+    type Item = strategy::Output;
+    type Error = error::General;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        loop {
+            if let Some(output) = self.output.pop_front() {
+                return Ok(Some(output));
+            }
+            if buf.is_empty() {
+                return Ok(None);
+            }
+            let data = buf.split_to(buf.len());
+            self.output.extend(self.strategy.process(&data[..])?);
+        }
+    }
+
+    fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if self.output.is_empty() && !self.terminated {
+            // trailing values are finished here
+            self.terminated = true;
+            let data = buf.split_to(buf.len());
+            self.output.extend(self.strategy.process_final(&data[..])?);
+            self.output.extend(self.strategy.terminate()?);
+        }
+        Ok(self.output.pop_front())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Extractor;
+    use super::{Extractor, OnError, Processor};
     use bytes::Bytes;
-    use std::io::Cursor;
-    use streamson_lib::matcher;
+    use std::{
+        convert::TryFrom,
+        io::Cursor,
+        sync::{Arc, Mutex},
+    };
+    use streamson_lib::{
+        handler, matcher,
+        path::Path,
+        strategy::{self, Output},
+    };
     use tokio::stream::StreamExt;
     use tokio_util::codec::FramedRead;
 
@@ -229,4 +458,126 @@ mod tests {
 
         assert!(output.next().await.is_none());
     }
+
+    #[tokio::test]
+    async fn skip_invalid() {
+        let input = b"{\"level\": \"info\"}\n{\"level\" \"debug\"}\n{\"level\": \"error\"}\n";
+        let matcher = matcher::Simple::new(r#"{"level"}"#).unwrap();
+
+        let extractor = Extractor::new(matcher.clone(), false);
+        let mut output = FramedRead::new(Cursor::new(input.to_vec()), extractor);
+        assert!(output.next().await.unwrap().is_err());
+
+        let extractor = Extractor::new(matcher, false).set_on_error(OnError::Skip);
+        let mut output = FramedRead::new(Cursor::new(input.to_vec()), extractor);
+        assert_eq!(
+            output.next().await.unwrap().unwrap(),
+            (None, Bytes::from_static(br#""info""#))
+        );
+        assert_eq!(
+            output.next().await.unwrap().unwrap(),
+            (None, Bytes::from_static(br#""error""#))
+        );
+        assert!(output.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn processor_convert() {
+        let cursor = Cursor::new(br#"{"users": [{"id": 1}, {"id": 2}]} 3"#.to_vec());
+        let mut convert = strategy::Convert::new();
+        convert.add_matcher(
+            Box::new(matcher::Simple::new(r#"{"users"}[]{"id"}"#).unwrap()),
+            Arc::new(Mutex::new(handler::Replace::new(b"0".to_vec()))),
+        );
+        let output = FramedRead::new(cursor, Processor::new(convert));
+
+        let data: Vec<u8> = output
+            .map(|item| match item.unwrap() {
+                Output::Data(data) => data,
+                _ => vec![],
+            })
+            .collect::<Vec<Vec<u8>>>()
+            .await
+            .concat();
+        assert_eq!(
+            String::from_utf8(data).unwrap(),
+            r#"{"users": [{"id": 0}, {"id": 0}]} 3"#
+        );
+    }
+
+    #[tokio::test]
+    async fn processor_extract() {
+        let cursor = Cursor::new(br#"{"users": ["mike", "john"]}"#.to_vec());
+        let mut extract = strategy::Extract::new().set_export_path(true);
+        extract.add_matcher(
+            Box::new(matcher::Simple::new(r#"{"users"}[1]"#).unwrap()),
+            None,
+        );
+        let mut output = FramedRead::new(cursor, Processor::new(extract));
+
+        assert_eq!(
+            output.next().await.unwrap().unwrap(),
+            Output::Start(Some(Path::try_from(r#"{"users"}[1]"#).unwrap()))
+        );
+        assert_eq!(
+            output.next().await.unwrap().unwrap(),
+            Output::Data(br#""john""#.to_vec())
+        );
+        assert_eq!(output.next().await.unwrap().unwrap(), Output::End);
+        assert!(output.next().await.is_none());
+
+        // invalid input
+        let cursor = Cursor::new(br#"{"users": ]"#.to_vec());
+        let mut output = FramedRead::new(cursor, Processor::new(strategy::Extract::new()));
+        assert!(output.next().await.unwrap().is_err());
+    }
+
+    #[cfg(feature = "with_serde")]
+    #[tokio::test]
+    async fn typed() {
+        use super::TypedExtractor;
+        use serde::Deserialize;
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct User {
+            name: String,
+            age: u8,
+        }
+
+        let cursor = Cursor::new(
+            br#"{"users": [{"name": "mike", "age": 30}, {"age": 5, "name": "john"}]}"#.to_vec(),
+        );
+        let matcher = matcher::Simple::new(r#"{"users"}[]"#).unwrap();
+        let extractor = TypedExtractor::<User>::new(matcher, true);
+        let mut output = FramedRead::new(cursor, extractor);
+
+        assert_eq!(
+            output.next().await.unwrap().unwrap(),
+            (
+                Some(r#"{"users"}[0]"#.to_string()),
+                User {
+                    name: "mike".to_string(),
+                    age: 30
+                }
+            )
+        );
+        assert_eq!(
+            output.next().await.unwrap().unwrap(),
+            (
+                Some(r#"{"users"}[1]"#.to_string()),
+                User {
+                    name: "john".to_string(),
+                    age: 5
+                }
+            )
+        );
+        assert!(output.next().await.is_none());
+
+        // data which don't match the type
+        let cursor = Cursor::new(br#"{"users": [{"name": "mike"}]}"#.to_vec());
+        let matcher = matcher::Simple::new(r#"{"users"}[]"#).unwrap();
+        let extractor = TypedExtractor::<User>::new(matcher, false);
+        let mut output = FramedRead::new(cursor, extractor);
+        assert!(output.next().await.unwrap().is_err());
+    }
 }